@@ -70,5 +70,48 @@ fn bench_batch_verify(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_batch_verify);
+fn bench_large_batch_verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Large Batch Verification");
+    group.sample_size(10);
+    for n in [128usize, 256, 1024, 4096].iter() {
+        group.throughput(Throughput::Elements(*n as u64));
+        let sigs = sigs_with_distinct_pubkeys().take(*n).collect::<Vec<_>>();
+        group.bench_with_input(
+            BenchmarkId::new("Signatures with Distinct Pubkeys", n),
+            &sigs,
+            |b, sigs| {
+                b.iter(|| {
+                    let mut batch = batch::Verifier::new();
+                    for (vk_bytes, sig) in sigs.iter().cloned() {
+                        batch.queue((vk_bytes, sig, b""));
+                    }
+                    batch.verify(thread_rng())
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_precomputed_verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Precomputed Verification");
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let pvk = PrecomputedVerificationKey::from(vk);
+    let sig = sk.sign(b"");
+    group.bench_function("VerificationKey::verify", |b| {
+        b.iter(|| vk.verify(&sig, b""))
+    });
+    group.bench_function("PrecomputedVerificationKey::verify", |b| {
+        b.iter(|| pvk.verify(&sig, b""))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_batch_verify,
+    bench_large_batch_verify,
+    bench_precomputed_verify
+);
 criterion_main!(benches);
@@ -0,0 +1,210 @@
+//! Half-aggregation of Ed25519 signatures.
+//!
+//! A set of `n` signatures `(R_i, s_i)`, on messages `m_i` under keys `A_i`,
+//! can be compressed into an [`AggregateSignature`] consisting of every
+//! `R_i` and a single scalar `s = sum(z_i * s_i)`, nearly halving its size.
+//! The coefficients `z_i` are derived by hashing all of the `(R_i, A_i, m_i)`,
+//! so that a signer cannot choose its signature to cancel out another's, as
+//! described in [Chalkias, Garillot, Kondi and Nikolaenko][paper].
+//!
+//! Aggregation needs no secrets, so any party holding the individual
+//! signatures can aggregate them. Verification checks the cofactored equation
+//!
+//! `[8][s]B = [8] sum([z_i]R_i + [z_i * k_i]A_i)`,
+//!
+//! which holds if all of the aggregated signatures are valid under the
+//! [ZIP215] rules used by [`VerificationKey::verify`](crate::VerificationKey::verify).
+//!
+//! # Example
+//! ```
+//! # use ed25519_zebra::*;
+//! let items = (0..4u8)
+//!     .map(|i| {
+//!         let sk = SigningKey::new(rand::thread_rng());
+//!         let msg = [i; 32];
+//!         (VerificationKeyBytes::from(&sk), msg, sk.sign(&msg))
+//!     })
+//!     .collect::<Vec<_>>();
+//! let agg = aggregate::aggregate(&items).unwrap();
+//!
+//! let keys_and_messages = items
+//!     .iter()
+//!     .map(|(vk_bytes, msg, _)| (*vk_bytes, msg))
+//!     .collect::<Vec<_>>();
+//! assert!(agg.verify(&keys_and_messages).is_ok());
+//! ```
+//!
+//! [paper]: https://eprint.iacr.org/2021/350
+//! [ZIP215]: https://github.com/zcash/zips/blob/master/zip-0215.rst
+
+use std::convert::TryFrom;
+
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
+};
+use sha2::{Digest, Sha512};
+
+use crate::{Error, Signature, VerificationKeyBytes};
+
+/// A half-aggregated set of Ed25519 signatures.
+///
+/// The encoding is `R_bytes_1 || ... || R_bytes_n || s_bytes`, of length
+/// `32 * (n + 1)`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(non_snake_case)]
+pub struct AggregateSignature {
+    R_bytes: Vec<[u8; 32]>,
+    s_bytes: [u8; 32],
+}
+
+/// Aggregate the signatures in `items`, each a `(key, message, signature)`
+/// tuple.
+///
+/// The signatures are not verified; an aggregate of signatures one of which
+/// is invalid will fail to verify. Returns
+/// [`Error::SignatureScalarOutOfRange`] if a signature's `s` is not reduced.
+pub fn aggregate<M: AsRef<[u8]>>(
+    items: &[(VerificationKeyBytes, M, Signature)],
+) -> Result<AggregateSignature, Error> {
+    let zs = coefficients(
+        items
+            .iter()
+            .map(|(vk_bytes, msg, sig)| (sig.R_bytes(), vk_bytes, msg.as_ref())),
+    );
+    let mut s = Scalar::zero();
+    for ((_, _, sig), z) in items.iter().zip(zs) {
+        let s_i =
+            Scalar::from_canonical_bytes(*sig.s_bytes()).ok_or(Error::SignatureScalarOutOfRange)?;
+        s += z * s_i;
+    }
+    Ok(AggregateSignature {
+        R_bytes: items.iter().map(|(_, _, sig)| *sig.R_bytes()).collect(),
+        s_bytes: s.to_bytes(),
+    })
+}
+
+/// Derive the aggregation coefficients for `(R_bytes, A_bytes, msg)` tuples.
+#[allow(non_snake_case)]
+fn coefficients<'a>(
+    items: impl Iterator<Item = (&'a [u8; 32], &'a VerificationKeyBytes, &'a [u8])>,
+) -> Vec<Scalar> {
+    let mut transcript = Sha512::default().chain(b"ed25519-zebra half-aggregation");
+    let mut n = 0u64;
+    for (R_bytes, vk_bytes, msg) in items {
+        transcript.update(&R_bytes[..]);
+        transcript.update(&vk_bytes.0[..]);
+        transcript.update((msg.len() as u64).to_le_bytes());
+        transcript.update(msg);
+        n += 1;
+    }
+    let digest = transcript.finalize();
+    (0..n)
+        .map(|i| Scalar::from_hash(Sha512::default().chain(&digest[..]).chain(i.to_le_bytes())))
+        .collect()
+}
+
+impl AggregateSignature {
+    /// The number of signatures in this aggregate.
+    pub fn len(&self) -> usize {
+        self.R_bytes.len()
+    }
+
+    /// Whether this aggregate contains no signatures.
+    pub fn is_empty(&self) -> bool {
+        self.R_bytes.is_empty()
+    }
+
+    /// Encode this aggregate as `R_bytes_1 || ... || R_bytes_n || s_bytes`.
+    #[allow(non_snake_case)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(32 * (self.R_bytes.len() + 1));
+        for R_bytes in &self.R_bytes {
+            bytes.extend_from_slice(&R_bytes[..]);
+        }
+        bytes.extend_from_slice(&self.s_bytes[..]);
+        bytes
+    }
+
+    /// Verify this aggregate against the `(key, message)` pairs of the
+    /// aggregated signatures, in the order they were aggregated.
+    ///
+    /// Returns [`Error::InvalidSignature`] if the number of pairs does not
+    /// match the aggregate or the verification equation fails, and the same
+    /// encoding errors as [`VerificationKey::verify`](crate::VerificationKey::verify)
+    /// otherwise.
+    #[allow(non_snake_case)]
+    pub fn verify<M: AsRef<[u8]>>(&self, items: &[(VerificationKeyBytes, M)]) -> Result<(), Error> {
+        if items.len() != self.R_bytes.len() {
+            return Err(Error::InvalidSignature);
+        }
+        let s =
+            Scalar::from_canonical_bytes(self.s_bytes).ok_or(Error::SignatureScalarOutOfRange)?;
+        let zs = coefficients(
+            self.R_bytes
+                .iter()
+                .zip(items.iter())
+                .map(|(R_bytes, (vk_bytes, msg))| (R_bytes, vk_bytes, msg.as_ref())),
+        );
+
+        let mut coeffs = Vec::with_capacity(2 * items.len() + 1);
+        let mut points = Vec::with_capacity(2 * items.len() + 1);
+        coeffs.push(-s);
+        points.push(ED25519_BASEPOINT_POINT);
+        for ((R_bytes, (vk_bytes, msg)), z) in self.R_bytes.iter().zip(items.iter()).zip(zs) {
+            let A = CompressedEdwardsY(vk_bytes.0)
+                .decompress()
+                .ok_or(Error::MalformedPublicKey)?;
+            let R = CompressedEdwardsY(*R_bytes)
+                .decompress()
+                .ok_or(Error::MalformedSignatureR)?;
+            let k = Scalar::from_hash(
+                Sha512::default()
+                    .chain(&R_bytes[..])
+                    .chain(&vk_bytes.0[..])
+                    .chain(msg.as_ref()),
+            );
+            coeffs.push(z);
+            points.push(R);
+            coeffs.push(z * k);
+            points.push(A);
+        }
+
+        if EdwardsPoint::vartime_multiscalar_mul(coeffs.iter(), points.iter())
+            .mul_by_cofactor()
+            .is_identity()
+        {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for AggregateSignature {
+    type Error = Error;
+
+    #[allow(non_snake_case)]
+    fn try_from(bytes: &[u8]) -> Result<AggregateSignature, Error> {
+        if bytes.is_empty() || !bytes.len().is_multiple_of(32) {
+            return Err(Error::InvalidSliceLength);
+        }
+        let (Rs, s) = bytes.split_at(bytes.len() - 32);
+        let mut s_bytes = [0u8; 32];
+        s_bytes.copy_from_slice(s);
+        Ok(AggregateSignature {
+            R_bytes: Rs
+                .chunks(32)
+                .map(|R| {
+                    let mut R_bytes = [0u8; 32];
+                    R_bytes.copy_from_slice(R);
+                    R_bytes
+                })
+                .collect(),
+            s_bytes,
+        })
+    }
+}
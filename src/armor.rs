@@ -0,0 +1,66 @@
+//! PEM-style ASCII armor shared by the key and signature file formats.
+
+use base64ct::{Base64, Encoding};
+use zeroize::Zeroizing;
+
+/// An error decoding ASCII armor.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum ArmorError {
+    /// No `-----BEGIN ...-----` boundary was found.
+    MissingBoundary,
+    /// The `-----END ...-----` boundary for the given label was missing or
+    /// mismatched.
+    MismatchedBoundary(String),
+    /// The body is not valid base64.
+    InvalidBase64,
+}
+
+/// Decode the first armored block in `text`, returning its label and
+/// contents.
+///
+/// Text outside the block, whitespace within the body, and RFC 1421-style
+/// headers are ignored.
+pub(crate) fn decode(text: &str) -> Result<(&str, Zeroizing<Vec<u8>>), ArmorError> {
+    let mut lines = text.lines().map(str::trim);
+    let label = lines
+        .by_ref()
+        .find_map(|line| line.strip_prefix("-----BEGIN ")?.strip_suffix("-----"))
+        .ok_or(ArmorError::MissingBoundary)?;
+
+    let mut body = Zeroizing::new(String::new());
+    loop {
+        match lines.next() {
+            Some(line) if line.starts_with("-----END ") => {
+                if line != format!("-----END {}-----", label) {
+                    return Err(ArmorError::MismatchedBoundary(label.to_string()));
+                }
+                break;
+            }
+            // Headers such as `Proc-Type:` contain a character outside the
+            // base64 alphabet.
+            Some(line) if line.contains(':') => {}
+            Some(line) => line.split_whitespace().for_each(|part| body.push_str(part)),
+            None => return Err(ArmorError::MismatchedBoundary(label.to_string())),
+        }
+    }
+
+    let contents = Base64::decode_vec(&body).map_err(|_| ArmorError::InvalidBase64)?;
+    Ok((label, Zeroizing::new(contents)))
+}
+
+/// Armor `contents` under `label`, wrapping the base64 body at `width`
+/// columns.
+#[cfg(any(feature = "openssh", feature = "x509"))]
+pub(crate) fn encode(label: &str, contents: &[u8], width: usize) -> Zeroizing<String> {
+    let body = Zeroizing::new(Base64::encode_string(contents));
+    let mut armored = Zeroizing::new(format!("-----BEGIN {}-----\n", label));
+    let mut rest = &body[..];
+    while !rest.is_empty() {
+        let (line, tail) = rest.split_at(width.min(rest.len()));
+        armored.push_str(line);
+        armored.push('\n');
+        rest = tail;
+    }
+    armored.push_str(&format!("-----END {}-----\n", label));
+    armored
+}
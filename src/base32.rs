@@ -0,0 +1,74 @@
+//! The unpadded RFC 4648 base32 encoding, shared by several key identifier
+//! formats, and the bit regrouping it shares with bech32.
+
+/// The lowercase RFC 4648 base32 alphabet.
+#[cfg(feature = "onion")]
+pub(crate) const LOWERCASE: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+/// The uppercase RFC 4648 base32 alphabet.
+#[cfg(feature = "stellar")]
+pub(crate) const UPPERCASE: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode `bytes` in base32 with `alphabet`, without padding.
+#[cfg(any(feature = "onion", feature = "stellar"))]
+pub(crate) fn encode(bytes: &[u8], alphabet: &[u8; 32]) -> String {
+    let values = regroup(bytes, 8, 5, true).expect("padded regrouping succeeds");
+    encode_values(&values, alphabet)
+}
+
+/// Decode unpadded base32 `encoded` with `alphabet`, returning `None` if it
+/// contains characters outside the alphabet or is not a canonical encoding.
+#[cfg(any(feature = "onion", feature = "stellar"))]
+pub(crate) fn decode(encoded: &str, alphabet: &[u8; 32]) -> Option<Vec<u8>> {
+    regroup(&decode_values(encoded, alphabet)?, 5, 8, false)
+}
+
+/// Map 5-bit `values` to the characters of `alphabet`.
+pub(crate) fn encode_values(values: &[u8], alphabet: &[u8; 32]) -> String {
+    values
+        .iter()
+        .map(|&value| char::from(alphabet[usize::from(value & 31)]))
+        .collect()
+}
+
+/// Map the characters of `encoded` to their 5-bit values in `alphabet`,
+/// returning `None` if it contains characters outside the alphabet.
+pub(crate) fn decode_values(encoded: &str, alphabet: &[u8; 32]) -> Option<Vec<u8>> {
+    encoded
+        .bytes()
+        .map(|c| {
+            alphabet
+                .iter()
+                .position(|&a| a == c)
+                .map(|value| value as u8)
+        })
+        .collect()
+}
+
+/// Regroup `values` of `from` bits each into values of `to` bits, for widths
+/// of at most 8 bits.
+///
+/// With `pad`, the last value is padded with zero bits. Without it, the
+/// leftover bits must be fewer than `from` and zero, so that only canonical
+/// encodings are accepted, or `None` is returned.
+pub(crate) fn regroup(values: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut regrouped = Vec::with_capacity(values.len() * from as usize / to as usize + 1);
+    let mask = (1 << to) - 1;
+    let mut bits = 0u16;
+    let mut len = 0;
+    for &value in values {
+        bits = bits << from | u16::from(value);
+        len += from;
+        while len >= to {
+            len -= to;
+            regrouped.push((bits >> len & mask) as u8);
+        }
+    }
+    if pad {
+        if len > 0 {
+            regrouped.push((bits << (to - len) & mask) as u8);
+        }
+    } else if len >= from || bits & ((1 << len) - 1) != 0 {
+        return None;
+    }
+    Some(regrouped)
+}
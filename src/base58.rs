@@ -0,0 +1,56 @@
+//! The base58 encoding with the Bitcoin alphabet, shared by several key
+//! identifier formats.
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encode `bytes` in base58. Each leading zero byte becomes a leading `1`.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    // Little-endian base-58 digits of the remaining bytes.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut encoded = "1".repeat(zeros);
+    encoded.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|&digit| char::from(ALPHABET[digit as usize])),
+    );
+    encoded
+}
+
+/// Decode base58 `text`, returning `None` if it contains characters outside
+/// the alphabet.
+pub(crate) fn decode(text: &str) -> Option<Vec<u8>> {
+    let zeros = text.bytes().take_while(|&c| c == b'1').count();
+    // Little-endian bytes of the remaining digits.
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len() * 733 / 1000 + 1);
+    for c in text.bytes().skip(zeros) {
+        let mut carry = ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes.iter().rev());
+    Some(decoded)
+}
@@ -24,7 +24,8 @@
 //! automatically coalesces terms in the final verification equation. In the
 //! limiting case where all signatures in the batch are made with the same
 //! verification key, coalesced batch verification runs twice as fast as ordinary
-//! batch verification.
+//! batch verification. Identical (key, signature, message) tuples are only
+//! verified once.
 //!
 //! ![benchmark](https://www.zfnd.org/images/coalesced-batch-graph.png)
 //!
@@ -32,6 +33,17 @@
 //! but could be useful in proof-of-stake systems where signatures come from a
 //! set of validators (provided that system uses the ZIP215 rules).
 //!
+//! The multiscalar multiplication switches to Pippenger's algorithm for large
+//! batches, so throughput per signature keeps improving for batches of
+//! thousands of signatures.
+//!
+//! A [`Verifier`] grows with every queued item. To put a hard cap on both the
+//! memory used by queued signatures and the latency of each verification,
+//! use a [`BoundedVerifier`], which verifies its queue whenever it reaches a
+//! fixed capacity and reports the result from [`BoundedVerifier::queue`].
+//! An [`ArrayVerifier`] instead stores a fixed number of items inline and
+//! verifies them on the stack, without heap allocation.
+//!
 //! # Example
 //! ```
 //! # use ed25519_zebra::*;
@@ -48,7 +60,10 @@
 //!
 //! [ZIP215]: https://github.com/zcash/zips/blob/master/zip-0215.rst
 
-use std::{collections::HashMap, convert::TryFrom};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    convert::TryFrom,
+};
 
 use curve25519_dalek::{
     edwards::{CompressedEdwardsY, EdwardsPoint},
@@ -58,7 +73,7 @@ use curve25519_dalek::{
 use rand_core::{CryptoRng, RngCore};
 use sha2::{Digest, Sha512};
 
-use crate::{Error, Signature, VerificationKey, VerificationKeyBytes};
+use crate::{Error, Signature, VerificationKey, VerificationKeyBytes, VerificationPolicy};
 
 // Shim to generate a u128 without importing `rand`.
 fn gen_u128<R: RngCore + CryptoRng>(mut rng: R) -> u128 {
@@ -67,25 +82,126 @@ fn gen_u128<R: RngCore + CryptoRng>(mut rng: R) -> u128 {
     u128::from_le_bytes(bytes)
 }
 
+/// Verify signatures on the same `msg` by each of a set of keys, returning
+/// `Ok(())` if all of them are valid.
+///
+/// This is the check needed for quorum certificates and multi-approval
+/// workflows. The signatures are checked with a single batch equation, using
+/// randomizers derived from the inputs as in [`Verifier::verify_deterministic`],
+/// so the result always agrees with verifying each signature individually.
+/// Each challenge hash covers the signature's `R` and the key before the
+/// message, so the message is still hashed once per signature.
+///
+/// To find out which signatures are invalid, queue the pairs in a
+/// [`Verifier`] and use [`Verifier::verify_identifying_failures`].
+pub fn verify_same_message(
+    msg: &[u8],
+    signatures: &[(VerificationKeyBytes, Signature)],
+) -> Result<(), Error> {
+    let mut batch = Verifier::new();
+    for (vk_bytes, sig) in signatures {
+        batch.queue((*vk_bytes, *sig, msg));
+    }
+    batch.verify_deterministic()
+}
+
 /// A batch verification item.
 ///
 /// This struct exists to allow batch processing to be decoupled from the
 /// lifetime of the message. This is useful when using the batch verification API
 /// in an async context.
+///
+/// An `Item` can also be sent to another process or machine for verification,
+/// without the message, using its 128-byte encoding `vk_bytes || sig || k`
+/// or, with the `serde` feature, any serde format.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "ItemSerdeHelper"))]
+#[cfg_attr(feature = "serde", serde(into = "ItemSerdeHelper"))]
 pub struct Item {
     vk_bytes: VerificationKeyBytes,
     sig: Signature,
     k: Scalar,
 }
 
+/// The challenge `k` is serialized as its canonical 32-byte encoding.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename = "Item"))]
+struct ItemSerdeHelper {
+    vk_bytes: VerificationKeyBytes,
+    sig: Signature,
+    k: [u8; 32],
+}
+
+impl From<ItemSerdeHelper> for Item {
+    fn from(helper: ItemSerdeHelper) -> Item {
+        Item {
+            vk_bytes: helper.vk_bytes,
+            sig: helper.sig,
+            k: Scalar::from_bytes_mod_order(helper.k),
+        }
+    }
+}
+
+impl From<Item> for ItemSerdeHelper {
+    fn from(item: Item) -> ItemSerdeHelper {
+        ItemSerdeHelper {
+            vk_bytes: item.vk_bytes,
+            sig: item.sig,
+            k: item.k.to_bytes(),
+        }
+    }
+}
+
+impl From<[u8; 128]> for Item {
+    /// Decode an item from its encoding `vk_bytes || sig || k`. The challenge
+    /// `k` is reduced modulo `l` if necessary.
+    fn from(bytes: [u8; 128]) -> Item {
+        let mut vk_bytes = [0u8; 32];
+        vk_bytes.copy_from_slice(&bytes[0..32]);
+        let mut sig_bytes = [0u8; 64];
+        sig_bytes.copy_from_slice(&bytes[32..96]);
+        let mut k_bytes = [0u8; 32];
+        k_bytes.copy_from_slice(&bytes[96..128]);
+        Item {
+            vk_bytes: vk_bytes.into(),
+            sig: sig_bytes.into(),
+            k: Scalar::from_bytes_mod_order(k_bytes),
+        }
+    }
+}
+
+impl From<Item> for [u8; 128] {
+    fn from(item: Item) -> [u8; 128] {
+        let mut bytes = [0u8; 128];
+        bytes[0..32].copy_from_slice(&item.vk_bytes.0[..]);
+        bytes[32..96].copy_from_slice(&item.sig.as_bytes()[..]);
+        bytes[96..128].copy_from_slice(item.k.as_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for Item {
+    type Error = Error;
+
+    fn try_from(slice: &[u8]) -> Result<Item, Error> {
+        if slice.len() == 128 {
+            let mut bytes = [0u8; 128];
+            bytes[..].copy_from_slice(slice);
+            Ok(bytes.into())
+        } else {
+            Err(Error::InvalidSliceLength)
+        }
+    }
+}
+
 impl<'msg, M: AsRef<[u8]> + ?Sized> From<(VerificationKeyBytes, Signature, &'msg M)> for Item {
     fn from(tup: (VerificationKeyBytes, Signature, &'msg M)) -> Self {
         let (vk_bytes, sig, msg) = tup;
         // Compute k now to avoid dependency on the msg lifetime.
         let k = Scalar::from_hash(
             Sha512::default()
-                .chain(&sig.R_bytes[..])
+                .chain(&sig.R_bytes()[..])
                 .chain(&vk_bytes.0[..])
                 .chain(msg),
         );
@@ -102,19 +218,119 @@ impl Item {
     /// borrowing the message data, the `Item` type is unlinked from the lifetime of
     /// the message.
     pub fn verify_single(self) -> Result<(), Error> {
+        VerificationKey::try_from(self.vk_bytes).and_then(|vk| vk.verify_with_k(&self.sig, self.k))
+    }
+
+    /// Perform non-batched verification of this `Item` under `policy`.
+    ///
+    /// This is the counterpart of [`Item::verify_single`] for a [`Verifier`]
+    /// constructed with [`Verifier::with_policy`].
+    pub fn verify_single_with_policy(self, policy: VerificationPolicy) -> Result<(), Error> {
         VerificationKey::try_from(self.vk_bytes)
-            .and_then(|vk| vk.verify_prehashed(&self.sig, self.k))
+            .and_then(|vk| vk.verify_with_k_and_policy(&self.sig, self.k, policy))
     }
 }
 
 /// A batch verification context.
-#[derive(Default)]
+///
+/// A verifier can be cloned, for instance to try verifying a batch both with
+/// and without a suspect item, and with the `serde` feature it can be
+/// serialized so that a long-running accumulation can be checkpointed and
+/// resumed. The serialized form records the policy and every queued item in
+/// queue order, so a restored verifier reports failures at the same
+/// positions as the original.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "VerifierSerdeHelper"))]
+#[cfg_attr(feature = "serde", serde(into = "VerifierSerdeHelper"))]
 pub struct Verifier {
-    /// Signature data queued for verification.
-    signatures: HashMap<VerificationKeyBytes, Vec<(Scalar, Signature)>>,
+    /// Signature data queued for verification, with the position of each
+    /// item in the queue.
+    signatures: HashMap<VerificationKeyBytes, Vec<(usize, Scalar, Signature)>>,
     /// Caching this count avoids a hash traversal to figure out
     /// how much to preallocate.
     batch_size: usize,
+    /// The acceptance criteria applied to each signature.
+    policy: VerificationPolicy,
+    /// The position of the first occurrence of each queued item, keyed by the
+    /// item with `k` encoded as bytes.
+    seen: HashMap<(VerificationKeyBytes, Signature, [u8; 32]), usize>,
+    /// Positions of items identical to an earlier item, paired with the
+    /// position of that earlier item. These are not verified again.
+    duplicates: Vec<(usize, usize)>,
+    /// The limits enforced by [`Verifier::try_queue`].
+    limits: Limits,
+    /// The total length of the messages queued with [`Verifier::try_queue`].
+    message_bytes: usize,
+}
+
+/// Limits on the work a [`Verifier`] will accept, enforced by
+/// [`Verifier::try_queue`].
+///
+/// Queuing an item hashes its message, and verifying a batch costs time
+/// proportional to the number of items, so a verifier fed by untrusted peers
+/// should bound both rather than discover a bad batch only after it has been
+/// filled. The default imposes no limits.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Limits {
+    /// The maximum number of queued items, or `None` for no limit.
+    pub max_items: Option<usize>,
+    /// The maximum total length in bytes of queued messages, or `None` for no
+    /// limit.
+    pub max_message_bytes: Option<usize>,
+    /// Whether to reject an item whose key or signature cannot be decoded
+    /// when it is queued, rather than failing the whole batch later.
+    pub reject_invalid_encodings: bool,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Verifier")]
+struct VerifierSerdeHelper {
+    policy: VerificationPolicy,
+    items: Vec<Item>,
+    #[serde(default)]
+    limits: Limits,
+    #[serde(default)]
+    message_bytes: usize,
+}
+
+#[cfg(feature = "serde")]
+impl From<VerifierSerdeHelper> for Verifier {
+    fn from(helper: VerifierSerdeHelper) -> Verifier {
+        let mut verifier = Verifier::with_policy(helper.policy);
+        for item in helper.items {
+            verifier.queue(item);
+        }
+        verifier.limits = helper.limits;
+        verifier.message_bytes = helper.message_bytes;
+        verifier
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Verifier> for VerifierSerdeHelper {
+    fn from(verifier: Verifier) -> VerifierSerdeHelper {
+        let mut items = vec![None; verifier.batch_size];
+        for (vk_bytes, entries) in verifier.signatures {
+            for (i, k, sig) in entries {
+                items[i] = Some(Item { vk_bytes, sig, k });
+            }
+        }
+        for (i, first) in verifier.duplicates {
+            items[i] = items[first].clone();
+        }
+        VerifierSerdeHelper {
+            policy: verifier.policy,
+            limits: verifier.limits,
+            message_bytes: verifier.message_bytes,
+            items: items
+                .into_iter()
+                .map(|item| item.expect("every queue position holds an item"))
+                .collect(),
+        }
+    }
 }
 
 impl Verifier {
@@ -123,16 +339,116 @@ impl Verifier {
         Verifier::default()
     }
 
+    /// Construct a batch verifier that holds at most `capacity` items,
+    /// drawing randomizers from `rng`.
+    ///
+    /// Queuing an item into a full batch first verifies and discards the
+    /// queued items, returning the result, so the memory and latency of each
+    /// verification are bounded by `capacity`. This is the same as
+    /// [`BoundedVerifier::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity<R: RngCore + CryptoRng>(capacity: usize, rng: R) -> BoundedVerifier<R> {
+        BoundedVerifier::new(capacity, rng)
+    }
+
+    /// Construct a new batch verifier with room for `capacity` items before
+    /// reallocating.
+    fn preallocated(capacity: usize) -> Verifier {
+        Verifier {
+            signatures: HashMap::with_capacity(capacity),
+            seen: HashMap::with_capacity(capacity),
+            ..Verifier::default()
+        }
+    }
+
+    /// Construct a new batch verifier that applies the acceptance criteria
+    /// selected by `policy`.
+    ///
+    /// The cofactorless equation used by the strict policies cannot be
+    /// checked soundly in a randomized batch, so with those policies each
+    /// queued item is verified individually when the batch is verified. This
+    /// keeps batch and individual verification in agreement under every
+    /// policy, but only [`VerificationPolicy::Zip215`] gets the speedup of
+    /// batching.
+    pub fn with_policy(policy: VerificationPolicy) -> Verifier {
+        Verifier {
+            policy,
+            ..Verifier::default()
+        }
+    }
+
+    /// Set the limits enforced by [`Verifier::try_queue`].
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Queue a (key, signature, message) tuple for verification, subject to
+    /// the configured [`Limits`].
+    ///
+    /// Returns [`Error::BatchLimitExceeded`] if queuing the item would exceed
+    /// the item or message length limits, and, if
+    /// [`Limits::reject_invalid_encodings`] is set, the same decoding errors
+    /// as [`VerificationKey::verify`] for an undecodable key or signature.
+    /// Nothing is queued when an error is returned.
+    ///
+    /// Items queued with [`Verifier::queue`] count towards the item limit but
+    /// are not otherwise checked.
+    pub fn try_queue<M: AsRef<[u8]> + ?Sized>(
+        &mut self,
+        vk_bytes: VerificationKeyBytes,
+        sig: Signature,
+        msg: &M,
+    ) -> Result<(), Error> {
+        let msg = msg.as_ref();
+        let message_bytes = self.message_bytes.saturating_add(msg.len());
+        if matches!(self.limits.max_items, Some(max) if self.batch_size >= max)
+            || matches!(self.limits.max_message_bytes, Some(max) if message_bytes > max)
+        {
+            return Err(Error::BatchLimitExceeded);
+        }
+        if self.limits.reject_invalid_encodings {
+            CompressedEdwardsY(vk_bytes.0)
+                .decompress()
+                .ok_or(Error::MalformedPublicKey)?;
+            CompressedEdwardsY(*sig.R_bytes())
+                .decompress()
+                .ok_or(Error::MalformedSignatureR)?;
+            Scalar::from_canonical_bytes(*sig.s_bytes()).ok_or(Error::SignatureScalarOutOfRange)?;
+        }
+        self.message_bytes = message_bytes;
+        self.queue((vk_bytes, sig, msg));
+        Ok(())
+    }
+
     /// Queue a (key, signature, message) tuple for verification.
+    ///
+    /// A tuple identical to one already queued is recorded but does not add
+    /// terms to the batch equation, so queuing the same signature many times
+    /// (as when it is relayed by several peers) costs no extra verification
+    /// work.
     pub fn queue<I: Into<Item>>(&mut self, item: I) {
         let Item { vk_bytes, sig, k } = item.into();
 
+        match self.seen.entry((vk_bytes, sig, k.to_bytes())) {
+            Entry::Occupied(first) => {
+                self.duplicates.push((self.batch_size, *first.get()));
+                self.batch_size += 1;
+                return;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(self.batch_size);
+            }
+        }
+
         self.signatures
             .entry(vk_bytes)
             // The common case is 1 signature per public key.
             // We could also consider using a smallvec here.
             .or_insert_with(|| Vec::with_capacity(1))
-            .push((k, sig));
+            .push((self.batch_size, k, sig));
         self.batch_size += 1;
     }
 
@@ -145,7 +461,6 @@ impl Verifier {
     /// verifications. This function does not have the same verification criteria
     /// as individual verification, which may reject some signatures this method
     /// accepts.
-    #[allow(non_snake_case)]
     pub fn verify<R: RngCore + CryptoRng>(self, mut rng: R) -> Result<(), Error> {
         // The batch verification equation is
         //
@@ -171,27 +486,204 @@ impl Verifier {
         // However, when m = 1 and all signatures are from a single verification
         // key, this is nearly twice as fast.
 
+        let zs = self.randomizers(&mut rng);
+        self.verify_with_randomizers(&zs)
+    }
+
+    /// Perform batch verification with randomizers derived from the queued
+    /// items, returning `Ok(())` if all signatures were valid and `Err`
+    /// otherwise.
+    ///
+    /// [`Verifier::verify`] draws the random coefficients of the batch
+    /// equation from an RNG, so two nodes checking the same invalid batch
+    /// reject it only with overwhelming probability, not with certainty. Here
+    /// the coefficients are instead derived by hashing a transcript of every
+    /// queued key, signature, and challenge in queue order, so the decision
+    /// is a deterministic function of the batch. Because the coefficients
+    /// depend on all of the batch contents, an adversary cannot choose
+    /// signatures that cancel out without breaking SHA-512.
+    ///
+    /// Passing a seeded RNG to [`Verifier::verify`] is also deterministic, as
+    /// randomizers are assigned in queue order.
+    pub fn verify_deterministic(self) -> Result<(), Error> {
+        let zs = self.transcript_randomizers();
+        self.verify_with_randomizers(&zs)
+    }
+
+    /// Perform batch verification in parallel, returning `Ok(())` if all
+    /// signatures were valid and `Err` otherwise.
+    ///
+    /// This splits the queued verification keys into one shard per thread in
+    /// the current [`rayon`] thread pool, decompressing points and evaluating a
+    /// partial multiscalar multiplication for each shard in parallel, then
+    /// sums the partial results. The randomizers are drawn from `rng` up front,
+    /// so the acceptance criteria are exactly those of [`Verifier::verify`].
+    ///
+    /// The challenge hashes `k` are computed when items are constructed, not
+    /// here; to parallelize hashing as well, build the [`Item`]s in parallel
+    /// (e.g. with `par_iter().map(Item::from)`) before queuing them.
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "rayon")))]
+    pub fn verify_parallel<R: RngCore + CryptoRng>(self, mut rng: R) -> Result<(), Error> {
+        use curve25519_dalek::traits::Identity;
+        use rayon::prelude::*;
+
+        let entries = self.signatures.iter().collect::<Vec<_>>();
+        let shard_size = entries.len().div_ceil(rayon::current_num_threads()).max(1);
+
+        let zs = self.randomizers(&mut rng);
+        let check = entries
+            .par_chunks(shard_size)
+            .map(|shard| {
+                let n = shard.iter().map(|(_, sigs)| sigs.len()).sum();
+                self.partial_check(shard.iter().copied(), &zs, shard.len(), n)
+            })
+            .try_reduce(EdwardsPoint::identity, |a, b| Ok(a + b))?;
+        self.finish(check)
+    }
+
+    /// Perform batch verification, and if it fails, identify the items that
+    /// caused the failure.
+    ///
+    /// Returns `Ok(())` if all signatures were valid. Otherwise, each queued
+    /// item is verified individually, and this returns the positions of the
+    /// invalid items in the order they were queued (starting from zero),
+    /// together with the error for each. This lets a caller penalize only the
+    /// source of a bad signature rather than discarding the whole batch.
+    ///
+    /// Individual verification uses the same [`VerificationPolicy`] as the
+    /// batch. Batch and individual verification agree under every policy, so
+    /// the list is non-empty on failure.
+    pub fn verify_identifying_failures<R: RngCore + CryptoRng>(
+        self,
+        mut rng: R,
+    ) -> Result<(), Vec<(usize, Error)>> {
+        let zs = self.randomizers(&mut rng);
+        if self.verify_with_randomizers(&zs).is_ok() {
+            return Ok(());
+        }
+
+        let mut failures = Vec::new();
+        for (vk_bytes, sigs) in self.signatures.iter() {
+            let vk = VerificationKey::try_from(*vk_bytes);
+            for (index, k, sig) in sigs.iter() {
+                let result = vk
+                    .and_then(|vk| vk.verify_with_k_and_policy(sig, *k, self.policy))
+                    .map_err(|e| (*index, e));
+                if let Err(failure) = result {
+                    failures.push(failure);
+                }
+            }
+        }
+        for (duplicate, first) in self.duplicates.iter() {
+            if let Some((_, e)) = failures.iter().find(|(index, _)| index == first) {
+                failures.push((*duplicate, *e));
+            }
+        }
+        failures.sort_by_key(|(index, _)| *index);
+        Err(failures)
+    }
+
+    /// Draw a random coefficient for each queued item, indexed by its position
+    /// in the queue.
+    fn randomizers<R: RngCore + CryptoRng>(&self, mut rng: R) -> Vec<Scalar> {
+        (0..self.batch_size)
+            .map(|_| Scalar::from(gen_u128(&mut rng)))
+            .collect()
+    }
+
+    /// Derive a coefficient for each queued item, indexed by its position in
+    /// the queue, from a hash of all queued items.
+    fn transcript_randomizers(&self) -> Vec<Scalar> {
+        let mut items = self
+            .signatures
+            .iter()
+            .flat_map(|(vk_bytes, sigs)| {
+                sigs.iter()
+                    .map(move |(index, k, sig)| (*index, vk_bytes, k, sig))
+            })
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(index, ..)| *index);
+
+        let mut transcript = Sha512::default().chain(b"ed25519-zebra batch randomizers");
+        for (_, vk_bytes, k, sig) in items {
+            transcript.update(&vk_bytes.0[..]);
+            transcript.update(&sig.as_bytes()[..]);
+            transcript.update(k.as_bytes());
+        }
+        let seed = transcript.finalize();
+
+        (0..self.batch_size as u64)
+            .map(|i| {
+                let h = Sha512::default()
+                    .chain(&seed[..])
+                    .chain(i.to_le_bytes())
+                    .finalize();
+                let mut bytes = [0u8; 16];
+                bytes.copy_from_slice(&h[0..16]);
+                Scalar::from(u128::from_le_bytes(bytes))
+            })
+            .collect()
+    }
+
+    /// Perform batch verification using the coefficients `zs`, indexed by
+    /// queue position.
+    fn verify_with_randomizers(&self, zs: &[Scalar]) -> Result<(), Error> {
         let m = self.signatures.keys().count();
+        let check = self.partial_check(self.signatures.iter(), zs, m, self.batch_size)?;
+        self.finish(check)
+    }
 
+    /// Evaluate the batch verification equation over the signatures from
+    /// `entries`, using the coefficients `zs`, indexed by queue position. `m`
+    /// and `n` are the expected numbers of keys and signatures, used for
+    /// preallocation.
+    #[allow(non_snake_case)]
+    fn partial_check<'a>(
+        &self,
+        entries: impl Iterator<
+            Item = (
+                &'a VerificationKeyBytes,
+                &'a Vec<(usize, Scalar, Signature)>,
+            ),
+        >,
+        zs: &[Scalar],
+        m: usize,
+        n: usize,
+    ) -> Result<EdwardsPoint, Error> {
         let mut A_coeffs = Vec::with_capacity(m);
         let mut As = Vec::with_capacity(m);
-        let mut R_coeffs = Vec::with_capacity(self.batch_size);
-        let mut Rs = Vec::with_capacity(self.batch_size);
+        let mut R_coeffs = Vec::with_capacity(n);
+        let mut Rs = Vec::with_capacity(n);
         let mut B_coeff = Scalar::zero();
 
-        for (vk_bytes, sigs) in self.signatures.iter() {
+        for (vk_bytes, sigs) in entries {
+            if self.policy != VerificationPolicy::Zip215 {
+                // The strict policies use the cofactorless equation, where a
+                // torsion component of some R_i cancels out whenever z_i is
+                // a multiple of its order, so a randomized batch would accept
+                // signatures that individual verification rejects. Check
+                // these items one at a time instead.
+                let vk = VerificationKey::try_from(*vk_bytes)?;
+                for (_, k, sig) in sigs.iter() {
+                    vk.verify_with_k_and_policy(sig, *k, self.policy)?;
+                }
+                continue;
+            }
+
             let A = CompressedEdwardsY(vk_bytes.0)
                 .decompress()
-                .ok_or(Error::InvalidSignature)?;
+                .ok_or(Error::MalformedPublicKey)?;
 
             let mut A_coeff = Scalar::zero();
 
-            for (k, sig) in sigs.iter() {
-                let R = CompressedEdwardsY(sig.R_bytes)
+            for (index, k, sig) in sigs.iter() {
+                let R = CompressedEdwardsY(*sig.R_bytes())
                     .decompress()
-                    .ok_or(Error::InvalidSignature)?;
-                let s = Scalar::from_canonical_bytes(sig.s_bytes).ok_or(Error::InvalidSignature)?;
-                let z = Scalar::from(gen_u128(&mut rng));
+                    .ok_or(Error::MalformedSignatureR)?;
+                let s = Scalar::from_canonical_bytes(*sig.s_bytes())
+                    .ok_or(Error::SignatureScalarOutOfRange)?;
+                let z = zs[*index];
                 B_coeff -= z * s;
                 Rs.push(R);
                 R_coeffs.push(z);
@@ -204,11 +696,18 @@ impl Verifier {
 
         use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as B;
         use std::iter::once;
-        let check = EdwardsPoint::vartime_multiscalar_mul(
+        // `vartime_multiscalar_mul` uses Straus' method for small inputs and
+        // switches to Pippenger's bucket method for large ones (at 190 points
+        // in curve25519-dalek 3), so large batches get the asymptotically
+        // faster algorithm without a separate code path here.
+        Ok(EdwardsPoint::vartime_multiscalar_mul(
             once(&B_coeff).chain(A_coeffs.iter()).chain(R_coeffs.iter()),
             once(&B).chain(As.iter()).chain(Rs.iter()),
-        );
+        ))
+    }
 
+    /// Check that the evaluated batch verification equation holds.
+    fn finish(&self, check: EdwardsPoint) -> Result<(), Error> {
         if check.mul_by_cofactor().is_identity() {
             Ok(())
         } else {
@@ -216,3 +715,520 @@ impl Verifier {
         }
     }
 }
+
+/// A batch verification context with bounded memory.
+///
+/// This wraps a [`Verifier`] that holds at most `capacity` items: when an item
+/// is queued into a full batch, the queued items are verified and discarded
+/// first. This suits long-running services that ingest an unbounded stream
+/// of signatures and should not queue them without limit.
+///
+/// # Example
+/// ```
+/// # use ed25519_zebra::*;
+/// let mut batch = batch::BoundedVerifier::new(16, rand::thread_rng());
+/// let sk = SigningKey::new(rand::thread_rng());
+/// let vk_bytes = VerificationKeyBytes::from(&sk);
+/// for _ in 0..100 {
+///     let msg = b"BatchVerifyTest";
+///     batch.queue((vk_bytes, sk.sign(&msg[..]), &msg[..])).unwrap();
+/// }
+/// assert!(batch.finalize().is_ok());
+/// ```
+pub struct BoundedVerifier<R: RngCore + CryptoRng> {
+    verifier: Verifier,
+    capacity: usize,
+    rng: R,
+}
+
+impl<R: RngCore + CryptoRng> BoundedVerifier<R> {
+    /// Construct a new bounded batch verifier that holds at most `capacity`
+    /// items, drawing randomizers from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize, rng: R) -> BoundedVerifier<R> {
+        BoundedVerifier::with_policy(capacity, VerificationPolicy::default(), rng)
+    }
+
+    /// Construct a new bounded batch verifier that applies the acceptance
+    /// criteria selected by `policy`, as in [`Verifier::with_policy`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_policy(capacity: usize, policy: VerificationPolicy, rng: R) -> BoundedVerifier<R> {
+        assert!(capacity > 0, "batch capacity must be nonzero");
+        BoundedVerifier {
+            verifier: Verifier {
+                policy,
+                ..Verifier::preallocated(capacity)
+            },
+            capacity,
+            rng,
+        }
+    }
+
+    /// Queue a (key, signature, message) tuple for verification, first
+    /// verifying the queued items if the batch is full.
+    ///
+    /// Returns the result of that verification if one was performed. The
+    /// queued items are discarded either way, so an `Err` means that some
+    /// item queued since the previous flush was invalid; `item` itself is
+    /// always queued into the new batch.
+    pub fn queue<I: Into<Item>>(&mut self, item: I) -> Result<(), Error> {
+        let result = if self.verifier.batch_size >= self.capacity {
+            self.flush()
+        } else {
+            Ok(())
+        };
+        self.verifier.queue(item);
+        result
+    }
+
+    /// Verify and discard the currently queued items.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        let policy = self.verifier.policy;
+        let empty = Verifier {
+            policy,
+            ..Verifier::preallocated(self.capacity)
+        };
+        let verifier = std::mem::replace(&mut self.verifier, empty);
+        verifier.verify(&mut self.rng)
+    }
+
+    /// Verify the remaining queued items, consuming the verifier.
+    pub fn finalize(mut self) -> Result<(), Error> {
+        self.flush()
+    }
+
+    /// The maximum number of items queued at once.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of items currently queued.
+    pub fn len(&self) -> usize {
+        self.verifier.batch_size
+    }
+
+    /// Whether no items are currently queued.
+    pub fn is_empty(&self) -> bool {
+        self.verifier.batch_size == 0
+    }
+}
+
+/// A fixed-capacity batch verification context for up to `N` items.
+///
+/// Unlike a [`Verifier`], an `ArrayVerifier` does not allocate on the heap:
+/// queued items are stored inline, and the batch equation is evaluated with
+/// lookup tables on the stack. This is for `std` callers that must avoid heap
+/// allocation while verifying; like the rest of the crate, it requires `std`,
+/// and it is not usable in `no_std` environments.
+///
+/// Stack use grows linearly with `N`, at about 3 KiB per item for the items
+/// and their lookup tables, so large capacities need a thread with a
+/// correspondingly large stack. Identical items are not coalesced, and the
+/// [ZIP215] rules are always used.
+///
+/// # Example
+/// ```
+/// # use ed25519_zebra::*;
+/// let mut batch = batch::ArrayVerifier::<16>::new();
+/// for _ in 0..16 {
+///     let sk = SigningKey::new(rand::thread_rng());
+///     let msg = b"BatchVerifyTest";
+///     assert!(batch
+///         .queue((VerificationKeyBytes::from(&sk), sk.sign(msg), msg))
+///         .is_ok());
+/// }
+/// assert!(batch.verify(rand::thread_rng()).is_ok());
+/// ```
+///
+/// [ZIP215]: https://github.com/zcash/zips/blob/master/zip-0215.rst
+#[derive(Clone, Debug)]
+pub struct ArrayVerifier<const N: usize> {
+    items: [Option<Item>; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for ArrayVerifier<N> {
+    fn default() -> ArrayVerifier<N> {
+        ArrayVerifier {
+            items: [(); N].map(|_| None),
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> ArrayVerifier<N> {
+    /// Construct a new, empty batch verifier.
+    pub fn new() -> ArrayVerifier<N> {
+        ArrayVerifier::default()
+    }
+
+    /// Queue a (key, signature, message) tuple for verification.
+    ///
+    /// If the verifier already holds `N` items, the item is returned in the
+    /// `Err` variant instead.
+    #[allow(clippy::result_large_err)]
+    pub fn queue<I: Into<Item>>(&mut self, item: I) -> Result<(), Item> {
+        let item = item.into();
+        if self.len == N {
+            return Err(item);
+        }
+        self.items[self.len] = Some(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// The maximum number of items that can be queued.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of items currently queued.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no items are currently queued.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Perform batch verification, returning `Ok(())` if all signatures were
+    /// valid and `Err` otherwise.
+    #[allow(non_snake_case)]
+    pub fn verify<R: RngCore + CryptoRng>(self, mut rng: R) -> Result<(), Error> {
+        use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, traits::Identity};
+
+        let identity = EdwardsPoint::identity();
+        let mut A_tables = [[identity; 8]; N];
+        let mut R_tables = [[identity; 8]; N];
+        let mut A_digits = [[0i8; 64]; N];
+        let mut R_digits = [[0i8; 64]; N];
+        let mut B_coeff = Scalar::zero();
+
+        for (i, item) in self.items.iter().flatten().enumerate() {
+            let A = CompressedEdwardsY(item.vk_bytes.0)
+                .decompress()
+                .ok_or(Error::MalformedPublicKey)?;
+            let R = CompressedEdwardsY(*item.sig.R_bytes())
+                .decompress()
+                .ok_or(Error::MalformedSignatureR)?;
+            let s = Scalar::from_canonical_bytes(*item.sig.s_bytes())
+                .ok_or(Error::SignatureScalarOutOfRange)?;
+            let z = Scalar::from(gen_u128(&mut rng));
+
+            B_coeff -= z * s;
+            A_tables[i] = multiples(A);
+            R_tables[i] = multiples(R);
+            A_digits[i] = signed_radix_16(&(z * item.k));
+            R_digits[i] = signed_radix_16(&z);
+        }
+
+        // Straus' method with signed 4-bit windows, sharing the doublings
+        // across every term of the batch equation.
+        let mut check = identity;
+        for j in (0..64).rev() {
+            for _ in 0..4 {
+                check = check + check;
+            }
+            for i in 0..self.len {
+                check = add_multiple(check, &A_tables[i], A_digits[i][j]);
+                check = add_multiple(check, &R_tables[i], R_digits[i][j]);
+            }
+        }
+        check += &B_coeff * &ED25519_BASEPOINT_TABLE;
+
+        if check.mul_by_cofactor().is_identity() {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
+
+/// Compute `[P, 2P, ..., 8P]`.
+#[allow(non_snake_case)]
+fn multiples(P: EdwardsPoint) -> [EdwardsPoint; 8] {
+    let mut table = [P; 8];
+    for i in 1..8 {
+        table[i] = table[i - 1] + P;
+    }
+    table
+}
+
+/// Add `[digit]P` to `acc`, where `table` holds the multiples of `P` and
+/// `-8 <= digit <= 8`.
+fn add_multiple(acc: EdwardsPoint, table: &[EdwardsPoint; 8], digit: i8) -> EdwardsPoint {
+    match digit {
+        0 => acc,
+        d if d > 0 => acc + table[d as usize - 1],
+        d => acc - table[(-d) as usize - 1],
+    }
+}
+
+/// Write a reduced scalar as `sum(d_i * 16^i)` with `-8 <= d_i < 8`.
+fn signed_radix_16(s: &Scalar) -> [i8; 64] {
+    let bytes = s.as_bytes();
+    let mut digits = [0i8; 64];
+    for (i, byte) in bytes.iter().enumerate() {
+        digits[2 * i] = (byte & 15) as i8;
+        digits[2 * i + 1] = (byte >> 4) as i8;
+    }
+    for i in 0..63 {
+        let carry = (digits[i] + 8) >> 4;
+        digits[i] -= carry << 4;
+        digits[i + 1] += carry;
+    }
+    digits
+}
+
+/// Verify a stream of (key, signature, message) tuples in batches, yielding
+/// the result for each item in order.
+///
+/// Items that are immediately available are gathered into a batch of at most
+/// `max_batch_size` items, which is verified as soon as it is full or the
+/// input stream has no further item ready. Batches therefore grow with load
+/// without delaying items when the input is idle, and no timer is needed.
+/// A failed batch is checked item by item, as in
+/// [`Verifier::verify_identifying_failures`], so only the invalid items
+/// yield an `Err`. To wait a bounded time for a batch to fill instead, use
+/// [`verify_stream_windowed`].
+///
+/// # Panics
+///
+/// Panics if `max_batch_size` is zero.
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "futures")))]
+pub fn verify_stream<S, I, R>(
+    stream: S,
+    max_batch_size: usize,
+    mut rng: R,
+) -> impl futures_util::stream::Stream<Item = Result<(), Error>>
+where
+    S: futures_util::stream::Stream<Item = I>,
+    I: Into<Item>,
+    R: RngCore + CryptoRng,
+{
+    use futures_util::stream::{self, StreamExt};
+
+    stream.ready_chunks(max_batch_size).flat_map(move |chunk| {
+        let mut batch = Verifier::new();
+        for item in chunk {
+            batch.queue(item);
+        }
+        stream::iter(verify_each(batch, &mut rng))
+    })
+}
+
+/// Verify a stream of (key, signature, message) tuples in batches collected
+/// over a time window, yielding the result for each item in order.
+///
+/// A batch is started by the first item to arrive and verified once it holds
+/// `max_batch_size` items, once `max_latency` has passed since it was
+/// started, or when the input stream ends, whichever comes first. No item
+/// therefore waits much longer than `max_latency` for its result.
+///
+/// This crate does not depend on an async runtime, so the window is timed by
+/// `sleep`, which is called with `max_latency` at the start of each batch and
+/// must return a future that completes after that duration, such as
+/// `tokio::time::sleep`. Failed batches are handled as in [`verify_stream`].
+///
+/// # Panics
+///
+/// Panics if `max_batch_size` is zero.
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "futures")))]
+pub fn verify_stream_windowed<S, I, R, F, T>(
+    stream: S,
+    max_batch_size: usize,
+    max_latency: std::time::Duration,
+    sleep: F,
+    rng: R,
+) -> impl futures_util::stream::Stream<Item = Result<(), Error>>
+where
+    S: futures_util::stream::Stream<Item = I>,
+    I: Into<Item>,
+    R: RngCore + CryptoRng,
+    F: FnMut(std::time::Duration) -> T,
+    T: std::future::Future<Output = ()>,
+{
+    use futures_util::{
+        future::{self, Either},
+        stream::{self, StreamExt},
+    };
+
+    assert!(max_batch_size > 0, "batch size must be nonzero");
+    let state = (Box::pin(stream.fuse()), sleep, rng);
+    stream::unfold(state, move |(mut items, mut sleep, mut rng)| async move {
+        let mut batch = Verifier::new();
+        batch.queue(items.next().await?);
+        let mut window = Box::pin(sleep(max_latency));
+        while batch.batch_size < max_batch_size {
+            match future::select(items.next(), window.as_mut()).await {
+                Either::Left((Some(item), _)) => batch.queue(item),
+                _ => break,
+            }
+        }
+        let results = verify_each(batch, &mut rng);
+        Some((stream::iter(results), (items, sleep, rng)))
+    })
+    .flatten()
+}
+
+/// A [`Sink`](futures_util::sink::Sink) that verifies the [`Item`]s sent to
+/// it in batches and sends the result for each item, in order, to another
+/// sink.
+///
+/// Items are queued until `max_batch_size` are waiting, and are then verified
+/// before the next item is accepted. Flushing or closing the sink verifies
+/// any queued items, so results are never held back indefinitely. Failed
+/// batches are handled as in [`verify_stream`].
+///
+/// Both the results sink and the RNG must be [`Unpin`]; a sink that is not
+/// can be wrapped with [`Box::pin`].
+///
+/// # Example
+/// ```
+/// # use ed25519_zebra::*;
+/// use futures::{channel::mpsc, executor::block_on, stream, SinkExt, StreamExt};
+///
+/// let (results, mut received) = mpsc::unbounded();
+/// let mut sink = batch::VerifySink::new(results, 8, rand::thread_rng());
+/// let sk = SigningKey::new(rand::thread_rng());
+/// let vk_bytes = VerificationKeyBytes::from(&sk);
+/// let msg = b"BatchVerifyTest";
+/// let items = (0..20).map(|_| Ok(batch::Item::from((vk_bytes, sk.sign(msg), msg))));
+/// block_on(sink.send_all(&mut stream::iter(items))).unwrap();
+/// block_on(sink.close()).unwrap();
+/// let results = block_on(received.collect::<Vec<_>>());
+/// assert_eq!(results, vec![Ok(()); 20]);
+/// ```
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "futures")))]
+pub struct VerifySink<Si, R> {
+    results: Si,
+    batch: Verifier,
+    max_batch_size: usize,
+    pending: std::collections::VecDeque<Result<(), Error>>,
+    rng: R,
+}
+
+#[cfg(feature = "futures")]
+impl<Si, R> VerifySink<Si, R>
+where
+    Si: futures_util::sink::Sink<Result<(), Error>> + Unpin,
+    R: RngCore + CryptoRng + Unpin,
+{
+    /// Construct a sink that verifies batches of at most `max_batch_size`
+    /// items, drawing randomizers from `rng`, and sends the results to
+    /// `results`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_batch_size` is zero.
+    pub fn new(results: Si, max_batch_size: usize, rng: R) -> VerifySink<Si, R> {
+        assert!(max_batch_size > 0, "batch size must be nonzero");
+        VerifySink {
+            results,
+            batch: Verifier::new(),
+            max_batch_size,
+            pending: Default::default(),
+            rng,
+        }
+    }
+
+    /// Verify the queued items, adding their results to those waiting to be
+    /// sent.
+    fn verify_queued(&mut self) {
+        let batch = std::mem::take(&mut self.batch);
+        self.pending.extend(verify_each(batch, &mut self.rng));
+    }
+
+    /// Send the waiting results to the results sink.
+    fn poll_send_pending(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Si::Error>> {
+        use futures_util::ready;
+        use std::pin::Pin;
+
+        while let Some(result) = self.pending.front() {
+            ready!(Pin::new(&mut self.results).poll_ready(cx))?;
+            Pin::new(&mut self.results).start_send(*result)?;
+            self.pending.pop_front();
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<Si, R> futures_util::sink::Sink<Item> for VerifySink<Si, R>
+where
+    Si: futures_util::sink::Sink<Result<(), Error>> + Unpin,
+    R: RngCore + CryptoRng + Unpin,
+{
+    type Error = Si::Error;
+
+    fn poll_ready(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Si::Error>> {
+        let this = self.get_mut();
+        if this.batch.batch_size >= this.max_batch_size {
+            this.verify_queued();
+        }
+        this.poll_send_pending(cx)
+    }
+
+    fn start_send(self: std::pin::Pin<&mut Self>, item: Item) -> Result<(), Si::Error> {
+        self.get_mut().batch.queue(item);
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Si::Error>> {
+        use futures_util::ready;
+        use std::pin::Pin;
+
+        let this = self.get_mut();
+        if this.batch.batch_size > 0 {
+            this.verify_queued();
+        }
+        ready!(this.poll_send_pending(cx))?;
+        Pin::new(&mut this.results).poll_flush(cx)
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Si::Error>> {
+        use futures_util::ready;
+        use std::pin::Pin;
+
+        let this = self.get_mut();
+        if this.batch.batch_size > 0 {
+            this.verify_queued();
+        }
+        ready!(this.poll_send_pending(cx))?;
+        Pin::new(&mut this.results).poll_close(cx)
+    }
+}
+
+/// Verify `batch`, returning the result for each item in queue order.
+#[cfg(feature = "futures")]
+fn verify_each<R: RngCore + CryptoRng>(batch: Verifier, rng: R) -> Vec<Result<(), Error>> {
+    let mut results = vec![Ok(()); batch.batch_size];
+    if let Err(failures) = batch.verify_identifying_failures(rng) {
+        for (index, e) in failures {
+            results[index] = Err(e);
+        }
+    }
+    results
+}
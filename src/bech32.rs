@@ -0,0 +1,194 @@
+//! Bech32 and bech32m encoding of keys and signatures.
+//!
+//! [Bech32] strings consist of a human-readable part (HRP) chosen by the
+//! protocol, such as `cosmosvalconspub` or `age`, the separator `1`, and the
+//! data in a 32-character alphabet followed by a 6-character checksum.
+//! [Bech32m] differs only in the checksum constant, which fixes a weakness
+//! of bech32 to inserted or deleted `q`s before a final `p`. Protocols pick
+//! one variant, so decoding checks that the string uses the expected one.
+//!
+//! Encoded strings are lowercase; uppercase strings are decoded, but strings
+//! of mixed case are rejected. The 90-character limit of BIP 173 is not
+//! enforced, as encoded signatures are longer.
+//!
+//! [Bech32]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki
+//! [Bech32m]: https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
+
+use std::convert::TryFrom;
+
+use thiserror::Error;
+
+use crate::{base32, Signature, VerificationKeyBytes};
+
+/// The bech32 data alphabet.
+const ALPHABET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+/// The maximum length of an HRP.
+const MAX_HRP_LEN: usize = 83;
+/// The length of the checksum.
+const CHECKSUM_LEN: usize = 6;
+
+/// The checksum variant of a bech32 string.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Variant {
+    /// The original bech32 checksum of BIP 173.
+    Bech32,
+    /// The bech32m checksum of BIP 350.
+    Bech32m,
+}
+
+impl Variant {
+    /// The value the checksum of a string of this variant leaves.
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc8_30a3,
+        }
+    }
+}
+
+/// An error encoding or decoding a bech32 string.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The HRP is empty, longer than 83 characters, or has characters
+    /// outside the printable ASCII range.
+    #[error("Invalid bech32 human-readable part.")]
+    InvalidHrp,
+    /// The string has no separator, mixed case, characters outside the
+    /// alphabet, or padding that is not zero.
+    #[error("Malformed bech32 string.")]
+    MalformedString,
+    /// The checksum does not match the string in either variant.
+    #[error("Bech32 checksum mismatch.")]
+    InvalidChecksum,
+    /// The string has a valid checksum of the other variant.
+    #[error("Unexpected bech32 variant {0:?}.")]
+    UnexpectedVariant(Variant),
+    /// The string has a different HRP than expected.
+    #[error("Unexpected bech32 human-readable part {0:?}.")]
+    UnexpectedHrp(String),
+    /// The data is not a key or signature.
+    #[error("Invalid bech32 data length {0}.")]
+    InvalidLength(usize),
+}
+
+impl VerificationKeyBytes {
+    /// Encode this key as a bech32 string with human-readable part `hrp`.
+    pub fn to_bech32(&self, hrp: &str, variant: Variant) -> Result<String, Error> {
+        encode(hrp, self.as_ref(), variant)
+    }
+
+    /// Decode a bech32 string of a key, which must have the human-readable
+    /// part `hrp` and checksum `variant`.
+    pub fn from_bech32(
+        encoded: &str,
+        hrp: &str,
+        variant: Variant,
+    ) -> Result<VerificationKeyBytes, Error> {
+        let data = decode(encoded, hrp, variant)?;
+        VerificationKeyBytes::try_from(&data[..]).map_err(|_| Error::InvalidLength(data.len()))
+    }
+}
+
+impl Signature {
+    /// Encode this signature as a bech32 string with human-readable part
+    /// `hrp`.
+    pub fn to_bech32(&self, hrp: &str, variant: Variant) -> Result<String, Error> {
+        encode(hrp, &<[u8; 64]>::from(*self), variant)
+    }
+
+    /// Decode a bech32 string of a signature, which must have the
+    /// human-readable part `hrp` and checksum `variant`.
+    pub fn from_bech32(encoded: &str, hrp: &str, variant: Variant) -> Result<Signature, Error> {
+        let data = decode(encoded, hrp, variant)?;
+        Signature::try_from(&data[..]).map_err(|_| Error::InvalidLength(data.len()))
+    }
+}
+
+/// The bech32 string of `data` with human-readable part `hrp`, in lowercase.
+fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Error> {
+    check_hrp(hrp)?;
+    let hrp = hrp.to_ascii_lowercase();
+
+    let mut values = base32::regroup(data, 8, 5, true).expect("padded regrouping succeeds");
+
+    let mut checked = expand_hrp(&hrp);
+    checked.extend_from_slice(&values);
+    checked.extend_from_slice(&[0; CHECKSUM_LEN]);
+    let checksum = polymod(&checked) ^ variant.constant();
+    values.extend((0..CHECKSUM_LEN).map(|i| (checksum >> (5 * (5 - i))) as u8 & 31));
+
+    let mut encoded = hrp;
+    encoded.push('1');
+    encoded.push_str(&base32::encode_values(&values, ALPHABET));
+    Ok(encoded)
+}
+
+/// The data in the bech32 string `encoded`, which must have the
+/// human-readable part `hrp` and checksum `variant`.
+fn decode(encoded: &str, hrp: &str, variant: Variant) -> Result<Vec<u8>, Error> {
+    check_hrp(hrp)?;
+    if encoded.bytes().any(|c| c.is_ascii_lowercase())
+        && encoded.bytes().any(|c| c.is_ascii_uppercase())
+    {
+        return Err(Error::MalformedString);
+    }
+    let encoded = encoded.to_ascii_lowercase();
+    let (encoded_hrp, data) = encoded.rsplit_once('1').ok_or(Error::MalformedString)?;
+    if check_hrp(encoded_hrp).is_err() || data.len() < CHECKSUM_LEN {
+        return Err(Error::MalformedString);
+    }
+    let values = base32::decode_values(data, ALPHABET).ok_or(Error::MalformedString)?;
+
+    let mut checked = expand_hrp(encoded_hrp);
+    checked.extend_from_slice(&values);
+    match polymod(&checked) {
+        c if c == variant.constant() => {}
+        c if c == Variant::Bech32.constant() => {
+            return Err(Error::UnexpectedVariant(Variant::Bech32))
+        }
+        c if c == Variant::Bech32m.constant() => {
+            return Err(Error::UnexpectedVariant(Variant::Bech32m))
+        }
+        _ => return Err(Error::InvalidChecksum),
+    }
+    if encoded_hrp != hrp.to_ascii_lowercase() {
+        return Err(Error::UnexpectedHrp(encoded_hrp.to_string()));
+    }
+
+    // The padding must be fewer than 5 zero bits.
+    base32::regroup(&values[..values.len() - CHECKSUM_LEN], 5, 8, false)
+        .ok_or(Error::MalformedString)
+}
+
+/// Check that `hrp` is a valid human-readable part.
+fn check_hrp(hrp: &str) -> Result<(), Error> {
+    if hrp.is_empty() || hrp.len() > MAX_HRP_LEN || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Error::InvalidHrp);
+    }
+    Ok(())
+}
+
+/// The high bits of each character of `hrp`, a zero, then the low bits of
+/// each, which the checksum covers.
+fn expand_hrp(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|c| c & 31));
+    expanded
+}
+
+/// The BCH checksum of `values`.
+fn polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut checksum = 1u32;
+    for &value in values {
+        let top = checksum >> 25;
+        checksum = (checksum & 0x1ff_ffff) << 5 ^ u32::from(value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if top >> i & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
@@ -0,0 +1,105 @@
+use std::collections::{BTreeMap, HashMap};
+
+use sha2::{Digest, Sha512};
+
+use crate::{Error, Signature, VerificationKeyBytes};
+
+/// A cache of successful signature verifications, keyed by
+/// `(vk_bytes, sig, SHA-512(msg))`.
+///
+/// Gossip networks often receive the same signed message from many peers.
+/// Verifying through a `VerificationCache` turns repeated verifications of
+/// the same signature into a hash of the message and a table lookup, instead
+/// of point decompression and scalar multiplication.
+///
+/// Only successful verifications are cached, so invalid signatures cannot
+/// evict valid entries, and at most `capacity` entries are kept, evicting the
+/// least recently used one when full. Verification follows the rules of
+/// [`VerificationKey::verify`](crate::VerificationKey::verify).
+///
+/// # Example
+/// ```
+/// # use ed25519_zebra::*;
+/// let mut cache = VerificationCache::new(1024);
+/// let sk = SigningKey::new(rand::thread_rng());
+/// let vk_bytes = VerificationKeyBytes::from(&sk);
+/// let sig = sk.sign(b"Zcash");
+/// assert!(cache.verify(vk_bytes, &sig, b"Zcash").is_ok());
+/// // The second verification is a cache hit.
+/// assert!(cache.verify(vk_bytes, &sig, b"Zcash").is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct VerificationCache {
+    capacity: usize,
+    /// Maps each cached entry to the tick at which it was last used.
+    entries: HashMap<CacheKey, u64>,
+    /// Maps each tick in `entries` back to its entry, in order of use.
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+}
+
+type CacheKey = (VerificationKeyBytes, Signature, [u8; 64]);
+
+impl VerificationCache {
+    /// Construct a new cache holding at most `capacity` verifications.
+    ///
+    /// Entries are allocated as they are added, not up front.
+    pub fn new(capacity: usize) -> VerificationCache {
+        VerificationCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Verify a purported `signature` on `msg` with the key `vk_bytes`,
+    /// consulting and updating the cache.
+    pub fn verify(
+        &mut self,
+        vk_bytes: VerificationKeyBytes,
+        signature: &Signature,
+        msg: &[u8],
+    ) -> Result<(), Error> {
+        let mut msg_hash = [0u8; 64];
+        msg_hash.copy_from_slice(&Sha512::digest(msg)[..]);
+        let key = (vk_bytes, *signature, msg_hash);
+
+        self.tick += 1;
+        if let Some(last_used) = self.entries.get_mut(&key) {
+            self.recency.remove(last_used);
+            *last_used = self.tick;
+            self.recency.insert(self.tick, key);
+            return Ok(());
+        }
+
+        vk_bytes.verify(signature, msg)?;
+
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                if let Some((_, oldest)) = self.recency.pop_first() {
+                    self.entries.remove(&oldest);
+                }
+            }
+            self.entries.insert(key, self.tick);
+            self.recency.insert(self.tick, key);
+        }
+        Ok(())
+    }
+
+    /// The number of cached verifications.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all cached verifications.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
@@ -0,0 +1,127 @@
+//! Signing of deterministically-encoded CBOR.
+//!
+//! A signature covers bytes, not values, so two implementations that sign the
+//! same logical message must agree on its encoding. [`SigningKey::sign_cbor`]
+//! encodes a serde value with the deterministic encoding of
+//! [RFC 8949 §4.2.1][det] (shortest-form arguments, definite lengths, and map
+//! keys sorted by their encoded bytes) before signing, and
+//! [`VerificationKey::verify_cbor`] verifies the signature, rejects encodings
+//! that are not deterministic, and decodes the value.
+//!
+//! [det]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+
+use ciborium::value::Value;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+use crate::{Signature, SigningKey, VerificationKey};
+
+/// An error encoding, verifying, or decoding signed CBOR.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The value could not be represented as CBOR.
+    #[error("CBOR encoding failed: {0}")]
+    Encode(String),
+    /// The bytes are not CBOR, or do not decode to the requested type.
+    #[error("CBOR decoding failed: {0}")]
+    Decode(String),
+    /// A map has two entries with the same key.
+    #[error("Duplicate CBOR map key.")]
+    DuplicateKey,
+    /// The bytes are valid CBOR, but not in the deterministic encoding.
+    #[error("CBOR is not deterministically encoded.")]
+    NonDeterministic,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// Encode `value` as deterministic CBOR.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let value = Value::serialized(value).map_err(|err| Error::Encode(err.to_string()))?;
+    Ok(encode(&canonicalize(value)?))
+}
+
+/// Decode deterministic CBOR, rejecting any other encoding of the value.
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    let mut reader = bytes;
+    let value: Value =
+        ciborium::de::from_reader(&mut reader).map_err(|err| Error::Decode(err.to_string()))?;
+    if !reader.is_empty() {
+        return Err(Error::Decode("trailing data".to_string()));
+    }
+    if encode(&canonicalize(value.clone())?) != bytes {
+        return Err(Error::NonDeterministic);
+    }
+    value
+        .deserialized()
+        .map_err(|err| Error::Decode(err.to_string()))
+}
+
+impl SigningKey {
+    /// Encode `value` as deterministic CBOR and sign the encoding, returning
+    /// both.
+    pub fn sign_cbor<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<(Vec<u8>, Signature), Error> {
+        let bytes = to_vec(value)?;
+        let signature = self.sign(&bytes);
+        Ok((bytes, signature))
+    }
+}
+
+impl VerificationKey {
+    /// Verify a `signature` on deterministic CBOR `bytes`, and decode them.
+    pub fn verify_cbor<T: DeserializeOwned>(
+        &self,
+        bytes: &[u8],
+        signature: &Signature,
+    ) -> Result<T, Error> {
+        self.verify(signature, bytes)
+            .map_err(Error::InvalidSignature)?;
+        from_slice(bytes)
+    }
+}
+
+/// Sort the entries of every map in `value` by their encoded keys.
+///
+/// The encoder already emits shortest-form integers, floats, and lengths, and
+/// definite lengths, so only map order needs fixing.
+fn canonicalize(value: Value) -> Result<Value, Error> {
+    Ok(match value {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(canonicalize)
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Map(entries) => {
+            let mut entries = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = canonicalize(key)?;
+                    Ok((encode(&key), key, canonicalize(value)?))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(Error::DuplicateKey);
+            }
+            Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(_, key, value)| (key, value))
+                    .collect(),
+            )
+        }
+        Value::Tag(tag, inner) => Value::Tag(tag, Box::new(canonicalize(*inner)?)),
+        other => other,
+    })
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(value, &mut bytes).expect("writing to a Vec cannot fail");
+    bytes
+}
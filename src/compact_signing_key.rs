@@ -0,0 +1,167 @@
+use std::convert::TryFrom;
+
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{Error, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+/// An Ed25519 signing key that stores only its 32-byte seed.
+///
+/// A [`SigningKey`] caches the expanded secret scalar, nonce prefix, and
+/// decoded verification key alongside the seed, which makes signing fast but
+/// takes several times as much memory. A `CompactSigningKey` re-derives them
+/// from the seed on every operation instead, trading a SHA-512 hash and a
+/// basepoint multiplication per call for a much smaller footprint. This suits
+/// processes holding many rarely-used keys.
+///
+/// The seed is zeroized when a `CompactSigningKey` is dropped.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SerdeHelper"))]
+#[cfg_attr(feature = "serde", serde(into = "SerdeHelper"))]
+pub struct CompactSigningKey {
+    seed: [u8; 32],
+}
+
+/// The seed is redacted unless the `debug-secrets` feature is enabled.
+impl core::fmt::Debug for CompactSigningKey {
+    #[cfg(not(feature = "debug-secrets"))]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_struct("CompactSigningKey")
+            .field("seed", &"<redacted>")
+            .finish()
+    }
+
+    #[cfg(feature = "debug-secrets")]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_struct("CompactSigningKey")
+            .field("seed", &hex::encode(self.seed))
+            .finish()
+    }
+}
+
+impl ConstantTimeEq for CompactSigningKey {
+    fn ct_eq(&self, other: &CompactSigningKey) -> Choice {
+        self.seed[..].ct_eq(&other.seed[..])
+    }
+}
+
+impl PartialEq for CompactSigningKey {
+    fn eq(&self, other: &CompactSigningKey) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for CompactSigningKey {}
+
+impl From<[u8; 32]> for CompactSigningKey {
+    fn from(seed: [u8; 32]) -> CompactSigningKey {
+        CompactSigningKey { seed }
+    }
+}
+
+impl From<CompactSigningKey> for [u8; 32] {
+    fn from(csk: CompactSigningKey) -> [u8; 32] {
+        csk.seed
+    }
+}
+
+impl TryFrom<&[u8]> for CompactSigningKey {
+    type Error = Error;
+    fn try_from(slice: &[u8]) -> Result<CompactSigningKey, Error> {
+        if slice.len() == 32 {
+            let mut seed = [0u8; 32];
+            seed[..].copy_from_slice(slice);
+            Ok(seed.into())
+        } else {
+            Err(Error::InvalidSliceLength)
+        }
+    }
+}
+
+impl AsRef<[u8]> for CompactSigningKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.seed[..]
+    }
+}
+
+impl<'a> From<&'a SigningKey> for CompactSigningKey {
+    fn from(sk: &'a SigningKey) -> CompactSigningKey {
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(sk.as_ref());
+        CompactSigningKey { seed }
+    }
+}
+
+impl<'a> From<&'a CompactSigningKey> for SigningKey {
+    fn from(csk: &'a CompactSigningKey) -> SigningKey {
+        SigningKey::from(csk.seed)
+    }
+}
+
+impl<'a> From<&'a CompactSigningKey> for VerificationKey {
+    fn from(csk: &'a CompactSigningKey) -> VerificationKey {
+        VerificationKey::from(&SigningKey::from(csk))
+    }
+}
+
+impl<'a> From<&'a CompactSigningKey> for VerificationKeyBytes {
+    fn from(csk: &'a CompactSigningKey) -> VerificationKeyBytes {
+        VerificationKeyBytes::from(&SigningKey::from(csk))
+    }
+}
+
+impl Zeroize for CompactSigningKey {
+    fn zeroize(&mut self) {
+        self.seed.zeroize()
+    }
+}
+
+impl Drop for CompactSigningKey {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for CompactSigningKey {}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct SerdeHelper([u8; 32]);
+
+impl Drop for SerdeHelper {
+    fn drop(&mut self) {
+        self.0.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SerdeHelper {}
+
+impl From<SerdeHelper> for CompactSigningKey {
+    fn from(helper: SerdeHelper) -> CompactSigningKey {
+        helper.0.into()
+    }
+}
+
+impl From<CompactSigningKey> for SerdeHelper {
+    fn from(csk: CompactSigningKey) -> Self {
+        Self(csk.into())
+    }
+}
+
+impl CompactSigningKey {
+    /// Generate a new signing key.
+    pub fn new<R: RngCore + CryptoRng>(mut rng: R) -> CompactSigningKey {
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed[..]);
+        seed.into()
+    }
+
+    /// Create a signature on `msg` using this key.
+    ///
+    /// The key is expanded for the duration of the call, and the expanded
+    /// secret is zeroized before returning.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        SigningKey::from(self).sign(msg)
+    }
+}
@@ -0,0 +1,359 @@
+//! CBOR Object Signing and Encryption (COSE) structures.
+//!
+//! [`VerificationKeyBytes::to_cose_key`] and [`SigningKey::to_cose_key`]
+//! encode keys as [RFC 9053] `COSE_Key` maps of key type `OKP`, curve
+//! `Ed25519`, and algorithm `EdDSA`, as used by CTAP2/FIDO authenticators and
+//! other CBOR-based protocols. Encoded keys use the deterministic encoding of
+//! RFC 8949 §4.2.1.
+//!
+//! [`SigningKey::sign_cose_sign1`] and [`CoseSign1`] create and verify
+//! [RFC 9052] `COSE_Sign1` messages, the single-signer signed messages used
+//! by CoAP, EDHOC, and other IoT protocols.
+//!
+//! [RFC 9052]: https://tools.ietf.org/html/rfc9052#section-4.2
+//! [RFC 9053]: https://tools.ietf.org/html/rfc9053#section-7.2
+
+use std::convert::TryFrom;
+
+use ciborium::value::{Integer, Value};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+/// The `alg` header parameter label.
+const HEADER_ALG: i64 = 1;
+/// The `crit` header parameter label.
+const HEADER_CRIT: i64 = 2;
+/// The `kid` header parameter label.
+const HEADER_KID: i64 = 4;
+/// The CBOR tag of a `COSE_Sign1` message.
+const TAG_SIGN1: u64 = 18;
+/// The serialized protected header of signed messages, `{1: -8}`.
+const PROTECTED_EDDSA: &[u8] = &[0xa1, 0x01, 0x27];
+
+/// The `kty` label.
+const KTY: i64 = 1;
+/// The `alg` label.
+const ALG: i64 = 3;
+/// The `crv` label of OKP keys.
+const CRV: i64 = -1;
+/// The `x` (public key) label of OKP keys.
+const X: i64 = -2;
+/// The `d` (private key) label of OKP keys.
+const D: i64 = -4;
+
+/// The `OKP` key type.
+const KTY_OKP: i64 = 1;
+/// The `Ed25519` curve.
+const CRV_ED25519: i64 = 6;
+/// The `EdDSA` algorithm.
+pub const ALG_EDDSA: i64 = -8;
+
+/// An error decoding a COSE structure.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The input is not well-formed CBOR.
+    #[error("Invalid CBOR.")]
+    InvalidCbor,
+    /// The CBOR does not have the structure of the expected COSE object.
+    #[error("Malformed COSE structure: {0}.")]
+    Malformed(&'static str),
+    /// The key is not an `OKP` key on the `Ed25519` curve.
+    #[error("Unsupported COSE key type or curve.")]
+    UnsupportedKeyType,
+    /// The `alg` parameter is not `EdDSA`.
+    #[error("Unsupported COSE algorithm {0}.")]
+    UnsupportedAlgorithm(i128),
+    /// The public key does not match the private key.
+    #[error("Public key does not match private key.")]
+    PublicKeyMismatch,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// A parsed `COSE_Sign1` message whose protected header specifies `EdDSA`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoseSign1 {
+    /// The serialized protected header, which is covered by the signature.
+    protected: Vec<u8>,
+    /// The `kid` from the unprotected header, if any.
+    pub kid: Option<Vec<u8>>,
+    /// The payload, or `None` if it is detached.
+    pub payload: Option<Vec<u8>>,
+    signature: Signature,
+}
+
+impl CoseSign1 {
+    /// Parse a `COSE_Sign1` message, tagged or untagged.
+    ///
+    /// The protected header must specify the `EdDSA` algorithm and must not
+    /// contain critical parameters.
+    pub fn from_slice(bytes: &[u8]) -> Result<CoseSign1, Error> {
+        let message = match decode(bytes)? {
+            Value::Tag(TAG_SIGN1, message) => *message,
+            Value::Tag(..) => return Err(Error::Malformed("unexpected tag")),
+            message => message,
+        };
+        let (protected, unprotected, payload, signature) = match message {
+            Value::Array(fields) => match <[Value; 4]>::try_from(fields) {
+                Ok(
+                    [Value::Bytes(protected), Value::Map(unprotected), payload, Value::Bytes(signature)],
+                ) => (protected, unprotected, payload, signature),
+                _ => return Err(Error::Malformed("invalid COSE_Sign1 fields")),
+            },
+            _ => return Err(Error::Malformed("COSE_Sign1 is not an array")),
+        };
+
+        let protected_header = if protected.is_empty() {
+            Vec::new()
+        } else {
+            match decode(&protected)? {
+                Value::Map(header) => header,
+                _ => return Err(Error::Malformed("protected header is not a map")),
+            }
+        };
+        let mut has_alg = false;
+        for (label, value) in &protected_header {
+            match (label.as_integer().map(i128::from), value) {
+                (Some(label), Value::Integer(value)) if label == HEADER_ALG.into() => {
+                    check_algorithm(*value)?;
+                    has_alg = true;
+                }
+                (Some(label), _) if label == HEADER_ALG.into() => {
+                    return Err(Error::Malformed("invalid alg header"))
+                }
+                (Some(label), _) if label == HEADER_CRIT.into() => {
+                    return Err(Error::Malformed("unsupported critical header"))
+                }
+                _ => {}
+            }
+        }
+        if !has_alg {
+            return Err(Error::Malformed("missing protected alg header"));
+        }
+
+        let mut kid = None;
+        for (label, value) in unprotected {
+            if label.as_integer().map(i128::from) == Some(HEADER_KID.into()) {
+                match value {
+                    Value::Bytes(value) => kid = Some(value),
+                    _ => return Err(Error::Malformed("invalid kid header")),
+                }
+            }
+        }
+        let payload = match payload {
+            Value::Bytes(payload) => Some(payload),
+            Value::Null => None,
+            _ => return Err(Error::Malformed("invalid payload")),
+        };
+        let signature = Signature::try_from(&signature[..])
+            .map_err(|_| Error::Malformed("signature is not 64 bytes"))?;
+
+        Ok(CoseSign1 {
+            protected,
+            kid,
+            payload,
+            signature,
+        })
+    }
+
+    /// Verify the message with `vk`, binding the caller-supplied
+    /// `external_aad`, and return the payload.
+    pub fn verify(&self, vk: &VerificationKey, external_aad: &[u8]) -> Result<&[u8], Error> {
+        let payload = self
+            .payload
+            .as_deref()
+            .ok_or(Error::Malformed("payload is detached"))?;
+        self.verify_detached(vk, payload, external_aad)?;
+        Ok(payload)
+    }
+
+    /// Verify the message with `vk` over a detached `payload`, binding the
+    /// caller-supplied `external_aad`.
+    pub fn verify_detached(
+        &self,
+        vk: &VerificationKey,
+        payload: &[u8],
+        external_aad: &[u8],
+    ) -> Result<(), Error> {
+        let to_be_signed = sig_structure(&self.protected, external_aad, payload);
+        vk.verify(&self.signature, &to_be_signed)
+            .map_err(Error::InvalidSignature)
+    }
+}
+
+impl VerificationKeyBytes {
+    /// Encode this key as a `COSE_Key` map.
+    pub fn to_cose_key(&self) -> Vec<u8> {
+        cose_key(self, None).to_vec()
+    }
+
+    /// Decode a `COSE_Key` map holding an Ed25519 public key.
+    ///
+    /// Private key material (`d`), if present, is ignored. As with
+    /// `From<[u8; 32]>`, this does not check that the key encodes a curve
+    /// point.
+    pub fn from_cose_key(bytes: &[u8]) -> Result<VerificationKeyBytes, Error> {
+        let (x, _) = parse_cose_key(&decode(bytes)?)?;
+        x.ok_or(Error::Malformed("missing x parameter"))
+    }
+}
+
+impl SigningKey {
+    /// Sign `payload` as a tagged `COSE_Sign1` message, binding the
+    /// caller-supplied `external_aad`.
+    ///
+    /// The protected header specifies the `EdDSA` algorithm, and the
+    /// unprotected header holds the `kid`, if given.
+    pub fn sign_cose_sign1(
+        &self,
+        kid: Option<&[u8]>,
+        payload: &[u8],
+        external_aad: &[u8],
+    ) -> Vec<u8> {
+        let to_be_signed = sig_structure(PROTECTED_EDDSA, external_aad, payload);
+        let signature = self.sign(&to_be_signed);
+        let unprotected = kid
+            .map(|kid| vec![(int(HEADER_KID), Value::Bytes(kid.to_vec()))])
+            .unwrap_or_default();
+        encode(&Value::Tag(
+            TAG_SIGN1,
+            Box::new(Value::Array(vec![
+                Value::Bytes(PROTECTED_EDDSA.to_vec()),
+                Value::Map(unprotected),
+                Value::Bytes(payload.to_vec()),
+                Value::Bytes(signature.as_ref().to_vec()),
+            ])),
+        ))
+    }
+
+    /// Encode this key, and its public key, as a `COSE_Key` map.
+    pub fn to_cose_key(&self) -> Zeroizing<Vec<u8>> {
+        let seed = Zeroizing::new(<[u8; 32]>::from(self.clone()));
+        cose_key(&VerificationKeyBytes::from(self), Some(&seed))
+    }
+
+    /// Decode a `COSE_Key` map holding an Ed25519 private key.
+    ///
+    /// If the map also holds the public key (`x`), it must match.
+    pub fn from_cose_key(bytes: &[u8]) -> Result<SigningKey, Error> {
+        let value = decode(bytes)?;
+        let (x, d) = parse_cose_key(&value)?;
+        let seed = Zeroizing::new(
+            <[u8; 32]>::try_from(d.ok_or(Error::Malformed("missing d parameter"))?)
+                .map_err(|_| Error::Malformed("d is not 32 bytes"))?,
+        );
+        let sk = SigningKey::from(*seed);
+        match x {
+            Some(x) if x != VerificationKeyBytes::from(&sk) => Err(Error::PublicKeyMismatch),
+            _ => Ok(sk),
+        }
+    }
+}
+
+/// Encode a `COSE_Key` map. The entries are written in the deterministic
+/// order, `kty`, `alg`, `crv`, `x`, `d`, with each label and value in its
+/// shortest form.
+fn cose_key(vk: &VerificationKeyBytes, seed: Option<&[u8; 32]>) -> Zeroizing<Vec<u8>> {
+    let mut key = Zeroizing::new(Vec::with_capacity(80));
+    key.extend_from_slice(&[
+        if seed.is_some() { 0xa5 } else { 0xa4 },
+        0x01, // kty:
+        0x01, // OKP
+        0x03, // alg:
+        0x27, // EdDSA (-8)
+        0x20, // crv:
+        0x06, // Ed25519
+        0x21, // x:
+        0x58, // 32-byte string
+        0x20,
+    ]);
+    key.extend_from_slice(vk.as_ref());
+    if let Some(seed) = seed {
+        key.extend_from_slice(&[
+            0x23, // d:
+            0x58, // 32-byte string
+            0x20,
+        ]);
+        key.extend_from_slice(&seed[..]);
+    }
+    key
+}
+
+/// Check a `COSE_Key` map, returning its `x` and `d` parameters.
+fn parse_cose_key(value: &Value) -> Result<(Option<VerificationKeyBytes>, Option<&[u8]>), Error> {
+    let entries = match value {
+        Value::Map(entries) => entries,
+        _ => return Err(Error::Malformed("COSE_Key is not a map")),
+    };
+    let (mut kty, mut crv, mut x, mut d) = (None, None, None, None);
+    for (label, value) in entries {
+        let label = match label {
+            Value::Integer(label) => i128::from(*label),
+            // Text labels are reserved for private use.
+            Value::Text(_) => continue,
+            _ => return Err(Error::Malformed("invalid label")),
+        };
+        match (i64::try_from(label), value) {
+            (Ok(KTY), Value::Integer(value)) => kty = Some(i128::from(*value)),
+            (Ok(CRV), Value::Integer(value)) => crv = Some(i128::from(*value)),
+            (Ok(ALG), Value::Integer(alg)) => check_algorithm(*alg)?,
+            (Ok(X), Value::Bytes(bytes)) => {
+                x = Some(
+                    VerificationKeyBytes::try_from(&bytes[..])
+                        .map_err(|_| Error::Malformed("x is not 32 bytes"))?,
+                )
+            }
+            (Ok(D), Value::Bytes(bytes)) => d = Some(&bytes[..]),
+            (Ok(KTY), _) | (Ok(ALG), _) | (Ok(CRV), _) | (Ok(X), _) | (Ok(D), _) => {
+                return Err(Error::Malformed("invalid key parameter"))
+            }
+            _ => {}
+        }
+    }
+    if kty != Some(KTY_OKP.into()) || crv != Some(CRV_ED25519.into()) {
+        return Err(Error::UnsupportedKeyType);
+    }
+    Ok((x, d))
+}
+
+/// Encode the `Sig_structure` signed by a `COSE_Sign1` message.
+fn sig_structure(protected: &[u8], external_aad: &[u8], payload: &[u8]) -> Vec<u8> {
+    encode(&Value::Array(vec![
+        Value::Text("Signature1".to_string()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(external_aad.to_vec()),
+        Value::Bytes(payload.to_vec()),
+    ]))
+}
+
+fn int(value: i64) -> Value {
+    Value::Integer(Integer::from(value))
+}
+
+/// Encode a CBOR value.
+fn encode(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(value, &mut bytes).expect("writing to a Vec cannot fail");
+    bytes
+}
+
+/// Check that an `alg` parameter is `EdDSA`.
+fn check_algorithm(alg: Integer) -> Result<(), Error> {
+    match i128::from(alg) {
+        alg if alg == ALG_EDDSA.into() => Ok(()),
+        alg => Err(Error::UnsupportedAlgorithm(alg)),
+    }
+}
+
+/// Decode a single CBOR value, rejecting trailing data.
+fn decode(bytes: &[u8]) -> Result<Value, Error> {
+    let mut reader = bytes;
+    let value = ciborium::de::from_reader(&mut reader).map_err(|_| Error::InvalidCbor)?;
+    if !reader.is_empty() {
+        return Err(Error::InvalidCbor);
+    }
+    Ok(value)
+}
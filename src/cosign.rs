@@ -0,0 +1,154 @@
+//! Sigstore `cosign` signatures.
+//!
+//! [`sign_blob`] produces the base64 signature file that
+//! `cosign verify-blob --key key.pub --signature <file> <blob>` accepts, and
+//! [`verify_blob`] checks one made by `cosign sign-blob`. Unlike ECDSA keys,
+//! which sign the SHA-256 digest of the blob, Ed25519 keys sign the blob
+//! itself. The `key.pub` file is the `PUBLIC KEY` PEM encoding of the
+//! verification key, available from the `pkcs8` feature.
+//!
+//! Container images are signed through a [`SimpleSigning`] payload naming the
+//! image and the digest of its manifest. [`sign_image`] returns the payload
+//! and its signature, to be attached to the image as a layer with the
+//! `dev.cosignproject.cosign/signature` annotation, and [`verify_image`]
+//! checks them.
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Signature, SigningKey, VerificationKey};
+
+/// The `type` of cosign simple signing payloads.
+pub const SIMPLE_SIGNING_TYPE: &str = "cosign container image signature";
+
+/// An error decoding or verifying a cosign signature.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The signature is not base64 encoding of 64 bytes.
+    #[error("Malformed signature.")]
+    MalformedSignature,
+    /// The payload is not a cosign simple signing payload.
+    #[error("Malformed simple signing payload.")]
+    MalformedPayload,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// Sign `blob`, returning the base64 signature as written by
+/// `cosign sign-blob`.
+pub fn sign_blob(signing_key: &SigningKey, blob: &[u8]) -> String {
+    Base64::encode_string(&<[u8; 64]>::from(signing_key.sign(blob)))
+}
+
+/// Verify the base64 `signature` of `blob`, as written by `cosign sign-blob`.
+///
+/// Whitespace around the signature, such as a trailing newline, is ignored.
+pub fn verify_blob(vk: &VerificationKey, blob: &[u8], signature: &str) -> Result<(), Error> {
+    let signature = Base64::decode_vec(signature.trim()).map_err(|_| Error::MalformedSignature)?;
+    let signature = Signature::try_from(&signature[..]).map_err(|_| Error::MalformedSignature)?;
+    vk.verify(&signature, blob).map_err(Error::InvalidSignature)
+}
+
+/// The claims of a cosign container image signature.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimpleSigning {
+    /// The image reference, such as `ghcr.io/zcashfoundation/zebra`.
+    pub docker_reference: String,
+    /// The digest of the image manifest, such as `sha256:<hex>`.
+    pub manifest_digest: String,
+}
+
+/// The JSON form of a simple signing payload, in the field order cosign
+/// writes.
+#[derive(Serialize, Deserialize)]
+struct PayloadJson {
+    critical: CriticalJson,
+    optional: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CriticalJson {
+    identity: IdentityJson,
+    image: ImageJson,
+    #[serde(rename = "type")]
+    payload_type: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdentityJson {
+    #[serde(rename = "docker-reference")]
+    docker_reference: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ImageJson {
+    #[serde(rename = "docker-manifest-digest")]
+    docker_manifest_digest: String,
+}
+
+impl SimpleSigning {
+    /// Claims for the image `docker_reference` with manifest digest
+    /// `manifest_digest`.
+    pub fn new(docker_reference: &str, manifest_digest: &str) -> SimpleSigning {
+        SimpleSigning {
+            docker_reference: docker_reference.to_string(),
+            manifest_digest: manifest_digest.to_string(),
+        }
+    }
+
+    /// The JSON payload that is signed, as cosign writes it.
+    pub fn to_payload(&self) -> Vec<u8> {
+        let payload = PayloadJson {
+            critical: CriticalJson {
+                identity: IdentityJson {
+                    docker_reference: self.docker_reference.clone(),
+                },
+                image: ImageJson {
+                    docker_manifest_digest: self.manifest_digest.clone(),
+                },
+                payload_type: SIMPLE_SIGNING_TYPE.to_string(),
+            },
+            optional: None,
+        };
+        serde_json::to_vec(&payload).expect("payloads serialize to JSON")
+    }
+
+    /// Decode a simple signing payload, ignoring any optional annotations.
+    pub fn from_payload(payload: &[u8]) -> Result<SimpleSigning, Error> {
+        let payload: PayloadJson =
+            serde_json::from_slice(payload).map_err(|_| Error::MalformedPayload)?;
+        if payload.critical.payload_type != SIMPLE_SIGNING_TYPE {
+            return Err(Error::MalformedPayload);
+        }
+        Ok(SimpleSigning {
+            docker_reference: payload.critical.identity.docker_reference,
+            manifest_digest: payload.critical.image.docker_manifest_digest,
+        })
+    }
+}
+
+/// Sign the image described by `claims`, returning the payload and its base64
+/// signature.
+pub fn sign_image(signing_key: &SigningKey, claims: &SimpleSigning) -> (Vec<u8>, String) {
+    let payload = claims.to_payload();
+    let signature = sign_blob(signing_key, &payload);
+    (payload, signature)
+}
+
+/// Verify the base64 `signature` of a simple signing `payload`, returning its
+/// claims.
+///
+/// The caller must check that the manifest digest is that of the image being
+/// verified.
+pub fn verify_image(
+    vk: &VerificationKey,
+    payload: &[u8],
+    signature: &str,
+) -> Result<SimpleSigning, Error> {
+    verify_blob(vk, payload, signature)?;
+    SimpleSigning::from_payload(payload)
+}
@@ -0,0 +1,83 @@
+//! `did:key` decentralized identifiers.
+//!
+//! [`VerificationKeyBytes::to_did_key`] and
+//! [`VerificationKeyBytes::from_did_key`] convert between keys and the
+//! [`did:key`][spec] identifiers used by decentralized identity applications.
+//! An Ed25519 identifier is `did:key:z6Mk...`: the multibase base58btc
+//! encoding (prefix `z`) of the `ed25519-pub` multicodec (`0xed 0x01`)
+//! followed by the key.
+//!
+//! [spec]: https://w3c-ccg.github.io/did-method-key/
+
+use std::convert::TryFrom;
+
+use thiserror::Error;
+
+use crate::{base58, VerificationKeyBytes};
+
+const PREFIX: &str = "did:key:";
+/// The multibase prefix of base58btc.
+const MULTIBASE_BASE58BTC: char = 'z';
+/// The unsigned varint encoding of the `ed25519-pub` multicodec, `0xed`.
+const MULTICODEC_ED25519_PUB: [u8; 2] = [0xed, 0x01];
+
+/// An error parsing a `did:key` identifier.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The identifier does not begin with `did:key:`.
+    #[error("Not a did:key identifier.")]
+    UnsupportedMethod,
+    /// The key is not multibase base58btc, as `did:key` requires.
+    #[error("Unsupported multibase encoding.")]
+    UnsupportedMultibase,
+    /// The key is not valid base58.
+    #[error("Invalid base58 encoding.")]
+    InvalidBase58,
+    /// The key is not an `ed25519-pub` key.
+    #[error("Not an Ed25519 public key.")]
+    UnsupportedKeyType,
+    /// The key is not 32 bytes.
+    #[error("Malformed Ed25519 public key.")]
+    MalformedKey,
+}
+
+impl VerificationKeyBytes {
+    /// Encode this key as a `did:key:z6Mk...` identifier.
+    pub fn to_did_key(&self) -> String {
+        format!("{}{}", PREFIX, self.to_multibase())
+    }
+
+    /// Decode a `did:key` identifier for an Ed25519 key.
+    ///
+    /// A DID URL whose fragment is the identifier's own key, as used to name
+    /// its verification method, is also accepted.
+    pub fn from_did_key(did: &str) -> Result<VerificationKeyBytes, Error> {
+        let id = did.strip_prefix(PREFIX).ok_or(Error::UnsupportedMethod)?;
+        let id = match id.split_once('#') {
+            Some((id, fragment)) if fragment == id => id,
+            Some(_) => return Err(Error::UnsupportedMethod),
+            None => id,
+        };
+        VerificationKeyBytes::from_multibase(id)
+    }
+
+    /// The multibase `z6Mk...` form of this key used in `did:key`
+    /// identifiers and `Ed25519VerificationKey2020` documents.
+    pub fn to_multibase(&self) -> String {
+        let mut bytes = MULTICODEC_ED25519_PUB.to_vec();
+        bytes.extend_from_slice(self.as_ref());
+        format!("{}{}", MULTIBASE_BASE58BTC, base58::encode(&bytes))
+    }
+
+    /// Decode the multibase `z6Mk...` form of a key.
+    pub fn from_multibase(multibase: &str) -> Result<VerificationKeyBytes, Error> {
+        let encoded = multibase
+            .strip_prefix(MULTIBASE_BASE58BTC)
+            .ok_or(Error::UnsupportedMultibase)?;
+        let bytes = base58::decode(encoded).ok_or(Error::InvalidBase58)?;
+        let key = bytes
+            .strip_prefix(&MULTICODEC_ED25519_PUB[..])
+            .ok_or(Error::UnsupportedKeyType)?;
+        VerificationKeyBytes::try_from(key).map_err(|_| Error::MalformedKey)
+    }
+}
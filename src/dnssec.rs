@@ -0,0 +1,442 @@
+//! DNSSEC Ed25519 keys and signatures.
+//!
+//! DNSSEC signs zones with Ed25519 as algorithm 15, specified in [RFC 8080].
+//! A zone's key is published as a [`Dnskey`] record, each set of records with
+//! the same owner name, class, and type (an [`Rrset`]) is signed by an
+//! [`Rrsig`] record, and the parent zone vouches for the key with a DS record
+//! holding a digest of the `DNSKEY`.
+//!
+//! Records are handled here as RDATA in wire format, as in [RFC 4034]. Names
+//! are given in presentation format, such as `www.example.com.`, without
+//! escapes; the final dot is optional. Signatures are made and checked over
+//! the canonical form of the RRset, but RDATA that contains domain names
+//! (such as `MX` or `NS` records) must already have those names in canonical,
+//! lowercase, uncompressed form.
+//!
+//! [RFC 8080]: https://www.rfc-editor.org/rfc/rfc8080
+//! [RFC 4034]: https://www.rfc-editor.org/rfc/rfc4034
+
+use std::convert::TryFrom;
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{Signature, SigningKey, VerificationKeyBytes};
+
+/// The DNSSEC algorithm number of Ed25519.
+pub const ALGORITHM: u8 = 15;
+/// The `DNSKEY` protocol field, which must be 3.
+const PROTOCOL: u8 = 3;
+/// The DS digest type of SHA-256.
+const DIGEST_SHA256: u8 = 2;
+/// The largest encoded length of a domain name.
+const MAX_NAME_LEN: usize = 255;
+/// The largest length of a label in a domain name.
+const MAX_LABEL_LEN: usize = 63;
+
+/// An error encoding, decoding, or verifying DNSSEC records.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// A domain name is not a valid name in presentation format.
+    #[error("Invalid domain name {0:?}.")]
+    InvalidName(String),
+    /// The RDATA is not well-formed for its record type.
+    #[error("Malformed RDATA.")]
+    MalformedRdata,
+    /// The record is for an algorithm other than Ed25519.
+    #[error("Unsupported DNSSEC algorithm {0}.")]
+    UnsupportedAlgorithm(u8),
+    /// The `RRSIG` covers a different type than the RRset.
+    #[error("RRSIG does not cover this RRset's type.")]
+    TypeMismatch,
+    /// The `RRSIG` labels field is larger than the owner name's label count.
+    #[error("RRSIG labels field exceeds the owner name's labels.")]
+    InvalidLabels,
+    /// The signer's name is not the owner name or one of its ancestors.
+    #[error("RRSIG signer is not an ancestor of the owner name.")]
+    SignerMismatch,
+    /// The `RRSIG` key tag does not match the `DNSKEY`.
+    #[error("RRSIG key tag does not match the DNSKEY.")]
+    KeyTagMismatch,
+    /// The `DNSKEY` does not have the Zone Key flag set, so may not be used
+    /// to verify RRsets.
+    #[error("DNSKEY is not a zone key.")]
+    NotZoneKey,
+    /// The signature expiration time has passed.
+    #[error("RRSIG has expired.")]
+    Expired,
+    /// The signature inception time has not yet been reached.
+    #[error("RRSIG is not yet valid.")]
+    NotYetValid,
+    /// The RRset is empty.
+    #[error("Cannot sign an empty RRset.")]
+    EmptyRrset,
+    /// The signature does not verify.
+    #[error("Invalid RRSIG signature: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// An Ed25519 `DNSKEY` record.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Dnskey {
+    /// The key flags, such as [`Dnskey::ZONE_KEY`] and
+    /// [`Dnskey::SECURE_ENTRY_POINT`].
+    pub flags: u16,
+    /// The key.
+    pub key: VerificationKeyBytes,
+}
+
+impl Dnskey {
+    /// The Zone Key flag, which is set on keys that sign zone data.
+    pub const ZONE_KEY: u16 = 0x0100;
+    /// The Secure Entry Point flag, conventionally set on key-signing keys.
+    pub const SECURE_ENTRY_POINT: u16 = 0x0001;
+
+    /// A zone-signing key: a `DNSKEY` with flags 256.
+    pub fn zone_signing_key(key: VerificationKeyBytes) -> Dnskey {
+        Dnskey {
+            flags: Dnskey::ZONE_KEY,
+            key,
+        }
+    }
+
+    /// A key-signing key: a `DNSKEY` with flags 257.
+    pub fn key_signing_key(key: VerificationKeyBytes) -> Dnskey {
+        Dnskey {
+            flags: Dnskey::ZONE_KEY | Dnskey::SECURE_ENTRY_POINT,
+            key,
+        }
+    }
+
+    /// Encode this key as `DNSKEY` RDATA.
+    pub fn to_rdata(&self) -> [u8; 36] {
+        let mut rdata = [0u8; 36];
+        rdata[..2].copy_from_slice(&self.flags.to_be_bytes());
+        rdata[2] = PROTOCOL;
+        rdata[3] = ALGORITHM;
+        rdata[4..].copy_from_slice(self.key.as_ref());
+        rdata
+    }
+
+    /// Decode `DNSKEY` RDATA for an Ed25519 key.
+    pub fn from_rdata(rdata: &[u8]) -> Result<Dnskey, Error> {
+        if rdata.len() < 4 || rdata[2] != PROTOCOL {
+            return Err(Error::MalformedRdata);
+        }
+        if rdata[3] != ALGORITHM {
+            return Err(Error::UnsupportedAlgorithm(rdata[3]));
+        }
+        Ok(Dnskey {
+            flags: u16::from_be_bytes([rdata[0], rdata[1]]),
+            key: VerificationKeyBytes::try_from(&rdata[4..]).map_err(|_| Error::MalformedRdata)?,
+        })
+    }
+
+    /// The key tag that identifies this key in `RRSIG` and DS records, as
+    /// computed in [RFC 4034 Appendix B].
+    ///
+    /// [RFC 4034 Appendix B]: https://www.rfc-editor.org/rfc/rfc4034#appendix-B
+    pub fn key_tag(&self) -> u16 {
+        let sum = self.to_rdata().chunks(2).fold(0u32, |sum, pair| {
+            sum + u32::from(pair[0]) * 256 + u32::from(pair[1])
+        });
+        (sum + (sum >> 16)) as u16
+    }
+
+    /// Encode the DS record for this key, owned by `owner`, as RDATA with a
+    /// SHA-256 digest (digest type 2).
+    pub fn to_ds_rdata(&self, owner: &str) -> Result<Vec<u8>, Error> {
+        let digest = Sha256::new()
+            .chain(name_to_wire(owner)?)
+            .chain(self.to_rdata())
+            .finalize();
+        let mut rdata = self.key_tag().to_be_bytes().to_vec();
+        rdata.push(ALGORITHM);
+        rdata.push(DIGEST_SHA256);
+        rdata.extend_from_slice(&digest);
+        Ok(rdata)
+    }
+}
+
+/// A set of resource records with the same owner name, class, and type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rrset {
+    /// The owner name.
+    pub name: String,
+    /// The record type, such as 1 for `A` or 15 for `MX`.
+    pub rr_type: u16,
+    /// The record class, usually 1 for `IN`.
+    pub class: u16,
+    /// The TTL of the records.
+    pub ttl: u32,
+    /// The RDATA of each record, in wire format.
+    pub rdata: Vec<Vec<u8>>,
+}
+
+impl Rrset {
+    /// Sign this RRset with `signing_key`, whose `DNSKEY` has flags
+    /// `key_flags` and is owned by the zone `signer_name`.
+    ///
+    /// The signature is valid from `inception` through `expiration`, in
+    /// seconds since the Unix epoch modulo 2<sup>32</sup>.
+    pub fn sign(
+        &self,
+        signing_key: &SigningKey,
+        key_flags: u16,
+        signer_name: &str,
+        inception: u32,
+        expiration: u32,
+    ) -> Result<Rrsig, Error> {
+        if self.rdata.is_empty() {
+            return Err(Error::EmptyRrset);
+        }
+        let dnskey = Dnskey {
+            flags: key_flags,
+            key: VerificationKeyBytes::from(signing_key),
+        };
+        let name = name_to_wire(&self.name)?;
+        let signer = name_to_wire(signer_name)?;
+        if !is_ancestor(&signer, &name) {
+            return Err(Error::SignerMismatch);
+        }
+        let mut rrsig = Rrsig {
+            type_covered: self.rr_type,
+            labels: label_count(&name),
+            original_ttl: self.ttl,
+            expiration,
+            inception,
+            key_tag: dnskey.key_tag(),
+            signer_name: signer_name.to_string(),
+            signature: Signature::from([0u8; 64]),
+        };
+        rrsig.signature = signing_key.sign(&rrsig.signed_data(&name, self)?);
+        Ok(rrsig)
+    }
+}
+
+/// An Ed25519 `RRSIG` record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rrsig {
+    /// The type of the RRset that this signature covers.
+    pub type_covered: u16,
+    /// The number of labels in the owner name, not counting the root or a
+    /// leading wildcard label.
+    pub labels: u8,
+    /// The TTL of the RRset as it appears in the zone.
+    pub original_ttl: u32,
+    /// The time after which the signature is invalid, in seconds since the
+    /// Unix epoch modulo 2<sup>32</sup>.
+    pub expiration: u32,
+    /// The time before which the signature is invalid, in seconds since the
+    /// Unix epoch modulo 2<sup>32</sup>.
+    pub inception: u32,
+    /// The key tag of the signing `DNSKEY`.
+    pub key_tag: u16,
+    /// The owner name of the signing `DNSKEY`, the zone name.
+    pub signer_name: String,
+    /// The signature.
+    pub signature: Signature,
+}
+
+impl Rrsig {
+    /// Encode this signature as `RRSIG` RDATA.
+    pub fn to_rdata(&self) -> Result<Vec<u8>, Error> {
+        let mut rdata = self.rdata_without_signature()?;
+        rdata.extend_from_slice(&<[u8; 64]>::from(self.signature));
+        Ok(rdata)
+    }
+
+    /// Decode `RRSIG` RDATA for an Ed25519 signature.
+    pub fn from_rdata(rdata: &[u8]) -> Result<Rrsig, Error> {
+        if rdata.len() < 18 {
+            return Err(Error::MalformedRdata);
+        }
+        if rdata[2] != ALGORITHM {
+            return Err(Error::UnsupportedAlgorithm(rdata[2]));
+        }
+        let u32_at =
+            |i: usize| u32::from_be_bytes([rdata[i], rdata[i + 1], rdata[i + 2], rdata[i + 3]]);
+        let (signer_name, signature) = name_from_wire(&rdata[18..])?;
+        Ok(Rrsig {
+            type_covered: u16::from_be_bytes([rdata[0], rdata[1]]),
+            labels: rdata[3],
+            original_ttl: u32_at(4),
+            expiration: u32_at(8),
+            inception: u32_at(12),
+            key_tag: u16::from_be_bytes([rdata[16], rdata[17]]),
+            signer_name,
+            signature: Signature::try_from(signature).map_err(|_| Error::MalformedRdata)?,
+        })
+    }
+
+    /// Verify that this signature covers `rrset`, is valid at time `now`, and
+    /// was made by `dnskey`, as in [RFC 4035 §5.3].
+    ///
+    /// Times are compared using serial number arithmetic, so `now` is in
+    /// seconds since the Unix epoch modulo 2<sup>32</sup>. The caller must
+    /// still check that `dnskey` is a key of the zone `signer_name`.
+    ///
+    /// [RFC 4035 §5.3]: https://www.rfc-editor.org/rfc/rfc4035#section-5.3
+    pub fn verify(&self, dnskey: &Dnskey, rrset: &Rrset, now: u32) -> Result<(), Error> {
+        if self.type_covered != rrset.rr_type {
+            return Err(Error::TypeMismatch);
+        }
+        let name = name_to_wire(&rrset.name)?;
+        if !is_ancestor(&name_to_wire(&self.signer_name)?, &name) {
+            return Err(Error::SignerMismatch);
+        }
+        if dnskey.flags & Dnskey::ZONE_KEY == 0 {
+            return Err(Error::NotZoneKey);
+        }
+        if self.key_tag != dnskey.key_tag() {
+            return Err(Error::KeyTagMismatch);
+        }
+        if (self.expiration.wrapping_sub(now) as i32) < 0 {
+            return Err(Error::Expired);
+        }
+        if (now.wrapping_sub(self.inception) as i32) < 0 {
+            return Err(Error::NotYetValid);
+        }
+        dnskey
+            .key
+            .verify(&self.signature, &self.signed_data(&name, rrset)?)
+            .map_err(Error::InvalidSignature)
+    }
+
+    /// The `RRSIG` RDATA up to and including the signer's name.
+    fn rdata_without_signature(&self) -> Result<Vec<u8>, Error> {
+        let mut rdata = self.type_covered.to_be_bytes().to_vec();
+        rdata.push(ALGORITHM);
+        rdata.push(self.labels);
+        rdata.extend_from_slice(&self.original_ttl.to_be_bytes());
+        rdata.extend_from_slice(&self.expiration.to_be_bytes());
+        rdata.extend_from_slice(&self.inception.to_be_bytes());
+        rdata.extend_from_slice(&self.key_tag.to_be_bytes());
+        rdata.extend_from_slice(&name_to_wire(&self.signer_name)?);
+        Ok(rdata)
+    }
+
+    /// The data signed by this signature over `rrset`, whose owner name is
+    /// `name` in wire format, as in RFC 4034 §3.1.8.1.
+    fn signed_data(&self, name: &[u8], rrset: &Rrset) -> Result<Vec<u8>, Error> {
+        // A signature over a wildcard expansion is made with the wildcard
+        // owner name, which has `labels` labels after the `*`.
+        let owner = match label_count(name).checked_sub(self.labels) {
+            Some(0) => name.to_vec(),
+            Some(extra) => {
+                let mut owner = b"\x01*".to_vec();
+                owner.extend_from_slice(skip_labels(name, extra));
+                owner
+            }
+            None => return Err(Error::InvalidLabels),
+        };
+
+        let mut rdata: Vec<&Vec<u8>> = rrset.rdata.iter().collect();
+        rdata.sort();
+        rdata.dedup();
+
+        let mut data = self.rdata_without_signature()?;
+        for rdata in rdata {
+            let rdata_len = u16::try_from(rdata.len()).map_err(|_| Error::MalformedRdata)?;
+            data.extend_from_slice(&owner);
+            data.extend_from_slice(&rrset.rr_type.to_be_bytes());
+            data.extend_from_slice(&rrset.class.to_be_bytes());
+            data.extend_from_slice(&self.original_ttl.to_be_bytes());
+            data.extend_from_slice(&rdata_len.to_be_bytes());
+            data.extend_from_slice(rdata);
+        }
+        Ok(data)
+    }
+}
+
+/// Encode `name` in canonical wire format: lowercase and uncompressed.
+fn name_to_wire(name: &str) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::InvalidName(name.to_string());
+    let relative = name.strip_suffix('.').unwrap_or(name);
+    let mut wire = Vec::with_capacity(relative.len() + 2);
+    if !relative.is_empty() {
+        for label in relative.split('.') {
+            if label.is_empty() || label.len() > MAX_LABEL_LEN || !label.is_ascii() {
+                return Err(invalid());
+            }
+            wire.push(label.len() as u8);
+            wire.extend(label.bytes().map(|b| b.to_ascii_lowercase()));
+        }
+    }
+    wire.push(0);
+    if wire.len() > MAX_NAME_LEN {
+        return Err(invalid());
+    }
+    Ok(wire)
+}
+
+/// Decode an uncompressed wire format name at the start of `wire`, returning
+/// it in presentation format and the bytes following it.
+fn name_from_wire(wire: &[u8]) -> Result<(String, &[u8]), Error> {
+    let mut name = String::new();
+    let mut rest = wire;
+    loop {
+        let (&len, tail) = rest.split_first().ok_or(Error::MalformedRdata)?;
+        let len = usize::from(len);
+        if len > MAX_LABEL_LEN || tail.len() < len {
+            return Err(Error::MalformedRdata);
+        }
+        let (label, tail) = tail.split_at(len);
+        rest = tail;
+        if len == 0 {
+            break;
+        }
+        if !label.iter().all(|b| b.is_ascii_graphic() && *b != b'.') {
+            return Err(Error::MalformedRdata);
+        }
+        name.extend(label.iter().map(|&b| char::from(b)));
+        name.push('.');
+    }
+    if wire.len() - rest.len() > MAX_NAME_LEN {
+        return Err(Error::MalformedRdata);
+    }
+    if name.is_empty() {
+        name.push('.');
+    }
+    Ok((name, rest))
+}
+
+/// The number of labels in the wire format `name`, not counting the root or
+/// a leading wildcard label.
+fn label_count(name: &[u8]) -> u8 {
+    let name = name.strip_prefix(b"\x01*").unwrap_or(name);
+    let mut count = 0;
+    let mut rest = name;
+    while let Some((&len, tail)) = rest.split_first() {
+        if len == 0 {
+            break;
+        }
+        count += 1;
+        rest = &tail[usize::from(len)..];
+    }
+    count
+}
+
+/// The wire format `name` without its first `count` labels.
+fn skip_labels(name: &[u8], count: u8) -> &[u8] {
+    let mut rest = name;
+    for _ in 0..count {
+        rest = &rest[1 + usize::from(rest[0])..];
+    }
+    rest
+}
+
+/// Whether the wire format name `ancestor` is `name` or one of its
+/// ancestors.
+fn is_ancestor(ancestor: &[u8], name: &[u8]) -> bool {
+    let mut rest = name;
+    loop {
+        if rest == ancestor {
+            return true;
+        }
+        match rest.first() {
+            Some(&len) if len > 0 => rest = &rest[1 + usize::from(len)..],
+            _ => return false,
+        }
+    }
+}
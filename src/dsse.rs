@@ -0,0 +1,185 @@
+//! DSSE (Dead Simple Signing Envelope) envelopes.
+//!
+//! An [`Envelope`] carries a payload together with its type and any number of
+//! signatures over the [pre-authentication encoding](pae) of the two, as
+//! specified by [DSSE]. It is the signature wrapper of in-toto attestations,
+//! including SLSA provenance, whose payload type is
+//! [`IN_TOTO_PAYLOAD_TYPE`].
+//!
+//! Each signature may carry a `keyid` hint; here it is the [`key_id`] used by
+//! in-toto and TUF. The hint is not authenticated, so [`Envelope::verify`]
+//! tries every signature against the given key.
+//!
+//! [DSSE]: https://github.com/secure-systems-lab/dsse/blob/master/protocol.md
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64, Base64Unpadded, Base64Url, Base64UrlUnpadded, Encoding};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+pub use crate::key_id::key_id;
+
+/// The payload type of in-toto statements.
+pub const IN_TOTO_PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// An error decoding or verifying an envelope.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The envelope is not a JSON object of the expected form.
+    #[error("Malformed DSSE envelope.")]
+    MalformedEnvelope,
+    /// The payload or a signature is not valid base64.
+    #[error("Invalid base64 encoding.")]
+    InvalidBase64,
+    /// The envelope has no signatures.
+    #[error("DSSE envelope is unsigned.")]
+    Unsigned,
+    /// No signature in the envelope verifies under the key.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// The DSSE v1 pre-authentication encoding of `payload` with type
+/// `payload_type`, which is what each signature signs:
+/// `DSSEv1 <len(type)> <type> <len(payload)> <payload>`, with the lengths in
+/// ASCII decimal.
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut encoded = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    encoded.extend_from_slice(payload);
+    encoded
+}
+
+/// A signed DSSE envelope.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Envelope {
+    /// The type of the payload, such as [`IN_TOTO_PAYLOAD_TYPE`].
+    pub payload_type: String,
+    /// The payload.
+    pub payload: Vec<u8>,
+    /// The signatures over the payload and its type.
+    pub signatures: Vec<EnvelopeSignature>,
+}
+
+/// A signature in a DSSE envelope.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnvelopeSignature {
+    /// An unauthenticated hint identifying the signing key, or empty.
+    pub keyid: String,
+    /// The signature.
+    pub sig: Signature,
+}
+
+/// The JSON form of an envelope.
+#[derive(Serialize, Deserialize)]
+struct EnvelopeJson {
+    payload: String,
+    #[serde(rename = "payloadType")]
+    payload_type: String,
+    signatures: Vec<SignatureJson>,
+}
+
+/// The JSON form of a signature.
+#[derive(Serialize, Deserialize)]
+struct SignatureJson {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    keyid: String,
+    sig: String,
+}
+
+impl Envelope {
+    /// An envelope for `payload` with type `payload_type` and no signatures.
+    pub fn new(payload_type: &str, payload: &[u8]) -> Envelope {
+        Envelope {
+            payload_type: payload_type.to_string(),
+            payload: payload.to_vec(),
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Add a signature by `signing_key`, with its [`key_id`] as the hint.
+    pub fn sign(&mut self, signing_key: &SigningKey) {
+        let sig = signing_key.sign(&pae(&self.payload_type, &self.payload));
+        self.signatures.push(EnvelopeSignature {
+            keyid: key_id(&VerificationKeyBytes::from(signing_key)),
+            sig,
+        });
+    }
+
+    /// Verify that some signature in the envelope was made by `vk`, returning
+    /// the payload.
+    ///
+    /// The payload type is authenticated, so the caller should also check
+    /// that it is the expected type.
+    pub fn verify(&self, vk: &VerificationKey) -> Result<&[u8], Error> {
+        let signed = pae(&self.payload_type, &self.payload);
+        let mut result = Err(Error::Unsigned);
+        for signature in &self.signatures {
+            result = vk
+                .verify(&signature.sig, &signed)
+                .map_err(Error::InvalidSignature);
+            if result.is_ok() {
+                break;
+            }
+        }
+        result.map(|()| &self.payload[..])
+    }
+
+    /// Decode the JSON form of an envelope.
+    ///
+    /// Base64 fields may be standard or URL-safe, with or without padding.
+    pub fn from_json(json: &str) -> Result<Envelope, Error> {
+        let envelope: EnvelopeJson =
+            serde_json::from_str(json).map_err(|_| Error::MalformedEnvelope)?;
+        let signatures = envelope
+            .signatures
+            .into_iter()
+            .map(|signature| {
+                let sig = decode(&signature.sig)?;
+                Ok(EnvelopeSignature {
+                    keyid: signature.keyid,
+                    sig: Signature::try_from(&sig[..]).map_err(|_| Error::MalformedEnvelope)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Envelope {
+            payload_type: envelope.payload_type,
+            payload: decode(&envelope.payload)?,
+            signatures,
+        })
+    }
+
+    /// Encode the envelope as JSON, with standard padded base64 fields.
+    pub fn to_json(&self) -> String {
+        let envelope = EnvelopeJson {
+            payload: Base64::encode_string(&self.payload),
+            payload_type: self.payload_type.clone(),
+            signatures: self
+                .signatures
+                .iter()
+                .map(|signature| SignatureJson {
+                    keyid: signature.keyid.clone(),
+                    sig: Base64::encode_string(&<[u8; 64]>::from(signature.sig)),
+                })
+                .collect(),
+        };
+        serde_json::to_string(&envelope).expect("envelopes serialize to JSON")
+    }
+}
+
+/// Decode standard or URL-safe base64, with or without padding.
+fn decode(encoded: &str) -> Result<Vec<u8>, Error> {
+    Base64::decode_vec(encoded)
+        .or_else(|_| Base64Unpadded::decode_vec(encoded))
+        .or_else(|_| Base64Url::decode_vec(encoded))
+        .or_else(|_| Base64UrlUnpadded::decode_vec(encoded))
+        .map_err(|_| Error::InvalidBase64)
+}
@@ -9,10 +9,33 @@ pub enum Error {
     /// The encoding of a public key was malformed.
     #[error("Malformed public key encoding.")]
     MalformedPublicKey,
-    /// Signature verification failed.
+    /// Signature verification failed: the verification equation was not
+    /// satisfied.
     #[error("Invalid signature.")]
     InvalidSignature,
+    /// The `R` component of a signature is not an encoding of a curve point.
+    #[error("Malformed signature R component.")]
+    MalformedSignatureR,
+    /// The `s` component of a signature does not represent an integer less
+    /// than `l`, the order of the prime-order subgroup.
+    #[error("Signature s component out of range.")]
+    SignatureScalarOutOfRange,
+    /// The `R` component of a signature is a non-canonical encoding of a
+    /// curve point.
+    #[error("Non-canonical signature R encoding.")]
+    NonCanonicalSignatureR,
+    /// A verification key or signature `R` component is a point of small
+    /// order, which strict verification rejects.
+    #[error("Small-order point.")]
+    SmallOrderPoint,
     /// A byte slice of the wrong length was supplied during parsing.
     #[error("Invalid length when parsing byte slice.")]
     InvalidSliceLength,
+    /// Queuing an item would exceed one of the [`batch::Limits`](crate::batch::Limits)
+    /// configured on a batch verifier.
+    #[error("Batch verification limit exceeded.")]
+    BatchLimitExceeded,
+    /// A string was not a valid hex encoding.
+    #[error("Invalid hex encoding.")]
+    InvalidHexEncoding,
 }
@@ -0,0 +1,259 @@
+use std::{convert::TryFrom, io::IoSlice, ops::Deref};
+
+use curve25519_dalek::{constants, scalar::Scalar};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{Error, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+/// An expanded Ed25519 signing key.
+///
+/// RFC 8032 derives a signing key from a 32-byte seed by hashing it with
+/// SHA-512; the low half of the hash becomes the (clamped) secret scalar `s`
+/// and the high half becomes the `prefix` used to derive nonces. This type
+/// holds that expanded form directly, so it can be used when only the expanded
+/// secret is available, as with keys exported from HSMs or produced by
+/// hierarchical key derivation schemes that never expose a seed.
+///
+/// The 64-byte encoding is `s || prefix`, with `s` in little-endian order. No
+/// clamping is applied to `s` on import: it is used as given, and must be less
+/// than `2^255`.
+///
+/// The key material is zeroized when an `ExpandedSigningKey` is dropped.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SerdeHelper"))]
+#[cfg_attr(feature = "serde", serde(into = "SerdeHelper"))]
+pub struct ExpandedSigningKey {
+    s: Scalar,
+    prefix: [u8; 32],
+    vk: VerificationKey,
+}
+
+/// Secret key material is redacted unless the `debug-secrets` feature is
+/// enabled; only the verification key is shown.
+impl core::fmt::Debug for ExpandedSigningKey {
+    #[cfg(not(feature = "debug-secrets"))]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_struct("ExpandedSigningKey")
+            .field("s", &"<redacted>")
+            .field("prefix", &"<redacted>")
+            .field("vk", &self.vk)
+            .finish()
+    }
+
+    #[cfg(feature = "debug-secrets")]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_struct("ExpandedSigningKey")
+            .field("s", &self.s)
+            .field("prefix", &hex::encode(self.prefix))
+            .field("vk", &self.vk)
+            .finish()
+    }
+}
+
+impl<'a> From<&'a ExpandedSigningKey> for VerificationKey {
+    fn from(esk: &'a ExpandedSigningKey) -> VerificationKey {
+        esk.vk
+    }
+}
+
+impl<'a> From<&'a ExpandedSigningKey> for VerificationKeyBytes {
+    fn from(esk: &'a ExpandedSigningKey) -> VerificationKeyBytes {
+        esk.vk.into()
+    }
+}
+
+impl<'a> From<&'a SigningKey> for ExpandedSigningKey {
+    fn from(sk: &'a SigningKey) -> ExpandedSigningKey {
+        sk.expanded.clone()
+    }
+}
+
+impl From<ExpandedSigningKey> for [u8; 64] {
+    fn from(esk: ExpandedSigningKey) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[0..32].copy_from_slice(esk.s.as_bytes());
+        bytes[32..64].copy_from_slice(&esk.prefix[..]);
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for ExpandedSigningKey {
+    type Error = Error;
+    fn try_from(slice: &[u8]) -> Result<ExpandedSigningKey, Error> {
+        if slice.len() == 64 {
+            let mut bytes = [0u8; 64];
+            bytes[..].copy_from_slice(slice);
+            let esk = bytes.into();
+            bytes.zeroize();
+            Ok(esk)
+        } else {
+            Err(Error::InvalidSliceLength)
+        }
+    }
+}
+
+impl From<[u8; 64]> for ExpandedSigningKey {
+    fn from(bytes: [u8; 64]) -> ExpandedSigningKey {
+        let mut s_bytes = [0u8; 32];
+        s_bytes[..].copy_from_slice(&bytes[0..32]);
+        let mut prefix = [0u8; 32];
+        prefix[..].copy_from_slice(&bytes[32..64]);
+        let esk = ExpandedSigningKey::from_parts(Scalar::from_bits(s_bytes), prefix);
+        s_bytes.zeroize();
+        esk
+    }
+}
+
+impl Zeroize for ExpandedSigningKey {
+    fn zeroize(&mut self) {
+        self.s.zeroize();
+        self.prefix.zeroize();
+    }
+}
+
+impl Drop for ExpandedSigningKey {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for ExpandedSigningKey {}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct SerdeHelper([u8; 32], [u8; 32]);
+
+impl Drop for SerdeHelper {
+    fn drop(&mut self) {
+        self.0.zeroize();
+        self.1.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SerdeHelper {}
+
+impl From<SerdeHelper> for ExpandedSigningKey {
+    fn from(helper: SerdeHelper) -> ExpandedSigningKey {
+        let mut bytes = [0u8; 64];
+        bytes[0..32].copy_from_slice(&helper.0[..]);
+        bytes[32..64].copy_from_slice(&helper.1[..]);
+        bytes.into()
+    }
+}
+
+impl From<ExpandedSigningKey> for SerdeHelper {
+    fn from(esk: ExpandedSigningKey) -> Self {
+        Self(esk.s.to_bytes(), esk.prefix)
+    }
+}
+
+impl ExpandedSigningKey {
+    /// Construct an expanded signing key from the secret scalar and nonce
+    /// prefix, computing the verification key as `A = [s]B`.
+    #[allow(non_snake_case)]
+    pub(crate) fn from_parts(s: Scalar, prefix: [u8; 32]) -> ExpandedSigningKey {
+        let A = &s * &constants::ED25519_BASEPOINT_TABLE;
+
+        ExpandedSigningKey {
+            s,
+            prefix,
+            vk: VerificationKey {
+                minus_A: -A,
+                A_bytes: VerificationKeyBytes(A.compress().to_bytes()),
+            },
+        }
+    }
+
+    /// Create a signature on `msg` using this key.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.sign_with_domain(Sha512::default(), &[msg])
+    }
+
+    /// Create a signature on each of `msgs` using this key.
+    ///
+    /// See [`SigningKey::sign_batch`] for details.
+    pub fn sign_batch<M: AsRef<[u8]>>(&self, msgs: &[M]) -> Vec<Signature> {
+        let r_hasher = Sha512::default().chain(&self.prefix[..]);
+        msgs.iter()
+            .map(|msg| {
+                let r = Scalar::from_hash(r_hasher.clone().chain(msg.as_ref()));
+                self.sign_with_nonce(Sha512::default(), r, &[msg.as_ref()])
+            })
+            .collect()
+    }
+
+    /// Create a signature on the concatenation of `bufs` using this key.
+    ///
+    /// See [`SigningKey::sign_vectored`] for details.
+    pub fn sign_vectored(&self, bufs: &[IoSlice<'_>]) -> Signature {
+        self.sign_with_domain(Sha512::default(), bufs)
+    }
+
+    /// Create an Ed25519ph signature on a prehashed message using this key.
+    ///
+    /// See [`SigningKey::sign_prehashed`] for details.
+    pub fn sign_prehashed(
+        &self,
+        prehashed_msg: Sha512,
+        context: Option<&[u8]>,
+    ) -> Result<Signature, Error> {
+        let hasher = crate::prehash::ed25519ph_hasher(context)?;
+        Ok(self.sign_with_domain(hasher, &[&prehashed_msg.finalize()[..]]))
+    }
+
+    /// Create a hedged signature on `msg` using this key, mixing fresh entropy
+    /// from `rng` into the nonce.
+    ///
+    /// See [`SigningKey::sign_with_rng`] for details.
+    pub fn sign_with_rng<R: RngCore + CryptoRng>(&self, mut rng: R, msg: &[u8]) -> Signature {
+        let mut z = [0u8; 32];
+        rng.fill_bytes(&mut z[..]);
+        let r = Scalar::from_hash(
+            Sha512::default()
+                .chain(&self.prefix[..])
+                .chain(&z[..])
+                .chain(msg),
+        );
+        z.zeroize();
+        self.sign_with_nonce(Sha512::default(), r, &[msg])
+    }
+
+    /// Create a signature on the message formed by concatenating `msg`, where
+    /// every hash invocation is prefixed by the state already absorbed into
+    /// `hasher` (empty for pure Ed25519).
+    fn sign_with_domain<M: Deref<Target = [u8]>>(&self, hasher: Sha512, msg: &[M]) -> Signature {
+        let mut r_hasher = hasher.clone().chain(&self.prefix[..]);
+        for part in msg {
+            r_hasher.update(&part[..]);
+        }
+        let r = Scalar::from_hash(r_hasher);
+        self.sign_with_nonce(hasher, r, msg)
+    }
+
+    /// Create a signature on the message formed by concatenating `msg` with
+    /// the nonce `r`, where the challenge hash is prefixed by the state already
+    /// absorbed into `hasher`.
+    #[allow(non_snake_case)]
+    pub(crate) fn sign_with_nonce<M: Deref<Target = [u8]>>(
+        &self,
+        hasher: Sha512,
+        r: Scalar,
+        msg: &[M],
+    ) -> Signature {
+        let R_bytes = (&r * &constants::ED25519_BASEPOINT_TABLE)
+            .compress()
+            .to_bytes();
+
+        let mut k_hasher = hasher.chain(&R_bytes[..]).chain(&self.vk.A_bytes.0[..]);
+        for part in msg {
+            k_hasher.update(&part[..]);
+        }
+        let k = Scalar::from_hash(k_hasher);
+
+        let s_bytes = (r + k * self.s).to_bytes();
+
+        Signature::from_parts(R_bytes, s_bytes)
+    }
+}
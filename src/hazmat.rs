@@ -0,0 +1,34 @@
+//! Low-level signing primitives that are easy to misuse.
+//!
+//! **This module is hazardous material.** The functions here bypass the
+//! deterministic nonce derivation that makes Ed25519 signing safe by default.
+//! Signing two different messages with the same nonce, or with a nonce an
+//! attacker can predict, reveals the signing key. They exist only so that test
+//! harnesses can reproduce known-answer vectors and so that protocol
+//! implementations (e.g. multi-party signing) can supply nonces they derived
+//! themselves.
+//!
+//! Most users should use [`SigningKey::sign`](crate::SigningKey::sign) instead.
+
+use curve25519_dalek::scalar::Scalar;
+use sha2::Sha512;
+
+use crate::{Signature, SigningKey};
+
+/// Create a signature on `msg` using the caller-supplied `nonce`.
+///
+/// The `nonce` is interpreted as a little-endian integer and reduced modulo
+/// `l`, the order of the prime-order subgroup, to obtain the scalar `r`; the
+/// signature's `R` component is `[r]B`. The remainder of the signature is
+/// computed exactly as in [`SigningKey::sign`], so the result verifies with
+/// [`VerificationKey::verify`](crate::VerificationKey::verify).
+///
+/// # Warning
+///
+/// The nonce must be secret, uniformly random (or derived as in RFC 8032),
+/// and never reused for a different message. Violating any of these
+/// requirements leaks the signing key.
+pub fn raw_sign_with_nonce(sk: &SigningKey, nonce: &[u8; 32], msg: &[u8]) -> Signature {
+    let r = Scalar::from_bytes_mod_order(*nonce);
+    sk.expanded.sign_with_nonce(Sha512::default(), r, &[msg])
+}
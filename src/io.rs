@@ -0,0 +1,166 @@
+//! Streaming signing and verification over `std::io`.
+//!
+//! Pure Ed25519 hashes the message twice, once to derive the nonce and once to
+//! compute the challenge, so it cannot sign a message in a single pass without
+//! buffering it. The adapters in this module instead produce Ed25519ph
+//! signatures (see [`SigningKey::sign_prehashed`]), which only need a single
+//! SHA-512 pass over the message and so run in constant memory.
+//!
+//! Verification of ordinary Ed25519 signatures already takes a single pass;
+//! see [`VerificationKey::verify_reader`](crate::VerificationKey::verify_reader).
+
+use std::io::{self, Write};
+
+#[cfg(feature = "futures")]
+use curve25519_dalek::scalar::Scalar;
+#[cfg(feature = "futures")]
+use futures_util::io::{AsyncRead, AsyncReadExt};
+use sha2::{Digest, Sha512};
+
+#[cfg(feature = "futures")]
+use crate::VerificationKey;
+use crate::{Signature, SigningKey};
+
+/// A [`Write`] adapter that signs all bytes written through it.
+///
+/// Bytes are forwarded to the inner writer and absorbed into a SHA-512 hash as
+/// they stream through; [`SigningWriter::finalize`] then produces an Ed25519ph
+/// signature (with no context) on everything that was written successfully.
+/// The signature can be checked with
+/// [`VerificationKey::verify_prehashed`](crate::VerificationKey::verify_prehashed).
+///
+/// ```
+/// # use rand::thread_rng;
+/// # use ed25519_zebra::*;
+/// use sha2::{Digest, Sha512};
+/// use std::io::Write;
+///
+/// let sk = SigningKey::new(thread_rng());
+/// let mut writer = io::SigningWriter::new(&sk, Vec::new());
+/// writer.write_all(b"a large ").unwrap();
+/// writer.write_all(b"payload").unwrap();
+/// let (sig, payload) = writer.finalize();
+///
+/// let prehashed_msg = Sha512::default().chain(&payload);
+/// assert!(VerificationKey::from(&sk)
+///     .verify_prehashed(&sig, prehashed_msg, None)
+///     .is_ok());
+/// ```
+pub struct SigningWriter<'a, W: Write> {
+    sk: &'a SigningKey,
+    hasher: Sha512,
+    inner: W,
+}
+
+impl<'a, W: Write> SigningWriter<'a, W> {
+    /// Wrap `inner`, signing the bytes written to it with `sk`.
+    pub fn new(sk: &'a SigningKey, inner: W) -> Self {
+        SigningWriter {
+            sk,
+            hasher: Sha512::default(),
+            inner,
+        }
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Sign everything written so far, returning the signature and the inner
+    /// writer.
+    pub fn finalize(self) -> (Signature, W) {
+        let sig = self
+            .sk
+            .sign_prehashed(self.hasher, None)
+            .expect("the empty context is always valid");
+        (sig, self.inner)
+    }
+}
+
+impl<'a, W: Write> Write for SigningWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        // Only hash the bytes the inner writer accepted.
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Read `reader` to the end, absorbing its contents into `hasher`.
+#[cfg(feature = "futures")]
+async fn hash_async_reader<R: AsyncRead + Unpin>(
+    mut hasher: Sha512,
+    mut reader: R,
+) -> io::Result<Sha512> {
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) => return Ok(hasher),
+            Ok(n) => hasher.update(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Sign the message read from the asynchronous `reader` with `sk`.
+///
+/// Like [`SigningWriter`], this produces an Ed25519ph signature (with no
+/// context) in a single pass over the message, which can be checked with
+/// [`verify_prehashed_async_reader`] or
+/// [`VerificationKey::verify_prehashed`]. An ordinary Ed25519 signature
+/// cannot be made in a single pass.
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "futures")))]
+pub async fn sign_prehashed_async_reader<R: AsyncRead + Unpin>(
+    sk: &SigningKey,
+    reader: R,
+) -> io::Result<Signature> {
+    let prehashed_msg = hash_async_reader(Sha512::default(), reader).await?;
+    Ok(sk
+        .sign_prehashed(prehashed_msg, None)
+        .expect("the empty context is always valid"))
+}
+
+/// Verify a purported `signature` on the message read from the asynchronous
+/// `reader`.
+///
+/// This is the asynchronous counterpart of
+/// [`VerificationKey::verify_reader`], and checks an ordinary Ed25519
+/// signature with the same criteria and error reporting. Use
+/// [`verify_prehashed_async_reader`] for signatures made by
+/// [`sign_prehashed_async_reader`].
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "futures")))]
+pub async fn verify_async_reader<R: AsyncRead + Unpin>(
+    vk: &VerificationKey,
+    reader: R,
+    signature: &Signature,
+) -> io::Result<()> {
+    let hasher = Sha512::default()
+        .chain(&signature.R_bytes()[..])
+        .chain(&vk.A_bytes.0[..]);
+    let k = Scalar::from_hash(hash_async_reader(hasher, reader).await?);
+    vk.verify_with_k(signature, k)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Verify a purported Ed25519ph `signature` (with no context) on the message
+/// read from the asynchronous `reader`, as made by
+/// [`sign_prehashed_async_reader`].
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "futures")))]
+pub async fn verify_prehashed_async_reader<R: AsyncRead + Unpin>(
+    vk: &VerificationKey,
+    reader: R,
+    signature: &Signature,
+) -> io::Result<()> {
+    let prehashed_msg = hash_async_reader(Sha512::default(), reader).await?;
+    vk.verify_prehashed(signature, prehashed_msg, None)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,409 @@
+//! IPNS names and records.
+//!
+//! An IPNS name is the libp2p peer ID of the key that publishes it, written
+//! as a CIDv1 with the `libp2p-key` codec in base36, `k51...`. The name points
+//! to a value, typically an `/ipfs/<cid>` path, through a signed [record]: a
+//! protobuf `IpnsEntry` whose `data` field is a DAG-CBOR map of the value, its
+//! validity, sequence number, and TTL, and whose `signatureV2` is an Ed25519
+//! signature over `"ipns-signature:" || data`.
+//!
+//! [`Record::sign`] creates V2-only records, which current IPFS
+//! implementations accept; the deprecated V1 signature is neither created nor
+//! checked. [`Record::verify`] checks the V2 signature, that any V1 fields
+//! agree with the signed data, and that the record has not expired.
+//!
+//! [record]: https://specs.ipfs.tech/ipns/ipns-record/
+
+use std::convert::TryFrom;
+
+use ciborium::value::{Integer, Value};
+use thiserror::Error;
+
+use crate::{
+    date::{civil_from_days, days_from_civil},
+    protobuf::{self, Field},
+    Signature, SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The prefix of the data signed by `signatureV2`.
+const SIGNATURE_PREFIX: &[u8] = b"ipns-signature:";
+/// The maximum size of a record.
+const MAX_RECORD_SIZE: usize = 10 * 1024;
+/// The only `ValidityType`, EOL: the record expires at the validity time.
+const VALIDITY_TYPE_EOL: u64 = 0;
+/// The latest validity time that can be written, 9999-12-31T23:59:59Z.
+const MAX_EXPIRES: u64 = 253_402_300_799;
+
+/// The `IpnsEntry` field numbers.
+const FIELD_VALUE: u64 = 1;
+const FIELD_VALIDITY_TYPE: u64 = 3;
+const FIELD_VALIDITY: u64 = 4;
+const FIELD_SEQUENCE: u64 = 5;
+const FIELD_TTL: u64 = 6;
+const FIELD_PUB_KEY: u64 = 7;
+const FIELD_SIGNATURE_V2: u64 = 8;
+const FIELD_DATA: u64 = 9;
+
+/// The CIDv1 prefix of IPNS names: version 1 and the `libp2p-key` codec.
+const CID_PREFIX: [u8; 2] = [0x01, 0x72];
+/// The multibase prefix of base36.
+const MULTIBASE_BASE36: char = 'k';
+const BASE36_ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// An error creating, decoding, or verifying an IPNS record or name.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The record is not a well-formed `IpnsEntry` with a V2 signature.
+    #[error("Malformed IPNS record.")]
+    MalformedRecord,
+    /// The record is larger than the 10 KiB limit.
+    #[error("IPNS record exceeds 10 KiB.")]
+    RecordTooLarge,
+    /// The record's validity type is not EOL.
+    #[error("Unsupported IPNS validity type {0}.")]
+    UnsupportedValidityType(u64),
+    /// The validity time is not an RFC 3339 time from 1970 through 9999.
+    #[error("Malformed IPNS validity time.")]
+    MalformedValidity,
+    /// The record expired before the time of verification.
+    #[error("IPNS record expired.")]
+    Expired,
+    /// The record embeds a public key other than the verification key.
+    #[error("IPNS record public key mismatch.")]
+    MismatchedPublicKey,
+    /// A V1 field of the record disagrees with the signed data.
+    #[error("IPNS record fields disagree with the signed data.")]
+    InconsistentRecord,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+    /// The name is not a `k51...` or `12D3KooW...` Ed25519 IPNS name.
+    #[error("Malformed or unsupported IPNS name.")]
+    MalformedName,
+}
+
+/// The contents of an IPNS record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Record {
+    /// The value the name points to, such as `/ipfs/<cid>`.
+    pub value: Vec<u8>,
+    /// The time the record expires, in seconds since the Unix epoch.
+    pub expires: u64,
+    /// The sequence number, which must increase with each new record.
+    pub sequence: u64,
+    /// How long the record may be cached, in nanoseconds.
+    pub ttl: u64,
+}
+
+impl Record {
+    /// Sign the record, returning the protobuf `IpnsEntry`.
+    pub fn sign(&self, signing_key: &SigningKey) -> Result<Vec<u8>, Error> {
+        if self.expires > MAX_EXPIRES {
+            return Err(Error::MalformedValidity);
+        }
+        let data = self.data();
+        let mut signed = SIGNATURE_PREFIX.to_vec();
+        signed.extend_from_slice(&data);
+        let signature = signing_key.sign(&signed);
+
+        let mut entry = Vec::new();
+        protobuf::write_bytes_field(&mut entry, FIELD_SIGNATURE_V2, &<[u8; 64]>::from(signature));
+        protobuf::write_bytes_field(&mut entry, FIELD_DATA, &data);
+        if entry.len() > MAX_RECORD_SIZE {
+            return Err(Error::RecordTooLarge);
+        }
+        Ok(entry)
+    }
+
+    /// Verify the protobuf `IpnsEntry` `entry` under `vk` at `now`, in
+    /// seconds since the Unix epoch, returning its contents.
+    ///
+    /// When replacing a cached record, the caller should also check that the
+    /// new record's sequence number is higher.
+    pub fn verify(entry: &[u8], vk: &VerificationKey, now: u64) -> Result<Record, Error> {
+        if entry.len() > MAX_RECORD_SIZE {
+            return Err(Error::RecordTooLarge);
+        }
+        let fields = protobuf::read_fields(entry).ok_or(Error::MalformedRecord)?;
+        // No `IpnsEntry` field is repeated.
+        for (i, (number, _)) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|(n, _)| n == number) {
+                return Err(Error::MalformedRecord);
+            }
+        }
+        let field = |number| fields.iter().find(|(n, _)| *n == number).map(|(_, f)| f);
+        let bytes_field = |number| match field(number) {
+            Some(Field::Bytes(bytes)) => Ok(Some(*bytes)),
+            Some(Field::Varint(_)) => Err(Error::MalformedRecord),
+            None => Ok(None),
+        };
+        let varint_field = |number| match field(number) {
+            Some(Field::Varint(value)) => Ok(Some(*value)),
+            Some(Field::Bytes(_)) => Err(Error::MalformedRecord),
+            None => Ok(None),
+        };
+
+        if let Some(pub_key) = bytes_field(FIELD_PUB_KEY)? {
+            let embedded = VerificationKeyBytes::from_libp2p_protobuf(pub_key)
+                .map_err(|_| Error::MismatchedPublicKey)?;
+            if embedded != VerificationKeyBytes::from(*vk) {
+                return Err(Error::MismatchedPublicKey);
+            }
+        }
+
+        let data = bytes_field(FIELD_DATA)?.ok_or(Error::MalformedRecord)?;
+        let signature = bytes_field(FIELD_SIGNATURE_V2)?.ok_or(Error::MalformedRecord)?;
+        let signature = Signature::try_from(signature).map_err(|_| Error::MalformedRecord)?;
+        let mut signed = SIGNATURE_PREFIX.to_vec();
+        signed.extend_from_slice(data);
+        vk.verify(&signature, &signed)
+            .map_err(Error::InvalidSignature)?;
+
+        let (record, validity) = Record::from_data(data)?;
+        let consistent = bytes_field(FIELD_VALUE)?.is_none_or(|v| v == &record.value[..])
+            && bytes_field(FIELD_VALIDITY)?.is_none_or(|v| v == &validity[..])
+            && varint_field(FIELD_VALIDITY_TYPE)?.is_none_or(|v| v == VALIDITY_TYPE_EOL)
+            && varint_field(FIELD_SEQUENCE)?.is_none_or(|v| v == record.sequence)
+            && varint_field(FIELD_TTL)?.is_none_or(|v| v == record.ttl);
+        if !consistent {
+            return Err(Error::InconsistentRecord);
+        }
+        if now > record.expires {
+            return Err(Error::Expired);
+        }
+        Ok(record)
+    }
+
+    /// The DAG-CBOR `data` field of this record, with its keys in DAG-CBOR
+    /// order.
+    fn data(&self) -> Vec<u8> {
+        let entries = vec![
+            (text("TTL"), Value::Integer(Integer::from(self.ttl))),
+            (text("Value"), Value::Bytes(self.value.clone())),
+            (
+                text("Sequence"),
+                Value::Integer(Integer::from(self.sequence)),
+            ),
+            (
+                text("Validity"),
+                Value::Bytes(format_rfc3339(self.expires).into_bytes()),
+            ),
+            (
+                text("ValidityType"),
+                Value::Integer(Integer::from(VALIDITY_TYPE_EOL)),
+            ),
+        ];
+        let mut data = Vec::new();
+        ciborium::ser::into_writer(&Value::Map(entries), &mut data)
+            .expect("writing to a Vec cannot fail");
+        data
+    }
+
+    /// Decode the `data` field of a record, returning the record and its raw
+    /// validity time.
+    fn from_data(data: &[u8]) -> Result<(Record, Vec<u8>), Error> {
+        let mut reader = data;
+        let value: Value =
+            ciborium::de::from_reader(&mut reader).map_err(|_| Error::MalformedRecord)?;
+        if !reader.is_empty() {
+            return Err(Error::MalformedRecord);
+        }
+        let entries = value.into_map().map_err(|_| Error::MalformedRecord)?;
+        let entry = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k.as_text() == Some(key))
+                .map(|(_, v)| v)
+                .ok_or(Error::MalformedRecord)
+        };
+        let bytes = |key| {
+            entry(key)?
+                .as_bytes()
+                .cloned()
+                .ok_or(Error::MalformedRecord)
+        };
+        let integer = |key| {
+            entry(key)?
+                .as_integer()
+                .and_then(|i| u64::try_from(i).ok())
+                .ok_or(Error::MalformedRecord)
+        };
+
+        let validity_type = integer("ValidityType")?;
+        if validity_type != VALIDITY_TYPE_EOL {
+            return Err(Error::UnsupportedValidityType(validity_type));
+        }
+        let validity = bytes("Validity")?;
+        let expires = std::str::from_utf8(&validity)
+            .ok()
+            .and_then(parse_rfc3339)
+            .ok_or(Error::MalformedValidity)?;
+        let record = Record {
+            value: bytes("Value")?,
+            expires,
+            sequence: integer("Sequence")?,
+            ttl: integer("TTL")?,
+        };
+        Ok((record, validity))
+    }
+}
+
+impl VerificationKeyBytes {
+    /// The `k51...` IPNS name published by this key.
+    pub fn to_ipns_name(&self) -> String {
+        let mut cid = CID_PREFIX.to_vec();
+        cid.extend_from_slice(&peer_id_multihash(self));
+        format!("{}{}", MULTIBASE_BASE36, base36_encode(&cid))
+    }
+
+    /// Decode the key of an Ed25519 IPNS name, either in its `k51...` form
+    /// or as a `12D3KooW...` peer ID, optionally prefixed by `/ipns/`.
+    pub fn from_ipns_name(name: &str) -> Result<VerificationKeyBytes, Error> {
+        let name = name.strip_prefix("/ipns/").unwrap_or(name);
+        let encoded = match name.strip_prefix(MULTIBASE_BASE36) {
+            Some(encoded) => encoded,
+            None => {
+                return VerificationKeyBytes::from_peer_id(name).map_err(|_| Error::MalformedName)
+            }
+        };
+        let cid = base36_decode(encoded).ok_or(Error::MalformedName)?;
+        match cid.strip_prefix(&CID_PREFIX[..]) {
+            Some(multihash) if cid.len() == CID_PREFIX.len() + 38 => {
+                let vk_bytes = VerificationKeyBytes::from_libp2p_protobuf(&multihash[2..])
+                    .map_err(|_| Error::MalformedName)?;
+                if multihash[..] != peer_id_multihash(&vk_bytes)[..] {
+                    return Err(Error::MalformedName);
+                }
+                Ok(vk_bytes)
+            }
+            _ => Err(Error::MalformedName),
+        }
+    }
+}
+
+/// The identity multihash of the libp2p `PublicKey` of `vk_bytes`.
+fn peer_id_multihash(vk_bytes: &VerificationKeyBytes) -> Vec<u8> {
+    let key = vk_bytes.to_libp2p_protobuf();
+    let mut multihash = vec![0x00, key.len() as u8];
+    multihash.extend_from_slice(&key);
+    multihash
+}
+
+fn text(text: &str) -> Value {
+    Value::Text(text.to_string())
+}
+
+/// Format `seconds` since the Unix epoch as an RFC 3339 UTC time, as Go's
+/// `time.RFC3339Nano` does for whole seconds.
+fn format_rfc3339(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days(seconds / 86400);
+    let second_of_day = seconds % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60
+    )
+}
+
+/// Parse an RFC 3339 time, `YYYY-MM-DDTHH:MM:SS[.frac](Z|+HH:MM|-HH:MM)`,
+/// into seconds since the Unix epoch, truncating fractional seconds.
+fn parse_rfc3339(time: &str) -> Option<u64> {
+    let bytes = time.as_bytes();
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || bytes[10] != b'T'
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| -> Option<u64> {
+        let digits = time.get(range)?;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    };
+    let days = days_from_civil(number(0..4)?, number(5..7)?, number(8..10)?)?;
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &time[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        rest = &fraction[digits..];
+    }
+    let local = days * 86400 + hour * 3600 + minute * 60 + second;
+    match rest.as_bytes() {
+        b"Z" => Some(local),
+        &[sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+            let offset = two_digits(h1, h2)? * 3600 + two_digits(m1, m2)? * 60;
+            if sign == b'+' {
+                local.checked_sub(offset)
+            } else {
+                local.checked_add(offset)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The value of the two ASCII decimal digits `tens` and `ones`.
+fn two_digits(tens: u8, ones: u8) -> Option<u64> {
+    if tens.is_ascii_digit() && ones.is_ascii_digit() {
+        Some(u64::from(tens - b'0') * 10 + u64::from(ones - b'0'))
+    } else {
+        None
+    }
+}
+
+fn base36_encode(bytes: &[u8]) -> String {
+    // Little-endian base-36 digits.
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 36) as u8;
+            carry /= 36;
+        }
+        while carry > 0 {
+            digits.push((carry % 36) as u8);
+            carry /= 36;
+        }
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|&digit| char::from(BASE36_ALPHABET[digit as usize]))
+        .collect()
+}
+
+fn base36_decode(text: &str) -> Option<Vec<u8>> {
+    // Little-endian bytes.
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in text.bytes() {
+        let mut carry = BASE36_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 36;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.reverse();
+    Some(bytes)
+}
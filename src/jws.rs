@@ -0,0 +1,144 @@
+//! JSON Web Signatures with `alg: EdDSA`.
+//!
+//! [`SigningKey::sign_jws`] and [`VerificationKey::verify_jws`] produce and
+//! consume the [RFC 7515] compact serialization,
+//! `BASE64URL(header).BASE64URL(payload).BASE64URL(signature)`, using the
+//! `EdDSA` algorithm of [RFC 8037]. Only the `EdDSA` algorithm is accepted
+//! when verifying, so a token cannot downgrade itself to `none` or to an HMAC
+//! keyed with the public key.
+//!
+//! [RFC 7515]: https://tools.ietf.org/html/rfc7515
+//! [RFC 8037]: https://tools.ietf.org/html/rfc8037
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::{Signature, SigningKey, VerificationKey};
+
+/// The JWS `alg` value for Ed25519.
+pub const ALGORITHM: &str = "EdDSA";
+
+/// An error decoding or verifying a JWS.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The token does not have three `.`-separated segments.
+    #[error("Malformed JWS compact serialization.")]
+    MalformedToken,
+    /// A segment is not unpadded base64url.
+    #[error("Invalid base64url encoding.")]
+    InvalidBase64,
+    /// The header is not a JSON object of the expected form.
+    #[error("Invalid JWS header.")]
+    InvalidHeader,
+    /// The header names an algorithm other than `EdDSA`.
+    #[error("Unsupported JWS algorithm {0:?}.")]
+    UnsupportedAlgorithm(String),
+    /// The header lists critical extensions, none of which are supported.
+    #[error("Unsupported critical JWS header parameters.")]
+    UnsupportedCritical,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// The JOSE header of a JWS, other than `alg`, which is always `EdDSA`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    /// The media type of the complete JWS, such as `JWT`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    /// The media type of the payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cty: Option<String>,
+    /// A hint identifying the signing key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    /// Any other header parameters.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The header as it appears in the token.
+#[derive(Serialize, Deserialize)]
+struct ProtectedHeader<H> {
+    alg: String,
+    #[serde(flatten)]
+    header: H,
+}
+
+/// Decode the header of `token` without verifying it, for example to read the
+/// `kid` that selects the verification key.
+pub fn decode_header(token: &str) -> Result<Header, Error> {
+    Ok(split(token)?.0)
+}
+
+/// Split `token` into its decoded header, the signing input, the encoded
+/// payload, and the signature.
+fn split(token: &str) -> Result<(Header, &str, &str, Signature), Error> {
+    let mut segments = token.split('.');
+    let (encoded_header, payload, signature) = match (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) {
+        (Some(header), Some(payload), Some(signature), None) => (header, payload, signature),
+        _ => return Err(Error::MalformedToken),
+    };
+
+    let header_json = decode(encoded_header)?;
+    let ProtectedHeader { alg, header } =
+        serde_json::from_slice::<ProtectedHeader<Header>>(&header_json)
+            .map_err(|_| Error::InvalidHeader)?;
+    if alg != ALGORITHM {
+        return Err(Error::UnsupportedAlgorithm(alg));
+    }
+    if header.extra.contains_key("crit") {
+        return Err(Error::UnsupportedCritical);
+    }
+
+    let signature =
+        Signature::try_from(&decode(signature)?[..]).map_err(|_| Error::MalformedToken)?;
+    let signing_input = &token[..encoded_header.len() + 1 + payload.len()];
+    Ok((header, signing_input, payload, signature))
+}
+
+fn decode(segment: &str) -> Result<Vec<u8>, Error> {
+    Base64UrlUnpadded::decode_vec(segment).map_err(|_| Error::InvalidBase64)
+}
+
+impl SigningKey {
+    /// Sign `payload` as a JWS in compact serialization, with the given
+    /// `header` and `alg: EdDSA`.
+    pub fn sign_jws(&self, header: &Header, payload: &[u8]) -> String {
+        let header = ProtectedHeader {
+            alg: ALGORITHM.to_string(),
+            header,
+        };
+        let header_json = serde_json::to_vec(&header).expect("headers serialize to JSON");
+        let mut token = Base64UrlUnpadded::encode_string(&header_json);
+        token.push('.');
+        token.push_str(&Base64UrlUnpadded::encode_string(payload));
+        let signature = self.sign(token.as_bytes());
+        token.push('.');
+        token.push_str(&Base64UrlUnpadded::encode_string(signature.as_ref()));
+        token
+    }
+}
+
+impl VerificationKey {
+    /// Verify a JWS in compact serialization, returning its header and
+    /// payload.
+    ///
+    /// The header must specify `alg: EdDSA` and must not contain `crit`.
+    pub fn verify_jws(&self, token: &str) -> Result<(Header, Vec<u8>), Error> {
+        let (header, signing_input, payload, signature) = split(token)?;
+        self.verify(&signature, signing_input.as_bytes())
+            .map_err(Error::InvalidSignature)?;
+        Ok((header, decode(payload)?))
+    }
+}
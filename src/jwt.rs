@@ -0,0 +1,206 @@
+//! EdDSA-signed JSON Web Tokens.
+//!
+//! A thin [RFC 7519] layer over [`jws`](crate::jws) for the common case of
+//! issuing and checking access tokens. [`SigningKey::sign_jwt`] signs a set of
+//! [`Claims`] with `typ: JWT`, and [`VerificationKey::verify_jwt`] verifies
+//! the signature and then checks the registered `exp`, `nbf`, `aud`, and `iss`
+//! claims as configured by a [`Validation`]. Application-specific claims are
+//! carried in the `custom` field of [`Claims`], which can be any serde type.
+//!
+//! [RFC 7519]: https://tools.ietf.org/html/rfc7519
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::{
+    jws::{self, Header},
+    SigningKey, VerificationKey,
+};
+
+/// An error signing or verifying a JWT.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The token is not a valid JWS.
+    #[error("{0}")]
+    Jws(#[from] jws::Error),
+    /// The payload is not a JSON claims set of the expected type, or the
+    /// claims to be signed do not serialize to a JSON object.
+    #[error("Invalid JWT claims.")]
+    InvalidClaims,
+    /// The `exp` claim is in the past.
+    #[error("Token has expired.")]
+    Expired,
+    /// The `nbf` claim is in the future.
+    #[error("Token is not yet valid.")]
+    NotYetValid,
+    /// The `exp` claim is required but missing.
+    #[error("Token has no expiration time.")]
+    MissingExpiration,
+    /// The `aud` claim does not include the expected audience.
+    #[error("Token is not intended for this audience.")]
+    InvalidAudience,
+    /// The `iss` claim is not the expected issuer.
+    #[error("Token has an unexpected issuer.")]
+    InvalidIssuer,
+}
+
+/// The audience of a token, which RFC 7519 allows to be a single string or an
+/// array of strings.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Audience {
+    /// A single audience.
+    Single(String),
+    /// Several audiences.
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    /// Whether `audience` is one of the audiences.
+    pub fn contains(&self, audience: &str) -> bool {
+        match self {
+            Audience::Single(single) => single == audience,
+            Audience::Multiple(multiple) => multiple.iter().any(|aud| aud == audience),
+        }
+    }
+}
+
+/// The claims set of a JWT: the registered claims of RFC 7519 §4.1, and any
+/// `custom` claims.
+///
+/// Times are in seconds since the Unix epoch.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Claims<T = Map<String, Value>> {
+    /// The issuer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    /// The subject.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    /// The intended audience.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<Audience>,
+    /// The expiration time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+    /// The time before which the token must not be accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<u64>,
+    /// The time at which the token was issued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<u64>,
+    /// A unique identifier for the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// Application-specific claims.
+    #[serde(flatten)]
+    pub custom: T,
+}
+
+/// The checks applied to the claims of a token after its signature verifies.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Validation {
+    /// The current time, in seconds since the Unix epoch.
+    pub now: u64,
+    /// The clock skew, in seconds, tolerated when checking `exp` and `nbf`.
+    pub leeway: u64,
+    /// Whether to reject tokens without an `exp` claim.
+    pub require_exp: bool,
+    /// If set, the `aud` claim must be present and include this audience.
+    pub audience: Option<String>,
+    /// If set, the `iss` claim must be present and equal to this issuer.
+    pub issuer: Option<String>,
+}
+
+impl Validation {
+    /// Validation at the current system time, with one minute of leeway,
+    /// requiring an `exp` claim and not checking the audience or issuer.
+    pub fn new() -> Validation {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Validation::at(now)
+    }
+
+    /// Like [`Validation::new`], but at the given time.
+    pub fn at(now: u64) -> Validation {
+        Validation {
+            now,
+            leeway: 60,
+            require_exp: true,
+            audience: None,
+            issuer: None,
+        }
+    }
+
+    /// Check the registered claims of `claims`.
+    pub fn validate<T>(&self, claims: &Claims<T>) -> Result<(), Error> {
+        match claims.exp {
+            Some(exp) if self.now >= exp.saturating_add(self.leeway) => return Err(Error::Expired),
+            None if self.require_exp => return Err(Error::MissingExpiration),
+            _ => {}
+        }
+        if let Some(nbf) = claims.nbf {
+            if self.now.saturating_add(self.leeway) < nbf {
+                return Err(Error::NotYetValid);
+            }
+        }
+        if let Some(audience) = &self.audience {
+            if !matches!(&claims.aud, Some(aud) if aud.contains(audience)) {
+                return Err(Error::InvalidAudience);
+            }
+        }
+        if let Some(issuer) = &self.issuer {
+            if claims.iss.as_ref() != Some(issuer) {
+                return Err(Error::InvalidIssuer);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Validation {
+    fn default() -> Validation {
+        Validation::new()
+    }
+}
+
+impl SigningKey {
+    /// Sign `claims` as a JWT with `typ: JWT` and `alg: EdDSA`.
+    ///
+    /// Returns [`Error::InvalidClaims`] if the claims cannot be serialized to
+    /// a JSON object, as when `custom` is not a struct or map, or is a map
+    /// with keys that are not strings.
+    pub fn sign_jwt<T: Serialize>(&self, claims: &Claims<T>) -> Result<String, Error> {
+        let header = Header {
+            typ: Some("JWT".to_string()),
+            ..Header::default()
+        };
+        let payload = match serde_json::to_value(claims) {
+            Ok(claims @ Value::Object(_)) => claims.to_string(),
+            _ => return Err(Error::InvalidClaims),
+        };
+        Ok(self.sign_jws(&header, payload.as_bytes()))
+    }
+}
+
+impl VerificationKey {
+    /// Verify a JWT and check its claims against `validation`.
+    ///
+    /// Further application-specific checks can be made on the returned
+    /// claims.
+    pub fn verify_jwt<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        validation: &Validation,
+    ) -> Result<Claims<T>, Error> {
+        let (_, payload) = self.verify_jws(token)?;
+        let claims = serde_json::from_slice(&payload).map_err(|_| Error::InvalidClaims)?;
+        validation.validate(&claims)?;
+        Ok(claims)
+    }
+}
@@ -0,0 +1,21 @@
+//! The key IDs of TUF and in-toto.
+
+use sha2::{Digest, Sha256};
+
+use crate::VerificationKeyBytes;
+
+/// The canonical JSON key object of `vk` used by TUF and in-toto,
+/// `{"keytype":"ed25519","keyval":{"public":"<hex>"},"scheme":"ed25519"}`.
+pub(crate) fn key_json(vk: &VerificationKeyBytes) -> String {
+    format!(
+        r#"{{"keytype":"ed25519","keyval":{{"public":"{}"}},"scheme":"ed25519"}}"#,
+        hex::encode(vk.as_ref())
+    )
+}
+
+/// The key ID of `vk` used by TUF and in-toto: the hex SHA-256 digest of the
+/// canonical JSON form of the key,
+/// `{"keytype":"ed25519","keyval":{"public":"<hex>"},"scheme":"ed25519"}`.
+pub fn key_id(vk: &VerificationKeyBytes) -> String {
+    hex::encode(Sha256::digest(key_json(vk).as_bytes()))
+}
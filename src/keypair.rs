@@ -0,0 +1,185 @@
+use std::convert::TryFrom;
+
+use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{Error, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+/// An Ed25519 signing key together with its verification key.
+///
+/// The two halves are always consistent: a `Keypair` can only be constructed
+/// from a signing key, or by [joining](Keypair::join) a signing key with the
+/// verification key it derives.
+///
+/// The byte encoding of a `Keypair` is the 64-byte `seed || A_bytes` layout
+/// used by libsodium; with the `serde` feature, a `Keypair` is serialized as
+/// its [`SigningKey`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SigningKey"))]
+#[cfg_attr(feature = "serde", serde(into = "SigningKey"))]
+pub struct Keypair {
+    signing: SigningKey,
+    verification: VerificationKey,
+}
+
+impl From<SigningKey> for Keypair {
+    fn from(signing: SigningKey) -> Keypair {
+        let verification = VerificationKey::from(&signing);
+        Keypair {
+            signing,
+            verification,
+        }
+    }
+}
+
+impl From<Keypair> for SigningKey {
+    fn from(keypair: Keypair) -> SigningKey {
+        keypair.signing
+    }
+}
+
+impl From<Keypair> for VerificationKey {
+    fn from(keypair: Keypair) -> VerificationKey {
+        keypair.verification
+    }
+}
+
+impl<'a> From<&'a Keypair> for VerificationKeyBytes {
+    fn from(keypair: &'a Keypair) -> VerificationKeyBytes {
+        keypair.verification.into()
+    }
+}
+
+impl From<Keypair> for [u8; 64] {
+    fn from(keypair: Keypair) -> [u8; 64] {
+        keypair.signing.to_libsodium_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for Keypair {
+    type Error = Error;
+    fn try_from(slice: &[u8]) -> Result<Keypair, Error> {
+        if slice.len() == 64 {
+            let mut bytes = [0u8; 64];
+            bytes[..].copy_from_slice(slice);
+            let keypair = Keypair::try_from(bytes);
+            bytes.zeroize();
+            keypair
+        } else {
+            Err(Error::InvalidSliceLength)
+        }
+    }
+}
+
+impl TryFrom<[u8; 64]> for Keypair {
+    type Error = Error;
+    fn try_from(bytes: [u8; 64]) -> Result<Keypair, Error> {
+        SigningKey::from_libsodium_bytes(&bytes).map(Keypair::from)
+    }
+}
+
+/// Decodes a PKCS#8 v1 or v2 private key; see the corresponding
+/// implementation for [`SigningKey`].
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl<'a> TryFrom<pkcs8::PrivateKeyInfo<'a>> for Keypair {
+    type Error = pkcs8::Error;
+
+    fn try_from(pki: pkcs8::PrivateKeyInfo<'a>) -> pkcs8::Result<Keypair> {
+        SigningKey::try_from(pki).map(Keypair::from)
+    }
+}
+
+/// Encodes the keypair as a PKCS#8 v2 `OneAsymmetricKey` ([RFC 5958]) that
+/// embeds the public key, matching the documents produced by `ring`.
+///
+/// [RFC 5958]: https://tools.ietf.org/html/rfc5958
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl pkcs8::EncodePrivateKey for Keypair {
+    fn to_pkcs8_der(&self) -> pkcs8::Result<pkcs8::SecretDocument> {
+        let public_key = VerificationKeyBytes::from(self);
+        self.signing.to_pkcs8_der_with_public_key(Some(&public_key))
+    }
+}
+
+#[cfg(feature = "pkcs8-encryption")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8-encryption")))]
+impl Keypair {
+    /// Encode this keypair as an encrypted PKCS#8 v2 document, as in
+    /// [`SigningKey::to_pkcs8_encrypted_der`].
+    pub fn to_pkcs8_encrypted_der(
+        &self,
+        passphrase: impl AsRef<[u8]>,
+    ) -> pkcs8::Result<pkcs8::SecretDocument> {
+        crate::signing_key::encrypt_pkcs8(pkcs8::EncodePrivateKey::to_pkcs8_der(self)?, passphrase)
+    }
+
+    /// Encode this keypair as an encrypted PKCS#8 v2 PEM document, as in
+    /// [`SigningKey::to_pkcs8_encrypted_pem`].
+    pub fn to_pkcs8_encrypted_pem(
+        &self,
+        passphrase: impl AsRef<[u8]>,
+        line_ending: pkcs8::LineEnding,
+    ) -> pkcs8::Result<zeroize::Zeroizing<String>> {
+        let document = self.to_pkcs8_encrypted_der(passphrase)?;
+        Ok(document.to_pem(crate::signing_key::ENCRYPTED_PEM_LABEL, line_ending)?)
+    }
+}
+
+impl Zeroize for Keypair {
+    fn zeroize(&mut self) {
+        self.signing.zeroize()
+    }
+}
+
+// The signing key zeroizes itself when dropped.
+impl ZeroizeOnDrop for Keypair {}
+
+impl Keypair {
+    /// Generate a new keypair.
+    pub fn new<R: RngCore + CryptoRng>(rng: R) -> Keypair {
+        SigningKey::new(rng).into()
+    }
+
+    /// Join a signing key and a verification key into a keypair.
+    ///
+    /// Returns [`Error::MalformedPublicKey`] if `verification` is not the
+    /// verification key for `signing`.
+    pub fn join(signing: SigningKey, verification: VerificationKey) -> Result<Keypair, Error> {
+        let keypair = Keypair::from(signing);
+        if VerificationKeyBytes::from(keypair.verification) == verification.into() {
+            Ok(keypair)
+        } else {
+            Err(Error::MalformedPublicKey)
+        }
+    }
+
+    /// Split this keypair into its signing and verification keys.
+    pub fn split(self) -> (SigningKey, VerificationKey) {
+        (self.signing, self.verification)
+    }
+
+    /// The signing half of this keypair.
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.signing
+    }
+
+    /// The verification half of this keypair.
+    pub fn verification_key(&self) -> &VerificationKey {
+        &self.verification
+    }
+
+    /// Create a signature on `msg` using this keypair.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.signing.sign(msg)
+    }
+
+    /// Verify a purported `signature` on the given `msg` using this keypair.
+    ///
+    /// See [`VerificationKey::verify`] for the verification criteria.
+    pub fn verify(&self, signature: &Signature, msg: &[u8]) -> Result<(), Error> {
+        self.verification.verify(signature, msg)
+    }
+}
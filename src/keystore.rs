@@ -0,0 +1,391 @@
+//! Password-encrypted JSON keystores.
+//!
+//! A keystore stores a signing key's 32-byte seed encrypted under a password,
+//! in the layout of Ethereum's [EIP-2335] keystores: a key derivation
+//! function (scrypt or PBKDF2-HMAC-SHA256) stretches the password into a
+//! 32-byte key, whose first half encrypts the seed with AES-128-CTR and whose
+//! second half checks the password, as the SHA-256 checksum of it and the
+//! ciphertext. The keystore also records the verification key, a UUID, and
+//! optional description and derivation path.
+//!
+//! EIP-2335 normalizes passwords to Unicode NFKD form and removes control
+//! characters. This module removes control characters but does not normalize
+//! the password; callers that accept non-ASCII passwords and must read
+//! keystores written by other implementations should normalize them first.
+//!
+//! [EIP-2335]: https://eips.ethereum.org/EIPS/eip-2335
+
+use std::convert::TryFrom;
+
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use hmac::Hmac;
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{SigningKey, VerificationKeyBytes};
+
+/// The keystore format version.
+const VERSION: u64 = 4;
+/// The length of the derived key.
+const DKLEN: u32 = 32;
+
+/// An error encrypting, decoding, or decrypting a keystore.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The keystore is not well-formed JSON of the expected layout.
+    #[error("Malformed keystore.")]
+    MalformedKeystore,
+    /// The keystore is of a version other than 4.
+    #[error("Unsupported keystore version {0}.")]
+    UnsupportedVersion(u64),
+    /// The keystore uses a KDF, PRF, checksum, or cipher other than those
+    /// this module implements.
+    #[error("Unsupported keystore function {0:?}.")]
+    UnsupportedFunction(String),
+    /// The KDF parameters are out of range.
+    #[error("Invalid KDF parameters.")]
+    InvalidKdfParameters,
+    /// The checksum does not match, so the password is wrong.
+    #[error("Incorrect keystore password.")]
+    InvalidPassword,
+    /// The decrypted key does not match the keystore's verification key.
+    #[error("Keystore public key mismatch.")]
+    MismatchedPublicKey,
+}
+
+/// The key derivation function of a keystore, and its parameters.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Kdf {
+    /// scrypt with cost `2^log_n`, block size `r`, and parallelism `p`.
+    Scrypt {
+        /// The base-2 logarithm of the cost parameter `n`.
+        log_n: u8,
+        /// The block size.
+        r: u32,
+        /// The parallelism.
+        p: u32,
+    },
+    /// PBKDF2 with HMAC-SHA256 and `c` iterations.
+    Pbkdf2 {
+        /// The iteration count.
+        c: u32,
+    },
+}
+
+impl Default for Kdf {
+    /// scrypt with the EIP-2335 parameters, `n = 2^18`, `r = 8`, and `p = 1`,
+    /// which use 256 MiB of memory.
+    fn default() -> Kdf {
+        Kdf::Scrypt {
+            log_n: 18,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// A password-encrypted signing key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Keystore {
+    /// A description of the key.
+    pub description: String,
+    /// The key's verification key.
+    pub pubkey: VerificationKeyBytes,
+    /// The derivation path of the key, if it was derived from a seed phrase.
+    pub path: String,
+    /// The keystore's UUID.
+    pub uuid: String,
+    kdf: Kdf,
+    salt: Vec<u8>,
+    checksum: [u8; 32],
+    iv: [u8; 16],
+    ciphertext: [u8; 32],
+}
+
+impl Keystore {
+    /// Encrypt `signing_key` under `password`, deriving the encryption key
+    /// with `kdf`.
+    ///
+    /// The salt, IV, and random UUID are drawn from `rng`.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        signing_key: &SigningKey,
+        password: &str,
+        kdf: Kdf,
+        mut rng: R,
+    ) -> Result<Keystore, Error> {
+        let mut salt = vec![0u8; 32];
+        let mut iv = [0u8; 16];
+        let mut uuid = [0u8; 16];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut iv);
+        rng.fill_bytes(&mut uuid);
+
+        let key = derive_key(kdf, password, &salt)?;
+        let mut ciphertext = [0u8; 32];
+        ciphertext.copy_from_slice(signing_key.as_ref());
+        aes128_ctr(&key[..16], &iv, &mut ciphertext);
+        Ok(Keystore {
+            description: String::new(),
+            pubkey: VerificationKeyBytes::from(signing_key),
+            path: String::new(),
+            uuid: uuid_v4(uuid),
+            kdf,
+            checksum: checksum(&key, &ciphertext),
+            salt,
+            iv,
+            ciphertext,
+        })
+    }
+
+    /// Decrypt the signing key with `password`.
+    pub fn decrypt(&self, password: &str) -> Result<SigningKey, Error> {
+        let key = derive_key(self.kdf, password, &self.salt)?;
+        if !bool::from(checksum(&key, &self.ciphertext).ct_eq(&self.checksum)) {
+            return Err(Error::InvalidPassword);
+        }
+        let mut seed = Zeroizing::new(self.ciphertext);
+        aes128_ctr(&key[..16], &self.iv, &mut seed[..]);
+        let signing_key = SigningKey::from(*seed);
+        if VerificationKeyBytes::from(&signing_key) != self.pubkey {
+            return Err(Error::MismatchedPublicKey);
+        }
+        Ok(signing_key)
+    }
+
+    /// The key derivation function of this keystore.
+    pub fn kdf(&self) -> Kdf {
+        self.kdf
+    }
+
+    /// Decode a keystore from JSON.
+    ///
+    /// The KDF parameters are taken from the keystore, so decrypting an
+    /// untrusted keystore may take as much time and memory as it specifies.
+    pub fn from_json(json: &str) -> Result<Keystore, Error> {
+        let keystore: KeystoreJson =
+            serde_json::from_str(json).map_err(|_| Error::MalformedKeystore)?;
+        if keystore.version != VERSION {
+            return Err(Error::UnsupportedVersion(keystore.version));
+        }
+        let crypto = keystore.crypto;
+        let (kdf, salt) = match (&crypto.kdf.function[..], crypto.kdf.params) {
+            ("scrypt", KdfParamsJson::Scrypt(params)) => {
+                if params.dklen != DKLEN || params.n < 2 || !params.n.is_power_of_two() {
+                    return Err(Error::InvalidKdfParameters);
+                }
+                let kdf = Kdf::Scrypt {
+                    log_n: params.n.trailing_zeros() as u8,
+                    r: params.r,
+                    p: params.p,
+                };
+                (kdf, params.salt)
+            }
+            ("pbkdf2", KdfParamsJson::Pbkdf2(params)) => {
+                if params.prf != "hmac-sha256" {
+                    return Err(Error::UnsupportedFunction(params.prf));
+                }
+                if params.dklen != DKLEN {
+                    return Err(Error::InvalidKdfParameters);
+                }
+                (Kdf::Pbkdf2 { c: params.c }, params.salt)
+            }
+            ("scrypt", _) | ("pbkdf2", _) => return Err(Error::MalformedKeystore),
+            (function, _) => return Err(Error::UnsupportedFunction(function.to_string())),
+        };
+        if crypto.checksum.function != "sha256" {
+            return Err(Error::UnsupportedFunction(crypto.checksum.function));
+        }
+        if crypto.cipher.function != "aes-128-ctr" {
+            return Err(Error::UnsupportedFunction(crypto.cipher.function));
+        }
+
+        Ok(Keystore {
+            description: keystore.description,
+            pubkey: decode_hex(&keystore.pubkey)?,
+            path: keystore.path,
+            uuid: keystore.uuid,
+            kdf,
+            salt: hex::decode(&salt).map_err(|_| Error::MalformedKeystore)?,
+            checksum: decode_hex(&crypto.checksum.message)?,
+            iv: decode_hex(&crypto.cipher.params.iv)?,
+            ciphertext: decode_hex(&crypto.cipher.message)?,
+        })
+    }
+
+    /// Encode this keystore as JSON.
+    pub fn to_json(&self) -> String {
+        let salt = hex::encode(&self.salt);
+        let params = match self.kdf {
+            Kdf::Scrypt { log_n, r, p } => KdfParamsJson::Scrypt(ScryptParamsJson {
+                dklen: DKLEN,
+                n: 1 << log_n,
+                r,
+                p,
+                salt,
+            }),
+            Kdf::Pbkdf2 { c } => KdfParamsJson::Pbkdf2(Pbkdf2ParamsJson {
+                dklen: DKLEN,
+                c,
+                prf: "hmac-sha256".to_string(),
+                salt,
+            }),
+        };
+        let function = match self.kdf {
+            Kdf::Scrypt { .. } => "scrypt",
+            Kdf::Pbkdf2 { .. } => "pbkdf2",
+        };
+        let keystore = KeystoreJson {
+            crypto: CryptoJson {
+                kdf: ModuleJson {
+                    function: function.to_string(),
+                    params,
+                    message: String::new(),
+                },
+                checksum: ModuleJson {
+                    function: "sha256".to_string(),
+                    params: EmptyJson {},
+                    message: hex::encode(self.checksum),
+                },
+                cipher: ModuleJson {
+                    function: "aes-128-ctr".to_string(),
+                    params: CipherParamsJson {
+                        iv: hex::encode(self.iv),
+                    },
+                    message: hex::encode(self.ciphertext),
+                },
+            },
+            description: self.description.clone(),
+            pubkey: hex::encode(self.pubkey.as_ref()),
+            path: self.path.clone(),
+            uuid: self.uuid.clone(),
+            version: VERSION,
+        };
+        serde_json::to_string_pretty(&keystore).expect("keystores serialize to JSON")
+    }
+}
+
+/// Derive the 32-byte decryption and checksum key from `password`.
+fn derive_key(kdf: Kdf, password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, Error> {
+    let password = Zeroizing::new(
+        password
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect::<String>(),
+    );
+    let mut key = Zeroizing::new([0u8; 32]);
+    match kdf {
+        Kdf::Scrypt { log_n, r, p } => {
+            let params = scrypt::Params::new(log_n, r, p, key.len())
+                .map_err(|_| Error::InvalidKdfParameters)?;
+            scrypt::scrypt(password.as_bytes(), salt, &params, &mut key[..])
+                .map_err(|_| Error::InvalidKdfParameters)?;
+        }
+        Kdf::Pbkdf2 { c } => {
+            if c == 0 {
+                return Err(Error::InvalidKdfParameters);
+            }
+            pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, c, &mut key[..]);
+        }
+    }
+    Ok(key)
+}
+
+/// The SHA-256 checksum of the second half of `key` and `ciphertext`.
+fn checksum(key: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&key[16..]);
+    hasher.update(ciphertext);
+    hasher.finalize().into()
+}
+
+/// Apply AES-128-CTR, which both encrypts and decrypts.
+fn aes128_ctr(key: &[u8], iv: &[u8; 16], data: &mut [u8]) {
+    ctr::Ctr128BE::<aes::Aes128>::new_from_slices(key, iv)
+        .expect("key and IV have the correct lengths")
+        .apply_keystream(data);
+}
+
+/// Format 16 random bytes as a version 4 UUID.
+fn uuid_v4(mut bytes: [u8; 16]) -> String {
+    bytes[6] = bytes[6] & 0x0f | 0x40;
+    bytes[8] = bytes[8] & 0x3f | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Decode a fixed-length hex string.
+fn decode_hex<T: for<'a> TryFrom<&'a [u8]>>(hex: &str) -> Result<T, Error> {
+    let bytes = hex::decode(hex).map_err(|_| Error::MalformedKeystore)?;
+    T::try_from(&bytes[..]).map_err(|_| Error::MalformedKeystore)
+}
+
+/// The JSON form of a keystore.
+#[derive(Serialize, Deserialize)]
+struct KeystoreJson {
+    crypto: CryptoJson,
+    #[serde(default)]
+    description: String,
+    pubkey: String,
+    #[serde(default)]
+    path: String,
+    uuid: String,
+    version: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CryptoJson {
+    kdf: ModuleJson<KdfParamsJson>,
+    checksum: ModuleJson<EmptyJson>,
+    cipher: ModuleJson<CipherParamsJson>,
+}
+
+/// A keystore module: a function, its parameters, and its message.
+#[derive(Serialize, Deserialize)]
+struct ModuleJson<P> {
+    function: String,
+    params: P,
+    message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum KdfParamsJson {
+    Scrypt(ScryptParamsJson),
+    Pbkdf2(Pbkdf2ParamsJson),
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScryptParamsJson {
+    dklen: u32,
+    n: u64,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Pbkdf2ParamsJson {
+    dklen: u32,
+    c: u32,
+    prf: String,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EmptyJson {}
+
+#[derive(Serialize, Deserialize)]
+struct CipherParamsJson {
+    iv: String,
+}
@@ -0,0 +1,99 @@
+use std::{convert::TryFrom, sync::OnceLock};
+
+use crate::{Error, Signature, VerificationKey, VerificationKeyBytes};
+
+/// An Ed25519 verification key that is decoded on first use.
+///
+/// Converting [`VerificationKeyBytes`] into a [`VerificationKey`] decompresses
+/// and negates the point up front. A `LazyVerificationKey` holds only the
+/// encoding until a signature is verified, then decodes it once and caches
+/// the result, so services that parse many keys but verify with few of them
+/// don't pay for decompression they never use.
+///
+/// A malformed encoding is only detected on first use, where it is reported
+/// as [`Error::MalformedPublicKey`] by every call. The cache is thread-safe,
+/// so a `LazyVerificationKey` can be shared between threads.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "VerificationKeyBytes"))]
+#[cfg_attr(feature = "serde", serde(into = "VerificationKeyBytes"))]
+pub struct LazyVerificationKey {
+    bytes: VerificationKeyBytes,
+    decoded: OnceLock<Result<VerificationKey, Error>>,
+}
+
+impl core::fmt::Debug for LazyVerificationKey {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_tuple("LazyVerificationKey")
+            .field(&self.bytes)
+            .finish()
+    }
+}
+
+impl From<VerificationKeyBytes> for LazyVerificationKey {
+    fn from(bytes: VerificationKeyBytes) -> LazyVerificationKey {
+        LazyVerificationKey {
+            bytes,
+            decoded: OnceLock::new(),
+        }
+    }
+}
+
+impl From<[u8; 32]> for LazyVerificationKey {
+    fn from(bytes: [u8; 32]) -> LazyVerificationKey {
+        VerificationKeyBytes::from(bytes).into()
+    }
+}
+
+impl From<VerificationKey> for LazyVerificationKey {
+    fn from(vk: VerificationKey) -> LazyVerificationKey {
+        LazyVerificationKey {
+            bytes: vk.A_bytes,
+            decoded: OnceLock::from(Ok(vk)),
+        }
+    }
+}
+
+impl From<LazyVerificationKey> for VerificationKeyBytes {
+    fn from(lazy: LazyVerificationKey) -> VerificationKeyBytes {
+        lazy.bytes
+    }
+}
+
+impl TryFrom<LazyVerificationKey> for VerificationKey {
+    type Error = Error;
+    fn try_from(lazy: LazyVerificationKey) -> Result<VerificationKey, Error> {
+        lazy.verification_key().copied()
+    }
+}
+
+impl AsRef<[u8]> for LazyVerificationKey {
+    fn as_ref(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl LazyVerificationKey {
+    /// The encoded verification key.
+    pub fn as_bytes(&self) -> &VerificationKeyBytes {
+        &self.bytes
+    }
+
+    /// Decode the verification key, or return the cached result of a previous
+    /// decoding.
+    pub fn verification_key(&self) -> Result<&VerificationKey, Error> {
+        self.decoded
+            .get_or_init(|| VerificationKey::try_from(self.bytes))
+            .as_ref()
+            .map_err(|e| *e)
+    }
+
+    /// Verify a purported `signature` on the given `msg`, decoding the key if
+    /// it has not been decoded yet.
+    ///
+    /// Apart from returning [`Error::MalformedPublicKey`] for a malformed key,
+    /// this is equivalent to [`VerificationKey::verify`].
+    pub fn verify(&self, signature: &Signature, msg: &[u8]) -> Result<(), Error> {
+        self.verification_key()?.verify(signature, msg)
+    }
+}
@@ -6,13 +6,167 @@
 
 //! Docs require the `nightly` feature until RFC 1990 lands.
 
+#[cfg(any(
+    all(feature = "u64_backend", feature = "u32_backend"),
+    all(feature = "u64_backend", feature = "simd_backend"),
+    all(feature = "u64_backend", feature = "fiat_u64_backend"),
+    all(feature = "u64_backend", feature = "fiat_u32_backend"),
+    all(feature = "u32_backend", feature = "simd_backend"),
+    all(feature = "u32_backend", feature = "fiat_u64_backend"),
+    all(feature = "u32_backend", feature = "fiat_u32_backend"),
+    all(feature = "simd_backend", feature = "fiat_u64_backend"),
+    all(feature = "simd_backend", feature = "fiat_u32_backend"),
+    all(feature = "fiat_u64_backend", feature = "fiat_u32_backend"),
+))]
+compile_error!(
+    "Only one curve25519-dalek backend feature can be enabled; disable default features to use a backend other than `u64_backend`."
+);
+
+pub mod aggregate;
+#[cfg(any(feature = "pkcs8", feature = "openssh", feature = "x509"))]
+mod armor;
+#[cfg(any(feature = "bech32", feature = "onion", feature = "stellar"))]
+mod base32;
+#[cfg(any(
+    feature = "did-key",
+    feature = "libp2p",
+    feature = "near",
+    feature = "solana",
+    feature = "tezos"
+))]
+mod base58;
 pub mod batch;
+#[cfg(feature = "bech32")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "bech32")))]
+pub mod bech32;
+mod cache;
+#[cfg(feature = "cbor")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "cbor")))]
+pub mod cbor;
+mod compact_signing_key;
+#[cfg(feature = "cose")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "cose")))]
+pub mod cose;
+#[cfg(feature = "cosign")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "cosign")))]
+pub mod cosign;
+#[cfg(feature = "ipns")]
+mod date;
+#[cfg(feature = "did-key")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "did-key")))]
+pub mod did;
+#[cfg(feature = "dnssec")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "dnssec")))]
+pub mod dnssec;
+#[cfg(feature = "dsse")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "dsse")))]
+pub mod dsse;
 mod error;
+mod expanded_signing_key;
+#[cfg(feature = "hazmat")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "hazmat")))]
+pub mod hazmat;
+pub mod io;
+#[cfg(feature = "ipns")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ipns")))]
+pub mod ipns;
+#[cfg(feature = "jws")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "jws")))]
+pub mod jws;
+#[cfg(feature = "jwt")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "jwt")))]
+pub mod jwt;
+#[cfg(any(feature = "dsse", feature = "tuf"))]
+mod key_id;
+mod keypair;
+#[cfg(feature = "keystore")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "keystore")))]
+pub mod keystore;
+mod lazy_verification_key;
+#[cfg(feature = "libp2p")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "libp2p")))]
+pub mod libp2p;
+#[cfg(feature = "minisign")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "minisign")))]
+pub mod minisign;
+#[cfg(feature = "near")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "near")))]
+pub mod near;
+#[cfg(feature = "onion")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "onion")))]
+pub mod onion;
+#[cfg(feature = "openpgp")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "openpgp")))]
+pub mod openpgp;
+#[cfg(feature = "paseto")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "paseto")))]
+pub mod paseto;
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+pub mod pem;
+mod policy;
+pub mod pool;
+#[cfg(feature = "ppk")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ppk")))]
+pub mod ppk;
+mod precomputed;
+mod prehash;
+#[cfg(any(feature = "ipns", feature = "libp2p", feature = "tink"))]
+mod protobuf;
+#[cfg(feature = "saltpack")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "saltpack")))]
+pub mod saltpack;
+#[cfg(feature = "secrecy")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "secrecy")))]
+mod secret;
 mod signature;
+#[cfg(feature = "signify")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "signify")))]
+pub mod signify;
 mod signing_key;
+#[cfg(feature = "solana")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "solana")))]
+pub mod solana;
+#[cfg(feature = "ssb")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ssb")))]
+pub mod ssb;
+#[cfg(feature = "openssh")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "openssh")))]
+pub mod ssh;
+#[cfg(feature = "openssh")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "openssh")))]
+pub mod sshsig;
+#[cfg(feature = "stellar")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "stellar")))]
+pub mod stellar;
+#[cfg(feature = "tendermint")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "tendermint")))]
+pub mod tendermint;
+#[cfg(feature = "tezos")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "tezos")))]
+pub mod tezos;
+#[cfg(feature = "tink")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "tink")))]
+pub mod tink;
+#[cfg(feature = "tuf")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "tuf")))]
+pub mod tuf;
 mod verification_key;
+#[cfg(feature = "x509")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "x509")))]
+pub mod x509;
 
+pub use cache::VerificationCache;
+pub use compact_signing_key::CompactSigningKey;
 pub use error::Error;
+pub use expanded_signing_key::ExpandedSigningKey;
+pub use keypair::Keypair;
+pub use lazy_verification_key::LazyVerificationKey;
+pub use policy::VerificationPolicy;
+pub use precomputed::PrecomputedVerificationKey;
+#[cfg(feature = "secrecy")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "secrecy")))]
+pub use secret::SecretSigningKey;
 pub use signature::Signature;
 pub use signing_key::SigningKey;
 pub use verification_key::{VerificationKey, VerificationKeyBytes};
@@ -0,0 +1,130 @@
+//! libp2p identity keys and peer IDs.
+//!
+//! libp2p exchanges and stores identity keys as the protobuf messages
+//! `PublicKey` and `PrivateKey` of its [peer ID specification][spec], each a
+//! key type (1 for Ed25519) and the key data: the 32-byte verification key,
+//! or the 64 bytes `seed || A_bytes`. A node's peer ID is the identity
+//! multihash of its `PublicKey` message, written in base58 as `12D3KooW...`.
+//!
+//! [spec]: https://github.com/libp2p/specs/blob/master/peer-ids/peer-ids.md
+
+use std::convert::TryFrom;
+
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{
+    base58,
+    protobuf::{self, Field},
+    Keypair, VerificationKeyBytes,
+};
+
+/// The field numbers of `Type` and `Data`.
+const FIELD_TYPE: u64 = 1;
+const FIELD_DATA: u64 = 2;
+/// The protobuf tag of the `Type` field, with the varint wire type.
+const TAG_TYPE: u8 = (FIELD_TYPE as u8) << 3;
+/// The protobuf tag of the `Data` field, with the length-delimited wire type.
+const TAG_DATA: u8 = (FIELD_DATA as u8) << 3 | 2;
+/// The `KeyType` of Ed25519 keys.
+const KEY_TYPE_ED25519: u8 = 1;
+/// The multihash code of the identity hash function.
+const MULTIHASH_IDENTITY: u8 = 0x00;
+
+/// An error decoding a libp2p key or peer ID.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The key is not a well-formed `PublicKey` or `PrivateKey` message.
+    #[error("Malformed libp2p key.")]
+    MalformedKey,
+    /// The key is of a type other than Ed25519.
+    #[error("Unsupported libp2p key type {0}.")]
+    UnsupportedKeyType(u64),
+    /// The public key in the private key does not match its seed.
+    #[error("Inconsistent keypair: {0}")]
+    InconsistentKeypair(crate::Error),
+    /// The peer ID is not valid base58.
+    #[error("Invalid base58 encoding.")]
+    InvalidBase58,
+    /// The peer ID is not an identity multihash of a key, as Ed25519 peer IDs
+    /// are.
+    #[error("Malformed or unsupported peer ID.")]
+    MalformedPeerId,
+}
+
+impl VerificationKeyBytes {
+    /// Encode this key as a libp2p `PublicKey` protobuf message.
+    pub fn to_libp2p_protobuf(&self) -> Vec<u8> {
+        encode(self.as_ref())
+    }
+
+    /// Decode a libp2p `PublicKey` protobuf message.
+    pub fn from_libp2p_protobuf(bytes: &[u8]) -> Result<VerificationKeyBytes, Error> {
+        VerificationKeyBytes::try_from(decode(bytes)?).map_err(|_| Error::MalformedKey)
+    }
+
+    /// The `12D3KooW...` peer ID of the libp2p node with this key.
+    pub fn to_peer_id(&self) -> String {
+        let key = self.to_libp2p_protobuf();
+        let mut multihash = vec![MULTIHASH_IDENTITY, key.len() as u8];
+        multihash.extend_from_slice(&key);
+        base58::encode(&multihash)
+    }
+
+    /// Decode the key of a base58 Ed25519 peer ID.
+    pub fn from_peer_id(peer_id: &str) -> Result<VerificationKeyBytes, Error> {
+        let multihash = base58::decode(peer_id).ok_or(Error::InvalidBase58)?;
+        match &multihash[..] {
+            [MULTIHASH_IDENTITY, len, key @ ..] if usize::from(*len) == key.len() => {
+                VerificationKeyBytes::from_libp2p_protobuf(key)
+            }
+            _ => Err(Error::MalformedPeerId),
+        }
+    }
+}
+
+impl Keypair {
+    /// Encode this keypair as a libp2p `PrivateKey` protobuf message.
+    pub fn to_libp2p_protobuf(&self) -> Zeroizing<Vec<u8>> {
+        let secret_key = Zeroizing::new(self.signing_key().to_libsodium_bytes());
+        Zeroizing::new(encode(&secret_key[..]))
+    }
+
+    /// Decode a libp2p `PrivateKey` protobuf message.
+    pub fn from_libp2p_protobuf(bytes: &[u8]) -> Result<Keypair, Error> {
+        let secret_key = decode(bytes)?;
+        if secret_key.len() != 64 {
+            return Err(Error::MalformedKey);
+        }
+        Keypair::try_from(secret_key).map_err(Error::InconsistentKeypair)
+    }
+}
+
+/// Encode an Ed25519 key message with `data`.
+fn encode(data: &[u8]) -> Vec<u8> {
+    let mut message = vec![TAG_TYPE, KEY_TYPE_ED25519, TAG_DATA, data.len() as u8];
+    message.extend_from_slice(data);
+    message
+}
+
+/// Decode a key message, returning the data of an Ed25519 key.
+///
+/// The fields may appear in either order, but each must appear exactly once.
+fn decode(message: &[u8]) -> Result<&[u8], Error> {
+    let mut key_type = None;
+    let mut data = None;
+    for (number, field) in protobuf::read_fields(message).ok_or(Error::MalformedKey)? {
+        match (number, field) {
+            (FIELD_TYPE, Field::Varint(value)) if key_type.is_none() => key_type = Some(value),
+            (FIELD_DATA, Field::Bytes(value)) if data.is_none() => data = Some(value),
+            _ => return Err(Error::MalformedKey),
+        }
+    }
+    match (key_type, data) {
+        (Some(key_type), _) if key_type != u64::from(KEY_TYPE_ED25519) => {
+            Err(Error::UnsupportedKeyType(key_type))
+        }
+        (Some(_), Some(data)) => Ok(data),
+        _ => Err(Error::MalformedKey),
+    }
+}
@@ -0,0 +1,558 @@
+//! [Minisign] keys and signatures.
+//!
+//! Minisign is a small tool for signing files, widely used to sign software
+//! releases. This module reads and writes its public key, secret key, and
+//! signature files, so that files signed here can be verified with
+//! `minisign -V` and files signed with `minisign -S` can be verified here.
+//!
+//! A minisign signature file carries two signatures: the signature of the file
+//! itself, and a *global signature* over that signature and a *trusted
+//! comment*, typically a timestamp and file name. Both are checked by
+//! [`MinisignSignature::verify`]. The file signature is either of the file
+//! contents ([`Algorithm::Legacy`]) or of their BLAKE2b-512 hash
+//! ([`Algorithm::Prehashed`], the default of current minisign versions).
+//!
+//! Each key pair has a random 8-byte key ID, which is copied into its
+//! signatures so that verifiers can tell which key to use.
+//!
+//! Encrypted secret keys carry their own scrypt `opslimit` and `memlimit`, so
+//! keys asking for more than [`MAX_OPSLIMIT`] or [`MAX_MEMLIMIT`] are rejected
+//! rather than decrypted.
+//!
+//! [Minisign]: https://jedisct1.github.io/minisign/
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64, Encoding};
+use blake2::{
+    digest::{Update, VariableOutput},
+    Blake2b, Digest, VarBlake2b,
+};
+use rand_core::{CryptoRng, RngCore};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{Signature, SigningKey, VerificationKey};
+
+/// The Ed25519 signature algorithm identifier of keys and legacy signatures.
+const ALG_ED25519: &[u8; 2] = b"Ed";
+/// The signature algorithm identifier of prehashed signatures.
+const ALG_ED25519_PREHASHED: &[u8; 2] = b"ED";
+/// The checksum algorithm of secret keys, BLAKE2b-256.
+const CHK_BLAKE2B: &[u8; 2] = b"B2";
+/// The key derivation algorithm of encrypted secret keys, scrypt.
+const KDF_SCRYPT: &[u8; 2] = b"Sc";
+/// The key derivation algorithm of unencrypted secret keys.
+const KDF_NONE: &[u8; 2] = b"\0\0";
+
+const UNTRUSTED_COMMENT: &str = "untrusted comment: ";
+const TRUSTED_COMMENT: &str = "trusted comment: ";
+
+/// The length of a decoded public key: algorithm, key ID, and key.
+const PUBLIC_KEY_LEN: usize = 2 + 8 + 32;
+/// The length of a decoded secret key: algorithms, KDF salt and limits, key
+/// ID, libsodium secret key, and checksum.
+const SECRET_KEY_LEN: usize = 2 + 2 + 2 + 32 + 8 + 8 + KEYNUM_SK_LEN;
+/// The length of the possibly-encrypted key ID, secret key, and checksum.
+const KEYNUM_SK_LEN: usize = 8 + 64 + 32;
+/// The length of a decoded signature: algorithm, key ID, and signature.
+const SIGNATURE_LEN: usize = 2 + 8 + 64;
+
+/// The scrypt `opslimit` used by minisign.
+#[cfg(feature = "minisign-encryption")]
+const OPSLIMIT: u64 = 1 << 25;
+/// The scrypt `memlimit` used by minisign.
+#[cfg(feature = "minisign-encryption")]
+const MEMLIMIT: u64 = 1 << 30;
+/// The largest scrypt `opslimit` accepted when decrypting a secret key.
+///
+/// This is the value written by `minisign -G`.
+pub const MAX_OPSLIMIT: u64 = 1 << 25;
+/// The largest scrypt `memlimit`, in bytes, accepted when decrypting a secret
+/// key.
+///
+/// This is the value written by `minisign -G`, which makes scrypt use 1 GiB.
+pub const MAX_MEMLIMIT: u64 = 1 << 30;
+
+/// An error decoding or verifying a minisign key or signature.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The file does not have the expected lines.
+    #[error("Malformed minisign file: {0}.")]
+    Malformed(&'static str),
+    /// A line is not valid base64.
+    #[error("Invalid base64 encoding.")]
+    InvalidBase64,
+    /// The key or signature is not an Ed25519 one.
+    #[error("Unsupported minisign algorithm.")]
+    UnsupportedAlgorithm,
+    /// The secret key is encrypted with an unsupported key derivation
+    /// function, or encryption support is not enabled.
+    #[error("Unsupported secret key encryption.")]
+    UnsupportedKdf,
+    /// The secret key asks for more than [`MAX_OPSLIMIT`] or
+    /// [`MAX_MEMLIMIT`] in scrypt parameters.
+    #[error("Secret key encryption parameters exceed the accepted limits.")]
+    KdfLimitExceeded,
+    /// The secret key is encrypted, but no password was given.
+    #[error("Secret key is encrypted and requires a password.")]
+    PassphraseRequired,
+    /// The secret key checksum does not match; the password is incorrect.
+    #[error("Incorrect password or corrupted secret key.")]
+    IncorrectPassphrase,
+    /// The public key embedded in the secret key does not match the secret.
+    #[error("Secret key does not match its embedded public key.")]
+    PublicKeyMismatch,
+    /// The signature was made by a key with a different key ID.
+    #[error("Signature was made with a different key.")]
+    KeyIdMismatch,
+    /// A comment contains a line break.
+    #[error("Comments must be a single line.")]
+    InvalidComment,
+    /// The file signature or the global signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// What a minisign signature covers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Algorithm {
+    /// The file contents, as produced by minisign before version 0.11 and by
+    /// `minisign -S -l`.
+    Legacy,
+    /// The BLAKE2b-512 hash of the file contents, the default of current
+    /// minisign versions.
+    Prehashed,
+}
+
+impl Algorithm {
+    fn id(self) -> &'static [u8; 2] {
+        match self {
+            Algorithm::Legacy => ALG_ED25519,
+            Algorithm::Prehashed => ALG_ED25519_PREHASHED,
+        }
+    }
+}
+
+/// A minisign public key.
+#[derive(Copy, Clone, Debug)]
+pub struct PublicKey {
+    /// The key ID.
+    pub key_id: [u8; 8],
+    /// The verification key.
+    pub verification_key: VerificationKey,
+}
+
+impl PublicKey {
+    /// Decode a public key file, as written by `minisign -G`.
+    pub fn decode(text: &str) -> Result<PublicKey, Error> {
+        let mut lines = text.lines();
+        untrusted_comment(lines.next())?;
+        let key = lines.next().ok_or(Error::Malformed("missing public key"))?;
+        if lines.any(|line| !line.trim().is_empty()) {
+            return Err(Error::Malformed("trailing data"));
+        }
+        PublicKey::from_base64(key)
+    }
+
+    /// Decode the base64 public key given to `minisign -P`, the second line of
+    /// a public key file.
+    pub fn from_base64(key: &str) -> Result<PublicKey, Error> {
+        let bytes = decode_base64(key)?;
+        if bytes.len() != PUBLIC_KEY_LEN {
+            return Err(Error::Malformed("wrong public key length"));
+        }
+        if &bytes[..2] != ALG_ED25519 {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&bytes[2..10]);
+        let verification_key = VerificationKey::try_from(&bytes[10..])
+            .map_err(|_| Error::Malformed("invalid public key"))?;
+        Ok(PublicKey {
+            key_id,
+            verification_key,
+        })
+    }
+
+    /// Encode this key as a public key file.
+    pub fn encode(&self) -> String {
+        format!(
+            "{}minisign public key {:016X}\n{}\n",
+            UNTRUSTED_COMMENT,
+            u64::from_le_bytes(self.key_id),
+            self.to_base64()
+        )
+    }
+
+    /// Encode this key in the base64 form given to `minisign -P`.
+    pub fn to_base64(&self) -> String {
+        let mut bytes = Vec::with_capacity(PUBLIC_KEY_LEN);
+        bytes.extend_from_slice(ALG_ED25519);
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(self.verification_key.as_ref());
+        Base64::encode_string(&bytes)
+    }
+}
+
+/// A minisign secret key.
+#[derive(Clone, Debug)]
+pub struct SecretKey {
+    /// The key ID.
+    pub key_id: [u8; 8],
+    /// The signing key.
+    pub signing_key: SigningKey,
+}
+
+impl SecretKey {
+    /// Generate a new key pair with a random key ID.
+    pub fn new<R: RngCore + CryptoRng>(mut rng: R) -> SecretKey {
+        let mut key_id = [0u8; 8];
+        rng.fill_bytes(&mut key_id);
+        SecretKey {
+            key_id,
+            signing_key: SigningKey::new(rng),
+        }
+    }
+
+    /// The public key of this key pair.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            key_id: self.key_id,
+            verification_key: VerificationKey::from(&self.signing_key),
+        }
+    }
+
+    /// Decode a secret key file, as written by `minisign -G`.
+    ///
+    /// Keys created with `minisign -G -W` are unencrypted, and need no
+    /// `password`; others are encrypted with scrypt, and can only be read
+    /// with the `minisign-encryption` feature.
+    pub fn decode(text: &str, password: Option<&[u8]>) -> Result<SecretKey, Error> {
+        let mut lines = text.lines();
+        untrusted_comment(lines.next())?;
+        let key = lines.next().ok_or(Error::Malformed("missing secret key"))?;
+        if lines.any(|line| !line.trim().is_empty()) {
+            return Err(Error::Malformed("trailing data"));
+        }
+        let mut bytes = Zeroizing::new(decode_base64(key)?);
+        if bytes.len() != SECRET_KEY_LEN {
+            return Err(Error::Malformed("wrong secret key length"));
+        }
+        if &bytes[..2] != ALG_ED25519 || &bytes[4..6] != CHK_BLAKE2B {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+
+        let (header, keynum_sk) = bytes.split_at_mut(SECRET_KEY_LEN - KEYNUM_SK_LEN);
+        let encrypted = match &header[2..4] {
+            kdf if kdf == KDF_NONE => false,
+            kdf if kdf == KDF_SCRYPT => {
+                let password = password.ok_or(Error::PassphraseRequired)?;
+                scrypt_xor(password, header, keynum_sk)?;
+                true
+            }
+            _ => return Err(Error::UnsupportedKdf),
+        };
+
+        let (key_id, rest) = keynum_sk.split_at(8);
+        let (sk, chk) = rest.split_at(64);
+        if checksum(key_id, sk)[..] != chk[..] {
+            return Err(if encrypted {
+                Error::IncorrectPassphrase
+            } else {
+                Error::Malformed("checksum mismatch")
+            });
+        }
+        let mut keypair = [0u8; 64];
+        keypair.copy_from_slice(sk);
+        let signing_key =
+            SigningKey::from_libsodium_bytes(&keypair).map_err(|_| Error::PublicKeyMismatch);
+        keypair.zeroize();
+        Ok(SecretKey {
+            key_id: <[u8; 8]>::try_from(key_id).expect("key IDs are 8 bytes"),
+            signing_key: signing_key?,
+        })
+    }
+
+    /// Encode this key as an unencrypted secret key file, as written by
+    /// `minisign -G -W`.
+    pub fn encode(&self) -> Zeroizing<String> {
+        let bytes = self.secret_key_bytes(KDF_NONE, &[0u8; 32], 0, 0);
+        secret_key_file("minisign secret key", &bytes)
+    }
+
+    /// Encode this key as a secret key file encrypted under `password`, with
+    /// the scrypt parameters used by `minisign -G`.
+    ///
+    /// The salt is drawn from `rng`. Deriving the key requires 1 GiB of
+    /// memory, as it does in minisign.
+    #[cfg(feature = "minisign-encryption")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "minisign-encryption")))]
+    pub fn encode_encrypted<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        password: &[u8],
+    ) -> Result<Zeroizing<String>, Error> {
+        let mut salt = [0u8; 32];
+        rng.fill_bytes(&mut salt);
+        let mut bytes = self.secret_key_bytes(KDF_SCRYPT, &salt, OPSLIMIT, MEMLIMIT);
+        let (header, keynum_sk) = bytes.split_at_mut(SECRET_KEY_LEN - KEYNUM_SK_LEN);
+        scrypt_xor(password, header, keynum_sk)?;
+        Ok(secret_key_file("minisign encrypted secret key", &bytes))
+    }
+
+    /// Encode the unencrypted binary secret key.
+    fn secret_key_bytes(
+        &self,
+        kdf: &[u8; 2],
+        salt: &[u8; 32],
+        opslimit: u64,
+        memlimit: u64,
+    ) -> Zeroizing<Vec<u8>> {
+        let mut keypair = self.signing_key.to_libsodium_bytes();
+        let mut bytes = Zeroizing::new(Vec::with_capacity(SECRET_KEY_LEN));
+        bytes.extend_from_slice(ALG_ED25519);
+        bytes.extend_from_slice(kdf);
+        bytes.extend_from_slice(CHK_BLAKE2B);
+        bytes.extend_from_slice(salt);
+        bytes.extend_from_slice(&opslimit.to_le_bytes());
+        bytes.extend_from_slice(&memlimit.to_le_bytes());
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(&keypair);
+        bytes.extend_from_slice(&checksum(&self.key_id, &keypair));
+        keypair.zeroize();
+        bytes
+    }
+
+    /// Sign `message` with the given `trusted_comment`, as `minisign -S -t`.
+    ///
+    /// `minisign` itself uses a trusted comment of the form
+    /// `timestamp:<unix time>\tfile:<file name>\thashed`. Returns
+    /// [`Error::InvalidComment`] if the comment contains a line break.
+    pub fn sign(
+        &self,
+        algorithm: Algorithm,
+        message: &[u8],
+        trusted_comment: &str,
+    ) -> Result<MinisignSignature, Error> {
+        check_comment(trusted_comment)?;
+        let signature = match algorithm {
+            Algorithm::Legacy => self.signing_key.sign(message),
+            Algorithm::Prehashed => self.signing_key.sign(&Blake2b::digest(message)),
+        };
+        let global_signature = self
+            .signing_key
+            .sign(&global_message(&signature, trusted_comment));
+        Ok(MinisignSignature {
+            untrusted_comment: "signature from minisign secret key".to_string(),
+            algorithm,
+            key_id: self.key_id,
+            signature,
+            trusted_comment: trusted_comment.to_string(),
+            global_signature,
+        })
+    }
+}
+
+/// A minisign signature file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinisignSignature {
+    /// The untrusted comment, which is not covered by any signature.
+    pub untrusted_comment: String,
+    algorithm: Algorithm,
+    key_id: [u8; 8],
+    signature: Signature,
+    trusted_comment: String,
+    global_signature: Signature,
+}
+
+impl MinisignSignature {
+    /// What the signature covers.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// The ID of the key that made the signature.
+    pub fn key_id(&self) -> [u8; 8] {
+        self.key_id
+    }
+
+    /// The trusted comment. This is only authentic once
+    /// [`MinisignSignature::verify`] has succeeded.
+    pub fn trusted_comment(&self) -> &str {
+        &self.trusted_comment
+    }
+
+    /// Verify this signature and its trusted comment on `message`, as
+    /// `minisign -V`.
+    pub fn verify(&self, public_key: &PublicKey, message: &[u8]) -> Result<(), Error> {
+        if self.key_id != public_key.key_id {
+            return Err(Error::KeyIdMismatch);
+        }
+        let vk = &public_key.verification_key;
+        match self.algorithm {
+            Algorithm::Legacy => vk.verify(&self.signature, message),
+            Algorithm::Prehashed => vk.verify(&self.signature, &Blake2b::digest(message)),
+        }
+        .map_err(Error::InvalidSignature)?;
+        vk.verify(
+            &self.global_signature,
+            &global_message(&self.signature, &self.trusted_comment),
+        )
+        .map_err(Error::InvalidSignature)
+    }
+
+    /// Decode a signature file, as written by `minisign -S`.
+    pub fn decode(text: &str) -> Result<MinisignSignature, Error> {
+        let mut lines = text.lines();
+        let untrusted_comment = untrusted_comment(lines.next())?.to_string();
+        let bytes = decode_base64(lines.next().ok_or(Error::Malformed("missing signature"))?)?;
+        let trusted_comment = lines
+            .next()
+            .and_then(|line| line.strip_prefix(TRUSTED_COMMENT))
+            .ok_or(Error::Malformed("missing trusted comment"))?
+            .trim_end_matches('\r')
+            .to_string();
+        let global_signature = decode_base64(
+            lines
+                .next()
+                .ok_or(Error::Malformed("missing global signature"))?,
+        )?;
+        if lines.any(|line| !line.trim().is_empty()) {
+            return Err(Error::Malformed("trailing data"));
+        }
+
+        if bytes.len() != SIGNATURE_LEN {
+            return Err(Error::Malformed("wrong signature length"));
+        }
+        let algorithm = match &bytes[..2] {
+            alg if alg == ALG_ED25519 => Algorithm::Legacy,
+            alg if alg == ALG_ED25519_PREHASHED => Algorithm::Prehashed,
+            _ => return Err(Error::UnsupportedAlgorithm),
+        };
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&bytes[2..10]);
+        let signature = Signature::try_from(&bytes[10..]).expect("signatures are 64 bytes");
+        let global_signature = Signature::try_from(&global_signature[..])
+            .map_err(|_| Error::Malformed("wrong global signature length"))?;
+        Ok(MinisignSignature {
+            untrusted_comment,
+            algorithm,
+            key_id,
+            signature,
+            trusted_comment,
+            global_signature,
+        })
+    }
+
+    /// Encode this signature as a signature file.
+    pub fn encode(&self) -> Result<String, Error> {
+        check_comment(&self.untrusted_comment)?;
+        let mut bytes = Vec::with_capacity(SIGNATURE_LEN);
+        bytes.extend_from_slice(self.algorithm.id());
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(self.signature.as_ref());
+        Ok(format!(
+            "{}{}\n{}\n{}{}\n{}\n",
+            UNTRUSTED_COMMENT,
+            self.untrusted_comment,
+            Base64::encode_string(&bytes),
+            TRUSTED_COMMENT,
+            self.trusted_comment,
+            Base64::encode_string(self.global_signature.as_ref()),
+        ))
+    }
+}
+
+/// Encode a binary secret key as a secret key file.
+fn secret_key_file(comment: &str, bytes: &[u8]) -> Zeroizing<String> {
+    let encoded = Zeroizing::new(Base64::encode_string(bytes));
+    Zeroizing::new(format!("{}{}\n{}\n", UNTRUSTED_COMMENT, comment, *encoded))
+}
+
+/// The message covered by the global signature.
+fn global_message(signature: &Signature, trusted_comment: &str) -> Vec<u8> {
+    let mut message = Vec::with_capacity(64 + trusted_comment.len());
+    message.extend_from_slice(signature.as_ref());
+    message.extend_from_slice(trusted_comment.as_bytes());
+    message
+}
+
+/// The BLAKE2b-256 checksum of a secret key.
+fn checksum(key_id: &[u8], keypair: &[u8]) -> [u8; 32] {
+    let mut chk = [0u8; 32];
+    let mut hasher = VarBlake2b::new(32).expect("32 is a valid BLAKE2b output size");
+    hasher.update(ALG_ED25519);
+    hasher.update(key_id);
+    hasher.update(keypair);
+    hasher.finalize_variable(|hash| chk.copy_from_slice(hash));
+    chk
+}
+
+/// Encrypt or decrypt the key ID, secret key, and checksum of a secret key in
+/// place, using the KDF parameters in `header`.
+#[cfg(feature = "minisign-encryption")]
+fn scrypt_xor(password: &[u8], header: &[u8], keynum_sk: &mut [u8]) -> Result<(), Error> {
+    let salt = &header[6..38];
+    let opslimit = u64::from_le_bytes(<[u8; 8]>::try_from(&header[38..46]).unwrap());
+    let memlimit = u64::from_le_bytes(<[u8; 8]>::try_from(&header[46..54]).unwrap());
+    if opslimit > MAX_OPSLIMIT || memlimit > MAX_MEMLIMIT {
+        return Err(Error::KdfLimitExceeded);
+    }
+    let (log_n, r, p) = scrypt_params(opslimit, memlimit);
+    // The output length is set by `stream`; the length in `Params` is only
+    // used for password hash strings, and must be at most 64 bytes.
+    let params = scrypt::Params::new(log_n, r, p, 32).map_err(|_| Error::UnsupportedKdf)?;
+    let mut stream = Zeroizing::new([0u8; KEYNUM_SK_LEN]);
+    scrypt::scrypt(password, salt, &params, &mut stream[..]).map_err(|_| Error::UnsupportedKdf)?;
+    for (byte, key) in keynum_sk.iter_mut().zip(stream.iter()) {
+        *byte ^= key;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "minisign-encryption"))]
+fn scrypt_xor(_password: &[u8], _header: &[u8], _keynum_sk: &mut [u8]) -> Result<(), Error> {
+    Err(Error::UnsupportedKdf)
+}
+
+/// Convert libsodium's `opslimit` and `memlimit` to scrypt's `log_n`, `r`, and
+/// `p`, as `crypto_pwhash_scryptsalsa208sha256` does.
+#[cfg(feature = "minisign-encryption")]
+fn scrypt_params(opslimit: u64, memlimit: u64) -> (u8, u32, u32) {
+    let opslimit = opslimit.max(32768);
+    let r = 8u64;
+    let max_n = if opslimit < memlimit / 32 {
+        opslimit / (r * 4)
+    } else {
+        memlimit / (r * 128)
+    };
+    let log_n = (1..63)
+        .find(|&log_n| 1u64 << log_n > max_n / 2)
+        .unwrap_or(63);
+    let p = if opslimit < memlimit / 32 {
+        1
+    } else {
+        ((opslimit / 4) >> log_n).min(0x3fff_ffff) / r
+    };
+    (log_n as u8, r as u32, p as u32)
+}
+
+/// Strip the prefix of an untrusted comment line.
+fn untrusted_comment(line: Option<&str>) -> Result<&str, Error> {
+    line.and_then(|line| line.strip_prefix(UNTRUSTED_COMMENT))
+        .map(|comment| comment.trim_end_matches('\r'))
+        .ok_or(Error::Malformed("missing untrusted comment"))
+}
+
+fn check_comment(comment: &str) -> Result<(), Error> {
+    if comment.contains(&['\n', '\r'][..]) {
+        Err(Error::InvalidComment)
+    } else {
+        Ok(())
+    }
+}
+
+fn decode_base64(line: &str) -> Result<Vec<u8>, Error> {
+    Base64::decode_vec(line.trim_end()).map_err(|_| Error::InvalidBase64)
+}
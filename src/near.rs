@@ -0,0 +1,82 @@
+//! NEAR key strings.
+//!
+//! NEAR writes keys as `ed25519:<base58>` strings, where a public key is the
+//! 32-byte verification key and a secret key is the 64 bytes
+//! `seed || A_bytes`. [`VerificationKeyBytes::to_near_public_key`],
+//! [`VerificationKeyBytes::from_near_public_key`],
+//! [`Keypair::to_near_secret_key`], and [`Keypair::from_near_secret_key`]
+//! convert between these strings and the types of this crate.
+//!
+//! As in `near-crypto`, a key without a `<type>:` prefix is taken to be an
+//! Ed25519 key.
+
+use std::convert::TryFrom;
+
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{base58, Keypair, VerificationKeyBytes};
+
+/// The key type of Ed25519 keys.
+const KEY_TYPE: &str = "ed25519";
+
+/// An error decoding a NEAR key string.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The key is of a type other than `ed25519`, such as `secp256k1`.
+    #[error("Unsupported key type {0:?}.")]
+    UnsupportedKeyType(String),
+    /// The key is not valid base58.
+    #[error("Invalid base58 encoding.")]
+    InvalidBase58,
+    /// The decoded key is not of the expected length.
+    #[error("Malformed NEAR key.")]
+    MalformedKey,
+    /// The public key in the secret key does not match its seed.
+    #[error("Inconsistent keypair: {0}")]
+    InconsistentKeypair(crate::Error),
+}
+
+impl VerificationKeyBytes {
+    /// Encode this key as an `ed25519:<base58>` NEAR public key.
+    pub fn to_near_public_key(&self) -> String {
+        format!("{}:{}", KEY_TYPE, base58::encode(self.as_ref()))
+    }
+
+    /// Decode a NEAR public key.
+    pub fn from_near_public_key(key: &str) -> Result<VerificationKeyBytes, Error> {
+        let bytes = decode(key)?;
+        VerificationKeyBytes::try_from(&bytes[..]).map_err(|_| Error::MalformedKey)
+    }
+}
+
+impl Keypair {
+    /// Encode this keypair as an `ed25519:<base58>` NEAR secret key.
+    pub fn to_near_secret_key(&self) -> Zeroizing<String> {
+        let mut bytes = self.signing_key().to_libsodium_bytes();
+        let key = Zeroizing::new(format!("{}:{}", KEY_TYPE, base58::encode(&bytes)));
+        bytes.zeroize();
+        key
+    }
+
+    /// Decode a NEAR secret key.
+    pub fn from_near_secret_key(key: &str) -> Result<Keypair, Error> {
+        let bytes = decode(key)?;
+        if bytes.len() != 64 {
+            return Err(Error::MalformedKey);
+        }
+        Keypair::try_from(&bytes[..]).map_err(Error::InconsistentKeypair)
+    }
+}
+
+/// Decode the base58 data of an Ed25519 key string.
+fn decode(key: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let data = match key.split_once(':') {
+        Some((KEY_TYPE, data)) => data,
+        Some((key_type, _)) => return Err(Error::UnsupportedKeyType(key_type.to_string())),
+        None => key,
+    };
+    base58::decode(data)
+        .map(Zeroizing::new)
+        .ok_or(Error::InvalidBase58)
+}
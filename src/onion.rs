@@ -0,0 +1,151 @@
+//! Tor v3 onion service keys.
+//!
+//! A v3 onion service is identified by its Ed25519 key. Its `.onion` address
+//! is `base32(PUBKEY || CHECKSUM || VERSION)`, where `VERSION` is `0x03` and
+//! `CHECKSUM` is the first two bytes of
+//! `SHA3-256(".onion checksum" || PUBKEY || VERSION)`, as specified in
+//! [`rend-spec-v3`][spec] §6.
+//!
+//! Tor stores the service's key in its `HiddenServiceDir` as
+//! `hs_ed25519_secret_key` and `hs_ed25519_public_key`: a 32-byte header
+//! followed by the 64-byte [`ExpandedSigningKey`] or 32-byte public key.
+//! Tor never stores the seed, so the secret key file can only be read as an
+//! [`ExpandedSigningKey`].
+//!
+//! [spec]: https://spec.torproject.org/rend-spec-v3
+
+use std::convert::TryFrom;
+
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{base32, ExpandedSigningKey, VerificationKey, VerificationKeyBytes};
+
+/// The suffix of an onion service address.
+const SUFFIX: &str = ".onion";
+/// The onion service protocol version encoded in v3 addresses.
+const VERSION: u8 = 0x03;
+/// The 35 address bytes, `PUBKEY || CHECKSUM || VERSION`, in base32.
+const ADDRESS_LEN: usize = 56;
+/// The NUL-padded header of `hs_ed25519_secret_key`.
+const SECRET_KEY_HEADER: &[u8; 32] = b"== ed25519v1-secret: type0 ==\0\0\0";
+/// The NUL-padded header of `hs_ed25519_public_key`.
+const PUBLIC_KEY_HEADER: &[u8; 32] = b"== ed25519v1-public: type0 ==\0\0\0";
+
+/// An error parsing an onion address or a Tor key file.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The address is not 56 base32 characters, optionally followed by
+    /// `.onion`.
+    #[error("Malformed onion address.")]
+    MalformedAddress,
+    /// The address is for an onion service version other than 3.
+    #[error("Unsupported onion service version {0}.")]
+    UnsupportedVersion(u8),
+    /// The checksum embedded in the address does not match its key.
+    #[error("Onion address checksum mismatch.")]
+    InvalidChecksum,
+    /// The address does not encode a valid Ed25519 point.
+    #[error("Invalid onion service key: {0}")]
+    InvalidKey(crate::Error),
+    /// The key file does not have the expected header and length.
+    #[error("Malformed Tor key file.")]
+    MalformedKeyFile,
+}
+
+impl VerificationKeyBytes {
+    /// The v3 `.onion` address of the onion service with this key.
+    pub fn to_onion_address(&self) -> String {
+        let mut bytes = [0u8; 35];
+        bytes[..32].copy_from_slice(self.as_ref());
+        bytes[32..34].copy_from_slice(&checksum(self)[..]);
+        bytes[34] = VERSION;
+        let mut address = base32::encode(&bytes, base32::LOWERCASE);
+        address.push_str(SUFFIX);
+        address
+    }
+
+    /// The onion service key encoded in a v3 `.onion` address.
+    ///
+    /// The address is case-insensitive, and the `.onion` suffix is optional.
+    /// Any subdomain labels before the service's own label are ignored.
+    pub fn from_onion_address(address: &str) -> Result<VerificationKeyBytes, Error> {
+        let address = address.to_ascii_lowercase();
+        let address = address.strip_suffix(SUFFIX).unwrap_or(&address);
+        let label = address.rsplit('.').next().unwrap_or(address);
+        if label.len() != ADDRESS_LEN {
+            return Err(Error::MalformedAddress);
+        }
+        let bytes = base32::decode(label, base32::LOWERCASE).ok_or(Error::MalformedAddress)?;
+        if bytes[34] != VERSION {
+            return Err(Error::UnsupportedVersion(bytes[34]));
+        }
+        let vk_bytes = VerificationKeyBytes::try_from(&bytes[..32])
+            .expect("32 bytes is a valid VerificationKeyBytes length");
+        if bytes[32..34] != checksum(&vk_bytes)[..] {
+            return Err(Error::InvalidChecksum);
+        }
+        Ok(vk_bytes)
+    }
+
+    /// Encode this key as the contents of Tor's `hs_ed25519_public_key` file.
+    pub fn to_tor_public_key_file(&self) -> [u8; 64] {
+        let mut file = [0u8; 64];
+        file[..32].copy_from_slice(PUBLIC_KEY_HEADER);
+        file[32..].copy_from_slice(self.as_ref());
+        file
+    }
+
+    /// Decode the contents of Tor's `hs_ed25519_public_key` file.
+    pub fn from_tor_public_key_file(file: &[u8]) -> Result<VerificationKeyBytes, Error> {
+        match file.strip_prefix(&PUBLIC_KEY_HEADER[..]) {
+            Some(key) => VerificationKeyBytes::try_from(key).map_err(|_| Error::MalformedKeyFile),
+            None => Err(Error::MalformedKeyFile),
+        }
+    }
+}
+
+impl VerificationKey {
+    /// The v3 `.onion` address of the onion service with this key.
+    pub fn to_onion_address(&self) -> String {
+        VerificationKeyBytes::from(*self).to_onion_address()
+    }
+
+    /// The onion service key encoded in a v3 `.onion` address.
+    ///
+    /// See [`VerificationKeyBytes::from_onion_address`]; the key must also be
+    /// a valid Ed25519 point.
+    pub fn from_onion_address(address: &str) -> Result<VerificationKey, Error> {
+        let vk_bytes = VerificationKeyBytes::from_onion_address(address)?;
+        VerificationKey::try_from(vk_bytes).map_err(Error::InvalidKey)
+    }
+}
+
+impl ExpandedSigningKey {
+    /// Encode this key as the contents of Tor's `hs_ed25519_secret_key` file.
+    pub fn to_tor_secret_key_file(&self) -> Zeroizing<Vec<u8>> {
+        let mut file = Zeroizing::new(Vec::with_capacity(96));
+        file.extend_from_slice(SECRET_KEY_HEADER);
+        file.extend_from_slice(&Zeroizing::new(<[u8; 64]>::from(self.clone()))[..]);
+        file
+    }
+
+    /// Decode the contents of Tor's `hs_ed25519_secret_key` file.
+    pub fn from_tor_secret_key_file(file: &[u8]) -> Result<ExpandedSigningKey, Error> {
+        match file.strip_prefix(&SECRET_KEY_HEADER[..]) {
+            Some(key) => ExpandedSigningKey::try_from(key).map_err(|_| Error::MalformedKeyFile),
+            None => Err(Error::MalformedKeyFile),
+        }
+    }
+}
+
+/// The address checksum of `vk_bytes`.
+fn checksum(vk_bytes: &VerificationKeyBytes) -> [u8; 2] {
+    let hash = Sha3_256::new()
+        .chain(b".onion checksum")
+        .chain(vk_bytes.as_ref())
+        .chain([VERSION])
+        .finalize();
+    [hash[0], hash[1]]
+}
@@ -0,0 +1,318 @@
+//! OpenPGP v4 keys and detached signatures.
+//!
+//! This module writes Ed25519 keys as OpenPGP v4 key packets using the EdDSA
+//! public key algorithm (22), and makes detached OpenPGP signatures over
+//! files, so that artifacts signed with this crate can be checked with
+//! `gpg --verify` after importing the signer's [certificate].
+//!
+//! An OpenPGP key is identified by a fingerprint that covers both the key and
+//! its creation time, so keys here are a [`SigningKey`] or
+//! [`VerificationKeyBytes`] together with a creation time in seconds since the
+//! Unix epoch. The same key with a different creation time is a different
+//! OpenPGP key.
+//!
+//! The formats are described in [RFC 4880] and, for EdDSA, in
+//! [RFC 9580 §5.5.5.5][eddsa] (the "legacy" EdDSA algorithm it retains).
+//!
+//! [certificate]: SecretKey::to_certificate
+//! [RFC 4880]: https://www.rfc-editor.org/rfc/rfc4880
+//! [eddsa]: https://www.rfc-editor.org/rfc/rfc9580#section-5.5.5.5
+
+use base64ct::{Base64, Encoding};
+use sha1::Sha1;
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+use crate::{SigningKey, VerificationKeyBytes};
+
+/// The OpenPGP public key algorithm identifier of EdDSA.
+pub const ALGORITHM: u8 = 22;
+
+/// The OID of Ed25519 in OpenPGP, 1.3.6.1.4.1.11591.15.1.
+const ED25519_OID: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0xda, 0x47, 0x0f, 0x01];
+/// The prefix of a native-encoded EdDSA point.
+const NATIVE_POINT: u8 = 0x40;
+/// The OpenPGP hash algorithm identifier of SHA-512.
+const HASH_SHA512: u8 = 10;
+
+const TAG_SIGNATURE: u8 = 2;
+const TAG_SECRET_KEY: u8 = 5;
+const TAG_PUBLIC_KEY: u8 = 6;
+const TAG_USER_ID: u8 = 13;
+
+const SIG_BINARY_DOCUMENT: u8 = 0x00;
+const SIG_POSITIVE_CERTIFICATION: u8 = 0x13;
+
+const SUBPACKET_CREATION_TIME: u8 = 2;
+const SUBPACKET_ISSUER: u8 = 16;
+const SUBPACKET_KEY_FLAGS: u8 = 27;
+const SUBPACKET_ISSUER_FINGERPRINT: u8 = 33;
+/// The key flags of a primary key that may certify and sign.
+const KEY_FLAGS_CERTIFY_SIGN: u8 = 0x03;
+
+/// The type of data in an ASCII-armored block.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Armor {
+    /// A certificate, from [`SecretKey::to_certificate`].
+    PublicKey,
+    /// A transferable secret key, from [`SecretKey::to_transferable_secret_key`].
+    PrivateKey,
+    /// A signature, from [`SecretKey::sign`].
+    Signature,
+}
+
+impl Armor {
+    fn label(self) -> &'static str {
+        match self {
+            Armor::PublicKey => "PGP PUBLIC KEY BLOCK",
+            Armor::PrivateKey => "PGP PRIVATE KEY BLOCK",
+            Armor::Signature => "PGP SIGNATURE",
+        }
+    }
+}
+
+/// Apply OpenPGP ASCII armor, as written by `gpg --armor`, to binary OpenPGP
+/// `data`.
+pub fn armor(kind: Armor, data: &[u8]) -> String {
+    let body = Base64::encode_string(data);
+    let mut armored = format!("-----BEGIN {}-----\n\n", kind.label());
+    let mut rest = &body[..];
+    while !rest.is_empty() {
+        let (line, tail) = rest.split_at(64.min(rest.len()));
+        armored.push_str(line);
+        armored.push('\n');
+        rest = tail;
+    }
+    armored.push('=');
+    armored.push_str(&Base64::encode_string(&crc24(data).to_be_bytes()[1..]));
+    armored.push_str(&format!("\n-----END {}-----\n", kind.label()));
+    armored
+}
+
+/// An Ed25519 OpenPGP v4 public key.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PublicKey {
+    /// The key.
+    pub key: VerificationKeyBytes,
+    /// The creation time of the key, in seconds since the Unix epoch.
+    pub created: u32,
+}
+
+impl PublicKey {
+    /// The v4 fingerprint of this key, as shown by `gpg --fingerprint`.
+    pub fn fingerprint(&self) -> [u8; 20] {
+        let body = self.packet_body();
+        let mut fingerprint = [0u8; 20];
+        fingerprint.copy_from_slice(&hash_key(Sha1::new(), &body).finalize());
+        fingerprint
+    }
+
+    /// The key ID of this key, the low 64 bits of its fingerprint.
+    pub fn key_id(&self) -> [u8; 8] {
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&self.fingerprint()[12..]);
+        key_id
+    }
+
+    /// Encode this key as a public key packet.
+    pub fn to_packet(&self) -> Vec<u8> {
+        packet(TAG_PUBLIC_KEY, &self.packet_body())
+    }
+
+    /// The body of a public key packet: version, creation time, algorithm,
+    /// curve OID, and point.
+    fn packet_body(&self) -> Vec<u8> {
+        let mut point = [NATIVE_POINT; 33];
+        point[1..].copy_from_slice(self.key.as_ref());
+        let mut body = vec![4];
+        body.extend_from_slice(&self.created.to_be_bytes());
+        body.push(ALGORITHM);
+        body.push(ED25519_OID.len() as u8);
+        body.extend_from_slice(ED25519_OID);
+        put_mpi(&mut body, &point);
+        body
+    }
+}
+
+/// An Ed25519 OpenPGP v4 secret key.
+#[derive(Clone, Debug)]
+pub struct SecretKey {
+    /// The signing key.
+    pub signing_key: SigningKey,
+    /// The creation time of the key, in seconds since the Unix epoch.
+    pub created: u32,
+}
+
+impl SecretKey {
+    /// The public key of this key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            key: VerificationKeyBytes::from(&self.signing_key),
+            created: self.created,
+        }
+    }
+
+    /// Encode this key's certificate (a "transferable public key") for
+    /// `gpg --import`: its public key packet, a User ID packet for `user_id`,
+    /// and a self-signature binding the two.
+    ///
+    /// `user_id` is conventionally of the form `Name <email@example.com>`.
+    pub fn to_certificate(&self, user_id: &str) -> Vec<u8> {
+        let public_key = self.public_key();
+        let mut certificate = public_key.to_packet();
+        certificate.extend_from_slice(&packet(TAG_USER_ID, user_id.as_bytes()));
+        certificate.extend_from_slice(&self.certify(user_id));
+        certificate
+    }
+
+    /// Encode this key, unencrypted, as a transferable secret key for
+    /// `gpg --import`: its secret key packet, a User ID packet for `user_id`,
+    /// and a self-signature binding the two.
+    pub fn to_transferable_secret_key(&self, user_id: &str) -> Zeroizing<Vec<u8>> {
+        let mut body = Zeroizing::new(self.public_key().packet_body());
+        // No string-to-key: the secret is stored in the clear.
+        body.push(0);
+        let secret_start = body.len();
+        put_mpi(&mut body, self.signing_key.as_ref());
+        let checksum = body[secret_start..]
+            .iter()
+            .fold(0u16, |sum, &byte| sum.wrapping_add(byte.into()));
+        body.extend_from_slice(&checksum.to_be_bytes());
+
+        let mut key = Zeroizing::new(packet(TAG_SECRET_KEY, &body));
+        key.extend_from_slice(&packet(TAG_USER_ID, user_id.as_bytes()));
+        key.extend_from_slice(&self.certify(user_id));
+        key
+    }
+
+    /// Make a detached signature on `message` at time `signed_at`, in seconds
+    /// since the Unix epoch, as `gpg --detach-sign`.
+    ///
+    /// The signature is a binary signature packet; use [`armor`] with
+    /// [`Armor::Signature`] for the `.asc` form of `gpg --armor`.
+    pub fn sign(&self, signed_at: u32, message: &[u8]) -> Vec<u8> {
+        self.signature_packet(
+            SIG_BINARY_DOCUMENT,
+            signed_at,
+            &[],
+            Sha512::new().chain(message),
+        )
+    }
+
+    /// The positive certification self-signature over `user_id`, made at the
+    /// key's creation time.
+    fn certify(&self, user_id: &str) -> Vec<u8> {
+        let body = self.public_key().packet_body();
+        let hasher = hash_key(Sha512::new(), &body)
+            .chain([0xb4u8])
+            .chain((user_id.len() as u32).to_be_bytes())
+            .chain(user_id);
+        let key_flags = subpacket(SUBPACKET_KEY_FLAGS, &[KEY_FLAGS_CERTIFY_SIGN]);
+        self.signature_packet(SIG_POSITIVE_CERTIFICATION, self.created, &key_flags, hasher)
+    }
+
+    /// Make a v4 signature packet of type `signature_type`, given a hasher
+    /// that has already absorbed the signed data.
+    fn signature_packet(
+        &self,
+        signature_type: u8,
+        signed_at: u32,
+        extra_subpackets: &[u8],
+        hasher: Sha512,
+    ) -> Vec<u8> {
+        let public_key = self.public_key();
+        let mut issuer_fingerprint = vec![4];
+        issuer_fingerprint.extend_from_slice(&public_key.fingerprint());
+
+        let mut hashed = subpacket(SUBPACKET_CREATION_TIME, &signed_at.to_be_bytes());
+        hashed.extend_from_slice(extra_subpackets);
+        hashed.extend_from_slice(&subpacket(
+            SUBPACKET_ISSUER_FINGERPRINT,
+            &issuer_fingerprint,
+        ));
+        let unhashed = subpacket(SUBPACKET_ISSUER, &public_key.key_id());
+
+        let mut body = vec![4, signature_type, ALGORITHM, HASH_SHA512];
+        body.extend_from_slice(&(hashed.len() as u16).to_be_bytes());
+        body.extend_from_slice(&hashed);
+        let digest = hasher
+            .chain(&body)
+            .chain([4u8, 0xff])
+            .chain((body.len() as u32).to_be_bytes())
+            .finalize();
+        let signature = <[u8; 64]>::from(self.signing_key.sign(&digest));
+
+        body.extend_from_slice(&(unhashed.len() as u16).to_be_bytes());
+        body.extend_from_slice(&unhashed);
+        body.extend_from_slice(&digest[..2]);
+        put_mpi(&mut body, &signature[..32]);
+        put_mpi(&mut body, &signature[32..]);
+        packet(TAG_SIGNATURE, &body)
+    }
+}
+
+/// Absorb a public key packet body into `hasher`, as for fingerprints and
+/// key signatures.
+fn hash_key<D: Digest>(hasher: D, body: &[u8]) -> D {
+    hasher
+        .chain([0x99u8])
+        .chain((body.len() as u16).to_be_bytes())
+        .chain(body)
+}
+
+/// Encode a packet with a new-format header.
+fn packet(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0xc0 | tag];
+    match body.len() {
+        len if len < 192 => packet.push(len as u8),
+        len if len < 8384 => {
+            let len = len - 192;
+            packet.push((len >> 8) as u8 + 192);
+            packet.push(len as u8);
+        }
+        len => {
+            packet.push(0xff);
+            packet.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// Encode a signature subpacket.
+fn subpacket(subpacket_type: u8, data: &[u8]) -> Vec<u8> {
+    let mut subpacket = vec![data.len() as u8 + 1, subpacket_type];
+    subpacket.extend_from_slice(data);
+    subpacket
+}
+
+/// Append `bytes`, a big-endian integer, as a multiprecision integer: a bit
+/// count followed by the integer without leading zero bytes.
+fn put_mpi(out: &mut Vec<u8>, bytes: &[u8]) {
+    let start = bytes
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    let bits = match bytes.first() {
+        Some(&first) => (bytes.len() - 1) * 8 + (8 - first.leading_zeros() as usize),
+        None => 0,
+    };
+    out.extend_from_slice(&(bits as u16).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// The CRC-24 checksum of ASCII armor.
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc = 0x00b7_04ce_u32;
+    for &byte in data {
+        crc ^= u32::from(byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= 0x0186_4cfb;
+            }
+        }
+    }
+    crc & 0x00ff_ffff
+}
@@ -0,0 +1,120 @@
+//! PASETO `v4.public` tokens.
+//!
+//! [`SigningKey::sign_paseto`] and [`VerificationKey::verify_paseto`] produce
+//! and consume [PASETO] version 4 public tokens,
+//! `v4.public.BASE64URL(message || signature)[.BASE64URL(footer)]`, where the
+//! Ed25519 signature covers the pre-authentication encoding (PAE) of the
+//! header, message, footer, and an optional implicit assertion. Unlike JWS,
+//! the version and purpose fix the algorithm, so there is no header to
+//! negotiate it.
+//!
+//! The footer is authenticated but not encrypted, and is typically used for a
+//! key identifier, which [`decode_footer`] reads before verification. An
+//! implicit assertion is authenticated but not included in the token, so the
+//! verifier must supply the same value as the signer.
+//!
+//! [PASETO]: https://github.com/paseto-standard/paseto-spec/blob/master/docs/01-Protocol-Versions/Version4.md
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use thiserror::Error;
+
+use crate::{Signature, SigningKey, VerificationKey};
+
+/// The header of `v4.public` tokens.
+pub const HEADER: &str = "v4.public.";
+
+/// An error decoding or verifying a PASETO.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The token is not a `v4.public` token.
+    #[error("Not a v4.public PASETO.")]
+    UnsupportedHeader,
+    /// The token does not have the expected segments, or its payload is too
+    /// short to hold a signature.
+    #[error("Malformed PASETO.")]
+    MalformedToken,
+    /// A segment is not canonical unpadded base64url.
+    #[error("Invalid base64url encoding.")]
+    InvalidBase64,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// Decode the footer of `token` without verifying it, for example to read the
+/// key identifier that selects the verification key.
+pub fn decode_footer(token: &str) -> Result<Vec<u8>, Error> {
+    Ok(split(token)?.2)
+}
+
+/// Split `token` into its decoded message, signature, and footer.
+fn split(token: &str) -> Result<(Vec<u8>, Signature, Vec<u8>), Error> {
+    let body = token.strip_prefix(HEADER).ok_or(Error::UnsupportedHeader)?;
+    let (payload, footer) = match body.split_once('.') {
+        Some((payload, footer)) if !footer.is_empty() => (payload, decode(footer)?),
+        Some(_) => return Err(Error::MalformedToken),
+        None => (body, Vec::new()),
+    };
+    let mut message = decode(payload)?;
+    if message.len() < 64 {
+        return Err(Error::MalformedToken);
+    }
+    let signature = Signature::try_from(&message.split_off(message.len() - 64)[..])
+        .map_err(|_| Error::MalformedToken)?;
+    Ok((message, signature, footer))
+}
+
+fn decode(segment: &str) -> Result<Vec<u8>, Error> {
+    Base64UrlUnpadded::decode_vec(segment).map_err(|_| Error::InvalidBase64)
+}
+
+/// The pre-authentication encoding of `pieces`: the number of pieces, then
+/// each piece prefixed by its length, as little-endian 64-bit integers.
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut encoded = (pieces.len() as u64).to_le_bytes().to_vec();
+    for piece in pieces {
+        encoded.extend_from_slice(&(piece.len() as u64).to_le_bytes());
+        encoded.extend_from_slice(piece);
+    }
+    encoded
+}
+
+impl SigningKey {
+    /// Sign `message` as a `v4.public` PASETO with the given `footer` and
+    /// `implicit_assertion`, either of which may be empty.
+    ///
+    /// The message of a PASETO is conventionally a JSON object of claims.
+    pub fn sign_paseto(&self, message: &[u8], footer: &[u8], implicit_assertion: &[u8]) -> String {
+        let signed = pae(&[HEADER.as_bytes(), message, footer, implicit_assertion]);
+        let mut payload = message.to_vec();
+        payload.extend_from_slice(&<[u8; 64]>::from(self.sign(&signed)));
+
+        let mut token = HEADER.to_string();
+        token.push_str(&Base64UrlUnpadded::encode_string(&payload));
+        if !footer.is_empty() {
+            token.push('.');
+            token.push_str(&Base64UrlUnpadded::encode_string(footer));
+        }
+        token
+    }
+}
+
+impl VerificationKey {
+    /// Verify a `v4.public` PASETO made with `implicit_assertion`, returning
+    /// its message and footer.
+    ///
+    /// The caller should check the footer if it expects a particular value.
+    pub fn verify_paseto(
+        &self,
+        token: &str,
+        implicit_assertion: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let (message, signature, footer) = split(token)?;
+        let signed = pae(&[HEADER.as_bytes(), &message, &footer, implicit_assertion]);
+        self.verify(&signature, &signed)
+            .map_err(Error::InvalidSignature)?;
+        Ok((message, footer))
+    }
+}
@@ -0,0 +1,243 @@
+//! Lenient PEM and DER import of keys from other implementations.
+//!
+//! The [`DecodePrivateKey`](pkcs8::DecodePrivateKey) and
+//! [`DecodePublicKey`](pkcs8::spki::DecodePublicKey) implementations enabled by
+//! the `pkcs8` feature follow RFC 7468 and RFC 5958 strictly. Keys found in the
+//! wild often deviate from them in harmless ways, so
+//! [`SigningKey::from_pem`] and [`VerificationKeyBytes::from_pem`] accept:
+//!
+//! * PEM bodies wrapped at any line length (or not at all), with `\r\n` line
+//!   endings, indentation, or explanatory text around the encapsulation
+//!   boundaries;
+//! * PKCS#8 v1 `PrivateKeyInfo` and v2 `OneAsymmetricKey` documents, with or
+//!   without attributes, and with or without the public key;
+//! * the public key tagged either implicitly, as in RFC 5958, or explicitly,
+//!   as produced by `ring`.
+//!
+//! Failures are reported as a [`DecodeError`] identifying which structural
+//! expectation was not met.
+
+use std::convert::TryFrom;
+
+use pkcs8::{
+    der::{
+        asn1::{AnyRef, BitStringRef, OctetStringRef},
+        Decode, Reader, SliceReader, Tag, TagNumber,
+    },
+    spki::{self, SubjectPublicKeyInfoRef},
+    AlgorithmIdentifierRef, ObjectIdentifier,
+};
+use thiserror::Error;
+
+use crate::{
+    armor::{self, ArmorError},
+    signing_key::ALGORITHM_ID,
+    SigningKey, VerificationKeyBytes,
+};
+
+/// An error describing why a PEM or DER key could not be decoded.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum DecodeError {
+    /// No `-----BEGIN ...-----` encapsulation boundary was found.
+    #[error("Missing PEM BEGIN boundary.")]
+    MissingPemBoundary,
+    /// The `-----END ...-----` encapsulation boundary was missing, or its
+    /// label did not match the `BEGIN` boundary.
+    #[error("Missing or mismatched PEM END boundary for {0:?}.")]
+    MismatchedPemBoundary(String),
+    /// The PEM label does not describe the expected kind of key.
+    #[error("Unexpected PEM label {0:?}.")]
+    UnexpectedPemLabel(String),
+    /// The PEM body is not valid base64.
+    #[error("Invalid base64 in PEM body.")]
+    InvalidBase64,
+    /// The document is not well-formed DER.
+    #[error("Malformed DER: {0}.")]
+    Asn1(pkcs8::der::Error),
+    /// The PKCS#8 version is neither v1 nor v2.
+    #[error("Unsupported PKCS#8 version {0}.")]
+    UnsupportedVersion(u8),
+    /// The key is for an algorithm other than `id-Ed25519`.
+    #[error("Unexpected algorithm {0}, expected id-Ed25519 (1.3.101.112).")]
+    UnexpectedAlgorithm(ObjectIdentifier),
+    /// The `id-Ed25519` algorithm identifier has parameters, which must be
+    /// absent.
+    #[error("Unexpected id-Ed25519 algorithm parameters.")]
+    UnexpectedParameters,
+    /// A `OneAsymmetricKey` contains a field other than the attributes and
+    /// public key.
+    #[error("Unexpected field with tag {0} in PKCS#8 document.")]
+    UnexpectedField(Tag),
+    /// The private key is not a 32-byte `CurvePrivateKey` OCTET STRING.
+    #[error("Malformed CurvePrivateKey.")]
+    MalformedPrivateKey,
+    /// The public key is not a 32-byte BIT STRING.
+    #[error("Malformed public key.")]
+    MalformedPublicKey,
+    /// The public key in a v2 document does not match the private key.
+    #[error("Public key does not match private key.")]
+    PublicKeyMismatch,
+}
+
+impl From<ArmorError> for DecodeError {
+    fn from(err: ArmorError) -> DecodeError {
+        match err {
+            ArmorError::MissingBoundary => DecodeError::MissingPemBoundary,
+            ArmorError::MismatchedBoundary(label) => DecodeError::MismatchedPemBoundary(label),
+            ArmorError::InvalidBase64 => DecodeError::InvalidBase64,
+        }
+    }
+}
+
+impl From<pkcs8::der::Error> for DecodeError {
+    fn from(err: pkcs8::der::Error) -> DecodeError {
+        DecodeError::Asn1(err)
+    }
+}
+
+impl From<DecodeError> for pkcs8::Error {
+    fn from(err: DecodeError) -> pkcs8::Error {
+        match err {
+            DecodeError::Asn1(err) => pkcs8::Error::Asn1(err),
+            DecodeError::UnexpectedAlgorithm(oid) => spki::Error::OidUnknown { oid }.into(),
+            DecodeError::UnexpectedParameters => pkcs8::Error::ParametersMalformed,
+            _ => pkcs8::Error::KeyMalformed,
+        }
+    }
+}
+
+impl From<DecodeError> for spki::Error {
+    fn from(err: DecodeError) -> spki::Error {
+        match err {
+            DecodeError::Asn1(err) => spki::Error::Asn1(err),
+            DecodeError::UnexpectedAlgorithm(oid) => spki::Error::OidUnknown { oid },
+            _ => spki::Error::KeyMalformed,
+        }
+    }
+}
+
+impl SigningKey {
+    /// Decode a signing key from a PEM `PRIVATE KEY` document, accepting the
+    /// variants described in the [module documentation](crate::pem).
+    pub fn from_pem(pem: &str) -> Result<SigningKey, DecodeError> {
+        let (label, der) = armor::decode(pem)?;
+        match label {
+            "PRIVATE KEY" => SigningKey::from_der(&der),
+            _ => Err(DecodeError::UnexpectedPemLabel(label.to_string())),
+        }
+    }
+
+    /// Decode a signing key from a DER-encoded PKCS#8 v1 or v2 document,
+    /// accepting the variants described in the [module
+    /// documentation](crate::pem).
+    pub fn from_der(der: &[u8]) -> Result<SigningKey, DecodeError> {
+        let document = AnyRef::from_der(der)?;
+        document.tag().assert_eq(Tag::Sequence)?;
+        let mut reader = SliceReader::new(document.value())?;
+
+        let version: u8 = reader.decode()?;
+        if version > 1 {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let algorithm: AlgorithmIdentifierRef<'_> = reader.decode()?;
+        let private_key: OctetStringRef<'_> = reader.decode()?;
+
+        let mut public_key = None;
+        while !reader.is_finished() {
+            let field: AnyRef<'_> = reader.decode()?;
+            match field.tag() {
+                // Attributes are ignored.
+                Tag::ContextSpecific { number, .. } if number == TagNumber::N0 => {}
+                // RFC 5958 tags the public key implicitly, so the field holds
+                // the contents of a BIT STRING.
+                Tag::ContextSpecific {
+                    constructed: false,
+                    number,
+                } if number == TagNumber::N1 => match field.value() {
+                    [0, key @ ..] => public_key = Some(key),
+                    _ => return Err(DecodeError::MalformedPublicKey),
+                },
+                // `ring` tags it explicitly, so the field holds a BIT STRING.
+                Tag::ContextSpecific {
+                    constructed: true,
+                    number,
+                } if number == TagNumber::N1 => {
+                    let key = BitStringRef::from_der(field.value())
+                        .map_err(|_| DecodeError::MalformedPublicKey)?;
+                    public_key = Some(key.as_bytes().ok_or(DecodeError::MalformedPublicKey)?);
+                }
+                tag => return Err(DecodeError::UnexpectedField(tag)),
+            }
+        }
+
+        signing_key_from_parts(algorithm, private_key.as_bytes(), public_key)
+    }
+}
+
+impl VerificationKeyBytes {
+    /// Decode a verification key from a PEM `PUBLIC KEY` document, accepting
+    /// the variants described in the [module documentation](crate::pem).
+    pub fn from_pem(pem: &str) -> Result<VerificationKeyBytes, DecodeError> {
+        let (label, der) = armor::decode(pem)?;
+        match label {
+            "PUBLIC KEY" => VerificationKeyBytes::from_der(&der),
+            _ => Err(DecodeError::UnexpectedPemLabel(label.to_string())),
+        }
+    }
+
+    /// Decode a verification key from a DER-encoded `SubjectPublicKeyInfo`.
+    ///
+    /// Like `From<[u8; 32]>`, this does not check that the key encodes a curve
+    /// point; that happens when converting to a
+    /// [`VerificationKey`](crate::VerificationKey).
+    pub fn from_der(der: &[u8]) -> Result<VerificationKeyBytes, DecodeError> {
+        let info = SubjectPublicKeyInfoRef::from_der(der)?;
+        verification_key_bytes_from_parts(info.algorithm, info.subject_public_key)
+    }
+}
+
+/// Check the components of a PKCS#8 document and decode the signing key.
+///
+/// The `private_key` is the DER encoding of the `CurvePrivateKey`, and the
+/// `public_key` (if present) is the contents of the public key BIT STRING.
+pub(crate) fn signing_key_from_parts(
+    algorithm: AlgorithmIdentifierRef<'_>,
+    private_key: &[u8],
+    public_key: Option<&[u8]>,
+) -> Result<SigningKey, DecodeError> {
+    check_algorithm(algorithm)?;
+    let sk = OctetStringRef::from_der(private_key)
+        .ok()
+        .and_then(|seed| SigningKey::try_from(seed.as_bytes()).ok())
+        .ok_or(DecodeError::MalformedPrivateKey)?;
+
+    match public_key {
+        Some(key) if key.len() != 32 => Err(DecodeError::MalformedPublicKey),
+        Some(key) if key != VerificationKeyBytes::from(&sk).as_ref() => {
+            Err(DecodeError::PublicKeyMismatch)
+        }
+        _ => Ok(sk),
+    }
+}
+
+/// Check the components of a `SubjectPublicKeyInfo` and decode the key.
+pub(crate) fn verification_key_bytes_from_parts(
+    algorithm: AlgorithmIdentifierRef<'_>,
+    subject_public_key: BitStringRef<'_>,
+) -> Result<VerificationKeyBytes, DecodeError> {
+    check_algorithm(algorithm)?;
+    subject_public_key
+        .as_bytes()
+        .and_then(|key| VerificationKeyBytes::try_from(key).ok())
+        .ok_or(DecodeError::MalformedPublicKey)
+}
+
+fn check_algorithm(algorithm: AlgorithmIdentifierRef<'_>) -> Result<(), DecodeError> {
+    if algorithm.oid != ALGORITHM_ID.oid {
+        Err(DecodeError::UnexpectedAlgorithm(algorithm.oid))
+    } else if algorithm.parameters.is_some() {
+        Err(DecodeError::UnexpectedParameters)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+/// The acceptance criteria used when verifying a signature.
+///
+/// Ed25519 implementations disagree on edge cases such as non-canonical point
+/// encodings and points of small order, so a signature accepted by one may be
+/// rejected by another. This selects which implementation's behavior to
+/// match; see [`VerificationKey::verify_with_policy`](crate::VerificationKey::verify_with_policy).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerificationPolicy {
+    /// The [ZIP215] rules used by [`VerificationKey::verify`](crate::VerificationKey::verify):
+    /// non-canonical point encodings and small-order points are accepted, and
+    /// the cofactored verification equation is used, so that batch
+    /// verification always agrees with individual verification.
+    ///
+    /// [ZIP215]: https://github.com/zcash/zips/blob/master/zip-0215.rst
+    #[default]
+    Zip215,
+    /// Strict RFC 8032 rules, as in
+    /// [`VerificationKey::verify_strict`](crate::VerificationKey::verify_strict)
+    /// and `ed25519-dalek`'s `verify_strict`: non-canonical encodings and
+    /// small-order points are rejected, and the cofactorless verification
+    /// equation is used.
+    Rfc8032Strict,
+    /// The rules of libsodium's `crypto_sign_verify_detached`: the
+    /// verification key must be canonically encoded, neither it nor `R` may
+    /// be of small order, and the recomputed `R` must encode to exactly
+    /// `R_bytes` under the cofactorless verification equation.
+    ///
+    /// This accepts the same signatures as
+    /// [`VerificationPolicy::Rfc8032Strict`], but a non-canonical `R` is
+    /// reported as [`Error::InvalidSignature`](crate::Error::InvalidSignature),
+    /// as libsodium does not distinguish it from a failed equation.
+    Libsodium,
+}
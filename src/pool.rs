@@ -0,0 +1,137 @@
+//! A pool of worker threads for signing.
+//!
+//! Signing is CPU-bound, so a server that signs many independent requests can
+//! saturate all cores by handing them to a [`SigningPool`], which owns the
+//! signing key and a fixed set of worker threads, instead of managing threads
+//! in each caller.
+//!
+//! # Example
+//! ```
+//! # use ed25519_zebra::*;
+//! let sk = SigningKey::new(rand::thread_rng());
+//! let vk = VerificationKey::from(&sk);
+//! let pool = pool::SigningPool::new(sk, 4);
+//! let pending = (0..16u8)
+//!     .map(|i| pool.submit(vec![i; 32]))
+//!     .collect::<Vec<_>>();
+//! for (i, pending) in pending.into_iter().enumerate() {
+//!     assert!(vk.verify(&pending.wait(), &[i as u8; 32]).is_ok());
+//! }
+//! ```
+
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use crate::{Signature, SigningKey};
+
+type Job = (Vec<u8>, mpsc::Sender<Signature>);
+
+/// A signing key shared by a fixed set of worker threads.
+///
+/// The key is zeroized when the pool and all of its workers have been
+/// dropped. Dropping the pool waits for the workers to finish any submitted
+/// requests.
+pub struct SigningPool {
+    jobs: Option<mpsc::Sender<Job>>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl core::fmt::Debug for SigningPool {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_struct("SigningPool")
+            .field("workers", &self.workers.len())
+            .finish()
+    }
+}
+
+/// A signature requested from a [`SigningPool`] that may not be ready yet.
+#[derive(Debug)]
+pub struct PendingSignature {
+    result: mpsc::Receiver<Signature>,
+}
+
+impl PendingSignature {
+    /// Block until the signature is ready, and return it.
+    pub fn wait(self) -> Signature {
+        self.result
+            .recv()
+            .expect("signing workers outlive their pending signatures")
+    }
+
+    /// Return the signature if it is ready, or `self` otherwise.
+    pub fn try_wait(self) -> Result<Signature, PendingSignature> {
+        match self.result.try_recv() {
+            Ok(sig) => Ok(sig),
+            Err(_) => Err(self),
+        }
+    }
+}
+
+impl SigningPool {
+    /// Construct a pool signing with `sk` on `threads` worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero, or if a thread cannot be spawned.
+    pub fn new(sk: SigningKey, threads: usize) -> SigningPool {
+        assert!(threads > 0, "a signing pool needs at least one thread");
+        let sk = Arc::new(sk);
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+
+        let workers = (0..threads)
+            .map(|i| {
+                let sk = Arc::clone(&sk);
+                let queue = Arc::clone(&queue);
+                thread::Builder::new()
+                    .name(format!("ed25519-signer-{}", i))
+                    .spawn(move || loop {
+                        // Release the lock before signing, so that other
+                        // workers can take the next job.
+                        let job = queue.lock().expect("no worker panics").recv();
+                        match job {
+                            Ok((msg, result)) => {
+                                // The requester may have dropped its `PendingSignature`.
+                                let _ = result.send(sk.sign(&msg));
+                            }
+                            Err(_) => break,
+                        }
+                    })
+                    .expect("signing worker should spawn")
+            })
+            .collect();
+
+        SigningPool {
+            jobs: Some(jobs),
+            workers,
+        }
+    }
+
+    /// Submit `msg` to be signed, returning a handle to the signature.
+    pub fn submit(&self, msg: Vec<u8>) -> PendingSignature {
+        let (result, receiver) = mpsc::channel();
+        self.jobs
+            .as_ref()
+            .expect("jobs channel is open until drop")
+            .send((msg, result))
+            .expect("signing workers run until the pool is dropped");
+        PendingSignature { result: receiver }
+    }
+
+    /// Sign `msg` on the pool and wait for the result.
+    pub fn sign(&self, msg: Vec<u8>) -> Signature {
+        self.submit(msg).wait()
+    }
+}
+
+impl Drop for SigningPool {
+    fn drop(&mut self) {
+        // Closing the channel stops the workers once the queue is drained.
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
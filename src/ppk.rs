@@ -0,0 +1,305 @@
+//! PuTTY private key files.
+//!
+//! [`SigningKey::from_ppk`] reads the `.ppk` files written by PuTTYgen, in
+//! both the version 2 format (PuTTY 0.52 to 0.74) and the version 3 format
+//! (PuTTY 0.75 onwards). The MAC that protects each file is always checked.
+//! Keys protected by a passphrase use AES-256-CBC, keyed by SHA-1 in version
+//! 2 and by Argon2 in version 3, and require the `ppk-encryption` feature.
+//! The Argon2 parameters are read from the key file, so files asking for more
+//! than [`MAX_ARGON2_MEMORY`] KiB of memory or [`MAX_ARGON2_PASSES`] passes
+//! are rejected rather than decrypted.
+//!
+//! The format is described in [appendix C][ppk] of the PuTTY manual.
+//!
+//! [ppk]: https://the.earth.li/~sgtatham/putty/0.78/htmldoc/AppendixC.html
+
+use base64ct::{Base64, Encoding};
+use hmac::{Hmac, Mac, NewMac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    ssh::{self, put_string, Error, Reader},
+    SigningKey, VerificationKeyBytes,
+};
+
+/// The only cipher PuTTY uses for passphrase-protected keys.
+const CIPHER: &str = "aes256-cbc";
+/// The largest Argon2 memory size, in KiB, accepted when decrypting a key.
+///
+/// PuTTYgen uses 8192 KiB by default; this allows 256 MiB.
+pub const MAX_ARGON2_MEMORY: u32 = 256 * 1024;
+/// The largest number of Argon2 passes accepted when decrypting a key.
+///
+/// PuTTYgen picks the number of passes to take about 100ms by default, which
+/// is well below this limit.
+pub const MAX_ARGON2_PASSES: u32 = 256;
+
+/// The fields of a `.ppk` file.
+struct PpkFile<'a> {
+    version: u32,
+    encryption: &'a str,
+    comment: &'a str,
+    public_key: Vec<u8>,
+    /// Headers between the public and private key, which describe the key
+    /// derivation for version 3 encrypted files.
+    #[cfg_attr(not(feature = "ppk-encryption"), allow(dead_code))]
+    kdf: Vec<(&'a str, &'a str)>,
+    private_key: Zeroizing<Vec<u8>>,
+    mac: Vec<u8>,
+}
+
+impl<'a> PpkFile<'a> {
+    fn parse(text: &'a str) -> Result<PpkFile<'a>, Error> {
+        let mut headers = Headers {
+            lines: text.lines().map(str::trim).filter(|line| !line.is_empty()),
+        };
+
+        let (name, key_type) = headers.next()?;
+        let version = name
+            .strip_prefix("PuTTY-User-Key-File-")
+            .and_then(|version| version.parse().ok())
+            .ok_or(Error::Malformed("not a PuTTY key file"))?;
+        if version != 2 && version != 3 {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        if key_type != ssh::KEY_TYPE {
+            return Err(Error::UnsupportedKeyType(key_type.to_string()));
+        }
+
+        let encryption = headers.expect("Encryption")?;
+        let comment = headers.expect("Comment")?;
+        let public_key = headers.base64("Public-Lines")?.to_vec();
+        let mut kdf = Vec::new();
+        let private_key = loop {
+            match headers.next()? {
+                ("Private-Lines", count) => break headers.base64_lines(count)?,
+                field => kdf.push(field),
+            }
+        };
+        let mac = hex::decode(headers.expect("Private-MAC")?)
+            .map_err(|_| Error::Malformed("invalid Private-MAC"))?;
+
+        Ok(PpkFile {
+            version,
+            encryption,
+            comment,
+            public_key,
+            kdf,
+            private_key,
+            mac,
+        })
+    }
+
+    /// The data covered by the MAC, given the decrypted private key.
+    fn mac_data(&self, private_key: &[u8]) -> Zeroizing<Vec<u8>> {
+        let mut data = Zeroizing::new(Vec::new());
+        put_string(&mut data, ssh::KEY_TYPE.as_bytes());
+        put_string(&mut data, self.encryption.as_bytes());
+        put_string(&mut data, self.comment.as_bytes());
+        put_string(&mut data, &self.public_key);
+        put_string(&mut data, private_key);
+        data
+    }
+
+    /// Check the MAC over the file under `mac_key`.
+    fn verify_mac(&self, mac_key: &[u8], private_key: &[u8]) -> bool {
+        let data = self.mac_data(private_key);
+        if self.version == 2 {
+            let mut mac = Hmac::<Sha1>::new_from_slice(mac_key).expect("HMAC accepts any key");
+            mac.update(&data);
+            mac.verify(&self.mac).is_ok()
+        } else {
+            let mut mac = Hmac::<Sha256>::new_from_slice(mac_key).expect("HMAC accepts any key");
+            mac.update(&data);
+            mac.verify(&self.mac).is_ok()
+        }
+    }
+}
+
+/// A reader for the `Name: value` headers of a `.ppk` file.
+struct Headers<I> {
+    lines: I,
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Headers<I> {
+    fn next(&mut self) -> Result<(&'a str, &'a str), Error> {
+        let line = self
+            .lines
+            .next()
+            .ok_or(Error::Malformed("unexpected end of PPK file"))?;
+        let (name, value) = line.split_at(
+            line.find(':')
+                .ok_or(Error::Malformed("invalid PPK header"))?,
+        );
+        Ok((name, value[1..].trim()))
+    }
+
+    fn expect(&mut self, expected: &'static str) -> Result<&'a str, Error> {
+        match self.next()? {
+            (name, value) if name == expected => Ok(value),
+            _ => Err(Error::Malformed("missing or out-of-order PPK header")),
+        }
+    }
+
+    fn base64(&mut self, name: &'static str) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let count = self.expect(name)?;
+        self.base64_lines(count)
+    }
+
+    /// Decode the base64 body of `count` lines following a `*-Lines` header.
+    fn base64_lines(&mut self, count: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let count: usize = count
+            .parse()
+            .map_err(|_| Error::Malformed("invalid line count"))?;
+        let mut body = Zeroizing::new(String::new());
+        for _ in 0..count {
+            body.push_str(
+                self.lines
+                    .next()
+                    .ok_or(Error::Malformed("unexpected end of PPK file"))?,
+            );
+        }
+        Base64::decode_vec(&body)
+            .map(Zeroizing::new)
+            .map_err(|_| Error::InvalidBase64)
+    }
+}
+
+impl SigningKey {
+    /// Decode a PuTTY `.ppk` private key file, returning the key and its
+    /// comment.
+    ///
+    /// The `passphrase` is only used if the key is encrypted, in which case it
+    /// is required. An incorrect passphrase, or a file that has been tampered
+    /// with, is reported as [`Error::IncorrectPassphrase`] for encrypted keys
+    /// and as [`Error::Malformed`] otherwise.
+    pub fn from_ppk(text: &str, passphrase: Option<&[u8]>) -> Result<(SigningKey, String), Error> {
+        let file = PpkFile::parse(text)?;
+        let mut private_key = file.private_key.clone();
+        let mac_key = match (file.encryption, file.version) {
+            ("none", 2) => v2_mac_key(b""),
+            ("none", _) => Zeroizing::new(Vec::new()),
+            (CIPHER, _) => {
+                let passphrase = passphrase.ok_or(Error::PassphraseRequired)?;
+                decrypt(&file, passphrase, &mut private_key)?
+            }
+            (other, _) => return Err(Error::UnsupportedCipher(other.to_string())),
+        };
+        if !file.verify_mac(&mac_key, &private_key) {
+            return Err(if file.encryption == "none" {
+                Error::Malformed("invalid Private-MAC")
+            } else {
+                Error::IncorrectPassphrase
+            });
+        }
+
+        // PuTTY stores the private key as a minimal-length little-endian
+        // integer, so trailing zero bytes of the seed may be omitted. Any
+        // bytes after it are cipher padding.
+        let mut reader = Reader::new(&private_key);
+        let stored = reader.read_string()?;
+        if stored.len() > 32 {
+            return Err(Error::Malformed("private key is longer than 32 bytes"));
+        }
+        let mut seed = [0u8; 32];
+        seed[..stored.len()].copy_from_slice(stored);
+        let sk = SigningKey::from(seed);
+        seed.zeroize();
+
+        if ssh::parse_public_key_blob(&file.public_key)? != VerificationKeyBytes::from(&sk) {
+            return Err(Error::PublicKeyMismatch);
+        }
+        Ok((sk, file.comment.to_string()))
+    }
+}
+
+/// The version 2 MAC key, `SHA-1("putty-private-key-file-mac-key" || passphrase)`.
+fn v2_mac_key(passphrase: &[u8]) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(
+        Sha1::new()
+            .chain(b"putty-private-key-file-mac-key")
+            .chain(passphrase)
+            .finalize()
+            .to_vec(),
+    )
+}
+
+/// Decrypt the private key in place, returning the MAC key.
+#[cfg(feature = "ppk-encryption")]
+fn decrypt(
+    file: &PpkFile<'_>,
+    passphrase: &[u8],
+    private_key: &mut [u8],
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    use cbc::cipher::{block_padding::NoPadding, BlockDecryptMut, KeyIvInit};
+
+    // The AES key and IV, followed by the MAC key.
+    let keys = match file.version {
+        2 => {
+            let mut keys = Zeroizing::new(Vec::with_capacity(68));
+            for counter in 0u32..2 {
+                let hash = Sha1::new()
+                    .chain(counter.to_be_bytes())
+                    .chain(passphrase)
+                    .finalize();
+                keys.extend_from_slice(&hash);
+            }
+            keys.truncate(32);
+            keys.extend_from_slice(&[0u8; 16]);
+            keys.extend_from_slice(&v2_mac_key(passphrase));
+            keys
+        }
+        _ => argon2_keys(&file.kdf, passphrase)?,
+    };
+
+    cbc::Decryptor::<aes::Aes256>::new_from_slices(&keys[..32], &keys[32..48])
+        .expect("key and IV have the correct lengths")
+        .decrypt_padded_mut::<NoPadding>(private_key)
+        .map_err(|_| Error::Malformed("private key is not a whole number of blocks"))?;
+    Ok(Zeroizing::new(keys[48..].to_vec()))
+}
+
+#[cfg(not(feature = "ppk-encryption"))]
+fn decrypt(_: &PpkFile<'_>, _: &[u8], _: &mut [u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+    Err(Error::UnsupportedCipher(CIPHER.to_string()))
+}
+
+/// Derive the AES key, IV, and MAC key for a version 3 file with Argon2.
+#[cfg(feature = "ppk-encryption")]
+fn argon2_keys(kdf: &[(&str, &str)], passphrase: &[u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let field = |name: &str| {
+        kdf.iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| *value)
+            .ok_or(Error::Malformed("missing Argon2 parameter"))
+    };
+    let number = |name: &str| -> Result<u32, Error> {
+        field(name)?
+            .parse()
+            .map_err(|_| Error::Malformed("invalid Argon2 parameter"))
+    };
+
+    let algorithm = match field("Key-Derivation")? {
+        "Argon2id" => argon2::Algorithm::Argon2id,
+        "Argon2i" => argon2::Algorithm::Argon2i,
+        "Argon2d" => argon2::Algorithm::Argon2d,
+        other => return Err(Error::UnsupportedKdf(other.to_string())),
+    };
+    let memory = number("Argon2-Memory")?;
+    let passes = number("Argon2-Passes")?;
+    if memory > MAX_ARGON2_MEMORY || passes > MAX_ARGON2_PASSES {
+        return Err(Error::KdfLimitExceeded);
+    }
+    let params = argon2::Params::new(memory, passes, number("Argon2-Parallelism")?, Some(80))
+        .map_err(|_| Error::Malformed("invalid Argon2 parameter"))?;
+    let salt =
+        hex::decode(field("Argon2-Salt")?).map_err(|_| Error::Malformed("invalid Argon2 salt"))?;
+
+    let mut keys = Zeroizing::new(vec![0u8; 80]);
+    argon2::Argon2::new(algorithm, argon2::Version::V0x13, params)
+        .hash_password_into(passphrase, &salt, &mut keys)
+        .map_err(|_| Error::Malformed("invalid Argon2 parameter"))?;
+    Ok(keys)
+}
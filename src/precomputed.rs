@@ -0,0 +1,101 @@
+use std::convert::TryFrom;
+
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT,
+    edwards::{CompressedEdwardsY, VartimeEdwardsPrecomputation},
+    scalar::Scalar,
+    traits::{IsIdentity, VartimePrecomputedMultiscalarMul},
+};
+use sha2::{Digest, Sha512};
+
+use crate::{Error, Signature, VerificationKey, VerificationKeyBytes};
+
+/// An Ed25519 verification key with precomputed tables for faster
+/// verification.
+///
+/// Verifying with a [`VerificationKey`] computes `[s]B - [k]A` with a single
+/// variable-base double-scalar multiplication, which builds a small table of
+/// multiples of `A` on every call. This type instead holds larger
+/// precomputed tables for both `B` and `-A`, built once, saving the
+/// per-call table construction and some point additions. Point decompression
+/// and hashing are unaffected, so the speedup is modest, and building the
+/// tables costs some memory; this is worthwhile for keys that verify many
+/// signatures, such as a handful of well-known keys on a hot path.
+///
+/// The verification criteria are exactly those of [`VerificationKey::verify`].
+pub struct PrecomputedVerificationKey {
+    vk: VerificationKey,
+    /// Tables for the static points `[B, -A]`.
+    tables: VartimeEdwardsPrecomputation,
+}
+
+impl core::fmt::Debug for PrecomputedVerificationKey {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_tuple("PrecomputedVerificationKey")
+            .field(&self.vk.A_bytes)
+            .finish()
+    }
+}
+
+impl From<VerificationKey> for PrecomputedVerificationKey {
+    fn from(vk: VerificationKey) -> PrecomputedVerificationKey {
+        PrecomputedVerificationKey {
+            vk,
+            tables: VartimeEdwardsPrecomputation::new([ED25519_BASEPOINT_POINT, vk.minus_A]),
+        }
+    }
+}
+
+impl From<PrecomputedVerificationKey> for VerificationKey {
+    fn from(pvk: PrecomputedVerificationKey) -> VerificationKey {
+        pvk.vk
+    }
+}
+
+impl From<&PrecomputedVerificationKey> for VerificationKeyBytes {
+    fn from(pvk: &PrecomputedVerificationKey) -> VerificationKeyBytes {
+        pvk.vk.A_bytes
+    }
+}
+
+impl TryFrom<VerificationKeyBytes> for PrecomputedVerificationKey {
+    type Error = Error;
+    fn try_from(bytes: VerificationKeyBytes) -> Result<Self, Self::Error> {
+        VerificationKey::try_from(bytes).map(PrecomputedVerificationKey::from)
+    }
+}
+
+impl PrecomputedVerificationKey {
+    /// The verification key these tables were built for.
+    pub fn verification_key(&self) -> &VerificationKey {
+        &self.vk
+    }
+
+    /// Verify a purported `signature` on the given `msg`.
+    ///
+    /// This accepts and rejects exactly the same signatures, with the same
+    /// errors, as [`VerificationKey::verify`].
+    #[allow(non_snake_case)]
+    pub fn verify(&self, signature: &Signature, msg: &[u8]) -> Result<(), Error> {
+        let k = Scalar::from_hash(
+            Sha512::default()
+                .chain(&signature.R_bytes()[..])
+                .chain(&self.vk.A_bytes.0[..])
+                .chain(msg),
+        );
+        let s = Scalar::from_canonical_bytes(*signature.s_bytes())
+            .ok_or(Error::SignatureScalarOutOfRange)?;
+        let R = CompressedEdwardsY(*signature.R_bytes())
+            .decompress()
+            .ok_or(Error::MalformedSignatureR)?;
+
+        // R' = [s]B - [k]A, as in `VerificationKey::verify_with_k`.
+        let R_prime = self.tables.vartime_multiscalar_mul([s, k]);
+
+        if (R - R_prime).mul_by_cofactor().is_identity() {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
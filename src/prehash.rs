@@ -0,0 +1,23 @@
+use sha2::{Digest, Sha512};
+
+use crate::Error;
+
+/// The `dom2` prefix string defined in [RFC 8032 §5.1][rfc].
+///
+/// [rfc]: https://tools.ietf.org/html/rfc8032#section-5.1
+const DOM2_PREFIX: &[u8] = b"SigEd25519 no Ed25519 collisions";
+
+/// Returns a SHA-512 instance already fed with `dom2(1, context)`, as used by
+/// Ed25519ph.
+///
+/// The context string must be at most 255 bytes long.
+pub(crate) fn ed25519ph_hasher(context: Option<&[u8]>) -> Result<Sha512, Error> {
+    let context = context.unwrap_or(b"");
+    if context.len() > 255 {
+        return Err(Error::InvalidSliceLength);
+    }
+    Ok(Sha512::default()
+        .chain(DOM2_PREFIX)
+        .chain(&[1u8, context.len() as u8][..])
+        .chain(context))
+}
@@ -0,0 +1,83 @@
+//! The protobuf wire format, shared by the formats that wrap keys and
+//! signatures in protobuf messages.
+//!
+//! Only the varint and length-delimited wire types are supported, as no
+//! message these formats use has fixed-width fields.
+
+use std::convert::TryFrom;
+
+/// The varint wire type.
+const VARINT: u64 = 0;
+/// The length-delimited wire type.
+const LENGTH_DELIMITED: u64 = 2;
+
+/// A protobuf field value.
+pub(crate) enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// Decode the fields of a protobuf message in order, including repeated
+/// fields, or return `None` if it is malformed or has fixed-width fields.
+pub(crate) fn read_fields(mut message: &[u8]) -> Option<Vec<(u64, Field<'_>)>> {
+    let mut fields = Vec::new();
+    while !message.is_empty() {
+        let (key, rest) = read_varint(message)?;
+        let (field, rest) = match key & 7 {
+            VARINT => {
+                let (value, rest) = read_varint(rest)?;
+                (Field::Varint(value), rest)
+            }
+            LENGTH_DELIMITED => {
+                let (len, rest) = read_varint(rest)?;
+                let len = usize::try_from(len).ok()?;
+                if rest.len() < len {
+                    return None;
+                }
+                let (bytes, rest) = rest.split_at(len);
+                (Field::Bytes(bytes), rest)
+            }
+            _ => return None,
+        };
+        fields.push((key >> 3, field));
+        message = rest;
+    }
+    Some(fields)
+}
+
+/// Decode a protobuf varint from the start of `bytes`, returning it and the
+/// remaining bytes.
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
+
+#[cfg(any(feature = "ipns", feature = "tink"))]
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Write the varint field `number` with value `value`.
+#[cfg(feature = "tink")]
+pub(crate) fn write_varint_field(out: &mut Vec<u8>, number: u64, value: u64) {
+    write_varint(out, number << 3 | VARINT);
+    write_varint(out, value);
+}
+
+/// Write the length-delimited field `number` with contents `bytes`.
+#[cfg(any(feature = "ipns", feature = "tink"))]
+pub(crate) fn write_bytes_field(out: &mut Vec<u8>, number: u64, bytes: &[u8]) {
+    write_varint(out, number << 3 | LENGTH_DELIMITED);
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
@@ -0,0 +1,483 @@
+//! Saltpack signed messages.
+//!
+//! [Saltpack] is the signing and encryption format of Keybase. An attached
+//! signed message carries the message in 1 MiB chunks, each signed along
+//! with its position, so that truncating or reordering the message is
+//! detected; a detached signature signs the whole message, which is sent
+//! separately. Both are MessagePack packets, which may be armored as
+//! base62 text between `BEGIN SALTPACK SIGNED MESSAGE.` or `BEGIN SALTPACK
+//! DETACHED SIGNATURE.` and matching `END` lines.
+//!
+//! Messages are written in saltpack version 2. Verification returns the
+//! sender's verification key, which the caller must check is the expected
+//! one.
+//!
+//! [Saltpack]: https://saltpack.org/signing-format-v2
+
+use std::convert::TryFrom;
+
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use thiserror::Error;
+
+use crate::{Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+/// The format name in saltpack headers.
+const FORMAT_NAME: &str = "saltpack";
+/// The major and minor version of the saltpack format.
+const VERSION: (u64, u64) = (2, 0);
+/// The modes of attached and detached signatures.
+const MODE_ATTACHED: u64 = 1;
+const MODE_DETACHED: u64 = 2;
+/// The size of the message chunks of attached signed messages.
+const CHUNK_SIZE: usize = 1 << 20;
+/// The prefixes of the signature inputs.
+const ATTACHED_CONTEXT: &[u8] = b"saltpack attached signature\0";
+const DETACHED_CONTEXT: &[u8] = b"saltpack detached signature\0";
+/// The message types of the armor header and footer.
+const ATTACHED_ARMOR: &str = "SIGNED MESSAGE";
+const DETACHED_ARMOR: &str = "DETACHED SIGNATURE";
+
+/// The base62 alphabet of the armor.
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// The number of bytes armored in each block.
+const BLOCK_SIZE: usize = 32;
+/// The number of characters in each armored word, and of words in each line.
+const WORD_SIZE: usize = 15;
+const LINE_WORDS: usize = 200;
+
+/// An error verifying a saltpack signed message or detached signature.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The message is not a well-formed sequence of saltpack packets, or is
+    /// truncated.
+    #[error("Malformed saltpack message.")]
+    MalformedMessage,
+    /// The armored message has a malformed frame or body.
+    #[error("Malformed saltpack armor.")]
+    MalformedArmor,
+    /// The message has a major version other than 2.
+    #[error("Unsupported saltpack version {0}.")]
+    UnsupportedVersion(u64),
+    /// The message is not an attached signed message, or not a detached
+    /// signature, as expected.
+    #[error("Unexpected saltpack mode {0}.")]
+    UnexpectedMode(u64),
+    /// A signature is invalid.
+    #[error("Invalid saltpack signature: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// Sign `msg` as an attached signed message.
+pub fn sign_attached<R: RngCore + CryptoRng>(
+    signing_key: &SigningKey,
+    msg: &[u8],
+    rng: R,
+) -> Vec<u8> {
+    let (mut signed, header_hash) = header(signing_key, MODE_ATTACHED, rng);
+    let chunks: Vec<&[u8]> = if msg.is_empty() {
+        vec![msg]
+    } else {
+        msg.chunks(CHUNK_SIZE).collect()
+    };
+    for (seqno, chunk) in chunks.iter().enumerate() {
+        let is_final = seqno + 1 == chunks.len();
+        let signature =
+            signing_key.sign(&attached_input(&header_hash, seqno as u64, is_final, chunk));
+        write_array_len(&mut signed, 3);
+        signed.push(if is_final { 0xc3 } else { 0xc2 });
+        write_bin(&mut signed, &<[u8; 64]>::from(signature));
+        write_bin(&mut signed, chunk);
+    }
+    signed
+}
+
+/// Sign `msg` as an armored attached signed message.
+pub fn sign_attached_armored<R: RngCore + CryptoRng>(
+    signing_key: &SigningKey,
+    msg: &[u8],
+    rng: R,
+) -> String {
+    armor(&sign_attached(signing_key, msg, rng), ATTACHED_ARMOR)
+}
+
+/// Verify the attached signed message `signed`, returning the sender's
+/// verification key and the message.
+pub fn verify_attached(signed: &[u8]) -> Result<(VerificationKeyBytes, Vec<u8>), Error> {
+    let mut reader = Reader(signed);
+    let (sender, header_hash) = read_header(&mut reader, MODE_ATTACHED)?;
+    let vk = VerificationKey::try_from(sender).map_err(Error::InvalidSignature)?;
+
+    let mut msg = Vec::new();
+    for seqno in 0.. {
+        let (is_final, signature, chunk) = read_payload_packet(&mut reader)?;
+        vk.verify(
+            &signature,
+            &attached_input(&header_hash, seqno, is_final, chunk),
+        )
+        .map_err(Error::InvalidSignature)?;
+        msg.extend_from_slice(chunk);
+        if is_final {
+            break;
+        }
+    }
+    if !reader.0.is_empty() {
+        return Err(Error::MalformedMessage);
+    }
+    Ok((sender, msg))
+}
+
+/// Verify the armored attached signed message `signed`, returning the
+/// sender's verification key and the message.
+pub fn verify_attached_armored(signed: &str) -> Result<(VerificationKeyBytes, Vec<u8>), Error> {
+    verify_attached(&dearmor(signed, ATTACHED_ARMOR)?)
+}
+
+/// Sign `msg` with a detached signature.
+pub fn sign_detached<R: RngCore + CryptoRng>(
+    signing_key: &SigningKey,
+    msg: &[u8],
+    rng: R,
+) -> Vec<u8> {
+    let (mut signed, header_hash) = header(signing_key, MODE_DETACHED, rng);
+    let signature = signing_key.sign(&detached_input(&header_hash, msg));
+    write_bin(&mut signed, &<[u8; 64]>::from(signature));
+    signed
+}
+
+/// Sign `msg` with an armored detached signature.
+pub fn sign_detached_armored<R: RngCore + CryptoRng>(
+    signing_key: &SigningKey,
+    msg: &[u8],
+    rng: R,
+) -> String {
+    armor(&sign_detached(signing_key, msg, rng), DETACHED_ARMOR)
+}
+
+/// Verify the detached `signature` of `msg`, returning the sender's
+/// verification key.
+pub fn verify_detached(signature: &[u8], msg: &[u8]) -> Result<VerificationKeyBytes, Error> {
+    let mut reader = Reader(signature);
+    let (sender, header_hash) = read_header(&mut reader, MODE_DETACHED)?;
+    let signature = reader
+        .bin()
+        .and_then(|bytes| Signature::try_from(bytes).ok())
+        .ok_or(Error::MalformedMessage)?;
+    if !reader.0.is_empty() {
+        return Err(Error::MalformedMessage);
+    }
+    VerificationKey::try_from(sender)
+        .and_then(|vk| vk.verify(&signature, &detached_input(&header_hash, msg)))
+        .map_err(Error::InvalidSignature)?;
+    Ok(sender)
+}
+
+/// Verify the armored detached `signature` of `msg`, returning the sender's
+/// verification key.
+pub fn verify_detached_armored(signature: &str, msg: &[u8]) -> Result<VerificationKeyBytes, Error> {
+    verify_detached(&dearmor(signature, DETACHED_ARMOR)?, msg)
+}
+
+/// Write the header packet of a message in `mode` with a random nonce,
+/// returning it and the header hash.
+fn header<R: RngCore + CryptoRng>(
+    signing_key: &SigningKey,
+    mode: u64,
+    mut rng: R,
+) -> (Vec<u8>, [u8; 64]) {
+    let mut nonce = [0u8; 32];
+    rng.fill_bytes(&mut nonce);
+
+    let mut header = Vec::new();
+    write_array_len(&mut header, 5);
+    header.push(0xa0 | FORMAT_NAME.len() as u8);
+    header.extend_from_slice(FORMAT_NAME.as_bytes());
+    write_array_len(&mut header, 2);
+    header.push(VERSION.0 as u8);
+    header.push(VERSION.1 as u8);
+    header.push(mode as u8);
+    write_bin(
+        &mut header,
+        VerificationKeyBytes::from(signing_key).as_ref(),
+    );
+    write_bin(&mut header, &nonce);
+
+    // The header is encoded twice, so that it can be hashed as it was read.
+    let mut packet = Vec::new();
+    write_bin(&mut packet, &header);
+    (packet, header_hash(&header))
+}
+
+/// Read the header packet of a message, checking that it is in `mode`,
+/// and return the sender's verification key and the header hash.
+fn read_header(reader: &mut Reader, mode: u64) -> Result<(VerificationKeyBytes, [u8; 64]), Error> {
+    let header = reader.bin().ok_or(Error::MalformedMessage)?;
+    let mut fields = Reader(header);
+    if fields.array_len() != Some(5) || fields.str() != Some(FORMAT_NAME.as_bytes()) {
+        return Err(Error::MalformedMessage);
+    }
+    let (major, _minor) = match (fields.array_len(), fields.uint(), fields.uint()) {
+        (Some(2), Some(major), Some(minor)) => (major, minor),
+        _ => return Err(Error::MalformedMessage),
+    };
+    if major != VERSION.0 {
+        return Err(Error::UnsupportedVersion(major));
+    }
+    match fields.uint() {
+        Some(m) if m == mode => {}
+        Some(m) => return Err(Error::UnexpectedMode(m)),
+        None => return Err(Error::MalformedMessage),
+    }
+    let sender = fields
+        .bin()
+        .and_then(|bytes| VerificationKeyBytes::try_from(bytes).ok())
+        .ok_or(Error::MalformedMessage)?;
+    match fields.bin() {
+        Some(nonce) if nonce.len() == 32 && fields.0.is_empty() => {}
+        _ => return Err(Error::MalformedMessage),
+    }
+    Ok((sender, header_hash(header)))
+}
+
+/// The hash of the encoded header `header`, which every signature signs.
+fn header_hash(header: &[u8]) -> [u8; 64] {
+    let mut hash = [0u8; 64];
+    hash.copy_from_slice(&Sha512::digest(header)[..]);
+    hash
+}
+
+/// Read a payload packet of an attached signed message, returning its final
+/// flag, signature, and message chunk.
+fn read_payload_packet<'a>(reader: &mut Reader<'a>) -> Result<(bool, Signature, &'a [u8]), Error> {
+    if reader.array_len() != Some(3) {
+        return Err(Error::MalformedMessage);
+    }
+    let is_final = reader.bool().ok_or(Error::MalformedMessage)?;
+    let signature = reader
+        .bin()
+        .and_then(|bytes| Signature::try_from(bytes).ok())
+        .ok_or(Error::MalformedMessage)?;
+    let chunk = reader.bin().ok_or(Error::MalformedMessage)?;
+    Ok((is_final, signature, chunk))
+}
+
+/// The signature input of the chunk `seqno` of an attached signed message.
+fn attached_input(header_hash: &[u8; 64], seqno: u64, is_final: bool, chunk: &[u8]) -> Vec<u8> {
+    let digest = Sha512::new()
+        .chain(header_hash)
+        .chain(seqno.to_be_bytes())
+        .chain([is_final as u8])
+        .chain(chunk)
+        .finalize();
+    [ATTACHED_CONTEXT, &digest[..]].concat()
+}
+
+/// The signature input of a detached signature.
+fn detached_input(header_hash: &[u8; 64], msg: &[u8]) -> Vec<u8> {
+    let digest = Sha512::new().chain(header_hash).chain(msg).finalize();
+    [DETACHED_CONTEXT, &digest[..]].concat()
+}
+
+/// Write a MessagePack array header of `len` elements, which is at most 15.
+fn write_array_len(out: &mut Vec<u8>, len: u8) {
+    out.push(0x90 | len);
+}
+
+/// Write `bytes` as a MessagePack binary object.
+fn write_bin(out: &mut Vec<u8>, bytes: &[u8]) {
+    match u8::try_from(bytes.len()) {
+        Ok(len) => out.extend_from_slice(&[0xc4, len]),
+        Err(_) => match u16::try_from(bytes.len()) {
+            Ok(len) => {
+                out.push(0xc5);
+                out.extend_from_slice(&len.to_be_bytes());
+            }
+            Err(_) => {
+                out.push(0xc6);
+                out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            }
+        },
+    }
+    out.extend_from_slice(bytes);
+}
+
+/// A reader of the MessagePack objects used by saltpack.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    /// Read a big-endian integer of `len` bytes.
+    fn be(&mut self, len: usize) -> Option<u64> {
+        let bytes = self.take(len)?;
+        Some(bytes.iter().fold(0, |n, &byte| n << 8 | u64::from(byte)))
+    }
+
+    fn array_len(&mut self) -> Option<usize> {
+        match self.byte()? {
+            tag @ 0x90..=0x9f => Some(usize::from(tag & 0x0f)),
+            0xdc => self.be(2).map(|len| len as usize),
+            0xdd => self.be(4).map(|len| len as usize),
+            _ => None,
+        }
+    }
+
+    fn uint(&mut self) -> Option<u64> {
+        match self.byte()? {
+            tag @ 0x00..=0x7f => Some(u64::from(tag)),
+            0xcc => self.be(1),
+            0xcd => self.be(2),
+            0xce => self.be(4),
+            0xcf => self.be(8),
+            _ => None,
+        }
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.byte()? {
+            0xc2 => Some(false),
+            0xc3 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn bin(&mut self) -> Option<&'a [u8]> {
+        let len = match self.byte()? {
+            0xc4 => self.be(1)?,
+            0xc5 => self.be(2)?,
+            0xc6 => self.be(4)?,
+            _ => return None,
+        };
+        self.take(usize::try_from(len).ok()?)
+    }
+
+    fn str(&mut self) -> Option<&'a [u8]> {
+        let len = match self.byte()? {
+            tag @ 0xa0..=0xbf => u64::from(tag & 0x1f),
+            0xd9 => self.be(1)?,
+            0xda => self.be(2)?,
+            0xdb => self.be(4)?,
+            _ => return None,
+        };
+        self.take(usize::try_from(len).ok()?)
+    }
+}
+
+/// The number of base62 characters that encode `len` bytes.
+fn encoded_len(len: usize) -> usize {
+    (8.0 * len as f64 / 62f64.log2()).ceil() as usize
+}
+
+/// The number of bytes that `len` base62 characters decode to.
+fn decoded_len(len: usize) -> usize {
+    (len as f64 * 62f64.log2() / 8.0).floor() as usize
+}
+
+/// Armor `bytes` as a message of type `message_type`.
+fn armor(bytes: &[u8], message_type: &str) -> String {
+    let mut encoded = Vec::with_capacity(encoded_len(bytes.len()) + 1);
+    for block in bytes.chunks(BLOCK_SIZE) {
+        // Little-endian base-62 digits of the block.
+        let mut digits = vec![0u8; encoded_len(block.len())];
+        for &byte in block {
+            let mut carry = u32::from(byte);
+            for digit in digits.iter_mut() {
+                carry += u32::from(*digit) << 8;
+                *digit = (carry % 62) as u8;
+                carry /= 62;
+            }
+        }
+        encoded.extend(digits.iter().rev().map(|&digit| ALPHABET[digit as usize]));
+    }
+
+    let words: Vec<&str> = encoded
+        .chunks(WORD_SIZE)
+        .map(|word| std::str::from_utf8(word).expect("the alphabet is ASCII"))
+        .collect();
+    let lines: Vec<String> = words
+        .chunks(LINE_WORDS)
+        .map(|line| line.join(" "))
+        .collect();
+    format!(
+        "BEGIN SALTPACK {}. {}. END SALTPACK {}.",
+        message_type,
+        lines.join("\n"),
+        message_type
+    )
+}
+
+/// Dearmor a message of type `message_type`, which may have a brand such as
+/// `KEYBASE` before `SALTPACK` in its header and footer.
+fn dearmor(text: &str, message_type: &str) -> Result<Vec<u8>, Error> {
+    let mut parts = text.split('.');
+    let (header, body, footer) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(header), Some(body), Some(footer)) => (header, body, footer),
+        _ => return Err(Error::MalformedArmor),
+    };
+    if parts.any(|rest| !rest.trim().is_empty()) {
+        return Err(Error::MalformedArmor);
+    }
+    let brand = frame_brand(header, "BEGIN", message_type).ok_or(Error::MalformedArmor)?;
+    if frame_brand(footer, "END", message_type) != Some(brand) {
+        return Err(Error::MalformedArmor);
+    }
+
+    let body: Vec<u8> = body
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b'>')
+        .collect();
+    let mut bytes = Vec::with_capacity(decoded_len(body.len()));
+    for block in body.chunks(encoded_len(BLOCK_SIZE)) {
+        let len = decoded_len(block.len());
+        if encoded_len(len) != block.len() {
+            return Err(Error::MalformedArmor);
+        }
+        // Little-endian bytes of the block.
+        let mut decoded = vec![0u8; len];
+        for c in block {
+            let mut carry = ALPHABET
+                .iter()
+                .position(|a| a == c)
+                .ok_or(Error::MalformedArmor)? as u32;
+            for byte in decoded.iter_mut() {
+                carry += u32::from(*byte) * 62;
+                *byte = carry as u8;
+                carry >>= 8;
+            }
+            if carry > 0 {
+                return Err(Error::MalformedArmor);
+            }
+        }
+        bytes.extend(decoded.iter().rev());
+    }
+    Ok(bytes)
+}
+
+/// Parse the armor header or footer `frame`, which starts with `marker`,
+/// returning its brand, which is empty if it has none.
+fn frame_brand<'a>(frame: &'a str, marker: &str, message_type: &str) -> Option<&'a str> {
+    let mut words = frame.split_whitespace();
+    if words.next() != Some(marker) {
+        return None;
+    }
+    let rest: Vec<&str> = words.collect();
+    let expected: Vec<&str> = std::iter::once("SALTPACK")
+        .chain(message_type.split(' '))
+        .collect();
+    match rest.len().checked_sub(expected.len())? {
+        0 if rest == expected => Some(""),
+        1 if rest[1..] == expected[..] => Some(rest[0]),
+        _ => None,
+    }
+}
@@ -0,0 +1,89 @@
+//! Integration with the [`secrecy`] crate.
+
+use std::convert::TryFrom;
+
+use secrecy::{CloneableSecret, ExposeSecret, SecretBox, SecretSlice};
+use zeroize::Zeroizing;
+
+use crate::{Error, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+impl CloneableSecret for SigningKey {}
+
+impl<'a> From<&'a SecretBox<[u8; 32]>> for SigningKey {
+    fn from(seed: &'a SecretBox<[u8; 32]>) -> SigningKey {
+        // Zeroize the copy of the seed taken out of the `SecretBox`.
+        let seed = Zeroizing::new(*seed.expose_secret());
+        SigningKey::from(*seed)
+    }
+}
+
+impl From<SecretBox<[u8; 32]>> for SigningKey {
+    fn from(seed: SecretBox<[u8; 32]>) -> SigningKey {
+        SigningKey::from(&seed)
+    }
+}
+
+impl<'a> TryFrom<&'a SecretSlice<u8>> for SigningKey {
+    type Error = Error;
+    fn try_from(seed: &'a SecretSlice<u8>) -> Result<SigningKey, Error> {
+        SigningKey::try_from(seed.expose_secret())
+    }
+}
+
+/// A [`SigningKey`] held in a [`SecretBox`].
+///
+/// This can create signatures and derive the verification key without
+/// exposing the seed; the seed itself is only reachable through
+/// [`ExposeSecret::expose_secret`], which makes accesses explicit and easy to
+/// audit. The key is zeroized on drop, and its `Debug` output is redacted.
+#[derive(Clone, Debug)]
+pub struct SecretSigningKey(SecretBox<SigningKey>);
+
+impl From<SigningKey> for SecretSigningKey {
+    fn from(sk: SigningKey) -> SecretSigningKey {
+        SecretSigningKey(SecretBox::new(Box::new(sk)))
+    }
+}
+
+impl From<SecretBox<SigningKey>> for SecretSigningKey {
+    fn from(sk: SecretBox<SigningKey>) -> SecretSigningKey {
+        SecretSigningKey(sk)
+    }
+}
+
+impl<'a> From<&'a SecretBox<[u8; 32]>> for SecretSigningKey {
+    fn from(seed: &'a SecretBox<[u8; 32]>) -> SecretSigningKey {
+        SigningKey::from(seed).into()
+    }
+}
+
+impl From<SecretBox<[u8; 32]>> for SecretSigningKey {
+    fn from(seed: SecretBox<[u8; 32]>) -> SecretSigningKey {
+        SigningKey::from(seed).into()
+    }
+}
+
+impl ExposeSecret<SigningKey> for SecretSigningKey {
+    fn expose_secret(&self) -> &SigningKey {
+        self.0.expose_secret()
+    }
+}
+
+impl<'a> From<&'a SecretSigningKey> for VerificationKey {
+    fn from(sk: &'a SecretSigningKey) -> VerificationKey {
+        VerificationKey::from(sk.0.expose_secret())
+    }
+}
+
+impl<'a> From<&'a SecretSigningKey> for VerificationKeyBytes {
+    fn from(sk: &'a SecretSigningKey) -> VerificationKeyBytes {
+        VerificationKeyBytes::from(sk.0.expose_secret())
+    }
+}
+
+impl SecretSigningKey {
+    /// Create a signature on `msg` using this key.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.0.expose_secret().sign(msg)
+    }
+}
@@ -1,32 +1,216 @@
-use crate::Error;
 use std::convert::TryFrom;
 
+use curve25519_dalek::{edwards::CompressedEdwardsY, scalar::Scalar};
+#[cfg(feature = "pkcs8")]
+use pkcs8::der::{
+    self,
+    asn1::{BitStringRef, OctetStringRef},
+    Decode, Encode, Tag,
+};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::{Error, VerificationKey};
+
 /// An Ed25519 signature.
-#[derive(Copy, Clone, Eq, PartialEq)]
+///
+/// The signature is stored as its 64-byte encoding `R_bytes || s_bytes`.
+///
+/// Signatures are ordered and hashed by this encoding. While the ordering is
+/// not cryptographically meaningful, it allows using a `Signature` as the key
+/// to a `BTreeMap` or `HashMap`, e.g. in a signature cache.
+#[derive(Copy, Clone, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[allow(non_snake_case)]
+#[cfg_attr(feature = "serde", serde(from = "SerdeHelper"))]
+#[cfg_attr(feature = "serde", serde(into = "SerdeHelper"))]
 pub struct Signature {
-    pub(crate) R_bytes: [u8; 32],
-    pub(crate) s_bytes: [u8; 32],
+    bytes: [u8; 64],
 }
 
 impl core::fmt::Debug for Signature {
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         fmt.debug_struct("Signature")
-            .field("R_bytes", &hex::encode(&self.R_bytes))
-            .field("s_bytes", &hex::encode(&self.s_bytes))
+            .field("R_bytes", &hex::encode(self.R_bytes()))
+            .field("s_bytes", &hex::encode(self.s_bytes()))
             .finish()
     }
 }
 
-impl From<[u8; 64]> for Signature {
+impl ConstantTimeEq for Signature {
+    fn ct_eq(&self, other: &Signature) -> Choice {
+        self.bytes[..].ct_eq(&other.bytes[..])
+    }
+}
+
+impl PartialEq for Signature {
+    fn eq(&self, other: &Signature) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+// `Hash` must agree with the manual `PartialEq` above, which compares the
+// same bytes.
+impl core::hash::Hash for Signature {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state)
+    }
+}
+
+impl core::fmt::Display for Signature {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.write_str(&hex::encode(self.bytes))
+    }
+}
+
+impl core::str::FromStr for Signature {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Signature, Error> {
+        let bytes = hex::decode(s).map_err(|_| Error::InvalidHexEncoding)?;
+        Signature::try_from(&bytes[..])
+    }
+}
+
+// Serde arrays are limited to 32 elements, so (de)serialize the two halves
+// separately, as the field names indicate.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename = "Signature"))]
+#[allow(non_snake_case)]
+struct SerdeHelper {
+    R_bytes: [u8; 32],
+    s_bytes: [u8; 32],
+}
+
+impl From<SerdeHelper> for Signature {
+    fn from(helper: SerdeHelper) -> Signature {
+        Signature::from_parts(helper.R_bytes, helper.s_bytes)
+    }
+}
+
+impl From<Signature> for SerdeHelper {
+    fn from(sig: Signature) -> Self {
+        SerdeHelper {
+            R_bytes: *sig.R_bytes(),
+            s_bytes: *sig.s_bytes(),
+        }
+    }
+}
+
+impl Signature {
+    /// Construct a signature from its 64-byte encoding in a `const` context.
+    ///
+    /// Like `From<[u8; 64]>`, this performs no validation.
+    pub const fn from_bytes_const(bytes: [u8; 64]) -> Signature {
+        Signature { bytes }
+    }
+
+    /// Construct a signature from its `R` and `s` components.
+    ///
+    /// Like `From<[u8; 64]>`, this performs no validation; malformed components
+    /// are rejected during verification. Use [`Signature::from_parts_checked`]
+    /// to validate the components up front.
+    #[allow(non_snake_case)]
+    pub const fn from_parts(R_bytes: [u8; 32], s_bytes: [u8; 32]) -> Signature {
+        let mut bytes = [0u8; 64];
+        let mut i = 0;
+        while i < 32 {
+            bytes[i] = R_bytes[i];
+            bytes[32 + i] = s_bytes[i];
+            i += 1;
+        }
+        Signature { bytes }
+    }
+
+    /// Construct a signature from its `R` and `s` components, checking that
+    /// they are well-formed.
+    ///
+    /// Returns [`Error::MalformedSignatureR`] unless `R_bytes` is an encoding
+    /// of a point on the twisted Edwards form of Curve25519 (non-canonical
+    /// encodings are accepted, as in verification), and
+    /// [`Error::SignatureScalarOutOfRange`] unless `s_bytes` represents an
+    /// integer less than `l`. These are exactly the checks on the encoding
+    /// performed by [`VerificationKey::verify`], so a signature that passes
+    /// them can still fail verification.
     #[allow(non_snake_case)]
+    pub fn from_parts_checked(R_bytes: [u8; 32], s_bytes: [u8; 32]) -> Result<Signature, Error> {
+        Scalar::from_canonical_bytes(s_bytes).ok_or(Error::SignatureScalarOutOfRange)?;
+        CompressedEdwardsY(R_bytes)
+            .decompress()
+            .ok_or(Error::MalformedSignatureR)?;
+        Ok(Signature::from_parts(R_bytes, s_bytes))
+    }
+
+    /// Construct a signature from its 64-byte encoding, rejecting malleable
+    /// encodings.
+    ///
+    /// In addition to the checks performed by
+    /// [`Signature::from_parts_checked`], this returns
+    /// [`Error::NonCanonicalSignatureR`] if `R_bytes` is not the canonical
+    /// encoding of its point. A signature accepted here satisfies
+    /// [`Signature::is_canonical`], so no other encoding of the same `(R, s)`
+    /// pair will be accepted.
+    #[allow(non_snake_case)]
+    pub fn from_canonical_bytes(bytes: [u8; 64]) -> Result<Signature, Error> {
+        let sig = Signature::from(bytes);
+        Scalar::from_canonical_bytes(*sig.s_bytes()).ok_or(Error::SignatureScalarOutOfRange)?;
+        let R = CompressedEdwardsY(*sig.R_bytes())
+            .decompress()
+            .ok_or(Error::MalformedSignatureR)?;
+        if R.compress().as_bytes() != sig.R_bytes() {
+            return Err(Error::NonCanonicalSignatureR);
+        }
+        Ok(sig)
+    }
+
+    /// Check whether this signature is canonically encoded, i.e., whether
+    /// `s_bytes` represents an integer less than `l` and `R_bytes` is the
+    /// canonical encoding of a curve point.
+    ///
+    /// Verification accepts non-canonical encodings of `R`, as ZIP 215
+    /// requires, so a canonically encoded signature may have malleated
+    /// counterparts that also verify. Systems that must reject these can use
+    /// this check or [`Signature::from_canonical_bytes`].
+    pub fn is_canonical(&self) -> bool {
+        Signature::from_canonical_bytes(self.bytes).is_ok()
+    }
+
+    /// The encoding of the commitment point `R`.
+    #[allow(non_snake_case)]
+    pub fn R_bytes(&self) -> &[u8; 32] {
+        <&[u8; 32]>::try_from(&self.bytes[0..32]).expect("slice has length 32")
+    }
+
+    /// The encoding of the response scalar `s`.
+    pub fn s_bytes(&self) -> &[u8; 32] {
+        <&[u8; 32]>::try_from(&self.bytes[32..64]).expect("slice has length 32")
+    }
+
+    /// The 64-byte encoding of this signature, `R_bytes || s_bytes`.
+    pub const fn as_bytes(&self) -> &[u8; 64] {
+        &self.bytes
+    }
+
+    /// Convert this signature to its 64-byte encoding, `R_bytes || s_bytes`.
+    pub const fn to_bytes(&self) -> [u8; 64] {
+        self.bytes
+    }
+
+    /// Verify this signature on the given `msg` with the verification key `vk`.
+    ///
+    /// This is equivalent to [`VerificationKey::verify`].
+    pub fn verify(&self, vk: &VerificationKey, msg: &[u8]) -> Result<(), Error> {
+        vk.verify(self, msg)
+    }
+}
+
+impl From<[u8; 64]> for Signature {
     fn from(bytes: [u8; 64]) -> Signature {
-        let mut R_bytes = [0; 32];
-        R_bytes.copy_from_slice(&bytes[0..32]);
-        let mut s_bytes = [0; 32];
-        s_bytes.copy_from_slice(&bytes[32..64]);
-        Signature { R_bytes, s_bytes }
+        Signature { bytes }
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..]
     }
 }
 
@@ -46,9 +230,66 @@ impl TryFrom<&[u8]> for Signature {
 
 impl From<Signature> for [u8; 64] {
     fn from(sig: Signature) -> [u8; 64] {
-        let mut bytes = [0; 64];
-        bytes[0..32].copy_from_slice(&sig.R_bytes[..]);
-        bytes[32..64].copy_from_slice(&sig.s_bytes[..]);
-        bytes
+        sig.bytes
+    }
+}
+
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl Signature {
+    /// The `id-Ed25519` algorithm identifier of RFC 8410, which accompanies
+    /// Ed25519 signatures in X.509 certificates and CMS `SignerInfo`s.
+    pub const ALGORITHM_ID: pkcs8::AlgorithmIdentifierRef<'static> =
+        crate::signing_key::ALGORITHM_ID;
+
+    /// Encode this signature as a DER BIT STRING, as in the `signatureValue`
+    /// of an X.509 certificate, CRL, or PKCS#10 request.
+    pub fn to_der_bit_string(&self) -> Vec<u8> {
+        BitStringRef::new(0, &self.bytes[..])
+            .and_then(|bits| bits.to_der())
+            .expect("a 64-byte BIT STRING encodes")
+    }
+
+    /// Decode a signature from a DER BIT STRING.
+    pub fn from_der_bit_string(der: &[u8]) -> Result<Signature, der::Error> {
+        let bits = BitStringRef::from_der(der)?;
+        let bytes = bits
+            .as_bytes()
+            .ok_or_else(|| Tag::BitString.value_error())?;
+        Signature::try_from(bytes).map_err(|_| Tag::BitString.length_error())
+    }
+
+    /// Encode this signature as a DER OCTET STRING, as in the `signature` of
+    /// a CMS `SignerInfo`.
+    pub fn to_der_octet_string(&self) -> Vec<u8> {
+        OctetStringRef::new(&self.bytes[..])
+            .and_then(|octets| octets.to_der())
+            .expect("a 64-byte OCTET STRING encodes")
+    }
+
+    /// Decode a signature from a DER OCTET STRING.
+    pub fn from_der_octet_string(der: &[u8]) -> Result<Signature, der::Error> {
+        let octets = OctetStringRef::from_der(der)?;
+        Signature::try_from(octets.as_bytes()).map_err(|_| Tag::OctetString.length_error())
+    }
+}
+
+#[cfg(feature = "ed25519")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ed25519")))]
+impl From<ed25519::Signature> for Signature {
+    fn from(sig: ed25519::Signature) -> Signature {
+        sig.to_bytes().into()
+    }
+}
+
+#[cfg(feature = "ed25519")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ed25519")))]
+impl TryFrom<Signature> for ed25519::Signature {
+    type Error = ed25519::Error;
+
+    /// Fails if the high bits of `s_bytes` are set, which `ed25519::Signature`
+    /// rejects as a cheap partial check that `s` is reduced.
+    fn try_from(sig: Signature) -> Result<ed25519::Signature, ed25519::Error> {
+        ed25519::Signature::from_bytes(&sig.bytes[..])
     }
 }
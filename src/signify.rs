@@ -0,0 +1,446 @@
+//! OpenBSD [signify] keys and signatures.
+//!
+//! signify signs OpenBSD's releases and packages. This module reads and writes
+//! its public key, secret key, and signature files, so that files signed here
+//! can be checked with `signify -V` and files signed with `signify -S` can be
+//! checked here. Embedded signatures (`signify -S -e`), where the signed
+//! message follows the signature in the same file, are also supported; the
+//! `SHA256.sig` checksum files of OpenBSD releases are of this form.
+//!
+//! Each key pair has a random 8-byte key number, which is copied into its
+//! signatures so that verifiers can tell which key to use.
+//!
+//! Encrypted secret keys carry their own bcrypt-pbkdf rounds count, so keys
+//! asking for more than [`MAX_KDF_ROUNDS`] are rejected rather than
+//! decrypted.
+//!
+//! [signify]: https://man.openbsd.org/signify
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64, Encoding};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{Signature, SigningKey, VerificationKey};
+
+/// The Ed25519 public key algorithm identifier.
+const PKALG: &[u8; 2] = b"Ed";
+/// The key derivation algorithm of secret keys, bcrypt-pbkdf.
+const KDFALG: &[u8; 2] = b"BK";
+/// The bcrypt-pbkdf rounds used by `signify -G`.
+#[cfg(feature = "signify-encryption")]
+const KDF_ROUNDS: u32 = 42;
+/// The largest number of bcrypt-pbkdf rounds accepted when decrypting a
+/// secret key.
+///
+/// This bounds the time an untrusted key file can make decryption take to a
+/// few seconds, while accepting keys with rounds well above `signify -G`'s 42.
+pub const MAX_KDF_ROUNDS: u32 = 1024;
+
+const UNTRUSTED_COMMENT: &str = "untrusted comment: ";
+
+/// The length of a decoded public key: algorithm, key number, and key.
+const PUBLIC_KEY_LEN: usize = 2 + 8 + 32;
+/// The length of a decoded secret key: algorithms, KDF rounds and salt,
+/// checksum, key number, and libsodium secret key.
+const SECRET_KEY_LEN: usize = 2 + 2 + 4 + 16 + 8 + 8 + 64;
+/// The length of a decoded signature: algorithm, key number, and signature.
+const SIGNATURE_LEN: usize = 2 + 8 + 64;
+
+/// An error decoding or verifying a signify key or signature.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The file does not have the expected lines.
+    #[error("Malformed signify file: {0}.")]
+    Malformed(&'static str),
+    /// A line is not valid base64.
+    #[error("Invalid base64 encoding.")]
+    InvalidBase64,
+    /// The key or signature is not an Ed25519 one.
+    #[error("Unsupported signify algorithm.")]
+    UnsupportedAlgorithm,
+    /// The secret key is encrypted with an unsupported key derivation
+    /// function, or encryption support is not enabled.
+    #[error("Unsupported secret key encryption.")]
+    UnsupportedKdf,
+    /// The secret key asks for more than [`MAX_KDF_ROUNDS`] rounds of
+    /// bcrypt-pbkdf.
+    #[error("Too many key derivation rounds.")]
+    KdfLimitExceeded,
+    /// The secret key is encrypted, but no passphrase was given.
+    #[error("Secret key is encrypted and requires a passphrase.")]
+    PassphraseRequired,
+    /// The secret key checksum does not match; the passphrase is incorrect.
+    #[error("Incorrect passphrase.")]
+    IncorrectPassphrase,
+    /// The public key embedded in the secret key does not match the secret.
+    #[error("Secret key does not match its embedded public key.")]
+    PublicKeyMismatch,
+    /// The signature was made by a key with a different key number.
+    #[error("Signature was made with a different key.")]
+    KeyIdMismatch,
+    /// A comment contains a line break.
+    #[error("Comments must be a single line.")]
+    InvalidComment,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+/// A signify public key.
+#[derive(Copy, Clone, Debug)]
+pub struct PublicKey {
+    /// The key number.
+    pub key_id: [u8; 8],
+    /// The verification key.
+    pub verification_key: VerificationKey,
+}
+
+impl PublicKey {
+    /// Decode a public key file, as written by `signify -G`.
+    pub fn decode(text: &str) -> Result<PublicKey, Error> {
+        let (_, key) = parse_file(text)?;
+        PublicKey::from_base64(key)
+    }
+
+    /// Decode the base64 second line of a public key file.
+    pub fn from_base64(key: &str) -> Result<PublicKey, Error> {
+        let bytes = decode_base64(key)?;
+        if bytes.len() != PUBLIC_KEY_LEN {
+            return Err(Error::Malformed("wrong public key length"));
+        }
+        if &bytes[..2] != PKALG {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&bytes[2..10]);
+        let verification_key = VerificationKey::try_from(&bytes[10..])
+            .map_err(|_| Error::Malformed("invalid public key"))?;
+        Ok(PublicKey {
+            key_id,
+            verification_key,
+        })
+    }
+
+    /// Encode this key as a public key file with the given `comment`, such
+    /// as `"signify public key"`.
+    pub fn encode(&self, comment: &str) -> Result<String, Error> {
+        check_comment(comment)?;
+        Ok(format!(
+            "{}{}\n{}\n",
+            UNTRUSTED_COMMENT,
+            comment,
+            self.to_base64()
+        ))
+    }
+
+    /// Encode this key as the base64 second line of a public key file.
+    pub fn to_base64(&self) -> String {
+        let mut bytes = Vec::with_capacity(PUBLIC_KEY_LEN);
+        bytes.extend_from_slice(PKALG);
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(self.verification_key.as_ref());
+        Base64::encode_string(&bytes)
+    }
+}
+
+/// A signify secret key.
+#[derive(Clone, Debug)]
+pub struct SecretKey {
+    /// The key number.
+    pub key_id: [u8; 8],
+    /// The signing key.
+    pub signing_key: SigningKey,
+}
+
+impl SecretKey {
+    /// Generate a new key pair with a random key number.
+    pub fn new<R: RngCore + CryptoRng>(mut rng: R) -> SecretKey {
+        let mut key_id = [0u8; 8];
+        rng.fill_bytes(&mut key_id);
+        SecretKey {
+            key_id,
+            signing_key: SigningKey::new(rng),
+        }
+    }
+
+    /// The public key of this key pair.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            key_id: self.key_id,
+            verification_key: VerificationKey::from(&self.signing_key),
+        }
+    }
+
+    /// Decode a secret key file, as written by `signify -G`.
+    ///
+    /// Keys created with `signify -G -n` are unencrypted, and need no
+    /// `passphrase`; others are encrypted with bcrypt-pbkdf, and can only be
+    /// read with the `signify-encryption` feature.
+    pub fn decode(text: &str, passphrase: Option<&[u8]>) -> Result<SecretKey, Error> {
+        let (_, key) = parse_file(text)?;
+        let mut bytes = Zeroizing::new(decode_base64(key)?);
+        if bytes.len() != SECRET_KEY_LEN {
+            return Err(Error::Malformed("wrong secret key length"));
+        }
+        if &bytes[..2] != PKALG {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+        if &bytes[2..4] != KDFALG {
+            return Err(Error::UnsupportedKdf);
+        }
+
+        let (header, keypair) = bytes.split_at_mut(SECRET_KEY_LEN - 64);
+        let rounds = u32::from_be_bytes(<[u8; 4]>::try_from(&header[4..8]).unwrap());
+        if rounds > MAX_KDF_ROUNDS {
+            return Err(Error::KdfLimitExceeded);
+        }
+        if rounds != 0 {
+            let passphrase = passphrase.ok_or(Error::PassphraseRequired)?;
+            bcrypt_pbkdf_xor(passphrase, &header[8..24], rounds, keypair)?;
+        }
+        if Sha512::digest(keypair)[..8] != header[24..32] {
+            return Err(if rounds != 0 {
+                Error::IncorrectPassphrase
+            } else {
+                Error::Malformed("checksum mismatch")
+            });
+        }
+
+        let mut libsodium_bytes = [0u8; 64];
+        libsodium_bytes.copy_from_slice(keypair);
+        let signing_key = SigningKey::from_libsodium_bytes(&libsodium_bytes)
+            .map_err(|_| Error::PublicKeyMismatch);
+        libsodium_bytes.zeroize();
+        Ok(SecretKey {
+            key_id: <[u8; 8]>::try_from(&header[32..40]).unwrap(),
+            signing_key: signing_key?,
+        })
+    }
+
+    /// Encode this key as an unencrypted secret key file, as written by
+    /// `signify -G -n`, with the given `comment`, such as
+    /// `"signify secret key"`.
+    pub fn encode(&self, comment: &str) -> Result<Zeroizing<String>, Error> {
+        check_comment(comment)?;
+        let bytes = self.secret_key_bytes(0, &[0u8; 16]);
+        Ok(secret_key_file(comment, &bytes))
+    }
+
+    /// Encode this key as a secret key file encrypted under `passphrase`, with
+    /// the bcrypt-pbkdf rounds used by `signify -G`.
+    ///
+    /// The salt is drawn from `rng`. Returns [`Error::PassphraseRequired`] if
+    /// `passphrase` is empty.
+    #[cfg(feature = "signify-encryption")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "signify-encryption")))]
+    pub fn encode_encrypted<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        comment: &str,
+        passphrase: &[u8],
+    ) -> Result<Zeroizing<String>, Error> {
+        check_comment(comment)?;
+        if passphrase.is_empty() {
+            return Err(Error::PassphraseRequired);
+        }
+        let mut salt = [0u8; 16];
+        rng.fill_bytes(&mut salt);
+        let mut bytes = self.secret_key_bytes(KDF_ROUNDS, &salt);
+        bcrypt_pbkdf_xor(
+            passphrase,
+            &salt,
+            KDF_ROUNDS,
+            &mut bytes[SECRET_KEY_LEN - 64..],
+        )?;
+        Ok(secret_key_file(comment, &bytes))
+    }
+
+    /// Encode the unencrypted binary secret key.
+    fn secret_key_bytes(&self, rounds: u32, salt: &[u8; 16]) -> Zeroizing<Vec<u8>> {
+        let mut keypair = self.signing_key.to_libsodium_bytes();
+        let mut bytes = Zeroizing::new(Vec::with_capacity(SECRET_KEY_LEN));
+        bytes.extend_from_slice(PKALG);
+        bytes.extend_from_slice(KDFALG);
+        bytes.extend_from_slice(&rounds.to_be_bytes());
+        bytes.extend_from_slice(salt);
+        bytes.extend_from_slice(&Sha512::digest(&keypair)[..8]);
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(&keypair);
+        keypair.zeroize();
+        bytes
+    }
+
+    /// Sign `message`, as `signify -S`.
+    pub fn sign(&self, message: &[u8]) -> SignifySignature {
+        SignifySignature {
+            untrusted_comment: "signature from signify secret key".to_string(),
+            key_id: self.key_id,
+            signature: self.signing_key.sign(message),
+        }
+    }
+}
+
+/// A signify signature file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignifySignature {
+    /// The untrusted comment, which is not covered by the signature.
+    ///
+    /// `signify` writes `verify with <public key file>` here.
+    pub untrusted_comment: String,
+    key_id: [u8; 8],
+    signature: Signature,
+}
+
+impl SignifySignature {
+    /// The number of the key that made the signature.
+    pub fn key_id(&self) -> [u8; 8] {
+        self.key_id
+    }
+
+    /// The Ed25519 signature.
+    pub fn signature(&self) -> Signature {
+        self.signature
+    }
+
+    /// Verify this signature on `message`, as `signify -V`.
+    pub fn verify(&self, public_key: &PublicKey, message: &[u8]) -> Result<(), Error> {
+        if self.key_id != public_key.key_id {
+            return Err(Error::KeyIdMismatch);
+        }
+        public_key
+            .verification_key
+            .verify(&self.signature, message)
+            .map_err(Error::InvalidSignature)
+    }
+
+    /// Decode a detached signature file, as written by `signify -S`.
+    pub fn decode(text: &str) -> Result<SignifySignature, Error> {
+        let (comment, signature) = parse_file(text)?;
+        SignifySignature::from_lines(comment, signature)
+    }
+
+    /// Decode an embedded signature file, as written by `signify -S -e`,
+    /// returning the signature and the message that follows it.
+    ///
+    /// The message is not verified; pass it to [`SignifySignature::verify`].
+    pub fn decode_embedded(file: &[u8]) -> Result<(SignifySignature, &[u8]), Error> {
+        let mut rest = file;
+        let mut lines = [""; 2];
+        for line in lines.iter_mut() {
+            let end = rest
+                .iter()
+                .position(|&b| b == b'\n')
+                .ok_or(Error::Malformed("missing signature"))?;
+            *line = std::str::from_utf8(&rest[..end])
+                .map_err(|_| Error::Malformed("comment is not UTF-8"))?;
+            rest = &rest[end + 1..];
+        }
+        let comment = lines[0]
+            .strip_prefix(UNTRUSTED_COMMENT)
+            .ok_or(Error::Malformed("missing untrusted comment"))?;
+        Ok((SignifySignature::from_lines(comment, lines[1])?, rest))
+    }
+
+    fn from_lines(comment: &str, signature: &str) -> Result<SignifySignature, Error> {
+        let bytes = decode_base64(signature)?;
+        if bytes.len() != SIGNATURE_LEN {
+            return Err(Error::Malformed("wrong signature length"));
+        }
+        if &bytes[..2] != PKALG {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&bytes[2..10]);
+        Ok(SignifySignature {
+            untrusted_comment: comment.to_string(),
+            key_id,
+            signature: Signature::try_from(&bytes[10..]).expect("signatures are 64 bytes"),
+        })
+    }
+
+    /// Encode this signature as a detached signature file.
+    pub fn encode(&self) -> Result<String, Error> {
+        check_comment(&self.untrusted_comment)?;
+        let mut bytes = Vec::with_capacity(SIGNATURE_LEN);
+        bytes.extend_from_slice(PKALG);
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(self.signature.as_ref());
+        Ok(format!(
+            "{}{}\n{}\n",
+            UNTRUSTED_COMMENT,
+            self.untrusted_comment,
+            Base64::encode_string(&bytes)
+        ))
+    }
+
+    /// Encode this signature as an embedded signature file, followed by
+    /// `message`.
+    pub fn encode_embedded(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut file = self.encode()?.into_bytes();
+        file.extend_from_slice(message);
+        Ok(file)
+    }
+}
+
+/// Split a two-line signify file into its untrusted comment and base64 line.
+fn parse_file(text: &str) -> Result<(&str, &str), Error> {
+    let mut lines = text.lines();
+    let comment = lines
+        .next()
+        .and_then(|line| line.strip_prefix(UNTRUSTED_COMMENT))
+        .ok_or(Error::Malformed("missing untrusted comment"))?;
+    let data = lines.next().ok_or(Error::Malformed("missing key data"))?;
+    if lines.any(|line| !line.trim().is_empty()) {
+        return Err(Error::Malformed("trailing data"));
+    }
+    Ok((comment.trim_end_matches('\r'), data))
+}
+
+/// Encode a binary secret key as a secret key file.
+fn secret_key_file(comment: &str, bytes: &[u8]) -> Zeroizing<String> {
+    let encoded = Zeroizing::new(Base64::encode_string(bytes));
+    Zeroizing::new(format!("{}{}\n{}\n", UNTRUSTED_COMMENT, comment, *encoded))
+}
+
+/// Encrypt or decrypt a secret key in place with bcrypt-pbkdf.
+#[cfg(feature = "signify-encryption")]
+fn bcrypt_pbkdf_xor(
+    passphrase: &[u8],
+    salt: &[u8],
+    rounds: u32,
+    keypair: &mut [u8],
+) -> Result<(), Error> {
+    let mut xorkey = Zeroizing::new([0u8; 64]);
+    bcrypt_pbkdf::bcrypt_pbkdf(passphrase, salt, rounds, &mut xorkey[..])
+        .map_err(|_| Error::Malformed("invalid bcrypt-pbkdf parameters"))?;
+    for (byte, key) in keypair.iter_mut().zip(xorkey.iter()) {
+        *byte ^= key;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "signify-encryption"))]
+fn bcrypt_pbkdf_xor(
+    _passphrase: &[u8],
+    _salt: &[u8],
+    _rounds: u32,
+    _keypair: &mut [u8],
+) -> Result<(), Error> {
+    Err(Error::UnsupportedKdf)
+}
+
+fn check_comment(comment: &str) -> Result<(), Error> {
+    if comment.contains(&['\n', '\r'][..]) {
+        Err(Error::InvalidComment)
+    } else {
+        Ok(())
+    }
+}
+
+fn decode_base64(line: &str) -> Result<Vec<u8>, Error> {
+    Base64::decode_vec(line.trim_end()).map_err(|_| Error::InvalidBase64)
+}
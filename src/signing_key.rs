@@ -1,45 +1,99 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, io::IoSlice};
 
-use curve25519_dalek::{constants, scalar::Scalar};
+use curve25519_dalek::scalar::Scalar;
+#[cfg(feature = "pkcs8")]
+use pkcs8::der::{asn1::OctetStringRef, Encode};
 use rand_core::{CryptoRng, RngCore};
 use sha2::{Digest, Sha512};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::{Error, Signature, VerificationKey, VerificationKeyBytes};
+use crate::{Error, ExpandedSigningKey, Signature, VerificationKey, VerificationKeyBytes};
 
 /// An Ed25519 signing key.
 ///
 /// This is also called a secret key by other implementations.
-#[derive(Copy, Clone)]
+///
+/// The key material is zeroized when a `SigningKey` is dropped.
+#[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(from = "SerdeHelper"))]
 #[cfg_attr(feature = "serde", serde(into = "SerdeHelper"))]
 pub struct SigningKey {
     seed: [u8; 32],
-    s: Scalar,
-    prefix: [u8; 32],
-    vk: VerificationKey,
+    pub(crate) expanded: ExpandedSigningKey,
 }
 
+/// Secret key material is redacted unless the `debug-secrets` feature is
+/// enabled; only the verification key is shown.
 impl core::fmt::Debug for SigningKey {
+    #[cfg(not(feature = "debug-secrets"))]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.debug_struct("SigningKey")
+            .field("seed", &"<redacted>")
+            .field("vk", &VerificationKey::from(self))
+            .finish()
+    }
+
+    #[cfg(feature = "debug-secrets")]
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         fmt.debug_struct("SigningKey")
-            .field("seed", &hex::encode(&self.seed))
-            .field("s", &self.s)
-            .field("prefix", &hex::encode(&self.prefix))
-            .field("vk", &self.vk)
+            .field("seed", &hex::encode(self.seed))
+            .field("expanded", &self.expanded)
             .finish()
     }
 }
 
+/// Displays the seed as hex. This writes the secret key in the clear, so it is
+/// only available with the `hex-secrets` feature.
+#[cfg(feature = "hex-secrets")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "hex-secrets")))]
+impl core::fmt::Display for SigningKey {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let mut encoded = hex::encode(self.seed);
+        let result = fmt.write_str(&encoded);
+        encoded.zeroize();
+        result
+    }
+}
+
+/// Parses a hex-encoded seed; only available with the `hex-secrets` feature.
+#[cfg(feature = "hex-secrets")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "hex-secrets")))]
+impl core::str::FromStr for SigningKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SigningKey, Error> {
+        let mut bytes = hex::decode(s).map_err(|_| Error::InvalidHexEncoding)?;
+        let sk = SigningKey::try_from(&bytes[..]);
+        bytes.zeroize();
+        sk
+    }
+}
+
+impl ConstantTimeEq for SigningKey {
+    fn ct_eq(&self, other: &SigningKey) -> Choice {
+        self.seed[..].ct_eq(&other.seed[..])
+    }
+}
+
+impl PartialEq for SigningKey {
+    fn eq(&self, other: &SigningKey) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SigningKey {}
+
 impl<'a> From<&'a SigningKey> for VerificationKey {
     fn from(sk: &'a SigningKey) -> VerificationKey {
-        sk.vk
+        VerificationKey::from(&sk.expanded)
     }
 }
 
 impl<'a> From<&'a SigningKey> for VerificationKeyBytes {
     fn from(sk: &'a SigningKey) -> VerificationKeyBytes {
-        sk.vk.into()
+        VerificationKeyBytes::from(&sk.expanded)
     }
 }
 
@@ -69,7 +123,6 @@ impl TryFrom<&[u8]> for SigningKey {
 }
 
 impl From<[u8; 32]> for SigningKey {
-    #[allow(non_snake_case)]
     fn from(seed: [u8; 32]) -> SigningKey {
         // Expand the seed to a 64-byte array with SHA512.
         let h = Sha512::digest(&seed[..]);
@@ -77,7 +130,7 @@ impl From<[u8; 32]> for SigningKey {
         // Convert the low half to a scalar with Ed25519 "clamping"
         let s = {
             let mut scalar_bytes = [0u8; 32];
-            scalar_bytes[..].copy_from_slice(&h.as_slice()[0..32]);
+            scalar_bytes[..].copy_from_slice(&h[0..32]);
             scalar_bytes[0] &= 248;
             scalar_bytes[31] &= 127;
             scalar_bytes[31] |= 64;
@@ -87,35 +140,184 @@ impl From<[u8; 32]> for SigningKey {
         // Extract and cache the high half.
         let prefix = {
             let mut prefix = [0u8; 32];
-            prefix[..].copy_from_slice(&h.as_slice()[32..64]);
+            prefix[..].copy_from_slice(&h[32..64]);
             prefix
         };
 
-        // Compute the public key as A = [s]B.
-        let A = &s * &constants::ED25519_BASEPOINT_TABLE;
-
         SigningKey {
             seed,
-            s,
-            prefix,
-            vk: VerificationKey {
-                minus_A: -A,
-                A_bytes: VerificationKeyBytes(A.compress().to_bytes()),
-            },
+            expanded: ExpandedSigningKey::from_parts(s, prefix),
         }
     }
 }
 
-impl zeroize::Zeroize for SigningKey {
+impl Zeroize for SigningKey {
     fn zeroize(&mut self) {
         self.seed.zeroize();
-        self.s.zeroize()
+        self.expanded.zeroize()
+    }
+}
+
+impl Drop for SigningKey {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SigningKey {}
+
+#[cfg(feature = "ed25519")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ed25519")))]
+impl ed25519::signature::Signer<ed25519::Signature> for SigningKey {
+    fn try_sign(&self, msg: &[u8]) -> Result<ed25519::Signature, ed25519::Error> {
+        ed25519::Signature::try_from(self.sign(msg))
+    }
+}
+
+/// Signs the digested message with Ed25519ph and no context, as in
+/// [`SigningKey::sign_prehashed`].
+#[cfg(feature = "ed25519")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ed25519")))]
+impl ed25519::signature::DigestSigner<Sha512, ed25519::Signature> for SigningKey {
+    fn try_sign_digest(&self, digest: Sha512) -> Result<ed25519::Signature, ed25519::Error> {
+        let sig = self
+            .sign_prehashed(digest, None)
+            .map_err(ed25519::Error::from_source)?;
+        ed25519::Signature::try_from(sig)
+    }
+}
+
+/// The `id-Ed25519` algorithm identifier from [RFC 8410], which has absent
+/// parameters.
+///
+/// [RFC 8410]: https://tools.ietf.org/html/rfc8410#section-3
+#[cfg(feature = "pkcs8")]
+pub(crate) const ALGORITHM_ID: pkcs8::AlgorithmIdentifierRef<'static> =
+    pkcs8::AlgorithmIdentifierRef {
+        oid: pkcs8::ObjectIdentifier::new_unwrap("1.3.101.112"),
+        parameters: None,
+    };
+
+/// Decodes a PKCS#8 v1 or v2 private key, as in [RFC 5958].
+///
+/// If the document is a v2 `OneAsymmetricKey` carrying the public key, as
+/// produced by `ring`, the public key must match the one derived from the
+/// seed; otherwise this returns [`pkcs8::Error::KeyMalformed`]. Use
+/// [`SigningKey::from_pem`] for more lenient parsing and more detailed errors.
+///
+/// [RFC 5958]: https://tools.ietf.org/html/rfc5958
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl<'a> TryFrom<pkcs8::PrivateKeyInfo<'a>> for SigningKey {
+    type Error = pkcs8::Error;
+
+    fn try_from(pki: pkcs8::PrivateKeyInfo<'a>) -> pkcs8::Result<SigningKey> {
+        crate::pem::signing_key_from_parts(pki.algorithm, pki.private_key, pki.public_key)
+            .map_err(Into::into)
+    }
+}
+
+/// Encodes the key as a PKCS#8 v1 `PrivateKeyInfo`, without the public key;
+/// see [`Keypair`](crate::Keypair) for the v2 encoding.
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl pkcs8::EncodePrivateKey for SigningKey {
+    fn to_pkcs8_der(&self) -> pkcs8::Result<pkcs8::SecretDocument> {
+        self.to_pkcs8_der_with_public_key(None)
+    }
+}
+
+#[cfg(feature = "pkcs8")]
+impl SigningKey {
+    /// Encode this key as a PKCS#8 document, including `public_key` (and so
+    /// using the v2 structure) if it is present.
+    pub(crate) fn to_pkcs8_der_with_public_key(
+        &self,
+        public_key: Option<&VerificationKeyBytes>,
+    ) -> pkcs8::Result<pkcs8::SecretDocument> {
+        // The encoded `CurvePrivateKey` is a 2-byte header and the seed.
+        let mut buf = [0u8; 34];
+        let document = OctetStringRef::new(&self.seed[..])
+            .and_then(|seed| seed.encode_to_slice(&mut buf[..]))
+            .and_then(|private_key| {
+                pkcs8::SecretDocument::encode_msg(&pkcs8::PrivateKeyInfo {
+                    algorithm: ALGORITHM_ID,
+                    private_key,
+                    public_key: public_key.map(AsRef::as_ref),
+                })
+            });
+        buf.zeroize();
+        Ok(document?)
+    }
+}
+
+#[cfg(feature = "pkcs8-encryption")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8-encryption")))]
+impl SigningKey {
+    /// Encode this key as a PKCS#8 `EncryptedPrivateKeyInfo`, encrypted under
+    /// `passphrase` with PBES2 using scrypt and AES-256-CBC.
+    ///
+    /// The salt and IV are drawn from the operating system's random number
+    /// generator, and scrypt uses the recommended parameters (`log_n = 17`,
+    /// `r = 8`, `p = 1`). The document can be decrypted with
+    /// [`DecodePrivateKey::from_pkcs8_encrypted_der`](pkcs8::DecodePrivateKey::from_pkcs8_encrypted_der).
+    pub fn to_pkcs8_encrypted_der(
+        &self,
+        passphrase: impl AsRef<[u8]>,
+    ) -> pkcs8::Result<pkcs8::SecretDocument> {
+        encrypt_pkcs8(pkcs8::EncodePrivateKey::to_pkcs8_der(self)?, passphrase)
+    }
+
+    /// Encode this key as a PEM `ENCRYPTED PRIVATE KEY`, as in
+    /// [`SigningKey::to_pkcs8_encrypted_der`].
+    ///
+    /// The document can be decrypted with
+    /// [`DecodePrivateKey::from_pkcs8_encrypted_pem`](pkcs8::DecodePrivateKey::from_pkcs8_encrypted_pem).
+    pub fn to_pkcs8_encrypted_pem(
+        &self,
+        passphrase: impl AsRef<[u8]>,
+        line_ending: pkcs8::LineEnding,
+    ) -> pkcs8::Result<zeroize::Zeroizing<String>> {
+        let document = self.to_pkcs8_encrypted_der(passphrase)?;
+        Ok(document.to_pem(ENCRYPTED_PEM_LABEL, line_ending)?)
     }
 }
 
+/// The PEM label of an `EncryptedPrivateKeyInfo`.
+#[cfg(feature = "pkcs8-encryption")]
+pub(crate) const ENCRYPTED_PEM_LABEL: &str = "ENCRYPTED PRIVATE KEY";
+
+/// Encrypt a PKCS#8 `document` under `passphrase` with PBES2, using scrypt
+/// and AES-256-CBC with a fresh salt and IV.
+#[cfg(feature = "pkcs8-encryption")]
+pub(crate) fn encrypt_pkcs8(
+    document: pkcs8::SecretDocument,
+    passphrase: impl AsRef<[u8]>,
+) -> pkcs8::Result<pkcs8::SecretDocument> {
+    let mut salt = [0u8; 16];
+    let mut iv = [0u8; 16];
+    rand_core::OsRng.fill_bytes(&mut salt[..]);
+    rand_core::OsRng.fill_bytes(&mut iv[..]);
+
+    let params = pkcs8::pkcs5::pbes2::Parameters::scrypt_aes256cbc(
+        scrypt::Params::recommended(),
+        &salt[..],
+        &iv,
+    )?;
+    pkcs8::PrivateKeyInfo::try_from(document.as_bytes())?.encrypt_with_params(params, passphrase)
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct SerdeHelper([u8; 32]);
 
+impl Drop for SerdeHelper {
+    fn drop(&mut self) {
+        self.0.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SerdeHelper {}
+
 impl From<SerdeHelper> for SigningKey {
     fn from(helper: SerdeHelper) -> SigningKey {
         helper.0.into()
@@ -136,24 +338,165 @@ impl SigningKey {
         bytes.into()
     }
 
+    /// Generate `n` new signing keys.
+    ///
+    /// The seeds are drawn from `rng` with a single call, so this is
+    /// deterministic for a given `rng` and avoids per-key overhead from
+    /// generators with expensive calls, such as the operating system's.
+    pub fn generate_many<R: RngCore + CryptoRng>(mut rng: R, n: usize) -> Vec<SigningKey> {
+        let mut seeds = vec![0u8; 32 * n];
+        rng.fill_bytes(&mut seeds[..]);
+        let keys = seeds.chunks_exact(32).map(SigningKey::from_seed).collect();
+        seeds.zeroize();
+        keys
+    }
+
+    /// Generate `n` new signing keys, deriving them on multiple threads.
+    ///
+    /// This draws the seeds exactly as [`SigningKey::generate_many`] does, so
+    /// both produce the same keys from the same `rng`.
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "rayon")))]
+    pub fn generate_many_parallel<R: RngCore + CryptoRng>(mut rng: R, n: usize) -> Vec<SigningKey> {
+        use rayon::prelude::*;
+
+        let mut seeds = vec![0u8; 32 * n];
+        rng.fill_bytes(&mut seeds[..]);
+        let keys = seeds
+            .par_chunks_exact(32)
+            .map(SigningKey::from_seed)
+            .collect();
+        seeds.zeroize();
+        keys
+    }
+
+    /// Construct a signing key from a 32-byte slice of a seed buffer.
+    fn from_seed(seed: &[u8]) -> SigningKey {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(seed);
+        bytes.into()
+    }
+
+    /// Generate a new signing key using the operating system's random number
+    /// generator.
+    ///
+    /// This is a convenience for applications that do not otherwise use a
+    /// random number generator; tests that need reproducible keys should pass
+    /// a seeded RNG to [`SigningKey::new`] instead.
+    #[cfg(feature = "getrandom")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "getrandom")))]
+    pub fn generate() -> SigningKey {
+        SigningKey::new(rand_core::OsRng)
+    }
+
+    /// Parse a signing key from libsodium's 64-byte secret key encoding.
+    ///
+    /// libsodium (and NaCl-derived libraries such as sodiumoxide) store an
+    /// Ed25519 secret key as `seed || A_bytes`, the 32-byte seed followed by
+    /// the encoded verification key. This returns
+    /// [`Error::MalformedSecretKey`] if the embedded verification key does not
+    /// match the one derived from the seed.
+    pub fn from_libsodium_bytes(bytes: &[u8; 64]) -> Result<SigningKey, Error> {
+        let mut seed = [0u8; 32];
+        seed[..].copy_from_slice(&bytes[0..32]);
+        let sk = SigningKey::from(seed);
+        seed.zeroize();
+        if VerificationKeyBytes::from(&sk).0[..] == bytes[32..64] {
+            Ok(sk)
+        } else {
+            Err(Error::MalformedSecretKey)
+        }
+    }
+
+    /// Encode this signing key in libsodium's 64-byte secret key format,
+    /// `seed || A_bytes`.
+    pub fn to_libsodium_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[0..32].copy_from_slice(&self.seed[..]);
+        bytes[32..64].copy_from_slice(&VerificationKeyBytes::from(self).0[..]);
+        bytes
+    }
+
     /// Create a signature on `msg` using this key.
-    #[allow(non_snake_case)]
     pub fn sign(&self, msg: &[u8]) -> Signature {
-        let r = Scalar::from_hash(Sha512::default().chain(&self.prefix[..]).chain(msg));
+        self.expanded.sign(msg)
+    }
 
-        let R_bytes = (&r * &constants::ED25519_BASEPOINT_TABLE)
-            .compress()
-            .to_bytes();
+    /// Create a signature on each of `msgs` using this key.
+    ///
+    /// The result is identical to calling [`SigningKey::sign`] on each
+    /// message in turn. The hash state that has absorbed the secret nonce
+    /// prefix is set up once and cloned for each message, and the results are
+    /// collected into a single allocation.
+    pub fn sign_batch<M: AsRef<[u8]>>(&self, msgs: &[M]) -> Vec<Signature> {
+        self.expanded.sign_batch(msgs)
+    }
 
-        let k = Scalar::from_hash(
-            Sha512::default()
-                .chain(&R_bytes[..])
-                .chain(&self.vk.A_bytes.0[..])
-                .chain(msg),
-        );
+    /// Create a signature on the concatenation of `bufs` using this key.
+    ///
+    /// This signs the buffers as one logical message, without first copying
+    /// them into a contiguous allocation, so that e.g. a protocol header and
+    /// payload can be signed in place. The result is identical to signing the
+    /// concatenated message with [`SigningKey::sign`].
+    pub fn sign_vectored(&self, bufs: &[IoSlice<'_>]) -> Signature {
+        self.expanded.sign_vectored(bufs)
+    }
 
-        let s_bytes = (r + k * self.s).to_bytes();
+    /// Create an attached signature on `msg` using this key, returning
+    /// `signature || msg`.
+    ///
+    /// This matches the combined mode of NaCl and libsodium's `crypto_sign`;
+    /// the result can be checked and unwrapped with
+    /// [`VerificationKey::open_attached`].
+    pub fn sign_attached(&self, msg: &[u8]) -> Vec<u8> {
+        let sig: [u8; 64] = self.sign(msg).into();
+        let mut signed_msg = Vec::with_capacity(sig.len() + msg.len());
+        signed_msg.extend_from_slice(&sig[..]);
+        signed_msg.extend_from_slice(msg);
+        signed_msg
+    }
+
+    /// Create an Ed25519ph signature on a prehashed message using this key.
+    ///
+    /// The `prehashed_msg` is a SHA-512 instance that has absorbed the message,
+    /// as specified by the Ed25519ph variant in [RFC 8032 §5.1][rfc]. The
+    /// optional `context` is bound into the signature and must be at most 255
+    /// bytes long; otherwise this returns [`Error::InvalidSliceLength`].
+    ///
+    /// ```
+    /// # use rand::thread_rng;
+    /// # use ed25519_zebra::*;
+    /// use sha2::{Digest, Sha512};
+    ///
+    /// let sk = SigningKey::new(thread_rng());
+    /// let prehashed_msg = Sha512::default().chain(b"Zcash");
+    /// let sig = sk.sign_prehashed(prehashed_msg.clone(), None).unwrap();
+    /// assert!(VerificationKey::from(&sk)
+    ///     .verify_prehashed(&sig, prehashed_msg, None)
+    ///     .is_ok());
+    /// ```
+    ///
+    /// [rfc]: https://tools.ietf.org/html/rfc8032#section-5.1
+    pub fn sign_prehashed(
+        &self,
+        prehashed_msg: Sha512,
+        context: Option<&[u8]>,
+    ) -> Result<Signature, Error> {
+        self.expanded.sign_prehashed(prehashed_msg, context)
+    }
 
-        Signature { R_bytes, s_bytes }
+    /// Create a hedged signature on `msg` using this key, mixing fresh entropy
+    /// from `rng` into the nonce.
+    ///
+    /// Deterministic Ed25519 nonces are derived only from the key and message,
+    /// which makes signing vulnerable to fault attacks that perturb one of two
+    /// signatures on the same message. Hedged signing derives the nonce as
+    /// `H(prefix || Z || msg)` for 32 random bytes `Z`, so that nonces stay
+    /// unpredictable even if the RNG or the deterministic derivation alone is
+    /// compromised. The result is an ordinary Ed25519 signature and verifies
+    /// with [`VerificationKey::verify`], but signing the same message twice
+    /// yields different signatures.
+    pub fn sign_with_rng<R: RngCore + CryptoRng>(&self, rng: R, msg: &[u8]) -> Signature {
+        self.expanded.sign_with_rng(rng, msg)
     }
 }
@@ -0,0 +1,92 @@
+//! Solana keypair files and addresses.
+//!
+//! The Solana CLI stores a keypair, such as `~/.config/solana/id.json`, as a
+//! JSON array of the 64 bytes `seed || A_bytes`, and an account address is the
+//! base58 encoding of the 32-byte verification key.
+//! [`Keypair::from_solana_json`], [`Keypair::to_solana_json`],
+//! [`VerificationKeyBytes::to_solana_address`], and
+//! [`VerificationKeyBytes::from_solana_address`] convert between these forms
+//! and the types of this crate.
+
+use std::{convert::TryFrom, fmt::Write};
+
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{base58, Keypair, VerificationKeyBytes};
+
+/// An error decoding a Solana keypair file or address.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The keypair file is not a JSON array of 64 byte values.
+    #[error("Malformed Solana keypair file.")]
+    MalformedKeypair,
+    /// The public key in the keypair file does not match its seed.
+    #[error("Inconsistent keypair: {0}")]
+    InconsistentKeypair(crate::Error),
+    /// The address is not valid base58.
+    #[error("Invalid base58 encoding.")]
+    InvalidBase58,
+    /// The address is not 32 bytes.
+    #[error("Malformed Solana address.")]
+    MalformedAddress,
+}
+
+impl Keypair {
+    /// Decode a Solana CLI keypair file.
+    ///
+    /// Whitespace around the array and its elements is ignored.
+    pub fn from_solana_json(json: &str) -> Result<Keypair, Error> {
+        let elements = json
+            .trim()
+            .strip_prefix('[')
+            .and_then(|json| json.strip_suffix(']'))
+            .ok_or(Error::MalformedKeypair)?;
+        let mut bytes = Zeroizing::new([0u8; 64]);
+        let mut len = 0;
+        for element in elements.split(',') {
+            let byte = element
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| Error::MalformedKeypair)?;
+            *bytes.get_mut(len).ok_or(Error::MalformedKeypair)? = byte;
+            len += 1;
+        }
+        if len != 64 {
+            return Err(Error::MalformedKeypair);
+        }
+        Keypair::try_from(&bytes[..]).map_err(Error::InconsistentKeypair)
+    }
+
+    /// Encode this keypair as a Solana CLI keypair file.
+    pub fn to_solana_json(&self) -> Zeroizing<String> {
+        let mut bytes = self.signing_key().to_libsodium_bytes();
+        let mut json = Zeroizing::new(String::with_capacity(64 * 4 + 2));
+        json.push('[');
+        for (i, byte) in bytes.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write!(json, "{}", byte).expect("writing to a String cannot fail");
+        }
+        json.push(']');
+        bytes.zeroize();
+        json
+    }
+}
+
+impl VerificationKeyBytes {
+    /// Encode this key as a base58 Solana address.
+    pub fn to_solana_address(&self) -> String {
+        base58::encode(self.as_ref())
+    }
+
+    /// Decode a base58 Solana address.
+    ///
+    /// Program-derived addresses are not on the curve, so decoding does not
+    /// check that the address is a valid verification key.
+    pub fn from_solana_address(address: &str) -> Result<VerificationKeyBytes, Error> {
+        let bytes = base58::decode(address).ok_or(Error::InvalidBase58)?;
+        VerificationKeyBytes::try_from(&bytes[..]).map_err(|_| Error::MalformedAddress)
+    }
+}
@@ -0,0 +1,394 @@
+//! [Secure Scuttlebutt][ssb] feed keys and message signatures.
+//!
+//! An SSB feed is identified by its verification key, written as the feed ID
+//! `@<base64>.ed25519`. Its keypair is stored in the `~/.ssb/secret` file: a
+//! JSON object with the feed ID and the base64 keys, surrounded by `#`
+//! comment lines; see [`Keypair::from_ssb_secret`].
+//!
+//! SSB signatures are written `<base64>.sig.ed25519`. A network may set an
+//! HMAC key, in which case what is signed is the HMAC-SHA-512-256 of the
+//! message under that key rather than the message itself; the main network
+//! has none. Legacy feed messages are signed over their JSON encoding as
+//! produced by JavaScript's `JSON.stringify(message, null, 2)`, with the
+//! signature appended as a final `signature` field; [`sign_message`] and
+//! [`verify_message`] reproduce that encoding from the message's JSON text,
+//! keeping the order of its fields.
+//!
+//! [ssb]: https://ssbc.github.io/scuttlebutt-protocol-guide/
+
+use std::{convert::TryFrom, fmt};
+
+use base64ct::{Base64, Encoding};
+use hmac::{Hmac, Mac, NewMac};
+use serde::{
+    de::{self, Deserializer, MapAccess, SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
+use sha2::Sha512;
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{Keypair, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+/// The suffix of feed IDs and keys.
+const KEY_SUFFIX: &str = ".ed25519";
+/// The suffix of signatures.
+const SIGNATURE_SUFFIX: &str = ".sig.ed25519";
+
+/// An error decoding an SSB key or verifying an SSB signature.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The feed ID is not `@<base64>.ed25519`.
+    #[error("Malformed SSB feed ID.")]
+    MalformedFeedId,
+    /// The secret file is not an Ed25519 SSB secret.
+    #[error("Malformed SSB secret file.")]
+    MalformedSecret,
+    /// The keys or feed ID in the secret file do not match its secret key.
+    #[error("Inconsistent SSB secret file.")]
+    InconsistentSecret,
+    /// The signature is not `<base64>.sig.ed25519`.
+    #[error("Malformed SSB signature.")]
+    MalformedSignature,
+    /// The message is not a JSON object with an `author` feed ID, or when
+    /// verifying, has no final `signature` field.
+    #[error("Malformed SSB message.")]
+    MalformedMessage,
+    /// The message's `author` is not the feed of the signing key.
+    #[error("SSB message author does not match the signing key.")]
+    MismatchedAuthor,
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+impl VerificationKeyBytes {
+    /// The `@<base64>.ed25519` SSB feed ID of this key.
+    pub fn to_ssb_feed_id(&self) -> String {
+        format!("@{}{}", Base64::encode_string(self.as_ref()), KEY_SUFFIX)
+    }
+
+    /// Decode an `@<base64>.ed25519` SSB feed ID.
+    pub fn from_ssb_feed_id(feed_id: &str) -> Result<VerificationKeyBytes, Error> {
+        feed_id
+            .strip_prefix('@')
+            .and_then(|feed_id| feed_id.strip_suffix(KEY_SUFFIX))
+            .and_then(|key| Base64::decode_vec(key).ok())
+            .and_then(|key| VerificationKeyBytes::try_from(&key[..]).ok())
+            .ok_or(Error::MalformedFeedId)
+    }
+}
+
+/// The JSON object in an SSB secret file.
+#[derive(Serialize, Deserialize)]
+struct SecretJson {
+    curve: String,
+    public: String,
+    private: String,
+    id: String,
+}
+
+impl Keypair {
+    /// Decode an SSB `secret` file.
+    ///
+    /// Lines starting with `#` are comments; the rest must be the JSON
+    /// object of an Ed25519 keypair.
+    pub fn from_ssb_secret(secret: &str) -> Result<Keypair, Error> {
+        let json = Zeroizing::new(
+            secret
+                .lines()
+                .filter(|line| !line.trim_start().starts_with('#'))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        let mut secret: SecretJson =
+            serde_json::from_str(&json).map_err(|_| Error::MalformedSecret)?;
+        let secret_key = secret
+            .private
+            .strip_suffix(KEY_SUFFIX)
+            .and_then(|key| Base64::decode_vec(key).ok())
+            .map(Zeroizing::new);
+        secret.private.zeroize();
+        let secret_key = secret_key.ok_or(Error::MalformedSecret)?;
+        if secret.curve != "ed25519" || secret_key.len() != 64 {
+            return Err(Error::MalformedSecret);
+        }
+        let keypair = Keypair::try_from(&secret_key[..]).map_err(|_| Error::InconsistentSecret)?;
+
+        let feed_id = VerificationKeyBytes::from(&keypair).to_ssb_feed_id();
+        if secret.id != feed_id || secret.public != feed_id[1..] {
+            return Err(Error::InconsistentSecret);
+        }
+        Ok(keypair)
+    }
+
+    /// Encode this keypair as an SSB `secret` file, with the comments
+    /// `ssb-keys` writes.
+    pub fn to_ssb_secret(&self) -> Zeroizing<String> {
+        let feed_id = VerificationKeyBytes::from(self).to_ssb_feed_id();
+        let mut secret_key = self.signing_key().to_libsodium_bytes();
+        let mut secret = SecretJson {
+            curve: "ed25519".to_string(),
+            public: feed_id[1..].to_string(),
+            private: format!("{}{}", Base64::encode_string(&secret_key), KEY_SUFFIX),
+            id: feed_id.clone(),
+        };
+        secret_key.zeroize();
+        let json = Zeroizing::new(
+            serde_json::to_string_pretty(&secret).expect("secrets serialize to JSON"),
+        );
+        secret.private.zeroize();
+        Zeroizing::new(format!(
+            "# this is your SECRET name.\n\
+             # this name gives you magical powers.\n\
+             # with it you can mark your messages so that your friends can verify\n\
+             # that they really did come from you.\n\
+             #\n\
+             # if any one learns this name, they can use it to destroy your identity\n\
+             # NEVER show this to anyone!!!\n\
+             \n\
+             {}\n\
+             \n\
+             # WARNING! It's vital that you DO NOT edit OR share your secret name\n\
+             # instead, share your public name\n\
+             # your public name: {}\n",
+            &json[..],
+            feed_id
+        ))
+    }
+}
+
+/// Sign `message`, using the network's `hmac_key` if it has one, returning
+/// the signature as `<base64>.sig.ed25519`.
+pub fn sign(signing_key: &SigningKey, hmac_key: Option<&[u8; 32]>, message: &[u8]) -> String {
+    let signature = signing_key.sign(&signed_bytes(hmac_key, message));
+    format!(
+        "{}{}",
+        Base64::encode_string(&<[u8; 64]>::from(signature)),
+        SIGNATURE_SUFFIX
+    )
+}
+
+/// Verify the `<base64>.sig.ed25519` `signature` of `message` under `vk`,
+/// using the network's `hmac_key` if it has one.
+pub fn verify(
+    vk: &VerificationKey,
+    hmac_key: Option<&[u8; 32]>,
+    message: &[u8],
+    signature: &str,
+) -> Result<(), Error> {
+    let signature = signature
+        .strip_suffix(SIGNATURE_SUFFIX)
+        .and_then(|signature| Base64::decode_vec(signature).ok())
+        .and_then(|signature| Signature::try_from(&signature[..]).ok())
+        .ok_or(Error::MalformedSignature)?;
+    vk.verify(&signature, &signed_bytes(hmac_key, message))
+        .map_err(Error::InvalidSignature)
+}
+
+/// Sign the unsigned legacy feed message `message`, a JSON object whose
+/// `author` is the feed of `signing_key`, returning the signed message as
+/// `JSON.stringify` formats it.
+pub fn sign_message(
+    signing_key: &SigningKey,
+    hmac_key: Option<&[u8; 32]>,
+    message: &str,
+) -> Result<String, Error> {
+    let mut fields = message_fields(message)?;
+    if fields.iter().any(|(key, _)| key == "signature") {
+        return Err(Error::MalformedMessage);
+    }
+    if author(&fields)? != VerificationKeyBytes::from(signing_key) {
+        return Err(Error::MismatchedAuthor);
+    }
+
+    let unsigned = Json::Object(fields.clone()).to_string();
+    let signature = sign(signing_key, hmac_key, unsigned.as_bytes());
+    fields.push(("signature".to_string(), Json::String(signature)));
+    Ok(Json::Object(fields).to_string())
+}
+
+/// Verify the signed legacy feed message `message`, a JSON object whose
+/// final field is its `signature`, returning the key of its `author`.
+pub fn verify_message(
+    hmac_key: Option<&[u8; 32]>,
+    message: &str,
+) -> Result<VerificationKeyBytes, Error> {
+    let mut fields = message_fields(message)?;
+    let signature = match fields.pop() {
+        Some((key, Json::String(signature))) if key == "signature" => signature,
+        _ => return Err(Error::MalformedMessage),
+    };
+    let vk_bytes = author(&fields)?;
+    let vk = VerificationKey::try_from(vk_bytes).map_err(Error::InvalidSignature)?;
+    let unsigned = Json::Object(fields).to_string();
+    verify(&vk, hmac_key, unsigned.as_bytes(), &signature)?;
+    Ok(vk_bytes)
+}
+
+/// The bytes actually signed for `message`: its HMAC-SHA-512-256 under
+/// `hmac_key`, or the message itself.
+fn signed_bytes(hmac_key: Option<&[u8; 32]>, message: &[u8]) -> Vec<u8> {
+    match hmac_key {
+        Some(hmac_key) => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(hmac_key).expect("HMAC accepts any key");
+            mac.update(message);
+            mac.finalize().into_bytes()[..32].to_vec()
+        }
+        None => message.to_vec(),
+    }
+}
+
+/// The fields of the JSON object `message`, in order.
+fn message_fields(message: &str) -> Result<Vec<(String, Json)>, Error> {
+    match serde_json::from_str(message) {
+        Ok(Json::Object(fields)) => Ok(fields),
+        _ => Err(Error::MalformedMessage),
+    }
+}
+
+/// The key of the message `author`.
+fn author(fields: &[(String, Json)]) -> Result<VerificationKeyBytes, Error> {
+    match fields.iter().find(|(key, _)| key == "author") {
+        Some((_, Json::String(author))) => {
+            VerificationKeyBytes::from_ssb_feed_id(author).map_err(|_| Error::MalformedMessage)
+        }
+        _ => Err(Error::MalformedMessage),
+    }
+}
+
+/// A JSON value that keeps the order of object fields, as JavaScript does.
+#[derive(Clone)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Json, D::Error> {
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
+
+struct JsonVisitor;
+
+impl<'de> Visitor<'de> for JsonVisitor {
+    type Value = Json;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Json, E> {
+        Ok(Json::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Json, E> {
+        Ok(Json::Bool(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Json, E> {
+        Ok(Json::Number(value.into()))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Json, E> {
+        Ok(Json::Number(value.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Json, E> {
+        serde_json::Number::from_f64(value)
+            .map(Json::Number)
+            .ok_or_else(|| E::custom("non-finite number"))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Json, E> {
+        Ok(Json::String(value.to_string()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Json::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+        let mut fields: Vec<(String, Json)> = Vec::new();
+        while let Some((key, value)) = map.next_entry::<String, Json>()? {
+            // As in JavaScript, a repeated key keeps its first position but
+            // takes its last value.
+            match fields.iter_mut().find(|(k, _)| *k == key) {
+                Some(field) => field.1 = value,
+                None => fields.push((key, value)),
+            }
+        }
+        Ok(Json::Object(fields))
+    }
+}
+
+impl Json {
+    /// Write this value as `JSON.stringify(value, null, 2)` does, at
+    /// `indent` levels of nesting.
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Json::Number(number) => match number.as_f64() {
+                // JavaScript writes integral numbers without a fraction.
+                Some(value) if number.is_f64() && value.fract() == 0.0 && value.abs() < 1e21 => {
+                    out.push_str(&format!("{:.0}", value))
+                }
+                _ => out.push_str(&number.to_string()),
+            },
+            Json::String(value) => out.push_str(&string(value)),
+            Json::Array(values) if values.is_empty() => out.push_str("[]"),
+            Json::Array(values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    push_indent(out, indent + 1);
+                    value.write(out, indent + 1);
+                }
+                out.push('\n');
+                push_indent(out, indent);
+                out.push(']');
+            }
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Object(fields) => {
+                out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    push_indent(out, indent + 1);
+                    out.push_str(&string(key));
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                }
+                out.push('\n');
+                push_indent(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        f.write_str(&out)
+    }
+}
+
+/// A JSON string literal, escaped as `JSON.stringify` escapes it.
+fn string(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize to JSON")
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    (0..indent).for_each(|_| out.push_str("  "));
+}
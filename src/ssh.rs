@@ -0,0 +1,497 @@
+//! OpenSSH key formats.
+//!
+//! [`SigningKey::from_openssh`] and [`SigningKey::to_openssh`] read and write
+//! the `openssh-key-v1` private key files created by `ssh-keygen -t ed25519`.
+//! Keys protected by a passphrase use bcrypt-pbkdf and AES-256-CTR, and
+//! require the `openssh-encryption` feature. The number of bcrypt-pbkdf
+//! rounds is read from the key file, so keys asking for more than
+//! [`MAX_KDF_ROUNDS`] are rejected rather than decrypted.
+//!
+//! [`VerificationKeyBytes::from_openssh`] and
+//! [`VerificationKeyBytes::to_openssh`] read and write the single-line
+//! `ssh-ed25519 AAAA... comment` public keys found in `.pub` and
+//! `authorized_keys` files, and [`VerificationKeyBytes::fingerprint_sha256`]
+//! and [`VerificationKeyBytes::fingerprint_md5`] compute the fingerprints
+//! printed by `ssh-keygen -l`. [`VerificationKeyBytes::to_sshfp`] computes the
+//! SSHFP DNS record that publishes a host key's fingerprint.
+//!
+//! The private key format is described in [`PROTOCOL.key`][key] in the
+//! OpenSSH sources.
+//!
+//! [key]: https://github.com/openssh/openssh-portable/blob/master/PROTOCOL.key
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64, Base64Unpadded, Encoding};
+use md5::Md5;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    armor::{self, ArmorError},
+    SigningKey, VerificationKeyBytes,
+};
+
+/// The magic string that begins an `openssh-key-v1` private key.
+const AUTH_MAGIC: &[u8] = b"openssh-key-v1\0";
+/// The SSH name of the Ed25519 key and signature algorithm.
+pub(crate) const KEY_TYPE: &str = "ssh-ed25519";
+const PRIVATE_KEY_LABEL: &str = "OPENSSH PRIVATE KEY";
+/// `ssh-keygen` wraps armored private keys and signatures at 70 columns.
+pub(crate) const LINE_WIDTH: usize = 70;
+
+/// The cipher used for passphrase-protected keys.
+const CIPHER: &str = "aes256-ctr";
+/// The key derivation function used for passphrase-protected keys.
+const KDF: &str = "bcrypt";
+/// The number of bcrypt-pbkdf rounds used by `ssh-keygen`.
+#[cfg(feature = "openssh-encryption")]
+const KDF_ROUNDS: u32 = 16;
+/// The largest number of bcrypt-pbkdf rounds accepted when decrypting a key.
+///
+/// Each round takes a few milliseconds, so this bounds the time an untrusted
+/// key file can make decryption take to a few seconds, while accepting keys
+/// made with `ssh-keygen -a` values well above the default of 16.
+pub const MAX_KDF_ROUNDS: u32 = 1024;
+
+/// An error decoding an OpenSSH key.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// No `-----BEGIN ...-----` boundary was found.
+    #[error("Missing PEM BEGIN boundary.")]
+    MissingPemBoundary,
+    /// The `-----END ...-----` boundary was missing, or its label did not match
+    /// the `BEGIN` boundary.
+    #[error("Missing or mismatched PEM END boundary for {0:?}.")]
+    MismatchedPemBoundary(String),
+    /// The armor label does not describe the expected kind of data.
+    #[error("Unexpected PEM label {0:?}.")]
+    UnexpectedPemLabel(String),
+    /// The data is not valid base64.
+    #[error("Invalid base64 encoding.")]
+    InvalidBase64,
+    /// The data does not have the expected structure.
+    #[error("Malformed key data: {0}.")]
+    Malformed(&'static str),
+    /// The key is not an Ed25519 key.
+    #[error("Unsupported key type {0:?}.")]
+    UnsupportedKeyType(String),
+    /// The private key is encrypted with an unsupported cipher, or encrypted
+    /// keys are not supported because the `openssh-encryption` feature is
+    /// disabled.
+    #[error("Unsupported cipher {0:?}.")]
+    UnsupportedCipher(String),
+    /// The private key is encrypted with an unsupported key derivation
+    /// function.
+    #[error("Unsupported key derivation function {0:?}.")]
+    UnsupportedKdf(String),
+    /// The private key is encrypted, but no passphrase was supplied.
+    #[error("Passphrase required.")]
+    PassphraseRequired,
+    /// The private key could not be decrypted with the supplied passphrase.
+    #[error("Incorrect passphrase.")]
+    IncorrectPassphrase,
+    /// The private key asks for more key derivation work than is accepted,
+    /// such as more than [`MAX_KDF_ROUNDS`] rounds of bcrypt-pbkdf.
+    #[error("Key derivation parameters exceed the accepted limits.")]
+    KdfLimitExceeded,
+    /// The public key does not match the private key.
+    #[error("Public key does not match private key.")]
+    PublicKeyMismatch,
+    /// The signature or key file has an unsupported format version.
+    #[error("Unsupported format version {0}.")]
+    UnsupportedVersion(u32),
+    /// The signature uses a message hash other than `sha256` or `sha512`.
+    #[error("Unsupported hash algorithm {0:?}.")]
+    UnsupportedHashAlgorithm(String),
+    /// The signature was made for a different namespace, given here.
+    #[error("Signature is for namespace {0:?}.")]
+    NamespaceMismatch(String),
+    /// The signature failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+impl From<ArmorError> for Error {
+    fn from(err: ArmorError) -> Error {
+        match err {
+            ArmorError::MissingBoundary => Error::MissingPemBoundary,
+            ArmorError::MismatchedBoundary(label) => Error::MismatchedPemBoundary(label),
+            ArmorError::InvalidBase64 => Error::InvalidBase64,
+        }
+    }
+}
+
+impl SigningKey {
+    /// Decode an `openssh-key-v1` private key, as written by
+    /// `ssh-keygen -t ed25519`, returning the key and its comment.
+    ///
+    /// The `passphrase` is only used if the key is encrypted, in which case it
+    /// is required.
+    pub fn from_openssh(
+        text: &str,
+        passphrase: Option<&[u8]>,
+    ) -> Result<(SigningKey, String), Error> {
+        let (label, data) = armor::decode(text)?;
+        if label != PRIVATE_KEY_LABEL {
+            return Err(Error::UnexpectedPemLabel(label.to_string()));
+        }
+
+        let mut reader = Reader::new(&data);
+        if reader.read_bytes(AUTH_MAGIC.len())? != AUTH_MAGIC {
+            return Err(Error::Malformed("missing openssh-key-v1 magic"));
+        }
+        let cipher = reader.read_str()?;
+        let kdf = reader.read_str()?;
+        let kdf_options = reader.read_string()?;
+        if reader.read_u32()? != 1 {
+            return Err(Error::Malformed("expected exactly one key"));
+        }
+        let public_key = parse_public_key_blob(reader.read_string()?)?;
+        let mut private = Zeroizing::new(reader.read_string()?.to_vec());
+        reader.finish()?;
+
+        let block_size = decrypt(cipher, kdf, kdf_options, passphrase, &mut private)?;
+        if private.len() % block_size != 0 {
+            return Err(Error::Malformed(
+                "private section is not a whole number of blocks",
+            ));
+        }
+
+        let mut reader = Reader::new(&private);
+        if reader.read_u32()? != reader.read_u32()? {
+            return Err(if cipher == "none" {
+                Error::Malformed("mismatched check integers")
+            } else {
+                Error::IncorrectPassphrase
+            });
+        }
+        let key_type = reader.read_str()?;
+        if key_type != KEY_TYPE {
+            return Err(Error::UnsupportedKeyType(key_type.to_string()));
+        }
+        let embedded_public_key = reader.read_string()?;
+        let keypair = reader.read_string()?;
+        let comment = reader.read_str()?.to_string();
+        let padding = reader.into_rest();
+        if padding.len() >= block_size
+            || !padding
+                .iter()
+                .zip(1usize..)
+                .all(|(&byte, i)| usize::from(byte) == i)
+        {
+            return Err(Error::Malformed("invalid padding"));
+        }
+
+        // The private key is stored in libsodium's `seed || A_bytes` format.
+        let mut bytes = [0u8; 64];
+        if keypair.len() != bytes.len() {
+            return Err(Error::Malformed("private key is not 64 bytes"));
+        }
+        bytes.copy_from_slice(keypair);
+        let sk = SigningKey::from_libsodium_bytes(&bytes).map_err(|_| Error::PublicKeyMismatch);
+        bytes.zeroize();
+        let sk = sk?;
+
+        let derived = VerificationKeyBytes::from(&sk);
+        if embedded_public_key != derived.as_ref() || public_key != derived {
+            return Err(Error::PublicKeyMismatch);
+        }
+        Ok((sk, comment))
+    }
+
+    /// Encode this key in the unencrypted `openssh-key-v1` format read by
+    /// OpenSSH, with the given `comment`.
+    ///
+    /// The check integers that OpenSSH uses to detect an incorrect passphrase
+    /// are drawn from `rng`.
+    pub fn to_openssh<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        comment: &str,
+    ) -> Zeroizing<String> {
+        let private = self.openssh_private_section(rng.next_u32(), comment, 8);
+        self.openssh_envelope("none", "none", &[], &private)
+    }
+
+    /// Encode this key in the `openssh-key-v1` format, encrypted under
+    /// `passphrase` with bcrypt-pbkdf and AES-256-CTR as by `ssh-keygen`.
+    ///
+    /// The salt and check integers are drawn from `rng`. Returns
+    /// [`Error::PassphraseRequired`] if `passphrase` is empty.
+    #[cfg(feature = "openssh-encryption")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "openssh-encryption")))]
+    pub fn to_openssh_encrypted<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        comment: &str,
+        passphrase: &[u8],
+    ) -> Result<Zeroizing<String>, Error> {
+        if passphrase.is_empty() {
+            return Err(Error::PassphraseRequired);
+        }
+        let mut salt = [0u8; 16];
+        rng.fill_bytes(&mut salt[..]);
+        let mut kdf_options = Vec::new();
+        put_string(&mut kdf_options, &salt[..]);
+        put_u32(&mut kdf_options, KDF_ROUNDS);
+
+        let mut private = self.openssh_private_section(rng.next_u32(), comment, 16);
+        aes256_ctr(passphrase, &salt[..], KDF_ROUNDS, &mut private)?;
+        Ok(self.openssh_envelope(CIPHER, KDF, &kdf_options, &private))
+    }
+
+    /// Encode the (unencrypted) private section of an `openssh-key-v1` key,
+    /// padded to a multiple of `block_size`.
+    fn openssh_private_section(
+        &self,
+        checkint: u32,
+        comment: &str,
+        block_size: usize,
+    ) -> Zeroizing<Vec<u8>> {
+        let mut keypair = self.to_libsodium_bytes();
+        let mut private = Zeroizing::new(Vec::new());
+        put_u32(&mut private, checkint);
+        put_u32(&mut private, checkint);
+        put_string(&mut private, KEY_TYPE.as_bytes());
+        put_string(&mut private, &keypair[32..]);
+        put_string(&mut private, &keypair[..]);
+        put_string(&mut private, comment.as_bytes());
+        keypair.zeroize();
+
+        let mut pad = 1;
+        while private.len() % block_size != 0 {
+            private.push(pad);
+            pad += 1;
+        }
+        private
+    }
+
+    /// Wrap an encoded private section in the `openssh-key-v1` envelope.
+    fn openssh_envelope(
+        &self,
+        cipher: &str,
+        kdf: &str,
+        kdf_options: &[u8],
+        private: &[u8],
+    ) -> Zeroizing<String> {
+        let mut data = Zeroizing::new(AUTH_MAGIC.to_vec());
+        put_string(&mut data, cipher.as_bytes());
+        put_string(&mut data, kdf.as_bytes());
+        put_string(&mut data, kdf_options);
+        put_u32(&mut data, 1);
+        put_string(
+            &mut data,
+            &public_key_blob(&VerificationKeyBytes::from(self)),
+        );
+        put_string(&mut data, private);
+        armor::encode(PRIVATE_KEY_LABEL, &data, LINE_WIDTH)
+    }
+}
+
+impl VerificationKeyBytes {
+    /// Parse a single-line OpenSSH public key, as found in `.pub` and
+    /// `authorized_keys` files, returning the key and its (possibly empty)
+    /// comment.
+    pub fn from_openssh(line: &str) -> Result<(VerificationKeyBytes, String), Error> {
+        let (key_type, rest) = next_field(line);
+        if key_type != KEY_TYPE {
+            return Err(Error::UnsupportedKeyType(key_type.to_string()));
+        }
+        let (blob, comment) = next_field(rest);
+        if blob.is_empty() {
+            return Err(Error::Malformed("missing public key data"));
+        }
+        let blob = Base64::decode_vec(blob).map_err(|_| Error::InvalidBase64)?;
+        let vk = parse_public_key_blob(&blob)?;
+        Ok((vk, comment.trim().to_string()))
+    }
+
+    /// Format this key as a single-line OpenSSH public key with the given
+    /// `comment`, which is omitted if empty.
+    pub fn to_openssh(&self, comment: &str) -> String {
+        let blob = Base64::encode_string(&public_key_blob(self));
+        if comment.is_empty() {
+            format!("{} {}", KEY_TYPE, blob)
+        } else {
+            format!("{} {} {}", KEY_TYPE, blob, comment)
+        }
+    }
+
+    /// The `SHA256:...` fingerprint of this key, as printed by `ssh-keygen -l`.
+    pub fn fingerprint_sha256(&self) -> String {
+        let digest = Sha256::digest(&public_key_blob(self));
+        format!("SHA256:{}", Base64Unpadded::encode_string(&digest))
+    }
+
+    /// The legacy `MD5:..` fingerprint of this key, as printed by
+    /// `ssh-keygen -l -E md5`.
+    pub fn fingerprint_md5(&self) -> String {
+        let digest = Md5::digest(&public_key_blob(self));
+        let hex: Vec<String> = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("MD5:{}", hex.join(":"))
+    }
+
+    /// The contents of the SSHFP record for this host key, `4 2 <hex>`: the
+    /// Ed25519 algorithm (4) and the SHA-256 fingerprint type (2), as in
+    /// [RFC 7479], followed by the fingerprint.
+    ///
+    /// This is the record printed by `ssh-keygen -r` for fingerprint type 2,
+    /// without the owner name, class, and type.
+    ///
+    /// [RFC 7479]: https://www.rfc-editor.org/rfc/rfc7479
+    pub fn to_sshfp(&self) -> String {
+        let digest = Sha256::digest(&public_key_blob(self));
+        format!("4 2 {}", hex::encode(digest))
+    }
+}
+
+/// Split the first whitespace-delimited field from `text`.
+fn next_field(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    text.split_at(text.find(char::is_whitespace).unwrap_or(text.len()))
+}
+
+/// Decrypt the private section of a key in place, returning the cipher's
+/// block size.
+fn decrypt(
+    cipher: &str,
+    kdf: &str,
+    kdf_options: &[u8],
+    passphrase: Option<&[u8]>,
+    private: &mut [u8],
+) -> Result<usize, Error> {
+    if cipher == "none" && kdf == "none" {
+        return if kdf_options.is_empty() {
+            Ok(8)
+        } else {
+            Err(Error::Malformed("unexpected KDF options"))
+        };
+    }
+    if kdf != KDF {
+        return Err(Error::UnsupportedKdf(kdf.to_string()));
+    }
+    if cipher != CIPHER {
+        return Err(Error::UnsupportedCipher(cipher.to_string()));
+    }
+
+    decrypt_aes256_ctr(kdf_options, passphrase, private)?;
+    Ok(16)
+}
+
+/// Decrypt a private section encrypted with bcrypt-pbkdf and AES-256-CTR.
+#[cfg(feature = "openssh-encryption")]
+fn decrypt_aes256_ctr(
+    kdf_options: &[u8],
+    passphrase: Option<&[u8]>,
+    private: &mut [u8],
+) -> Result<(), Error> {
+    let passphrase = passphrase.ok_or(Error::PassphraseRequired)?;
+    let mut reader = Reader::new(kdf_options);
+    let salt = reader.read_string()?;
+    let rounds = reader.read_u32()?;
+    reader.finish()?;
+    if rounds > MAX_KDF_ROUNDS {
+        return Err(Error::KdfLimitExceeded);
+    }
+    aes256_ctr(passphrase, salt, rounds, private)
+}
+
+#[cfg(not(feature = "openssh-encryption"))]
+fn decrypt_aes256_ctr(_: &[u8], _: Option<&[u8]>, _: &mut [u8]) -> Result<(), Error> {
+    Err(Error::UnsupportedCipher(CIPHER.to_string()))
+}
+
+/// Apply AES-256-CTR keyed by bcrypt-pbkdf, which both encrypts and decrypts.
+#[cfg(feature = "openssh-encryption")]
+fn aes256_ctr(passphrase: &[u8], salt: &[u8], rounds: u32, data: &mut [u8]) -> Result<(), Error> {
+    use ctr::cipher::{KeyIvInit, StreamCipher};
+
+    let mut key_iv = Zeroizing::new([0u8; 48]);
+    bcrypt_pbkdf::bcrypt_pbkdf(passphrase, salt, rounds, &mut key_iv[..])
+        .map_err(|_| Error::Malformed("invalid bcrypt-pbkdf parameters"))?;
+    ctr::Ctr128BE::<aes::Aes256>::new_from_slices(&key_iv[..32], &key_iv[32..])
+        .expect("key and IV have the correct lengths")
+        .apply_keystream(data);
+    Ok(())
+}
+
+/// Encode an Ed25519 public key in the SSH wire format.
+pub(crate) fn public_key_blob(vk: &VerificationKeyBytes) -> Vec<u8> {
+    let mut blob = Vec::with_capacity(51);
+    put_string(&mut blob, KEY_TYPE.as_bytes());
+    put_string(&mut blob, vk.as_ref());
+    blob
+}
+
+/// Decode an Ed25519 public key from the SSH wire format.
+pub(crate) fn parse_public_key_blob(blob: &[u8]) -> Result<VerificationKeyBytes, Error> {
+    let mut reader = Reader::new(blob);
+    let key_type = reader.read_str()?;
+    if key_type != KEY_TYPE {
+        return Err(Error::UnsupportedKeyType(key_type.to_string()));
+    }
+    let key = VerificationKeyBytes::try_from(reader.read_string()?)
+        .map_err(|_| Error::Malformed("public key is not 32 bytes"))?;
+    reader.finish()?;
+    Ok(key)
+}
+
+/// A reader for the SSH wire encoding of [RFC 4251 §5].
+///
+/// [RFC 4251 §5]: https://tools.ietf.org/html/rfc4251#section-5
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader(bytes)
+    }
+
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::Malformed("unexpected end of data"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub(crate) fn read_string(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.read_u32()?;
+        self.read_bytes(len as usize)
+    }
+
+    pub(crate) fn read_str(&mut self) -> Result<&'a str, Error> {
+        std::str::from_utf8(self.read_string()?).map_err(|_| Error::Malformed("invalid UTF-8"))
+    }
+
+    /// The bytes that have not been read.
+    pub(crate) fn into_rest(self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Check that all bytes have been read.
+    pub(crate) fn finish(self) -> Result<(), Error> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Malformed("unexpected trailing data"))
+        }
+    }
+}
+
+pub(crate) fn put_u32(buf: &mut Vec<u8>, n: u32) {
+    buf.extend_from_slice(&n.to_be_bytes());
+}
+
+pub(crate) fn put_string(buf: &mut Vec<u8>, bytes: &[u8]) {
+    put_u32(buf, bytes.len() as u32);
+    buf.extend_from_slice(bytes);
+}
@@ -0,0 +1,198 @@
+//! OpenSSH `SSHSIG` detached signatures.
+//!
+//! These are the signatures made and checked by `ssh-keygen -Y sign` and
+//! `ssh-keygen -Y verify`, and used by git to sign commits and tags with SSH
+//! keys. Each signature is bound to a namespace, such as `"git"` or `"file"`,
+//! so that a signature made for one purpose cannot be replayed for another.
+//!
+//! The format is described in [`PROTOCOL.sshsig`][sshsig] in the OpenSSH
+//! sources.
+//!
+//! [sshsig]: https://github.com/openssh/openssh-portable/blob/master/PROTOCOL.sshsig
+
+use std::convert::TryFrom;
+
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{
+    armor,
+    ssh::{self, put_string, put_u32, Error, Reader},
+    Signature, SigningKey, VerificationKeyBytes,
+};
+
+/// The magic preamble of signature blobs and signed data.
+const MAGIC: &[u8] = b"SSHSIG";
+const VERSION: u32 = 1;
+const LABEL: &str = "SSH SIGNATURE";
+
+/// The hash function applied to the message before signing.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HashAlgorithm {
+    /// SHA-256.
+    Sha256,
+    /// SHA-512, the default used by `ssh-keygen`.
+    Sha512,
+}
+
+impl HashAlgorithm {
+    fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    fn hash(self, message: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha256 => Sha256::digest(message).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(message).to_vec(),
+        }
+    }
+}
+
+/// An `SSHSIG` signature, together with the public key and namespace it was
+/// made with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SshSignature {
+    public_key: VerificationKeyBytes,
+    namespace: String,
+    hash_algorithm: HashAlgorithm,
+    signature: Signature,
+}
+
+impl SshSignature {
+    /// The public key embedded in the signature.
+    ///
+    /// [`SshSignature::verify`] only checks the signature against this key;
+    /// callers must separately decide whether the key is trusted, as
+    /// `ssh-keygen -Y verify` does with its allowed signers file.
+    pub fn public_key(&self) -> VerificationKeyBytes {
+        self.public_key
+    }
+
+    /// The namespace the signature was made for.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The hash function applied to the message.
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm
+    }
+
+    /// The Ed25519 signature on the signed data.
+    pub fn signature(&self) -> Signature {
+        self.signature
+    }
+
+    /// Verify this signature on `message` in the given `namespace`, with the
+    /// embedded public key.
+    pub fn verify(&self, namespace: &str, message: &[u8]) -> Result<(), Error> {
+        if namespace != self.namespace {
+            return Err(Error::NamespaceMismatch(self.namespace.to_string()));
+        }
+        let signed = signed_data(&self.namespace, self.hash_algorithm, message);
+        self.public_key
+            .verify(&self.signature, &signed)
+            .map_err(Error::InvalidSignature)
+    }
+
+    /// Decode the binary `SSHSIG` blob.
+    pub fn from_bytes(blob: &[u8]) -> Result<SshSignature, Error> {
+        let mut reader = Reader::new(blob);
+        if reader.read_bytes(MAGIC.len())? != MAGIC {
+            return Err(Error::Malformed("missing SSHSIG magic"));
+        }
+        let version = reader.read_u32()?;
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let public_key = ssh::parse_public_key_blob(reader.read_string()?)?;
+        let namespace = reader.read_str()?.to_string();
+        // The reserved field is ignored, as by OpenSSH.
+        reader.read_string()?;
+        let hash_algorithm = match reader.read_str()? {
+            "sha256" => HashAlgorithm::Sha256,
+            "sha512" => HashAlgorithm::Sha512,
+            other => return Err(Error::UnsupportedHashAlgorithm(other.to_string())),
+        };
+
+        let mut sig_reader = Reader::new(reader.read_string()?);
+        reader.finish()?;
+        let sig_type = sig_reader.read_str()?;
+        if sig_type != ssh::KEY_TYPE {
+            return Err(Error::UnsupportedKeyType(sig_type.to_string()));
+        }
+        let signature = Signature::try_from(sig_reader.read_string()?)
+            .map_err(|_| Error::Malformed("signature is not 64 bytes"))?;
+        sig_reader.finish()?;
+
+        Ok(SshSignature {
+            public_key,
+            namespace,
+            hash_algorithm,
+            signature,
+        })
+    }
+
+    /// Encode the binary `SSHSIG` blob.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut sig = Vec::with_capacity(83);
+        put_string(&mut sig, ssh::KEY_TYPE.as_bytes());
+        put_string(&mut sig, self.signature.as_ref());
+
+        let mut blob = MAGIC.to_vec();
+        put_u32(&mut blob, VERSION);
+        put_string(&mut blob, &ssh::public_key_blob(&self.public_key));
+        put_string(&mut blob, self.namespace.as_bytes());
+        put_string(&mut blob, &[]);
+        put_string(&mut blob, self.hash_algorithm.name().as_bytes());
+        put_string(&mut blob, &sig);
+        blob
+    }
+
+    /// Decode the armored `-----BEGIN SSH SIGNATURE-----` form written by
+    /// `ssh-keygen -Y sign`.
+    pub fn from_pem(text: &str) -> Result<SshSignature, Error> {
+        let (label, blob) = armor::decode(text)?;
+        if label != LABEL {
+            return Err(Error::UnexpectedPemLabel(label.to_string()));
+        }
+        SshSignature::from_bytes(&blob)
+    }
+
+    /// Encode the armored `-----BEGIN SSH SIGNATURE-----` form written by
+    /// `ssh-keygen -Y sign`.
+    pub fn to_pem(&self) -> String {
+        armor::encode(LABEL, &self.to_bytes(), ssh::LINE_WIDTH).to_string()
+    }
+}
+
+impl SigningKey {
+    /// Sign `message` in the given `namespace` as by `ssh-keygen -Y sign`,
+    /// hashing it with `hash_algorithm`.
+    pub fn sign_sshsig(
+        &self,
+        namespace: &str,
+        hash_algorithm: HashAlgorithm,
+        message: &[u8],
+    ) -> SshSignature {
+        SshSignature {
+            public_key: VerificationKeyBytes::from(self),
+            namespace: namespace.to_string(),
+            hash_algorithm,
+            signature: self.sign(&signed_data(namespace, hash_algorithm, message)),
+        }
+    }
+}
+
+/// The data that is actually signed: the hash of the message, bound to the
+/// namespace.
+fn signed_data(namespace: &str, hash_algorithm: HashAlgorithm, message: &[u8]) -> Vec<u8> {
+    let mut signed = MAGIC.to_vec();
+    put_string(&mut signed, namespace.as_bytes());
+    put_string(&mut signed, &[]);
+    put_string(&mut signed, hash_algorithm.name().as_bytes());
+    put_string(&mut signed, &hash_algorithm.hash(message));
+    signed
+}
@@ -0,0 +1,109 @@
+//! Stellar StrKey encoding.
+//!
+//! Stellar encodes keys as [StrKeys][SEP-23]: the unpadded uppercase base32
+//! encoding of a version byte, the key, and a CRC16-XModem checksum of both in
+//! little-endian order. Account IDs are `G...` StrKeys of verification keys,
+//! and secret seeds are `S...` StrKeys of signing key seeds.
+//!
+//! [SEP-23]: https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0023.md
+
+use std::convert::TryFrom;
+
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{base32, SigningKey, VerificationKeyBytes};
+
+/// The version byte of account IDs, which encodes as `G`.
+const ACCOUNT_ID: u8 = 6 << 3;
+/// The version byte of secret seeds, which encodes as `S`.
+const SECRET_SEED: u8 = 18 << 3;
+/// The length of a StrKey of a 32-byte key.
+const STRKEY_LEN: usize = 56;
+
+/// An error decoding a StrKey.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The StrKey is not 56 uppercase base32 characters.
+    #[error("Malformed StrKey.")]
+    MalformedStrKey,
+    /// The StrKey is not of the expected type, such as a secret seed where an
+    /// account ID was expected.
+    #[error("Unexpected StrKey version byte {0}.")]
+    UnexpectedVersion(u8),
+    /// The checksum embedded in the StrKey does not match its contents.
+    #[error("StrKey checksum mismatch.")]
+    InvalidChecksum,
+}
+
+impl VerificationKeyBytes {
+    /// Encode this key as a `G...` Stellar account ID.
+    pub fn to_strkey(&self) -> String {
+        encode(ACCOUNT_ID, self.as_ref())
+    }
+
+    /// Decode a `G...` Stellar account ID.
+    pub fn from_strkey(strkey: &str) -> Result<VerificationKeyBytes, Error> {
+        let key = decode(ACCOUNT_ID, strkey)?;
+        Ok(VerificationKeyBytes::try_from(&key[..])
+            .expect("32 bytes is a valid VerificationKeyBytes length"))
+    }
+}
+
+impl SigningKey {
+    /// Encode the seed of this key as an `S...` Stellar secret seed.
+    pub fn to_strkey(&self) -> Zeroizing<String> {
+        Zeroizing::new(encode(SECRET_SEED, self.as_ref()))
+    }
+
+    /// Decode an `S...` Stellar secret seed.
+    pub fn from_strkey(strkey: &str) -> Result<SigningKey, Error> {
+        let key = decode(SECRET_SEED, strkey)?;
+        Ok(SigningKey::try_from(&key[..]).expect("32 bytes is a valid SigningKey length"))
+    }
+}
+
+/// The StrKey of `key` with the given `version` byte.
+fn encode(version: u8, key: &[u8]) -> String {
+    let mut bytes = Zeroizing::new(Vec::with_capacity(1 + key.len() + 2));
+    bytes.push(version);
+    bytes.extend_from_slice(key);
+    let checksum = crc16(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    base32::encode(&bytes, base32::UPPERCASE)
+}
+
+/// The 32-byte key in the StrKey `strkey`, which must have the given
+/// `version` byte.
+fn decode(version: u8, strkey: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+    if strkey.len() != STRKEY_LEN {
+        return Err(Error::MalformedStrKey);
+    }
+    let mut bytes =
+        Zeroizing::new(base32::decode(strkey, base32::UPPERCASE).ok_or(Error::MalformedStrKey)?);
+    if bytes[0] != version {
+        return Err(Error::UnexpectedVersion(bytes[0]));
+    }
+    let len = bytes.len();
+    let checksum = bytes.split_off(len - 2);
+    if checksum[..] != crc16(&bytes).to_le_bytes()[..] {
+        return Err(Error::InvalidChecksum);
+    }
+    Ok(Zeroizing::new(bytes[1..].to_vec()))
+}
+
+/// The CRC16-XModem checksum of `bytes`.
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in bytes {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                crc << 1 ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
@@ -0,0 +1,339 @@
+//! CometBFT (Tendermint) validator keys and double-sign protection.
+//!
+//! A CometBFT validator's key is stored in `priv_validator_key.json`, which
+//! [`Keypair::from_priv_validator_key`] and [`Keypair::to_priv_validator_key`]
+//! read and write. The validator's address is the first 20 bytes of the
+//! SHA-256 digest of its verification key; see [`address`].
+//!
+//! A validator that signs two different votes or proposals at the same
+//! height, round, and step can be slashed. [`ValidatorSigner`] guards against
+//! this as CometBFT's file signer does: it records the last height, round, and
+//! step it signed in `priv_validator_state.json` before releasing each
+//! signature, and refuses to sign anything at or below that point unless it
+//! is exactly what was signed last.
+
+use std::{
+    convert::TryFrom,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use base64ct::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{Keypair, Signature, VerificationKeyBytes};
+
+/// The Amino type name of Ed25519 verification keys.
+const PUB_KEY_TYPE: &str = "tendermint/PubKeyEd25519";
+/// The Amino type name of Ed25519 secret keys.
+const PRIV_KEY_TYPE: &str = "tendermint/PrivKeyEd25519";
+
+/// An error reading a validator key or state, or signing with a
+/// [`ValidatorSigner`].
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The key file is not a CometBFT Ed25519 validator key.
+    #[error("Malformed priv_validator_key.json.")]
+    MalformedKeyFile,
+    /// The address or verification key in the key file does not match its
+    /// secret key.
+    #[error("Inconsistent priv_validator_key.json.")]
+    InconsistentKeyFile,
+    /// The state file is not a CometBFT validator signing state.
+    #[error("Malformed priv_validator_state.json.")]
+    MalformedStateFile,
+    /// The height, round, and step to sign are below the last ones signed.
+    #[error("Refusing to sign at {0}/{1}/{2}, below the last signed height, round, and step.")]
+    Regression(i64, i32, u8),
+    /// Different data was already signed at this height, round, and step.
+    #[error("Refusing to sign conflicting data at {0}/{1}/{2}.")]
+    ConflictingData(i64, i32, u8),
+    /// The state file could not be read or written.
+    #[error("Failed to access the signing state: {0:?}.")]
+    Io(io::ErrorKind),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e.kind())
+    }
+}
+
+/// The CometBFT address of the validator with verification key `vk_bytes`.
+pub fn address(vk_bytes: &VerificationKeyBytes) -> [u8; 20] {
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&Sha256::digest(vk_bytes.as_ref())[..20]);
+    address
+}
+
+/// The JSON form of `priv_validator_key.json`.
+#[derive(Serialize, Deserialize)]
+struct KeyFileJson {
+    address: String,
+    pub_key: TypedValueJson,
+    priv_key: TypedValueJson,
+}
+
+/// An Amino JSON value tagged with its type name.
+#[derive(Serialize, Deserialize)]
+struct TypedValueJson {
+    #[serde(rename = "type")]
+    value_type: String,
+    value: String,
+}
+
+impl Keypair {
+    /// Decode a CometBFT `priv_validator_key.json` file.
+    pub fn from_priv_validator_key(json: &str) -> Result<Keypair, Error> {
+        let mut key_file: KeyFileJson =
+            serde_json::from_str(json).map_err(|_| Error::MalformedKeyFile)?;
+        if key_file.pub_key.value_type != PUB_KEY_TYPE
+            || key_file.priv_key.value_type != PRIV_KEY_TYPE
+        {
+            return Err(Error::MalformedKeyFile);
+        }
+        let secret_key = Zeroizing::new(
+            Base64::decode_vec(&key_file.priv_key.value).map_err(|_| Error::MalformedKeyFile)?,
+        );
+        key_file.priv_key.value.zeroize();
+        if secret_key.len() != 64 {
+            return Err(Error::MalformedKeyFile);
+        }
+        let keypair = Keypair::try_from(&secret_key[..]).map_err(|_| Error::InconsistentKeyFile)?;
+
+        let vk_bytes = VerificationKeyBytes::from(&keypair);
+        let pub_key =
+            Base64::decode_vec(&key_file.pub_key.value).map_err(|_| Error::MalformedKeyFile)?;
+        if pub_key != vk_bytes.as_ref()
+            || !key_file
+                .address
+                .eq_ignore_ascii_case(&hex::encode(address(&vk_bytes)))
+        {
+            return Err(Error::InconsistentKeyFile);
+        }
+        Ok(keypair)
+    }
+
+    /// Encode this keypair as a CometBFT `priv_validator_key.json` file.
+    pub fn to_priv_validator_key(&self) -> Zeroizing<String> {
+        let vk_bytes = VerificationKeyBytes::from(self);
+        let mut secret_key = self.signing_key().to_libsodium_bytes();
+        let mut key_file = KeyFileJson {
+            address: hex::encode_upper(address(&vk_bytes)),
+            pub_key: TypedValueJson {
+                value_type: PUB_KEY_TYPE.to_string(),
+                value: Base64::encode_string(vk_bytes.as_ref()),
+            },
+            priv_key: TypedValueJson {
+                value_type: PRIV_KEY_TYPE.to_string(),
+                value: Base64::encode_string(&secret_key),
+            },
+        };
+        secret_key.zeroize();
+        let json = Zeroizing::new(
+            serde_json::to_string_pretty(&key_file).expect("key files serialize to JSON"),
+        );
+        key_file.priv_key.value.zeroize();
+        json
+    }
+}
+
+/// The step of a consensus round that a signature is for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Step {
+    /// A block proposal.
+    Propose = 1,
+    /// A prevote.
+    Prevote = 2,
+    /// A precommit.
+    Precommit = 3,
+}
+
+/// The last height, round, and step signed by a validator, as stored in
+/// CometBFT's `priv_validator_state.json`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SignState {
+    /// The last height signed.
+    pub height: i64,
+    /// The last round signed.
+    pub round: i32,
+    /// The last [`Step`] signed, or 0 if none has been.
+    pub step: u8,
+    /// The last signature made.
+    pub signature: Option<Signature>,
+    /// The last bytes signed.
+    pub sign_bytes: Vec<u8>,
+}
+
+/// The JSON form of `priv_validator_state.json`.
+#[derive(Serialize, Deserialize)]
+struct StateFileJson {
+    // CometBFT encodes 64-bit integers as strings.
+    height: String,
+    round: i32,
+    step: u8,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    signature: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    signbytes: String,
+}
+
+impl SignState {
+    /// Decode a CometBFT `priv_validator_state.json` file.
+    pub fn from_json(json: &str) -> Result<SignState, Error> {
+        let state: StateFileJson =
+            serde_json::from_str(json).map_err(|_| Error::MalformedStateFile)?;
+        let signature = match &state.signature[..] {
+            "" => None,
+            signature => {
+                let signature =
+                    Base64::decode_vec(signature).map_err(|_| Error::MalformedStateFile)?;
+                Some(Signature::try_from(&signature[..]).map_err(|_| Error::MalformedStateFile)?)
+            }
+        };
+        Ok(SignState {
+            height: state
+                .height
+                .parse()
+                .map_err(|_| Error::MalformedStateFile)?,
+            round: state.round,
+            step: state.step,
+            signature,
+            sign_bytes: hex::decode(&state.signbytes).map_err(|_| Error::MalformedStateFile)?,
+        })
+    }
+
+    /// Encode this state as a CometBFT `priv_validator_state.json` file.
+    pub fn to_json(&self) -> String {
+        let state = StateFileJson {
+            height: self.height.to_string(),
+            round: self.round,
+            step: self.step,
+            signature: self
+                .signature
+                .map(|signature| Base64::encode_string(&<[u8; 64]>::from(signature)))
+                .unwrap_or_default(),
+            signbytes: hex::encode_upper(&self.sign_bytes),
+        };
+        serde_json::to_string_pretty(&state).expect("states serialize to JSON")
+    }
+
+    /// Check whether `sign_bytes` may be signed at `height`, `round`, and
+    /// `step`, returning the earlier signature if exactly these bytes were
+    /// the last ones signed.
+    fn check(
+        &self,
+        height: i64,
+        round: i32,
+        step: Step,
+        sign_bytes: &[u8],
+    ) -> Result<Option<Signature>, Error> {
+        let step = step as u8;
+        let last = (self.height, self.round, self.step);
+        if (height, round, step) > last {
+            return Ok(None);
+        }
+        if (height, round, step) < last {
+            return Err(Error::Regression(height, round, step));
+        }
+        match self.signature {
+            Some(signature) if self.sign_bytes == sign_bytes => Ok(Some(signature)),
+            _ => Err(Error::ConflictingData(height, round, step)),
+        }
+    }
+}
+
+/// A validator signer that refuses to double-sign.
+///
+/// Before each signature is returned, the [`SignState`] recording it is
+/// written to the state file, so that the guard survives restarts. The state
+/// file is replaced atomically by writing a temporary file next to it and
+/// renaming it into place.
+///
+/// Unlike CometBFT, the signer does not parse votes, so a vote that differs
+/// from the last one signed only in its timestamp is refused rather than
+/// given the earlier signature; callers re-signing after a crash should
+/// resend exactly the same bytes.
+pub struct ValidatorSigner {
+    keypair: Keypair,
+    state: SignState,
+    state_path: PathBuf,
+}
+
+impl ValidatorSigner {
+    /// A signer for `keypair` whose state is kept at `state_path`.
+    ///
+    /// If the state file does not exist, it is created with an empty state.
+    pub fn open(keypair: Keypair, state_path: impl AsRef<Path>) -> Result<ValidatorSigner, Error> {
+        let state_path = state_path.as_ref().to_path_buf();
+        let state = match fs::read_to_string(&state_path) {
+            Ok(json) => SignState::from_json(&json)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let state = SignState::default();
+                write_state(&state_path, &state)?;
+                state
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(ValidatorSigner {
+            keypair,
+            state,
+            state_path,
+        })
+    }
+
+    /// The last height, round, and step signed.
+    pub fn state(&self) -> &SignState {
+        &self.state
+    }
+
+    /// The verification key of this signer.
+    pub fn verification_key_bytes(&self) -> VerificationKeyBytes {
+        VerificationKeyBytes::from(&self.keypair)
+    }
+
+    /// Sign the canonical `sign_bytes` of a vote or proposal at `height`,
+    /// `round`, and `step`.
+    ///
+    /// Signing exactly the last bytes signed again returns the same
+    /// signature; signing anything else at or below the last height, round,
+    /// and step fails.
+    pub fn sign(
+        &mut self,
+        height: i64,
+        round: i32,
+        step: Step,
+        sign_bytes: &[u8],
+    ) -> Result<Signature, Error> {
+        if let Some(signature) = self.state.check(height, round, step, sign_bytes)? {
+            return Ok(signature);
+        }
+        let signature = self.keypair.sign(sign_bytes);
+        let state = SignState {
+            height,
+            round,
+            step: step as u8,
+            signature: Some(signature),
+            sign_bytes: sign_bytes.to_vec(),
+        };
+        write_state(&self.state_path, &state)?;
+        self.state = state;
+        Ok(signature)
+    }
+}
+
+/// Atomically replace the state file at `path` with `state`.
+fn write_state(path: &Path, state: &SignState) -> io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    {
+        let mut file = fs::File::create(&temp_path)?;
+        io::Write::write_all(&mut file, state.to_json().as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp_path, path)
+}
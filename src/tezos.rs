@@ -0,0 +1,122 @@
+//! Tezos addresses and key strings.
+//!
+//! Tezos encodes keys and addresses in base58check: the base58 encoding of a
+//! type prefix, the data, and the first four bytes of the double SHA-256 of
+//! both. An Ed25519 account's `tz1...` address is the BLAKE2b-160 digest of
+//! its verification key, which is itself written `edpk...`. Secret keys are
+//! written `edsk...`, either as the 32-byte seed or as the 64 bytes
+//! `seed || A_bytes`; encrypted `edesk...` keys are not supported.
+
+use std::convert::TryFrom;
+
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{base58, SigningKey, VerificationKeyBytes};
+
+/// The base58check prefix of `tz1` addresses.
+const TZ1: [u8; 3] = [6, 161, 159];
+/// The base58check prefix of `edpk` verification keys.
+const EDPK: [u8; 4] = [13, 15, 37, 217];
+/// The base58check prefix of `edsk` seeds.
+const EDSK_SEED: [u8; 4] = [13, 15, 58, 7];
+/// The base58check prefix of `edsk` secret keys, `seed || A_bytes`.
+const EDSK_SECRET_KEY: [u8; 4] = [43, 246, 78, 7];
+
+/// An error decoding a Tezos key string.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The key is not valid base58.
+    #[error("Invalid base58 encoding.")]
+    InvalidBase58,
+    /// The base58check checksum does not match the data.
+    #[error("Base58check checksum mismatch.")]
+    InvalidChecksum,
+    /// The key is not of the expected type, such as a `tz1` address where an
+    /// `edpk` key was expected.
+    #[error("Not an Ed25519 key of the expected type.")]
+    UnexpectedPrefix,
+    /// The public key in a 64-byte secret key does not match its seed.
+    #[error("Inconsistent secret key: {0}")]
+    InconsistentKeypair(crate::Error),
+}
+
+impl VerificationKeyBytes {
+    /// The `tz1...` address of the account with this key.
+    pub fn to_tz1(&self) -> String {
+        let mut hash = [0u8; 20];
+        let mut hasher = VarBlake2b::new(20).expect("20 is a valid BLAKE2b output size");
+        hasher.update(self.as_ref());
+        hasher.finalize_variable(|digest| hash.copy_from_slice(digest));
+        encode(&TZ1, &hash)
+    }
+
+    /// Encode this key as an `edpk...` string.
+    pub fn to_edpk(&self) -> String {
+        encode(&EDPK, self.as_ref())
+    }
+
+    /// Decode an `edpk...` string.
+    pub fn from_edpk(edpk: &str) -> Result<VerificationKeyBytes, Error> {
+        let bytes = decode(edpk)?;
+        match bytes.strip_prefix(&EDPK[..]) {
+            Some(key) if key.len() == 32 => Ok(VerificationKeyBytes::try_from(key)
+                .expect("32 bytes is a valid VerificationKeyBytes length")),
+            _ => Err(Error::UnexpectedPrefix),
+        }
+    }
+}
+
+impl SigningKey {
+    /// Encode the seed of this key as an `edsk...` string.
+    pub fn to_edsk(&self) -> Zeroizing<String> {
+        Zeroizing::new(encode(&EDSK_SEED, self.as_ref()))
+    }
+
+    /// Decode an `edsk...` string of either a seed or a 64-byte secret key.
+    pub fn from_edsk(edsk: &str) -> Result<SigningKey, Error> {
+        let bytes = decode(edsk)?;
+        if let Some(seed) = bytes.strip_prefix(&EDSK_SEED[..]) {
+            if seed.len() == 32 {
+                return Ok(
+                    SigningKey::try_from(seed).expect("32 bytes is a valid SigningKey length")
+                );
+            }
+        }
+        if let Some(secret_key) = bytes.strip_prefix(&EDSK_SECRET_KEY[..]) {
+            if let Ok(secret_key) = <&[u8; 64]>::try_from(secret_key) {
+                return SigningKey::from_libsodium_bytes(secret_key)
+                    .map_err(Error::InconsistentKeypair);
+            }
+        }
+        Err(Error::UnexpectedPrefix)
+    }
+}
+
+/// The base58check encoding of `data` with `prefix`.
+fn encode(prefix: &[u8], data: &[u8]) -> String {
+    let mut bytes = Zeroizing::new(prefix.to_vec());
+    bytes.extend_from_slice(data);
+    let checksum = Sha256::digest(&Sha256::digest(&bytes));
+    bytes.extend_from_slice(&checksum[..4]);
+    base58::encode(&bytes)
+}
+
+/// Decode base58check `text`, returning the prefix and data.
+fn decode(text: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let mut bytes = Zeroizing::new(base58::decode(text).ok_or(Error::InvalidBase58)?);
+    if bytes.len() < 4 {
+        return Err(Error::InvalidChecksum);
+    }
+    let len = bytes.len();
+    let checksum = bytes.split_off(len - 4);
+    if checksum[..] != Sha256::digest(&Sha256::digest(&bytes))[..4] {
+        return Err(Error::InvalidChecksum);
+    }
+    Ok(bytes)
+}
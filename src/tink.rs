@@ -0,0 +1,576 @@
+//! Google Tink Ed25519 keysets.
+//!
+//! Tink stores keys in a [`Keyset`]: a list of keys, each with a 32-bit key
+//! ID, a status, and an output prefix type, and the ID of the primary key
+//! used to sign. Keysets are serialized as the `Keyset` protobuf message or
+//! its JSON form; [`Keyset::from_binary`] and [`Keyset::from_json`] read
+//! unencrypted keysets of Ed25519 private or public keys, as written by
+//! Tink's `CleartextKeysetHandle` and `KeysetHandle.writeNoSecret`.
+//! Encrypted keysets must be decrypted with Tink or the KMS that wraps them.
+//!
+//! A Tink signature is the key's output prefix followed by the Ed25519
+//! signature: `0x01 || key_id` for [`OutputPrefixType::Tink`], `0x00 ||
+//! key_id` for [`OutputPrefixType::Legacy`] and [`OutputPrefixType::Crunchy`],
+//! and nothing for [`OutputPrefixType::Raw`]. Legacy keys sign the message
+//! followed by a zero byte.
+
+use std::convert::TryFrom;
+
+use base64ct::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    protobuf::{self, Field},
+    Signature, SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The type URL of Ed25519 private keys.
+const PRIVATE_KEY_TYPE_URL: &str = "type.googleapis.com/google.crypto.tink.Ed25519PrivateKey";
+/// The type URL of Ed25519 public keys.
+const PUBLIC_KEY_TYPE_URL: &str = "type.googleapis.com/google.crypto.tink.Ed25519PublicKey";
+
+/// The `KeyMaterialType`s of private and public keys.
+const ASYMMETRIC_PRIVATE: u64 = 2;
+const ASYMMETRIC_PUBLIC: u64 = 3;
+
+/// The `KeyStatusType` and `OutputPrefixType` enum names, indexed by value.
+const STATUS_NAMES: [&str; 4] = ["UNKNOWN_STATUS", "ENABLED", "DISABLED", "DESTROYED"];
+const OUTPUT_PREFIX_NAMES: [&str; 5] = ["UNKNOWN_PREFIX", "TINK", "LEGACY", "RAW", "CRUNCHY"];
+/// The `KeyMaterialType` enum names, indexed by value.
+const KEY_MATERIAL_NAMES: [&str; 5] = [
+    "UNKNOWN_KEYMATERIAL",
+    "SYMMETRIC",
+    "ASYMMETRIC_PRIVATE",
+    "ASYMMETRIC_PUBLIC",
+    "REMOTE",
+];
+
+/// An error decoding a keyset, or signing or verifying with one.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The keyset is not a well-formed `Keyset` message or JSON keyset.
+    #[error("Malformed Tink keyset.")]
+    MalformedKeyset,
+    /// The keyset has a key of a type other than Ed25519, identified by its
+    /// type URL.
+    #[error("Unsupported Tink key type {0:?}.")]
+    UnsupportedKeyType(String),
+    /// The public key in a private key does not match its seed.
+    #[error("Inconsistent Tink private key.")]
+    InconsistentKey,
+    /// The primary key is missing, not enabled, or a public key.
+    #[error("The keyset has no enabled primary private key.")]
+    NoPrimaryKey,
+    /// No enabled key in the keyset verifies the signature.
+    #[error("No key in the keyset verifies the signature.")]
+    InvalidSignature,
+}
+
+/// The status of a key in a keyset.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum KeyStatus {
+    /// The key may be used.
+    Enabled = 1,
+    /// The key is kept but may not be used.
+    Disabled = 2,
+    /// The key material has been deleted.
+    Destroyed = 3,
+}
+
+/// How signatures made with a key are prefixed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OutputPrefixType {
+    /// `0x01 || key_id`.
+    Tink = 1,
+    /// `0x00 || key_id`, signing the message followed by a zero byte.
+    Legacy = 2,
+    /// No prefix.
+    Raw = 3,
+    /// `0x00 || key_id`.
+    Crunchy = 4,
+}
+
+/// The key material of a key in a keyset.
+// Keysets hold few keys, so boxing private keys would not save much.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyMaterial {
+    /// An Ed25519 private key.
+    Private(SigningKey),
+    /// An Ed25519 public key.
+    Public(VerificationKeyBytes),
+}
+
+impl KeyMaterial {
+    /// The verification key of this key material.
+    pub fn verification_key_bytes(&self) -> VerificationKeyBytes {
+        match self {
+            KeyMaterial::Private(signing_key) => VerificationKeyBytes::from(signing_key),
+            KeyMaterial::Public(vk_bytes) => *vk_bytes,
+        }
+    }
+}
+
+/// A key in a keyset.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Key {
+    /// The key ID, which is part of the output prefix.
+    pub key_id: u32,
+    /// The status of the key.
+    pub status: KeyStatus,
+    /// The output prefix type of the key.
+    pub output_prefix_type: OutputPrefixType,
+    /// The key material, or `None` if the key was destroyed.
+    pub material: Option<KeyMaterial>,
+}
+
+impl Key {
+    /// The prefix of signatures made with this key.
+    pub fn output_prefix(&self) -> Vec<u8> {
+        let mut prefix = match self.output_prefix_type {
+            OutputPrefixType::Tink => vec![0x01],
+            OutputPrefixType::Legacy | OutputPrefixType::Crunchy => vec![0x00],
+            OutputPrefixType::Raw => return Vec::new(),
+        };
+        prefix.extend_from_slice(&self.key_id.to_be_bytes());
+        prefix
+    }
+
+    /// The message that is signed for `msg`.
+    fn signed_message(&self, msg: &[u8]) -> Vec<u8> {
+        let mut signed = msg.to_vec();
+        if self.output_prefix_type == OutputPrefixType::Legacy {
+            signed.push(0x00);
+        }
+        signed
+    }
+}
+
+/// A Tink keyset of Ed25519 keys.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Keyset {
+    /// The ID of the key used to sign.
+    pub primary_key_id: u32,
+    /// The keys.
+    pub keys: Vec<Key>,
+}
+
+impl Keyset {
+    /// Sign `msg` with the primary key, returning the prefixed signature.
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Error> {
+        let primary = self
+            .keys
+            .iter()
+            .find(|key| key.key_id == self.primary_key_id && key.status == KeyStatus::Enabled)
+            .ok_or(Error::NoPrimaryKey)?;
+        let signing_key = match &primary.material {
+            Some(KeyMaterial::Private(signing_key)) => signing_key,
+            _ => return Err(Error::NoPrimaryKey),
+        };
+        let mut signature = primary.output_prefix();
+        signature.extend_from_slice(&<[u8; 64]>::from(
+            signing_key.sign(&primary.signed_message(msg)),
+        ));
+        Ok(signature)
+    }
+
+    /// Verify the prefixed `signature` of `msg` with the enabled keys whose
+    /// output prefix it starts with, then with the enabled raw keys.
+    pub fn verify(&self, signature: &[u8], msg: &[u8]) -> Result<(), Error> {
+        let verifies = |key: &Key| {
+            let prefix = key.output_prefix();
+            let signature = match signature.strip_prefix(&prefix[..]) {
+                Some(signature) => signature,
+                None => return false,
+            };
+            let vk_bytes = match &key.material {
+                Some(material) if key.status == KeyStatus::Enabled => {
+                    material.verification_key_bytes()
+                }
+                _ => return false,
+            };
+            match (
+                VerificationKey::try_from(vk_bytes),
+                Signature::try_from(signature),
+            ) {
+                (Ok(vk), Ok(signature)) => vk.verify(&signature, &key.signed_message(msg)).is_ok(),
+                _ => false,
+            }
+        };
+        let prefixed = self
+            .keys
+            .iter()
+            .filter(|key| key.output_prefix_type != OutputPrefixType::Raw);
+        let raw = self
+            .keys
+            .iter()
+            .filter(|key| key.output_prefix_type == OutputPrefixType::Raw);
+        if prefixed.chain(raw).any(verifies) {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+
+    /// This keyset with its private keys replaced by their public keys.
+    pub fn public_keyset(&self) -> Keyset {
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| Key {
+                material: key
+                    .material
+                    .as_ref()
+                    .map(|material| KeyMaterial::Public(material.verification_key_bytes())),
+                ..key.clone()
+            })
+            .collect();
+        Keyset {
+            primary_key_id: self.primary_key_id,
+            keys,
+        }
+    }
+
+    /// Decode a serialized `Keyset` protobuf message.
+    pub fn from_binary(bytes: &[u8]) -> Result<Keyset, Error> {
+        let mut primary_key_id = 0;
+        let mut keys = Vec::new();
+        for (number, field) in protobuf::read_fields(bytes).ok_or(Error::MalformedKeyset)? {
+            match (number, field) {
+                (1, Field::Varint(value)) => primary_key_id = key_id(value)?,
+                (2, Field::Bytes(key)) => keys.push(RawKey::from_binary(key)?.into_key()?),
+                _ => {}
+            }
+        }
+        Ok(Keyset {
+            primary_key_id,
+            keys,
+        })
+    }
+
+    /// Encode this keyset as a serialized `Keyset` protobuf message.
+    pub fn to_binary(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(Vec::new());
+        if self.primary_key_id != 0 {
+            protobuf::write_varint_field(&mut bytes, 1, self.primary_key_id.into());
+        }
+        for key in &self.keys {
+            protobuf::write_bytes_field(&mut bytes, 2, &RawKey::from_key(key).to_binary());
+        }
+        bytes
+    }
+
+    /// Decode a JSON keyset.
+    pub fn from_json(json: &str) -> Result<Keyset, Error> {
+        let keyset: KeysetJson = serde_json::from_str(json).map_err(|_| Error::MalformedKeyset)?;
+        let keys = keyset
+            .key
+            .into_iter()
+            .map(|key| RawKey::from_json(key)?.into_key())
+            .collect::<Result<_, _>>()?;
+        Ok(Keyset {
+            primary_key_id: key_id(keyset.primary_key_id)?,
+            keys,
+        })
+    }
+
+    /// Encode this keyset as JSON.
+    pub fn to_json(&self) -> Zeroizing<String> {
+        let mut keyset = KeysetJson {
+            primary_key_id: self.primary_key_id.into(),
+            key: self
+                .keys
+                .iter()
+                .map(|key| RawKey::from_key(key).to_json())
+                .collect(),
+        };
+        let json = Zeroizing::new(
+            serde_json::to_string_pretty(&keyset).expect("keysets serialize to JSON"),
+        );
+        for key in keyset.key.iter_mut() {
+            if let Some(key_data) = key.key_data.as_mut() {
+                key_data.value.zeroize();
+            }
+        }
+        json
+    }
+}
+
+/// Decode a key ID, which JSON keysets written by some Tink versions store
+/// as a signed 32-bit integer.
+fn key_id<T: Copy>(value: T) -> Result<u32, Error>
+where
+    u32: TryFrom<T>,
+    i32: TryFrom<T>,
+{
+    u32::try_from(value)
+        .ok()
+        .or_else(|| i32::try_from(value).ok().map(|id| id as u32))
+        .ok_or(Error::MalformedKeyset)
+}
+
+/// A `Key` message, before its enums and key data are checked.
+struct RawKey {
+    /// The type URL, serialized key proto, and key material type.
+    key_data: Option<(String, Zeroizing<Vec<u8>>, u64)>,
+    status: u64,
+    key_id: u32,
+    output_prefix_type: u64,
+}
+
+impl RawKey {
+    fn from_binary(bytes: &[u8]) -> Result<RawKey, Error> {
+        let mut key = RawKey {
+            key_data: None,
+            status: 0,
+            key_id: 0,
+            output_prefix_type: 0,
+        };
+        for (number, field) in protobuf::read_fields(bytes).ok_or(Error::MalformedKeyset)? {
+            match (number, field) {
+                (1, Field::Bytes(key_data)) => key.key_data = Some(key_data_from_binary(key_data)?),
+                (2, Field::Varint(value)) => key.status = value,
+                (3, Field::Varint(value)) => key.key_id = key_id(value)?,
+                (4, Field::Varint(value)) => key.output_prefix_type = value,
+                _ => {}
+            }
+        }
+        Ok(key)
+    }
+
+    fn to_binary(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(Vec::new());
+        if let Some((type_url, value, key_material_type)) = &self.key_data {
+            let mut key_data = Zeroizing::new(Vec::new());
+            protobuf::write_bytes_field(&mut key_data, 1, type_url.as_bytes());
+            protobuf::write_bytes_field(&mut key_data, 2, value);
+            protobuf::write_varint_field(&mut key_data, 3, *key_material_type);
+            protobuf::write_bytes_field(&mut bytes, 1, &key_data);
+        }
+        protobuf::write_varint_field(&mut bytes, 2, self.status);
+        if self.key_id != 0 {
+            protobuf::write_varint_field(&mut bytes, 3, self.key_id.into());
+        }
+        protobuf::write_varint_field(&mut bytes, 4, self.output_prefix_type);
+        bytes
+    }
+
+    fn from_json(mut key: KeyJson) -> Result<RawKey, Error> {
+        let key_data = match key.key_data.as_mut() {
+            Some(key_data) => {
+                let value = Base64::decode_vec(&key_data.value).map(Zeroizing::new);
+                key_data.value.zeroize();
+                Some((
+                    key_data.type_url.clone(),
+                    value.map_err(|_| Error::MalformedKeyset)?,
+                    enum_value(&KEY_MATERIAL_NAMES, &key_data.key_material_type)?,
+                ))
+            }
+            None => None,
+        };
+        Ok(RawKey {
+            key_data,
+            status: enum_value(&STATUS_NAMES, &key.status)?,
+            key_id: key_id(key.key_id)?,
+            output_prefix_type: enum_value(&OUTPUT_PREFIX_NAMES, &key.output_prefix_type)?,
+        })
+    }
+
+    fn to_json(&self) -> KeyJson {
+        KeyJson {
+            key_data: self
+                .key_data
+                .as_ref()
+                .map(|(type_url, value, key_material_type)| KeyDataJson {
+                    type_url: type_url.clone(),
+                    value: Base64::encode_string(value),
+                    key_material_type: KEY_MATERIAL_NAMES[*key_material_type as usize].to_string(),
+                }),
+            status: STATUS_NAMES[self.status as usize].to_string(),
+            key_id: self.key_id.into(),
+            output_prefix_type: OUTPUT_PREFIX_NAMES[self.output_prefix_type as usize].to_string(),
+        }
+    }
+
+    /// Check the enums and decode the key data of this key.
+    fn into_key(self) -> Result<Key, Error> {
+        let status = match self.status {
+            1 => KeyStatus::Enabled,
+            2 => KeyStatus::Disabled,
+            3 => KeyStatus::Destroyed,
+            _ => return Err(Error::MalformedKeyset),
+        };
+        let output_prefix_type = match self.output_prefix_type {
+            1 => OutputPrefixType::Tink,
+            2 => OutputPrefixType::Legacy,
+            3 => OutputPrefixType::Raw,
+            4 => OutputPrefixType::Crunchy,
+            _ => return Err(Error::MalformedKeyset),
+        };
+        let material = match self.key_data {
+            // Destroyed keys may keep their public key material.
+            None if status == KeyStatus::Destroyed => None,
+            None => return Err(Error::MalformedKeyset),
+            Some((type_url, value, key_material_type)) => {
+                Some(key_material(&type_url, &value, key_material_type)?)
+            }
+        };
+        Ok(Key {
+            key_id: self.key_id,
+            status,
+            output_prefix_type,
+            material,
+        })
+    }
+
+    fn from_key(key: &Key) -> RawKey {
+        let key_data = key.material.as_ref().map(|material| match material {
+            KeyMaterial::Private(signing_key) => {
+                let public_key = public_key_proto(&VerificationKeyBytes::from(signing_key));
+                let mut value = Zeroizing::new(Vec::new());
+                protobuf::write_bytes_field(&mut value, 2, signing_key.as_ref());
+                protobuf::write_bytes_field(&mut value, 3, &public_key);
+                (PRIVATE_KEY_TYPE_URL.to_string(), value, ASYMMETRIC_PRIVATE)
+            }
+            KeyMaterial::Public(vk_bytes) => (
+                PUBLIC_KEY_TYPE_URL.to_string(),
+                Zeroizing::new(public_key_proto(vk_bytes)),
+                ASYMMETRIC_PUBLIC,
+            ),
+        });
+        RawKey {
+            key_data,
+            status: key.status as u64,
+            key_id: key.key_id,
+            output_prefix_type: key.output_prefix_type as u64,
+        }
+    }
+}
+
+/// Decode a `KeyData` message.
+fn key_data_from_binary(bytes: &[u8]) -> Result<(String, Zeroizing<Vec<u8>>, u64), Error> {
+    let mut type_url = String::new();
+    let mut value = Zeroizing::new(Vec::new());
+    let mut key_material_type = 0;
+    for (number, field) in protobuf::read_fields(bytes).ok_or(Error::MalformedKeyset)? {
+        match (number, field) {
+            (1, Field::Bytes(bytes)) => {
+                type_url = String::from_utf8(bytes.to_vec()).map_err(|_| Error::MalformedKeyset)?
+            }
+            (2, Field::Bytes(bytes)) => value = Zeroizing::new(bytes.to_vec()),
+            (3, Field::Varint(value)) => key_material_type = value,
+            _ => {}
+        }
+    }
+    Ok((type_url, value, key_material_type))
+}
+
+/// Decode the serialized key proto `value` of type `type_url`.
+fn key_material(
+    type_url: &str,
+    value: &[u8],
+    key_material_type: u64,
+) -> Result<KeyMaterial, Error> {
+    match (type_url, key_material_type) {
+        (PRIVATE_KEY_TYPE_URL, ASYMMETRIC_PRIVATE) => {
+            let mut seed = None;
+            let mut public_key = None;
+            for (number, field) in key_fields(value)? {
+                match (number, field) {
+                    (2, Field::Bytes(bytes)) => seed = Some(bytes),
+                    (3, Field::Bytes(bytes)) => public_key = Some(public_key_from_proto(bytes)?),
+                    _ => {}
+                }
+            }
+            let signing_key = seed
+                .and_then(|seed| SigningKey::try_from(seed).ok())
+                .ok_or(Error::MalformedKeyset)?;
+            match public_key {
+                Some(public_key) if public_key == VerificationKeyBytes::from(&signing_key) => {
+                    Ok(KeyMaterial::Private(signing_key))
+                }
+                Some(_) => Err(Error::InconsistentKey),
+                None => Err(Error::MalformedKeyset),
+            }
+        }
+        (PUBLIC_KEY_TYPE_URL, ASYMMETRIC_PUBLIC) => {
+            Ok(KeyMaterial::Public(public_key_from_proto(value)?))
+        }
+        (PRIVATE_KEY_TYPE_URL, _) | (PUBLIC_KEY_TYPE_URL, _) => Err(Error::MalformedKeyset),
+        _ => Err(Error::UnsupportedKeyType(type_url.to_string())),
+    }
+}
+
+/// Decode the fields of an Ed25519 key proto, checking that its version is 0.
+fn key_fields(value: &[u8]) -> Result<Vec<(u64, Field<'_>)>, Error> {
+    let fields = protobuf::read_fields(value).ok_or(Error::MalformedKeyset)?;
+    if fields
+        .iter()
+        .any(|(number, field)| *number == 1 && !matches!(field, Field::Varint(0)))
+    {
+        return Err(Error::MalformedKeyset);
+    }
+    Ok(fields)
+}
+
+/// Decode a serialized `Ed25519PublicKey` proto.
+fn public_key_from_proto(value: &[u8]) -> Result<VerificationKeyBytes, Error> {
+    let mut public_key = None;
+    for (number, field) in key_fields(value)? {
+        if let (2, Field::Bytes(bytes)) = (number, field) {
+            public_key = Some(bytes);
+        }
+    }
+    public_key
+        .and_then(|bytes| VerificationKeyBytes::try_from(bytes).ok())
+        .ok_or(Error::MalformedKeyset)
+}
+
+/// Encode a serialized `Ed25519PublicKey` proto.
+fn public_key_proto(vk_bytes: &VerificationKeyBytes) -> Vec<u8> {
+    let mut value = Vec::new();
+    protobuf::write_bytes_field(&mut value, 2, vk_bytes.as_ref());
+    value
+}
+
+/// The value of the protobuf enum constant `name`, given the names of the
+/// enum's values.
+fn enum_value(names: &[&str], name: &str) -> Result<u64, Error> {
+    names
+        .iter()
+        .position(|n| *n == name)
+        .map(|value| value as u64)
+        .ok_or(Error::MalformedKeyset)
+}
+
+/// The JSON form of a `Keyset`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeysetJson {
+    #[serde(default)]
+    primary_key_id: i64,
+    key: Vec<KeyJson>,
+}
+
+/// The JSON form of a `Key`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyJson {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_data: Option<KeyDataJson>,
+    status: String,
+    #[serde(default)]
+    key_id: i64,
+    output_prefix_type: String,
+}
+
+/// The JSON form of a `KeyData`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyDataJson {
+    type_url: String,
+    value: String,
+    key_material_type: String,
+}
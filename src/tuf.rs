@@ -0,0 +1,220 @@
+//! TUF metadata signatures.
+//!
+//! [The Update Framework][TUF] signs each metadata file, such as `root.json`
+//! or `targets.json`, as
+//! `{"signatures": [{"keyid": ..., "sig": ...}], "signed": {...}}`, where each
+//! `sig` is a hex Ed25519 signature over the [canonical JSON] form of
+//! `signed`, and each `keyid` is the [`key_id`] of the signing key. Root
+//! metadata lists keys as the [`key_object`] they are identified by.
+//!
+//! [`Metadata`] signs and verifies such files; the contents of `signed` are
+//! left to the caller as a [`serde_json::Value`].
+//!
+//! [TUF]: https://theupdateframework.github.io/specification/latest/
+//! [canonical JSON]: http://wiki.laptop.org/go/Canonical_JSON
+
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{key_id::key_json, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+pub use crate::key_id::key_id;
+
+/// An error signing, decoding, or verifying TUF metadata.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The metadata is not a JSON object of the expected form.
+    #[error("Malformed TUF metadata.")]
+    MalformedMetadata,
+    /// The signed metadata contains a number that is not an integer, which
+    /// canonical JSON cannot represent.
+    #[error("Canonical JSON does not support non-integer numbers.")]
+    UnsupportedNumber,
+    /// A signature is not 64 bytes of hex.
+    #[error("Malformed signature.")]
+    MalformedSignature,
+    /// No signature has the key ID of the verification key.
+    #[error("No signature by key {0}.")]
+    MissingSignature(String),
+    /// The signature by the verification key failed to verify.
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(crate::Error),
+    /// The signature threshold is zero, which any metadata would meet.
+    #[error("The signature threshold must be at least 1.")]
+    InvalidThreshold,
+    /// Fewer than the threshold of keys have valid signatures.
+    #[error("Only {0} of the required {1} signatures are valid.")]
+    ThresholdNotMet(usize, usize),
+}
+
+/// The key object of `vk` in root metadata, whose canonical form determines
+/// its [`key_id`]:
+/// `{"keytype":"ed25519","keyval":{"public":"<hex>"},"scheme":"ed25519"}`.
+pub fn key_object(vk: &VerificationKeyBytes) -> Value {
+    serde_json::from_str(&key_json(vk)).expect("key objects are valid JSON")
+}
+
+/// The OLPC canonical JSON encoding of `value`, as signed in TUF metadata:
+/// no insignificant whitespace, object keys sorted by code point, only `"`
+/// and `\` escaped in strings, and integers only.
+pub fn canonical_json(value: &Value) -> Result<Vec<u8>, Error> {
+    let mut encoded = Vec::new();
+    write_canonical(value, &mut encoded)?;
+    Ok(encoded)
+}
+
+fn write_canonical(value: &Value, out: &mut Vec<u8>) -> Result<(), Error> {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(true) => out.extend_from_slice(b"true"),
+        Value::Bool(false) => out.extend_from_slice(b"false"),
+        Value::Number(number) if number.is_i64() || number.is_u64() => {
+            out.extend_from_slice(number.to_string().as_bytes())
+        }
+        Value::Number(_) => return Err(Error::UnsupportedNumber),
+        Value::String(string) => write_string(string, out),
+        Value::Array(array) => {
+            out.push(b'[');
+            for (i, item) in array.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push(b'{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_string(key, out);
+                out.push(b':');
+                write_canonical(item, out)?;
+            }
+            out.push(b'}');
+        }
+    }
+    Ok(())
+}
+
+fn write_string(string: &str, out: &mut Vec<u8>) {
+    out.push(b'"');
+    for byte in string.bytes() {
+        if byte == b'"' || byte == b'\\' {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b'"');
+}
+
+/// A TUF metadata file: the `signed` metadata and its signatures.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// The signatures over the canonical JSON form of `signed`.
+    pub signatures: Vec<MetadataSignature>,
+    /// The metadata, such as a `root` or `targets` object.
+    pub signed: Value,
+}
+
+/// A signature in a TUF metadata file.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MetadataSignature {
+    /// The [`key_id`] of the signing key.
+    pub keyid: String,
+    /// The signature, in lowercase hex.
+    pub sig: String,
+}
+
+impl Metadata {
+    /// Unsigned metadata with the contents `signed`.
+    pub fn new(signed: Value) -> Metadata {
+        Metadata {
+            signatures: Vec::new(),
+            signed,
+        }
+    }
+
+    /// Decode a metadata file.
+    pub fn from_json(json: &str) -> Result<Metadata, Error> {
+        serde_json::from_str(json).map_err(|_| Error::MalformedMetadata)
+    }
+
+    /// Encode this metadata file as JSON.
+    ///
+    /// The file itself need not be canonical; only `signed` is signed.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("metadata serializes to JSON")
+    }
+
+    /// Sign the metadata with `signing_key`, replacing any earlier signature
+    /// by the same key.
+    pub fn sign(&mut self, signing_key: &SigningKey) -> Result<(), Error> {
+        let keyid = key_id(&VerificationKeyBytes::from(signing_key));
+        let signature = signing_key.sign(&canonical_json(&self.signed)?);
+        self.signatures.retain(|signature| signature.keyid != keyid);
+        self.signatures.push(MetadataSignature {
+            keyid,
+            sig: hex::encode(<[u8; 64]>::from(signature)),
+        });
+        Ok(())
+    }
+
+    /// Verify a signature by `vk`, found by its [`key_id`].
+    ///
+    /// Every signature with the key ID of `vk` is tried, so a valid signature
+    /// is found even if an invalid one with the same key ID precedes it. If
+    /// none is valid, the error for the last one is returned.
+    pub fn verify(&self, vk: &VerificationKey) -> Result<(), Error> {
+        let keyid = key_id(&VerificationKeyBytes::from(*vk));
+        let msg = canonical_json(&self.signed)?;
+        let mut result = Err(Error::MissingSignature(keyid.clone()));
+        for signature in self
+            .signatures
+            .iter()
+            .filter(|signature| signature.keyid == keyid)
+        {
+            result = hex::decode(&signature.sig)
+                .ok()
+                .and_then(|sig| Signature::try_from(&sig[..]).ok())
+                .ok_or(Error::MalformedSignature)
+                .and_then(|sig| vk.verify(&sig, &msg).map_err(Error::InvalidSignature));
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Verify that at least `threshold` of the distinct `keys` of a role have
+    /// signed the metadata, as TUF clients require.
+    ///
+    /// A `threshold` of zero is rejected with [`Error::InvalidThreshold`].
+    pub fn verify_threshold(
+        &self,
+        keys: &[VerificationKey],
+        threshold: usize,
+    ) -> Result<(), Error> {
+        if threshold == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+        let mut verified: Vec<VerificationKeyBytes> = Vec::new();
+        for vk in keys {
+            let vk_bytes = VerificationKeyBytes::from(*vk);
+            if !verified.contains(&vk_bytes) && self.verify(vk).is_ok() {
+                verified.push(vk_bytes);
+            }
+        }
+        if verified.len() < threshold {
+            return Err(Error::ThresholdNotMet(verified.len(), threshold));
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,21 @@
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    io,
+};
 
 use curve25519_dalek::{
     edwards::{CompressedEdwardsY, EdwardsPoint},
     scalar::Scalar,
     traits::IsIdentity,
 };
+#[cfg(feature = "pkcs8")]
+use pkcs8::{der::asn1::BitStringRef, spki};
 use sha2::{Digest, Sha512};
+use subtle::{Choice, ConstantTimeEq};
 
-use crate::{Error, Signature};
+#[cfg(feature = "pkcs8")]
+use crate::signing_key::ALGORITHM_ID;
+use crate::{Error, Signature, VerificationPolicy};
 
 /// A refinement type for `[u8; 32]` indicating that the bytes represent an
 /// encoding of an Ed25519 verification key.
@@ -29,18 +37,53 @@ use crate::{Error, Signature};
 /// VerificationKey::try_from(vk_bytes)
 ///     .and_then(|vk| vk.verify(&sig, msg));
 /// ```
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Copy, Clone, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VerificationKeyBytes(pub(crate) [u8; 32]);
 
 impl core::fmt::Debug for VerificationKeyBytes {
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         fmt.debug_tuple("VerificationKeyBytes")
-            .field(&hex::encode(&self.0))
+            .field(&hex::encode(self.0))
             .finish()
     }
 }
 
+impl ConstantTimeEq for VerificationKeyBytes {
+    fn ct_eq(&self, other: &VerificationKeyBytes) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl PartialEq for VerificationKeyBytes {
+    fn eq(&self, other: &VerificationKeyBytes) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+// `Hash` must agree with the manual `PartialEq` above, which compares the
+// same bytes.
+impl core::hash::Hash for VerificationKeyBytes {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl core::fmt::Display for VerificationKeyBytes {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.write_str(&hex::encode(self.0))
+    }
+}
+
+impl core::str::FromStr for VerificationKeyBytes {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<VerificationKeyBytes, Error> {
+        let bytes = hex::decode(s).map_err(|_| Error::InvalidHexEncoding)?;
+        VerificationKeyBytes::try_from(&bytes[..])
+    }
+}
+
 impl AsRef<[u8]> for VerificationKeyBytes {
     fn as_ref(&self) -> &[u8] {
         &self.0[..]
@@ -72,6 +115,116 @@ impl From<VerificationKeyBytes> for [u8; 32] {
     }
 }
 
+/// Decodes an RFC 8410 `SubjectPublicKeyInfo` with the `id-Ed25519` algorithm.
+///
+/// Like `From<[u8; 32]>`, this does not check that the key encodes a curve
+/// point; that happens when converting to a [`VerificationKey`].
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl<'a> TryFrom<spki::SubjectPublicKeyInfoRef<'a>> for VerificationKeyBytes {
+    type Error = spki::Error;
+
+    fn try_from(info: spki::SubjectPublicKeyInfoRef<'a>) -> spki::Result<VerificationKeyBytes> {
+        crate::pem::verification_key_bytes_from_parts(info.algorithm, info.subject_public_key)
+            .map_err(Into::into)
+    }
+}
+
+/// Encodes the key as an RFC 8410 `SubjectPublicKeyInfo`.
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl spki::EncodePublicKey for VerificationKeyBytes {
+    fn to_public_key_der(&self) -> spki::Result<pkcs8::Document> {
+        let info = spki::SubjectPublicKeyInfoRef {
+            algorithm: ALGORITHM_ID,
+            subject_public_key: BitStringRef::new(0, &self.0[..])?,
+        };
+        Ok(pkcs8::Document::encode_msg(&info)?)
+    }
+}
+
+impl VerificationKeyBytes {
+    /// Construct a `VerificationKeyBytes` from a byte array in a `const`
+    /// context.
+    ///
+    /// This allows embedding a trusted verification key in a binary as a
+    /// `static` or `const` item:
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// # use ed25519_zebra::*;
+    /// static TRUSTED_KEY: VerificationKeyBytes = VerificationKeyBytes::from_bytes_const([
+    ///     0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64,
+    ///     0x07, 0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68,
+    ///     0xf7, 0x07, 0x51, 0x1a,
+    /// ]);
+    /// # assert!(VerificationKey::try_from(TRUSTED_KEY).is_ok());
+    /// ```
+    ///
+    /// Like `From<[u8; 32]>`, this does not check that the bytes encode a
+    /// curve point; that happens when converting to a [`VerificationKey`].
+    pub const fn from_bytes_const(bytes: [u8; 32]) -> VerificationKeyBytes {
+        VerificationKeyBytes(bytes)
+    }
+
+    /// The encoded verification key, usable in a `const` context.
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Verify a purported `signature` on the given `msg`.
+    ///
+    /// This decodes the verification key on every call, returning
+    /// [`Error::MalformedPublicKey`] if the encoding is invalid, and then
+    /// verifies as in [`VerificationKey::verify`]. Callers verifying several
+    /// signatures with the same key should decode it once into a
+    /// [`VerificationKey`] instead.
+    pub fn verify(&self, signature: &Signature, msg: &[u8]) -> Result<(), Error> {
+        VerificationKey::try_from(*self).and_then(|vk| vk.verify(signature, msg))
+    }
+
+    /// Verify a purported `signature` on the given `msg`, for a key that will
+    /// not be used again.
+    ///
+    /// This accepts and rejects the same signatures, with the same errors, as
+    /// [`VerificationKeyBytes::verify`], but decompresses the key directly
+    /// into the verification equation instead of building the cached state of
+    /// a [`VerificationKey`], negating the challenge scalar rather than the
+    /// point.
+    #[allow(non_snake_case)]
+    pub fn verify_once(&self, signature: &Signature, msg: &[u8]) -> Result<(), Error> {
+        let A = CompressedEdwardsY(self.0)
+            .decompress()
+            .ok_or(Error::MalformedPublicKey)?;
+        let k = Scalar::from_hash(
+            Sha512::default()
+                .chain(&signature.R_bytes()[..])
+                .chain(&self.0[..])
+                .chain(msg),
+        );
+        verify_zip215(signature, &-k, &A)
+    }
+
+    /// Check whether these bytes encode a weak verification key, i.e., a point
+    /// of small order.
+    ///
+    /// For a small-order key `A`, the signature whose `R` is the identity and
+    /// whose `s` is zero satisfies the cofactored verification equation for
+    /// *any* message, so
+    /// such keys provide no security and key-registration services should
+    /// reject them. This recognizes every encoding of the eight small-order
+    /// points, including the non-canonical ones, which is the blacklist
+    /// checked by libsodium and strict verifiers.
+    ///
+    /// Returns `false` if the bytes do not encode a curve point at all; such
+    /// keys are rejected by [`VerificationKey::try_from`] instead.
+    pub fn is_weak(&self) -> bool {
+        CompressedEdwardsY(self.0)
+            .decompress()
+            .map(|point| point.is_small_order())
+            .unwrap_or(false)
+    }
+}
+
 /// A valid Ed25519 verification key.
 ///
 /// This is also called a public key by other implementations.
@@ -105,6 +258,21 @@ impl From<VerificationKey> for VerificationKeyBytes {
     }
 }
 
+impl core::fmt::Display for VerificationKey {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.A_bytes, fmt)
+    }
+}
+
+impl core::str::FromStr for VerificationKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<VerificationKey, Error> {
+        s.parse::<VerificationKeyBytes>()
+            .and_then(VerificationKey::try_from)
+    }
+}
+
 impl AsRef<[u8]> for VerificationKey {
     fn as_ref(&self) -> &[u8] {
         &self.A_bytes.0[..]
@@ -148,6 +316,55 @@ impl TryFrom<[u8; 32]> for VerificationKey {
     }
 }
 
+/// Decodes an RFC 8410 `SubjectPublicKeyInfo`, returning
+/// [`spki::Error::KeyMalformed`] if the key is not a curve point.
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl<'a> TryFrom<spki::SubjectPublicKeyInfoRef<'a>> for VerificationKey {
+    type Error = spki::Error;
+
+    fn try_from(info: spki::SubjectPublicKeyInfoRef<'a>) -> spki::Result<VerificationKey> {
+        VerificationKeyBytes::try_from(info)?
+            .try_into()
+            .map_err(|_| spki::Error::KeyMalformed)
+    }
+}
+
+/// Encodes the key as an RFC 8410 `SubjectPublicKeyInfo`.
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "pkcs8")))]
+impl spki::EncodePublicKey for VerificationKey {
+    fn to_public_key_der(&self) -> spki::Result<pkcs8::Document> {
+        spki::EncodePublicKey::to_public_key_der(&self.A_bytes)
+    }
+}
+
+#[cfg(feature = "ed25519")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ed25519")))]
+impl ed25519::signature::Verifier<ed25519::Signature> for VerificationKey {
+    /// Verify a purported `signature` on the given `msg` using the ZIP215
+    /// rules described on [`VerificationKey::verify`].
+    fn verify(&self, msg: &[u8], signature: &ed25519::Signature) -> Result<(), ed25519::Error> {
+        VerificationKey::verify(self, &Signature::from(*signature), msg)
+            .map_err(ed25519::Error::from_source)
+    }
+}
+
+/// Verifies an Ed25519ph signature with no context, as in
+/// [`VerificationKey::verify_prehashed`].
+#[cfg(feature = "ed25519")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "ed25519")))]
+impl ed25519::signature::DigestVerifier<Sha512, ed25519::Signature> for VerificationKey {
+    fn verify_digest(
+        &self,
+        digest: Sha512,
+        signature: &ed25519::Signature,
+    ) -> Result<(), ed25519::Error> {
+        self.verify_prehashed(&Signature::from(*signature), digest, None)
+            .map_err(ed25519::Error::from_source)
+    }
+}
+
 impl VerificationKey {
     /// Verify a purported `signature` on the given `msg`.
     ///
@@ -168,40 +385,296 @@ impl VerificationKey {
     /// * the alternate verification equation `[s]B = R + [k]A`, allowed by RFC 8032, MUST NOT be
     ///   used.
     ///
+    /// Each failure is reported with a distinct [`Error`] variant, so that
+    /// malformed input can be told apart from a signature that is well-formed
+    /// but does not verify:
+    ///
+    /// * [`Error::MalformedSignatureR`] if `R_bytes` is not a point encoding;
+    /// * [`Error::SignatureScalarOutOfRange`] if `s` is not less than `l`;
+    /// * [`Error::InvalidSignature`] if the verification equation fails.
+    ///
     /// [ps]: https://zips.z.cash/protocol/protocol.pdf#concreteed25519
     /// [ZIP215]: https://github.com/zcash/zips/blob/master/zip-0215.rst
     pub fn verify(&self, signature: &Signature, msg: &[u8]) -> Result<(), Error> {
         let k = Scalar::from_hash(
             Sha512::default()
-                .chain(&signature.R_bytes[..])
+                .chain(&signature.R_bytes()[..])
+                .chain(&self.A_bytes.0[..])
+                .chain(msg),
+        );
+        self.verify_with_k(signature, k)
+    }
+
+    /// Verify a purported `signature` on the given `msg` under strict RFC 8032
+    /// criteria.
+    ///
+    /// This accepts a subset of the signatures accepted by
+    /// [`VerificationKey::verify`], matching the behavior of
+    /// `ed25519-dalek`'s `verify_strict`. It is intended for applications that
+    /// want the tightest acceptance set and do not need to agree with Zcash
+    /// consensus rules; consensus-critical code should use
+    /// [`VerificationKey::verify`] instead. In addition to the checks
+    /// performed there:
+    ///
+    /// * `A_bytes` MUST be the canonical encoding of `A`, or this returns
+    ///   [`Error::MalformedPublicKey`];
+    ///
+    /// * `R_bytes` MUST be the canonical encoding of `R`, or this returns
+    ///   [`Error::NonCanonicalSignatureR`];
+    ///
+    /// * neither `A` nor `R` may be of small order, or this returns
+    ///   [`Error::SmallOrderPoint`];
+    ///
+    /// * the cofactorless verification equation `[s]B = R + [k]A` MUST be
+    ///   satisfied.
+    pub fn verify_strict(&self, signature: &Signature, msg: &[u8]) -> Result<(), Error> {
+        self.verify_with_policy(signature, msg, VerificationPolicy::Rfc8032Strict)
+    }
+
+    /// Verify a purported `signature` on the given `msg` under the acceptance
+    /// criteria selected by `policy`.
+    ///
+    /// With [`VerificationPolicy::Zip215`] this is equivalent to
+    /// [`VerificationKey::verify`], and with
+    /// [`VerificationPolicy::Rfc8032Strict`] to
+    /// [`VerificationKey::verify_strict`].
+    pub fn verify_with_policy(
+        &self,
+        signature: &Signature,
+        msg: &[u8],
+        policy: VerificationPolicy,
+    ) -> Result<(), Error> {
+        let k = Scalar::from_hash(
+            Sha512::default()
+                .chain(&signature.R_bytes()[..])
                 .chain(&self.A_bytes.0[..])
                 .chain(msg),
         );
-        self.verify_prehashed(signature, k)
+        self.verify_with_k_and_policy(signature, k, policy)
     }
 
-    /// Verify a signature with a prehashed `k` value. Note that this is not the
-    /// same as "prehashing" in RFC8032.
+    /// Verify many signatures made with this key, returning `Ok(())` if all
+    /// of them are valid.
+    ///
+    /// This checks all of the `(msg, signature)` pairs with a single batch
+    /// equation in which this key appears once, as in
+    /// [`batch::Verifier`](crate::batch::Verifier), using randomizers derived
+    /// from the signatures as in
+    /// [`batch::Verifier::verify_deterministic`](crate::batch::Verifier::verify_deterministic).
+    /// Because this crate implements ZIP215, the result always agrees with
+    /// verifying each signature with [`VerificationKey::verify`], but an
+    /// error does not identify which signature was invalid.
+    pub fn verify_many<M: AsRef<[u8]>>(&self, items: &[(M, Signature)]) -> Result<(), Error> {
+        let mut batch = crate::batch::Verifier::new();
+        for (msg, signature) in items {
+            batch.queue((self.A_bytes, *signature, msg));
+        }
+        batch.verify_deterministic()
+    }
+
+    /// Verify a purported `signature` on the concatenation of `bufs`.
+    ///
+    /// This is equivalent to verifying the concatenated message with
+    /// [`VerificationKey::verify`], without first copying the buffers into a
+    /// contiguous allocation.
+    pub fn verify_vectored(
+        &self,
+        signature: &Signature,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Result<(), Error> {
+        let mut hasher = Sha512::default()
+            .chain(&signature.R_bytes()[..])
+            .chain(&self.A_bytes.0[..]);
+        for buf in bufs {
+            hasher.update(&buf[..]);
+        }
+        self.verify_with_k(signature, Scalar::from_hash(hasher))
+    }
+
+    /// Verify a purported `signature` on the message read from `reader`.
+    ///
+    /// Unlike signing, Ed25519 verification only needs a single pass over the
+    /// message, so this checks an ordinary Ed25519 signature (as produced by
+    /// [`SigningKey::sign`](crate::SigningKey::sign)) while reading the message
+    /// in fixed-size chunks, using constant memory regardless of its length.
+    /// The verification criteria are the same as for
+    /// [`VerificationKey::verify`].
+    ///
+    /// Errors from `reader` are returned as-is; an invalid signature is
+    /// returned as an [`io::ErrorKind::InvalidData`] error wrapping the
+    /// [`Error`].
+    pub fn verify_reader<R: io::Read>(
+        &self,
+        mut reader: R,
+        signature: &Signature,
+    ) -> io::Result<()> {
+        let mut hasher = Sha512::default()
+            .chain(&signature.R_bytes()[..])
+            .chain(&self.A_bytes.0[..]);
+        let mut buf = [0u8; 8192];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => hasher.update(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        self.verify_with_k(signature, Scalar::from_hash(hasher))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Verify an attached signature `signature || msg` and return `msg`.
+    ///
+    /// This matches the combined mode of NaCl and libsodium's
+    /// `crypto_sign_open`, and accepts the output of
+    /// [`SigningKey::sign_attached`](crate::SigningKey::sign_attached). Returns
+    /// [`Error::InvalidSliceLength`] if `signed_msg` is shorter than a
+    /// signature.
+    pub fn open_attached<'a>(&self, signed_msg: &'a [u8]) -> Result<&'a [u8], Error> {
+        if signed_msg.len() < 64 {
+            return Err(Error::InvalidSliceLength);
+        }
+        let (sig_bytes, msg) = signed_msg.split_at(64);
+        self.verify(&Signature::try_from(sig_bytes)?, msg)?;
+        Ok(msg)
+    }
+
+    /// Verify a purported Ed25519ph `signature` on a prehashed message.
+    ///
+    /// The `prehashed_msg` is a SHA-512 instance that has absorbed the message,
+    /// and `context` is the optional context string (at most 255 bytes) the
+    /// signature was created with, as specified by the Ed25519ph variant in
+    /// [RFC 8032 §5.1][rfc].  Apart from the hash input, the verification criteria are
+    /// the same as for [`VerificationKey::verify`].
+    ///
+    /// [rfc]: https://tools.ietf.org/html/rfc8032#section-5.1
+    pub fn verify_prehashed(
+        &self,
+        signature: &Signature,
+        prehashed_msg: Sha512,
+        context: Option<&[u8]>,
+    ) -> Result<(), Error> {
+        let k = Scalar::from_hash(
+            crate::prehash::ed25519ph_hasher(context)?
+                .chain(&signature.R_bytes()[..])
+                .chain(&self.A_bytes.0[..])
+                .chain(prehashed_msg.finalize()),
+        );
+        self.verify_with_k(signature, k)
+    }
+
+    /// Verify a signature with a precomputed `k` value under `policy`.
     #[allow(non_snake_case)]
-    pub(crate) fn verify_prehashed(&self, signature: &Signature, k: Scalar) -> Result<(), Error> {
-        // `s_bytes` MUST represent an integer less than the prime `l`.
-        let s = Scalar::from_canonical_bytes(signature.s_bytes).ok_or(Error::InvalidSignature)?;
-        // `R_bytes` MUST be an encoding of a point on the twisted Edwards form of Curve25519.
-        let R = CompressedEdwardsY(signature.R_bytes)
+    pub(crate) fn verify_with_k_and_policy(
+        &self,
+        signature: &Signature,
+        k: Scalar,
+        policy: VerificationPolicy,
+    ) -> Result<(), Error> {
+        if policy == VerificationPolicy::Zip215 {
+            return self.verify_with_k(signature, k);
+        }
+
+        let s = Scalar::from_canonical_bytes(*signature.s_bytes())
+            .ok_or(Error::SignatureScalarOutOfRange)?;
+        let R = CompressedEdwardsY(*signature.R_bytes())
             .decompress()
-            .ok_or(Error::InvalidSignature)?;
-        // We checked the encoding of A_bytes when constructing `self`.
+            .ok_or(Error::MalformedSignatureR)?;
+        let R_is_canonical = R.compress().as_bytes() == signature.R_bytes();
+        if policy == VerificationPolicy::Rfc8032Strict && !R_is_canonical {
+            return Err(Error::NonCanonicalSignatureR);
+        }
+        self.check_strict_key()?;
+        if R.is_small_order() {
+            return Err(Error::SmallOrderPoint);
+        }
 
-        //       [8][s]B = [8]R + [8][k]A
-        // <=>   [8]R = [8][s]B - [8][k]A
-        // <=>   0 = [8](R - ([s]B - [k]A))
-        // <=>   0 = [8](R - R')  where R' = [s]B - [k]A
-        let R_prime = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &self.minus_A, &s);
+        //       [s]B = R + [k]A
+        // <=>   R = [s]B - [k]A
+        let R_prime = vartime_double_scalar_mul_basepoint(&k, &self.minus_A, &s);
 
-        if (R - R_prime).mul_by_cofactor().is_identity() {
+        // libsodium compares encodings, so a non-canonical `R` fails here.
+        if R_is_canonical && R == R_prime {
             Ok(())
         } else {
             Err(Error::InvalidSignature)
         }
     }
+
+    /// Check that `A_bytes` is the canonical encoding of a point that is not
+    /// of small order, as required by the strict verification policies.
+    pub(crate) fn check_strict_key(&self) -> Result<(), Error> {
+        if (-self.minus_A).compress().as_bytes() != &self.A_bytes.0 {
+            return Err(Error::MalformedPublicKey);
+        }
+        if self.minus_A.is_small_order() {
+            return Err(Error::SmallOrderPoint);
+        }
+        Ok(())
+    }
+
+    /// Verify a signature with a precomputed `k` value. Note that this is not
+    /// the same as "prehashing" in RFC8032.
+    #[allow(non_snake_case)]
+    pub(crate) fn verify_with_k(&self, signature: &Signature, k: Scalar) -> Result<(), Error> {
+        // We checked the encoding of A_bytes when constructing `self`.
+        verify_zip215(signature, &k, &self.minus_A)
+    }
+}
+
+/// Check the ZIP215 verification equation for `signature`, where `[a]P` is
+/// `-[k]A` for the verification key `A` and challenge `k`.
+#[allow(non_snake_case)]
+fn verify_zip215(signature: &Signature, a: &Scalar, P: &EdwardsPoint) -> Result<(), Error> {
+    // `s_bytes` MUST represent an integer less than the prime `l`.
+    let s = Scalar::from_canonical_bytes(*signature.s_bytes())
+        .ok_or(Error::SignatureScalarOutOfRange)?;
+    // `R_bytes` MUST be an encoding of a point on the twisted Edwards form of Curve25519.
+    let R = CompressedEdwardsY(*signature.R_bytes())
+        .decompress()
+        .ok_or(Error::MalformedSignatureR)?;
+
+    //       [8][s]B = [8]R + [8][k]A
+    // <=>   [8]R = [8][s]B - [8][k]A
+    // <=>   0 = [8](R - ([s]B - [k]A))
+    // <=>   0 = [8](R - R')  where R' = [s]B - [k]A = [s]B + [a]P
+    let R_prime = vartime_double_scalar_mul_basepoint(a, P, &s);
+
+    if (R - R_prime).mul_by_cofactor().is_identity() {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+/// Compute `[a]A + [b]B`, where `B` is the Ed25519 basepoint, in variable time.
+#[cfg(not(feature = "small-code"))]
+#[allow(non_snake_case)]
+fn vartime_double_scalar_mul_basepoint(a: &Scalar, A: &EdwardsPoint, b: &Scalar) -> EdwardsPoint {
+    EdwardsPoint::vartime_double_scalar_mul_basepoint(a, A, b)
+}
+
+/// Compute `[a]A + [b]B`, where `B` is the Ed25519 basepoint, in variable time.
+///
+/// This uses plain double-and-add rather than curve25519-dalek's
+/// implementation, which relies on a large precomputed table of multiples of
+/// the basepoint, so that verification-only binaries don't link the table.
+#[cfg(feature = "small-code")]
+#[allow(non_snake_case)]
+fn vartime_double_scalar_mul_basepoint(a: &Scalar, A: &EdwardsPoint, b: &Scalar) -> EdwardsPoint {
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT as B, traits::Identity};
+
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut acc = EdwardsPoint::identity();
+    for i in (0..256).rev() {
+        acc = acc + acc;
+        if (a[i / 8] >> (i % 8)) & 1 == 1 {
+            acc += A;
+        }
+        if (b[i / 8] >> (i % 8)) & 1 == 1 {
+            acc += B;
+        }
+    }
+    acc
 }
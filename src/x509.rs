@@ -0,0 +1,595 @@
+//! Self-signed X.509 certificates and PKCS#10 certificate signing requests.
+//!
+//! [`CertificateBuilder`] makes a minimal self-signed [RFC 5280] certificate
+//! for a [`SigningKey`], with an `id-Ed25519` subject public key and signature
+//! as specified in [RFC 8410]. This is enough to give a service a TLS or mTLS
+//! identity backed by a key from this crate, for peers that pin the
+//! certificate or its key rather than checking a chain to a public CA.
+//!
+//! The subject (and issuer) name is a single common name, and the only
+//! extension is an optional subject alternative name listing DNS names, which
+//! TLS clients match against the server name.
+//!
+//! [`CertificateRequestBuilder`] makes an [RFC 2986] certificate signing
+//! request with the same subject and subject alternative names, to obtain a
+//! certificate from a CA instead. A CA can parse and verify incoming Ed25519
+//! requests with [`CertificateRequest::from_pem`] and
+//! [`CertificateRequest::verify`].
+//!
+//! [RFC 2986]: https://www.rfc-editor.org/rfc/rfc2986
+//! [RFC 5280]: https://www.rfc-editor.org/rfc/rfc5280
+//! [RFC 8410]: https://www.rfc-editor.org/rfc/rfc8410
+
+use std::{convert::TryFrom, time::Duration};
+
+use der::{
+    asn1::{
+        AnyRef, BitStringRef, GeneralizedTime, ObjectIdentifier, OctetStringRef, SetOfVec, UintRef,
+        UtcTime,
+    },
+    Choice, Decode, Encode, Sequence, Tag, TagNumber, ValueOrd,
+};
+use pkcs8::{spki::SubjectPublicKeyInfoRef, AlgorithmIdentifierRef};
+use sha2::{Digest, Sha512};
+use thiserror::Error;
+
+use crate::{armor, Signature, SigningKey, VerificationKeyBytes};
+
+/// The `id-at-commonName` OID, 2.5.4.3.
+const ID_AT_COMMON_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.4.3");
+/// The `id-ce-subjectAltName` OID, 2.5.29.17.
+const ID_CE_SUBJECT_ALT_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.17");
+/// The PKCS#9 `extensionRequest` OID, 1.2.840.113549.1.9.14.
+const ID_EXTENSION_REQUEST: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.14");
+
+/// The tag of a `dNSName` general name, `[2] IMPLICIT IA5String`.
+const TAG_DNS_NAME: Tag = Tag::ContextSpecific {
+    constructed: false,
+    number: TagNumber::N2,
+};
+
+/// The largest time representable in a certificate,
+/// 9999-12-31T23:59:59Z.
+const MAX_TIME: u64 = 253_402_300_799;
+/// 2050-01-01T00:00:00Z, from which RFC 5280 requires `GeneralizedTime`.
+const GENERALIZED_TIME_FROM: u64 = 2_524_608_000;
+/// The width of PEM body lines.
+const PEM_LINE_WIDTH: usize = 64;
+
+const CERTIFICATE_LABEL: &str = "CERTIFICATE";
+const CERTIFICATE_REQUEST_LABEL: &str = "CERTIFICATE REQUEST";
+/// The label of certificate requests written by some older tools.
+const NEW_CERTIFICATE_REQUEST_LABEL: &str = "NEW CERTIFICATE REQUEST";
+
+/// An error building a certificate or request, or parsing or verifying a
+/// request.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The end of the validity period is before its start.
+    #[error("Certificate validity period ends before it starts.")]
+    InvalidValidity,
+    /// A validity time is after the year 9999.
+    #[error("Certificate validity time is out of range.")]
+    TimeOutOfRange,
+    /// A DNS name is empty or contains characters other than printable
+    /// ASCII.
+    #[error("Invalid DNS name {0:?}.")]
+    InvalidDnsName(String),
+    /// The text is not a PEM `CERTIFICATE REQUEST`.
+    #[error("Invalid PEM certificate request.")]
+    InvalidPem,
+    /// The request is not well-formed DER.
+    #[error("Malformed DER: {0}.")]
+    Asn1(der::Error),
+    /// The request is well-formed DER, but not of the expected structure.
+    #[error("Malformed certificate request: {0}.")]
+    Malformed(&'static str),
+    /// The request is for a key, or signed with an algorithm, other than
+    /// Ed25519.
+    #[error("Unsupported certificate request algorithm.")]
+    UnsupportedAlgorithm,
+    /// The request's signature does not verify under its key.
+    #[error("Invalid certificate request signature: {0}")]
+    InvalidSignature(crate::Error),
+}
+
+impl From<der::Error> for Error {
+    fn from(err: der::Error) -> Error {
+        Error::Asn1(err)
+    }
+}
+
+/// A builder of self-signed X.509 v3 certificates.
+///
+/// ```
+/// # use ed25519_zebra::{SigningKey, x509::CertificateBuilder};
+/// let sk = SigningKey::new(rand::thread_rng());
+/// let certificate = CertificateBuilder::new("node.example.com", 1_700_000_000, 1_731_536_000)
+///     .dns_name("node.example.com")
+///     .self_sign(&sk)
+///     .expect("validity period and DNS name are valid");
+/// assert!(certificate.to_pem().starts_with("-----BEGIN CERTIFICATE-----\n"));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateBuilder {
+    common_name: String,
+    not_before: u64,
+    not_after: u64,
+    dns_names: Vec<String>,
+    serial_number: Option<[u8; 16]>,
+}
+
+impl CertificateBuilder {
+    /// A certificate for the subject with common name `common_name`, valid
+    /// from `not_before` through `not_after`, in seconds since the Unix
+    /// epoch.
+    pub fn new(common_name: &str, not_before: u64, not_after: u64) -> CertificateBuilder {
+        CertificateBuilder {
+            common_name: common_name.to_string(),
+            not_before,
+            not_after,
+            dns_names: Vec::new(),
+            serial_number: None,
+        }
+    }
+
+    /// Add `name` to the certificate's subject alternative names.
+    pub fn dns_name(mut self, name: &str) -> CertificateBuilder {
+        self.dns_names.push(name.to_string());
+        self
+    }
+
+    /// Use `serial_number` as the certificate's serial number.
+    ///
+    /// By default, the serial number is derived from the key, subject, and
+    /// validity period, so that certificates that differ in any of them have
+    /// different serial numbers.
+    pub fn serial_number(mut self, serial_number: [u8; 16]) -> CertificateBuilder {
+        self.serial_number = Some(serial_number);
+        self
+    }
+
+    /// Make the certificate, signed by `signing_key`, for its verification
+    /// key.
+    pub fn self_sign(&self, signing_key: &SigningKey) -> Result<Certificate, Error> {
+        if self.not_after < self.not_before {
+            return Err(Error::InvalidValidity);
+        }
+        let not_before = time(self.not_before)?;
+        let not_after = time(self.not_after)?;
+        let vk_bytes = VerificationKeyBytes::from(signing_key);
+
+        let serial_number = self
+            .serial_number
+            .unwrap_or_else(|| self.default_serial_number(&vk_bytes));
+
+        let dns_names = subject_alt_name(&self.dns_names)?;
+        let extensions = if self.dns_names.is_empty() {
+            None
+        } else {
+            Some(vec![Extension {
+                extn_id: ID_CE_SUBJECT_ALT_NAME,
+                critical: false,
+                extn_value: OctetStringRef::new(&dns_names)?,
+            }])
+        };
+        let name = name(&self.common_name)?;
+        let tbs = TbsCertificate {
+            version: 2,
+            serial_number: UintRef::new(&serial_number)?,
+            signature: Signature::ALGORITHM_ID,
+            issuer: name.clone(),
+            validity: Validity {
+                not_before,
+                not_after,
+            },
+            subject: name,
+            subject_public_key_info: subject_public_key_info(&vk_bytes)?,
+            extensions,
+        };
+        Ok(Certificate(sign(signing_key, &tbs.to_der()?)?))
+    }
+
+    fn default_serial_number(&self, vk_bytes: &VerificationKeyBytes) -> [u8; 16] {
+        let hash = Sha512::new()
+            .chain(vk_bytes.as_ref())
+            .chain(self.not_before.to_be_bytes())
+            .chain(self.not_after.to_be_bytes())
+            .chain(&self.common_name)
+            .finalize();
+        let mut serial_number = [0u8; 16];
+        serial_number.copy_from_slice(&hash[..16]);
+        // Some implementations mishandle serial numbers that need a leading
+        // zero octet to be positive.
+        serial_number[0] &= 0x7f;
+        serial_number
+    }
+}
+
+/// A DER-encoded X.509 certificate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Certificate(Vec<u8>);
+
+impl Certificate {
+    /// The DER encoding of this certificate.
+    pub fn to_der(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    /// The PEM `CERTIFICATE` encoding of this certificate.
+    pub fn to_pem(&self) -> String {
+        armor::encode(CERTIFICATE_LABEL, &self.0, PEM_LINE_WIDTH).to_string()
+    }
+}
+
+impl AsRef<[u8]> for Certificate {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Certificate> for Vec<u8> {
+    fn from(certificate: Certificate) -> Vec<u8> {
+        certificate.0
+    }
+}
+
+/// A builder of PKCS#10 certificate signing requests.
+///
+/// ```
+/// # use ed25519_zebra::{SigningKey, x509::CertificateRequestBuilder};
+/// let sk = SigningKey::new(rand::thread_rng());
+/// let request = CertificateRequestBuilder::new("device-0042.example.com")
+///     .dns_name("device-0042.example.com")
+///     .sign(&sk)
+///     .expect("DNS name is valid");
+/// assert!(request.verify().is_ok());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateRequestBuilder {
+    common_name: String,
+    dns_names: Vec<String>,
+}
+
+impl CertificateRequestBuilder {
+    /// A request for a certificate for the subject with common name
+    /// `common_name`.
+    pub fn new(common_name: &str) -> CertificateRequestBuilder {
+        CertificateRequestBuilder {
+            common_name: common_name.to_string(),
+            dns_names: Vec::new(),
+        }
+    }
+
+    /// Request `name` as one of the certificate's subject alternative names.
+    pub fn dns_name(mut self, name: &str) -> CertificateRequestBuilder {
+        self.dns_names.push(name.to_string());
+        self
+    }
+
+    /// Make the request for the verification key of `signing_key`, signed by
+    /// `signing_key` to prove possession of it.
+    pub fn sign(&self, signing_key: &SigningKey) -> Result<CertificateRequest, Error> {
+        let vk_bytes = VerificationKeyBytes::from(signing_key);
+        let dns_names = subject_alt_name(&self.dns_names)?;
+        let extensions = vec![Extension {
+            extn_id: ID_CE_SUBJECT_ALT_NAME,
+            critical: false,
+            extn_value: OctetStringRef::new(&dns_names)?,
+        }]
+        .to_der()?;
+        let mut attributes = Vec::new();
+        if !self.dns_names.is_empty() {
+            attributes.push(Attribute {
+                oid: ID_EXTENSION_REQUEST,
+                values: SetOfVec::try_from(vec![AnyRef::from_der(&extensions)?])?,
+            });
+        }
+
+        let info = CertificationRequestInfo {
+            version: 0,
+            subject: name(&self.common_name)?,
+            subject_public_key_info: subject_public_key_info(&vk_bytes)?,
+            attributes: SetOfVec::try_from(attributes)?,
+        };
+        CertificateRequest::from_der(&sign(signing_key, &info.to_der()?)?)
+    }
+}
+
+/// A PKCS#10 certificate signing request for an Ed25519 key.
+///
+/// Parsing a request does not verify it; a CA must call
+/// [`CertificateRequest::verify`] before issuing a certificate for it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateRequest {
+    der: Vec<u8>,
+    /// The DER of the signed `CertificationRequestInfo`.
+    info: Vec<u8>,
+    common_name: Option<String>,
+    dns_names: Vec<String>,
+    verification_key_bytes: VerificationKeyBytes,
+    signature: Signature,
+}
+
+impl CertificateRequest {
+    /// Parse a DER-encoded request.
+    ///
+    /// Subject attributes other than the common name, and requested
+    /// extensions other than subject alternative names, are accepted but
+    /// ignored, as are subject alternative names other than DNS names.
+    pub fn from_der(der: &[u8]) -> Result<CertificateRequest, Error> {
+        let request = Signed::from_der(der)?;
+        check_algorithm(&request.algorithm)?;
+        let signature = request
+            .signature
+            .as_bytes()
+            .and_then(|signature| Signature::try_from(signature).ok())
+            .ok_or(Error::Malformed("signature"))?;
+
+        let info_der = request.data.to_der()?;
+        let info = CertificationRequestInfo::from_der(&info_der)?;
+        if info.version != 0 {
+            return Err(Error::Malformed("unsupported version"));
+        }
+        let common_name = read_common_name(&info.subject)?;
+        let spki = info.subject_public_key_info;
+        check_algorithm(&spki.algorithm)?;
+        let verification_key_bytes = spki
+            .subject_public_key
+            .as_bytes()
+            .and_then(|key| VerificationKeyBytes::try_from(key).ok())
+            .ok_or(Error::Malformed("public key"))?;
+        let dns_names = read_requested_dns_names(&info.attributes)?;
+
+        Ok(CertificateRequest {
+            der: der.to_vec(),
+            info: info_der,
+            common_name,
+            dns_names,
+            verification_key_bytes,
+            signature,
+        })
+    }
+
+    /// Parse a PEM `CERTIFICATE REQUEST`, as written by `openssl req`.
+    pub fn from_pem(pem: &str) -> Result<CertificateRequest, Error> {
+        let (label, der) = armor::decode(pem).map_err(|_| Error::InvalidPem)?;
+        if label != CERTIFICATE_REQUEST_LABEL && label != NEW_CERTIFICATE_REQUEST_LABEL {
+            return Err(Error::InvalidPem);
+        }
+        CertificateRequest::from_der(&der)
+    }
+
+    /// The DER encoding of this request.
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    /// The PEM `CERTIFICATE REQUEST` encoding of this request.
+    pub fn to_pem(&self) -> String {
+        armor::encode(CERTIFICATE_REQUEST_LABEL, &self.der, PEM_LINE_WIDTH).to_string()
+    }
+
+    /// Verify that the request is signed by the key it is for.
+    pub fn verify(&self) -> Result<(), Error> {
+        self.verification_key_bytes
+            .verify(&self.signature, &self.info)
+            .map_err(Error::InvalidSignature)
+    }
+
+    /// The key that the certificate is requested for.
+    pub fn verification_key_bytes(&self) -> VerificationKeyBytes {
+        self.verification_key_bytes
+    }
+
+    /// The common name of the subject, if it has one.
+    pub fn common_name(&self) -> Option<&str> {
+        self.common_name.as_deref()
+    }
+
+    /// The requested DNS subject alternative names.
+    pub fn dns_names(&self) -> &[String] {
+        &self.dns_names
+    }
+}
+
+impl AsRef<[u8]> for CertificateRequest {
+    fn as_ref(&self) -> &[u8] {
+        &self.der
+    }
+}
+
+/// A signed `TBSCertificate` or `CertificationRequestInfo`, with the
+/// signature algorithm and signature that follow it in a certificate or
+/// request.
+#[derive(Sequence)]
+struct Signed<'a> {
+    data: AnyRef<'a>,
+    algorithm: AlgorithmIdentifierRef<'a>,
+    signature: BitStringRef<'a>,
+}
+
+/// The RFC 5280 `TBSCertificate` of a v3 certificate.
+#[derive(Sequence)]
+struct TbsCertificate<'a> {
+    #[asn1(context_specific = "0", tag_mode = "EXPLICIT")]
+    version: u8,
+    serial_number: UintRef<'a>,
+    signature: AlgorithmIdentifierRef<'a>,
+    issuer: Name<'a>,
+    validity: Validity,
+    subject: Name<'a>,
+    subject_public_key_info: SubjectPublicKeyInfoRef<'a>,
+    #[asn1(context_specific = "3", tag_mode = "EXPLICIT", optional = "true")]
+    extensions: Option<Vec<Extension<'a>>>,
+}
+
+/// The RFC 2986 `CertificationRequestInfo`.
+#[derive(Sequence)]
+struct CertificationRequestInfo<'a> {
+    version: u8,
+    subject: Name<'a>,
+    subject_public_key_info: SubjectPublicKeyInfoRef<'a>,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT")]
+    attributes: SetOfVec<Attribute<'a>>,
+}
+
+/// A `Name`, as a sequence of relative distinguished names.
+type Name<'a> = Vec<SetOfVec<AttributeTypeAndValue<'a>>>;
+
+/// An attribute of a relative distinguished name.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+struct AttributeTypeAndValue<'a> {
+    oid: ObjectIdentifier,
+    value: AnyRef<'a>,
+}
+
+/// An attribute of a certification request.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+struct Attribute<'a> {
+    oid: ObjectIdentifier,
+    values: SetOfVec<AnyRef<'a>>,
+}
+
+/// A certificate extension.
+#[derive(Sequence)]
+struct Extension<'a> {
+    extn_id: ObjectIdentifier,
+    #[asn1(default = "Default::default")]
+    critical: bool,
+    extn_value: OctetStringRef<'a>,
+}
+
+/// The validity period of a certificate.
+#[derive(Sequence)]
+struct Validity {
+    not_before: Time,
+    not_after: Time,
+}
+
+/// An RFC 5280 `Time`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Choice)]
+enum Time {
+    #[asn1(type = "UTCTime")]
+    UtcTime(UtcTime),
+    #[asn1(type = "GeneralizedTime")]
+    GeneralTime(GeneralizedTime),
+}
+
+/// Sign `data`, the DER of a `TBSCertificate` or `CertificationRequestInfo`,
+/// with `signing_key`, returning the DER of the signed structure.
+fn sign(signing_key: &SigningKey, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let signature = <[u8; 64]>::from(signing_key.sign(data));
+    let signed = Signed {
+        data: AnyRef::from_der(data)?,
+        algorithm: Signature::ALGORITHM_ID,
+        signature: BitStringRef::new(0, &signature)?,
+    };
+    Ok(signed.to_der()?)
+}
+
+/// Check that `algorithm` is `id-Ed25519`, which has no parameters.
+fn check_algorithm(algorithm: &AlgorithmIdentifierRef) -> Result<(), Error> {
+    if *algorithm != Signature::ALGORITHM_ID {
+        return Err(Error::UnsupportedAlgorithm);
+    }
+    Ok(())
+}
+
+/// The RFC 8410 SubjectPublicKeyInfo of `vk_bytes`.
+fn subject_public_key_info(
+    vk_bytes: &VerificationKeyBytes,
+) -> Result<SubjectPublicKeyInfoRef<'_>, Error> {
+    Ok(SubjectPublicKeyInfoRef {
+        algorithm: Signature::ALGORITHM_ID,
+        subject_public_key: BitStringRef::new(0, vk_bytes.as_ref())?,
+    })
+}
+
+/// A Name consisting of the single common name `common_name`.
+fn name(common_name: &str) -> Result<Name<'_>, Error> {
+    let attribute = AttributeTypeAndValue {
+        oid: ID_AT_COMMON_NAME,
+        value: AnyRef::new(Tag::Utf8String, common_name.as_bytes())?,
+    };
+    Ok(vec![SetOfVec::try_from(vec![attribute])?])
+}
+
+/// The DER of the subject alternative name extension value listing
+/// `dns_names`.
+fn subject_alt_name(dns_names: &[String]) -> Result<Vec<u8>, Error> {
+    let mut names = Vec::new();
+    for name in dns_names {
+        if name.is_empty() || !name.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(Error::InvalidDnsName(name.clone()));
+        }
+        names.push(AnyRef::new(TAG_DNS_NAME, name.as_bytes())?);
+    }
+    Ok(names.to_der()?)
+}
+
+/// The RFC 5280 Time of `seconds` since the Unix epoch: `UTCTime` before
+/// 2050, and `GeneralizedTime` from then on.
+fn time(seconds: u64) -> Result<Time, Error> {
+    if seconds > MAX_TIME {
+        return Err(Error::TimeOutOfRange);
+    }
+    let duration = Duration::from_secs(seconds);
+    if seconds < GENERALIZED_TIME_FROM {
+        Ok(Time::UtcTime(UtcTime::from_unix_duration(duration)?))
+    } else {
+        Ok(Time::GeneralTime(GeneralizedTime::from_unix_duration(
+            duration,
+        )?))
+    }
+}
+
+/// Find the first common name in a Name.
+fn read_common_name(name: &Name) -> Result<Option<String>, Error> {
+    let attribute = match name
+        .iter()
+        .flat_map(|rdn| rdn.iter())
+        .find(|attribute| attribute.oid == ID_AT_COMMON_NAME)
+    {
+        Some(attribute) => attribute,
+        None => return Ok(None),
+    };
+    match attribute.value.tag() {
+        Tag::Utf8String | Tag::PrintableString | Tag::Ia5String => {
+            let value = std::str::from_utf8(attribute.value.value())
+                .map_err(|_| Error::Malformed("common name"))?;
+            Ok(Some(value.to_string()))
+        }
+        _ => Err(Error::Malformed("unsupported common name string type")),
+    }
+}
+
+/// Find the DNS names in a subject alternative name extension requested in
+/// a request's attributes.
+fn read_requested_dns_names(attributes: &SetOfVec<Attribute>) -> Result<Vec<String>, Error> {
+    let mut dns_names = Vec::new();
+    for attribute in attributes.iter() {
+        if attribute.oid != ID_EXTENSION_REQUEST {
+            continue;
+        }
+        let extensions = match attribute.values.as_slice() {
+            [extensions] => extensions.decode_as::<Vec<Extension>>()?,
+            _ => return Err(Error::Malformed("extension request")),
+        };
+        for extension in extensions {
+            if extension.extn_id != ID_CE_SUBJECT_ALT_NAME {
+                continue;
+            }
+            // Names other than DNS names, such as IP addresses, are ignored.
+            for name in Vec::<AnyRef>::from_der(extension.extn_value.as_bytes())? {
+                if name.tag() == TAG_DNS_NAME {
+                    let name = std::str::from_utf8(name.value())
+                        .map_err(|_| Error::Malformed("DNS name"))?;
+                    dns_names.push(name.to_string());
+                }
+            }
+        }
+    }
+    Ok(dns_names)
+}
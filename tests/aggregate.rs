@@ -0,0 +1,76 @@
+use std::convert::TryFrom;
+
+use rand::thread_rng;
+
+use ed25519_zebra::{aggregate::*, *};
+
+fn signed_items(n: u8) -> Vec<(VerificationKeyBytes, Vec<u8>, Signature)> {
+    (0..n)
+        .map(|i| {
+            let sk = SigningKey::new(thread_rng());
+            let msg = vec![i; i as usize];
+            (VerificationKeyBytes::from(&sk), msg.clone(), sk.sign(&msg))
+        })
+        .collect()
+}
+
+fn keys_and_messages(
+    items: &[(VerificationKeyBytes, Vec<u8>, Signature)],
+) -> Vec<(VerificationKeyBytes, Vec<u8>)> {
+    items
+        .iter()
+        .map(|(vk_bytes, msg, _)| (*vk_bytes, msg.clone()))
+        .collect()
+}
+
+#[test]
+fn aggregate_and_verify() {
+    let items = signed_items(8);
+    let agg = aggregate(&items).unwrap();
+    assert_eq!(agg.len(), 8);
+    assert!(agg.verify(&keys_and_messages(&items)).is_ok());
+
+    let bytes = agg.to_bytes();
+    assert_eq!(bytes.len(), 32 * 9);
+    assert_eq!(AggregateSignature::try_from(&bytes[..]), Ok(agg.clone()));
+    assert_eq!(
+        AggregateSignature::try_from(&bytes[1..]),
+        Err(Error::InvalidSliceLength)
+    );
+
+    let decoded: AggregateSignature =
+        bincode::deserialize(&bincode::serialize(&agg).unwrap()).unwrap();
+    assert_eq!(decoded, agg);
+}
+
+#[test]
+fn aggregate_rejects_tampering() {
+    let items = signed_items(4);
+    let agg = aggregate(&items).unwrap();
+
+    let mut wrong_msg = keys_and_messages(&items);
+    wrong_msg[2].1 = b"wrong message".to_vec();
+    assert_eq!(agg.verify(&wrong_msg), Err(Error::InvalidSignature));
+
+    let mut reordered = keys_and_messages(&items);
+    reordered.swap(0, 1);
+    assert_eq!(agg.verify(&reordered), Err(Error::InvalidSignature));
+
+    let fewer = &keys_and_messages(&items)[1..];
+    assert_eq!(agg.verify(fewer), Err(Error::InvalidSignature));
+
+    let mut with_bad_sig = items.clone();
+    with_bad_sig[3].2 = with_bad_sig[0].2;
+    let bad_agg = aggregate(&with_bad_sig).unwrap();
+    assert_eq!(
+        bad_agg.verify(&keys_and_messages(&with_bad_sig)),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn aggregate_empty() {
+    let agg = aggregate::<&[u8]>(&[]).unwrap();
+    assert!(agg.is_empty());
+    assert!(agg.verify::<&[u8]>(&[]).is_ok());
+}
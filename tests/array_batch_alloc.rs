@@ -0,0 +1,55 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use rand::thread_rng;
+
+use ed25519_zebra::*;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts the heap allocations made by each thread.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn array_batch_does_not_allocate() {
+    const N: usize = 16;
+    let msg = b"BatchVerifyTest";
+    let items = (0..N)
+        .map(|_| {
+            let sk = SigningKey::new(thread_rng());
+            (VerificationKeyBytes::from(&sk), sk.sign(msg))
+        })
+        .collect::<Vec<_>>();
+    let rng = thread_rng();
+
+    let before = allocations();
+    let mut batch = batch::ArrayVerifier::<N>::new();
+    for (vk_bytes, sig) in items.iter() {
+        assert!(batch.queue((*vk_bytes, *sig, msg)).is_ok());
+    }
+    let result = batch.verify(rng);
+    assert_eq!(allocations(), before);
+    assert!(result.is_ok());
+}
@@ -0,0 +1,48 @@
+use futures::executor::block_on;
+use rand::thread_rng;
+use sha2::{Digest, Sha512};
+
+use ed25519_zebra::{io, SigningKey, VerificationKey};
+
+#[test]
+fn sign_prehashed_async_reader_matches_prehashed_signing() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let payload = vec![0x5a; 100_000];
+
+    let sig = block_on(io::sign_prehashed_async_reader(&sk, &payload[..])).unwrap();
+
+    let prehashed_msg = Sha512::default().chain(&payload);
+    assert_eq!(sig, sk.sign_prehashed(prehashed_msg.clone(), None).unwrap());
+    assert_eq!(vk.verify_prehashed(&sig, prehashed_msg, None), Ok(()));
+}
+
+#[test]
+fn verify_async_reader_matches_verify() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let payload = vec![0xa5; 100_000];
+    let sig = sk.sign(&payload);
+
+    assert!(block_on(io::verify_async_reader(&vk, &payload[..], &sig)).is_ok());
+
+    let err = block_on(io::verify_async_reader(&vk, &payload[1..], &sig))
+        .expect_err("a truncated message should not verify");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn prehashed_async_round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let payload = vec![0x3c; 100_000];
+
+    let sig = block_on(io::sign_prehashed_async_reader(&sk, &payload[..])).unwrap();
+    assert!(block_on(io::verify_prehashed_async_reader(&vk, &payload[..], &sig)).is_ok());
+
+    let err = block_on(io::verify_prehashed_async_reader(&vk, &payload[1..], &sig))
+        .expect_err("a truncated message should not verify");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    // Ed25519ph and Ed25519 signatures are not interchangeable.
+    assert!(block_on(io::verify_async_reader(&vk, &payload[..], &sig)).is_err());
+}
@@ -42,3 +42,402 @@ fn batch_verify_with_one_bad_sig() {
         }
     }
 }
+
+#[test]
+fn batch_verify_with_policy() {
+    for policy in [
+        VerificationPolicy::Zip215,
+        VerificationPolicy::Rfc8032Strict,
+        VerificationPolicy::Libsodium,
+    ] {
+        let mut batch = batch::Verifier::with_policy(policy);
+        for _ in 0..32 {
+            let sk = SigningKey::new(thread_rng());
+            let pk_bytes = VerificationKeyBytes::from(&sk);
+            let msg = b"BatchVerifyTest";
+            let sig = sk.sign(&msg[..]);
+            batch.queue((pk_bytes, sig, msg));
+        }
+        assert!(batch.verify(thread_rng()).is_ok());
+    }
+
+    // A small-order key with a signature that satisfies the cofactored
+    // equation is accepted under ZIP215 only.
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let item: batch::Item = (
+        VerificationKeyBytes::from(identity),
+        Signature::from_parts(identity, [0; 32]),
+        b"BatchVerifyTest",
+    )
+        .into();
+    for (policy, valid) in [
+        (VerificationPolicy::Zip215, true),
+        (VerificationPolicy::Rfc8032Strict, false),
+        (VerificationPolicy::Libsodium, false),
+    ] {
+        let mut batch = batch::Verifier::with_policy(policy);
+        batch.queue(item.clone());
+        assert_eq!(batch.verify(thread_rng()).is_ok(), valid);
+        assert_eq!(
+            item.clone().verify_single_with_policy(policy).is_ok(),
+            valid
+        );
+    }
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn batch_verify_with_policy_mixed_order_R() {
+    use curve25519_dalek::{constants::EIGHT_TORSION, scalar::Scalar};
+    use sha2::{Digest, Sha512};
+
+    // A signature whose R has an order-2 component satisfies the cofactored
+    // equation but not the cofactorless one. In a randomized cofactorless
+    // batch the component cancels whenever z_i is even, so without care the
+    // batch would accept it about half the time.
+    let a = Scalar::from_bytes_mod_order([7; 32]);
+    let r = Scalar::from_bytes_mod_order([11; 32]);
+    let A_bytes = (&a * &curve25519_dalek::constants::ED25519_BASEPOINT_TABLE)
+        .compress()
+        .to_bytes();
+    let R_bytes = (&r * &curve25519_dalek::constants::ED25519_BASEPOINT_TABLE + EIGHT_TORSION[4])
+        .compress()
+        .to_bytes();
+    let msg = b"BatchVerifyTest";
+    let k = Scalar::from_hash(
+        Sha512::default()
+            .chain(&R_bytes[..])
+            .chain(&A_bytes[..])
+            .chain(&msg[..]),
+    );
+    let s = r + k * a;
+    let item: batch::Item = (
+        VerificationKeyBytes::from(A_bytes),
+        Signature::from_parts(R_bytes, s.to_bytes()),
+        msg,
+    )
+        .into();
+
+    for (policy, valid) in [
+        (VerificationPolicy::Zip215, true),
+        (VerificationPolicy::Rfc8032Strict, false),
+        (VerificationPolicy::Libsodium, false),
+    ] {
+        assert_eq!(
+            item.clone().verify_single_with_policy(policy).is_ok(),
+            valid
+        );
+        for _ in 0..64 {
+            let mut batch = batch::Verifier::with_policy(policy);
+            batch.queue(item.clone());
+            assert_eq!(batch.verify(thread_rng()).is_ok(), valid);
+        }
+    }
+}
+
+#[test]
+fn batch_verify_identifying_failures() {
+    let bad_indices = [3, 10, 11];
+    let mut batch = batch::Verifier::new();
+    let mut good_batch = batch::Verifier::new();
+    let sk = SigningKey::new(thread_rng());
+    for i in 0..32 {
+        // Reuse one key for some items, so failures are also found among
+        // coalesced entries.
+        let sk = if i % 2 == 0 {
+            sk.clone()
+        } else {
+            SigningKey::new(thread_rng())
+        };
+        let pk_bytes = VerificationKeyBytes::from(&sk);
+        let msg = b"BatchVerifyTest";
+        good_batch.queue((pk_bytes, sk.sign(&msg[..]), msg));
+        let sig = if !bad_indices.contains(&i) {
+            sk.sign(&msg[..])
+        } else {
+            sk.sign(b"badmsg")
+        };
+        batch.queue((pk_bytes, sig, msg));
+    }
+    assert_eq!(good_batch.verify_identifying_failures(thread_rng()), Ok(()));
+    assert_eq!(
+        batch.verify_identifying_failures(thread_rng()),
+        Err(bad_indices
+            .iter()
+            .map(|i| (*i, Error::InvalidSignature))
+            .collect())
+    );
+}
+
+#[test]
+fn batch_verify_deterministic() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let bad_index = 7;
+    let mut batch = batch::Verifier::new();
+    let mut good_batch = batch::Verifier::new();
+    let mut seeded_batch = batch::Verifier::new();
+    for i in 0..16 {
+        let sk = SigningKey::new(thread_rng());
+        let pk_bytes = VerificationKeyBytes::from(&sk);
+        let msg = b"BatchVerifyTest";
+        let sig = sk.sign(&msg[..]);
+        good_batch.queue((pk_bytes, sig, msg));
+        seeded_batch.queue((pk_bytes, sig, msg));
+        if i != bad_index {
+            batch.queue((pk_bytes, sig, msg));
+        } else {
+            batch.queue((pk_bytes, sk.sign(b"badmsg"), msg));
+        }
+    }
+    assert!(good_batch.verify_deterministic().is_ok());
+    assert!(seeded_batch.verify(StdRng::seed_from_u64(0)).is_ok());
+    assert_eq!(batch.verify_deterministic(), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn batch_verify_with_capacity() {
+    let msg = b"BatchVerifyTest";
+    let sk = SigningKey::new(thread_rng());
+    let item = batch::Item::from((VerificationKeyBytes::from(&sk), sk.sign(msg), msg));
+
+    let mut batch = batch::Verifier::with_capacity(4, thread_rng());
+    assert_eq!(batch.capacity(), 4);
+    for _ in 0..8 {
+        let sk = SigningKey::new(thread_rng());
+        assert!(batch
+            .queue((VerificationKeyBytes::from(&sk), sk.sign(msg), msg))
+            .is_ok());
+        assert!(batch.len() <= 4);
+    }
+    assert_eq!(batch.len(), 4);
+
+    // The bad item is reported when the batch holding it is flushed.
+    assert!(batch.queue(item.clone()).is_ok());
+    assert!(batch
+        .queue((VerificationKeyBytes::from(&sk), sk.sign(b"badmsg"), msg))
+        .is_ok());
+    assert!(batch.queue(item.clone()).is_ok());
+    assert!(batch.queue(item.clone()).is_ok());
+    assert_eq!(batch.queue(item), Err(Error::InvalidSignature));
+    assert_eq!(batch.len(), 1);
+    assert!(batch.finalize().is_ok());
+}
+
+#[test]
+fn bounded_batch_verify() {
+    let mut batch = batch::BoundedVerifier::new(8, thread_rng());
+    assert_eq!(batch.capacity(), 8);
+    let msg = b"BatchVerifyTest";
+    for i in 0..20 {
+        let sk = SigningKey::new(thread_rng());
+        let pk_bytes = VerificationKeyBytes::from(&sk);
+        let sig = if i != 10 {
+            sk.sign(&msg[..])
+        } else {
+            sk.sign(b"badmsg")
+        };
+        let result = batch.queue((pk_bytes, sig, msg));
+        // Items 0..8 are flushed when queuing item 8, and items 8..16, which
+        // include the bad signature, when queuing item 16.
+        assert_eq!(result.is_ok(), i != 16, "item {}", i);
+        assert!(batch.len() <= 8);
+    }
+    assert_eq!(batch.len(), 4);
+    assert!(batch.finalize().is_ok());
+}
+
+#[test]
+fn batch_item_encodings() {
+    use std::convert::TryFrom;
+
+    let sk = SigningKey::new(thread_rng());
+    let msg = b"BatchVerifyTest";
+    let item: batch::Item = (VerificationKeyBytes::from(&sk), sk.sign(&msg[..]), msg).into();
+
+    let bytes: [u8; 128] = item.clone().into();
+    assert!(batch::Item::from(bytes).verify_single().is_ok());
+    assert!(batch::Item::try_from(&bytes[..]).is_ok());
+    assert_eq!(
+        batch::Item::try_from(&bytes[1..]).err(),
+        Some(Error::InvalidSliceLength)
+    );
+
+    let encoded = bincode::serialize(&item).unwrap();
+    let decoded: batch::Item = bincode::deserialize(&encoded).unwrap();
+    let mut batch = batch::Verifier::new();
+    batch.queue(decoded);
+    assert!(batch.verify(thread_rng()).is_ok());
+}
+
+#[test]
+fn batch_verify_with_duplicates() {
+    let sk = SigningKey::new(thread_rng());
+    let pk_bytes = VerificationKeyBytes::from(&sk);
+    let msg = b"BatchVerifyTest";
+    let sig = sk.sign(&msg[..]);
+    let bad_sig = sk.sign(b"badmsg");
+
+    let mut batch = batch::Verifier::new();
+    for _ in 0..8 {
+        batch.queue((pk_bytes, sig, msg));
+    }
+    assert!(batch.verify(thread_rng()).is_ok());
+
+    let mut batch = batch::Verifier::new();
+    for i in 0..8 {
+        if i % 3 == 1 {
+            batch.queue((pk_bytes, bad_sig, msg));
+        } else {
+            batch.queue((pk_bytes, sig, msg));
+        }
+    }
+    assert_eq!(
+        batch.verify_identifying_failures(thread_rng()),
+        Err(vec![
+            (1, Error::InvalidSignature),
+            (4, Error::InvalidSignature),
+            (7, Error::InvalidSignature)
+        ])
+    );
+}
+
+#[test]
+fn batch_verify_same_message() {
+    let msg = b"QuorumCertificate";
+    let mut signatures = (0..10)
+        .map(|_| {
+            let sk = SigningKey::new(thread_rng());
+            (VerificationKeyBytes::from(&sk), sk.sign(&msg[..]))
+        })
+        .collect::<Vec<_>>();
+    assert!(batch::verify_same_message(&msg[..], &signatures).is_ok());
+    assert!(batch::verify_same_message(b"other", &signatures).is_err());
+
+    // A signature by a different key.
+    signatures[0].1 = signatures[1].1;
+    assert!(batch::verify_same_message(&msg[..], &signatures).is_err());
+}
+
+#[test]
+fn batch_verifier_checkpoint() {
+    let mut batch = batch::Verifier::with_policy(VerificationPolicy::Rfc8032Strict);
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    for i in 0..8u8 {
+        let msg = [i; 16];
+        batch.queue((vk_bytes, sk.sign(&msg), &msg));
+    }
+    let msg = [0u8; 16];
+    batch.queue((vk_bytes, sk.sign(&msg), &msg));
+
+    let restored: batch::Verifier =
+        bincode::deserialize(&bincode::serialize(&batch).unwrap()).unwrap();
+    let bytes = bincode::serialize(&restored).unwrap();
+
+    // Fork the restored verifier and add a bad item to one copy.
+    let mut forked = restored.clone();
+    forked.queue((vk_bytes, sk.sign(b"other"), b"message"));
+
+    assert_eq!(bincode::serialize(&batch).unwrap(), bytes);
+    assert!(restored.verify(thread_rng()).is_ok());
+    assert_eq!(
+        forked.verify_identifying_failures(thread_rng()),
+        Err(vec![(9, Error::InvalidSignature)])
+    );
+}
+
+#[test]
+fn array_batch_verify() {
+    let mut batch = batch::ArrayVerifier::<8>::new();
+    let mut items = Vec::new();
+    for i in 0..8u8 {
+        let sk = SigningKey::new(thread_rng());
+        let msg = [i; 8];
+        let item = batch::Item::from((VerificationKeyBytes::from(&sk), sk.sign(&msg), &msg));
+        items.push(item.clone());
+        assert!(batch.queue(item).is_ok());
+    }
+    assert_eq!(batch.len(), batch.capacity());
+    assert!(batch.queue(items[0].clone()).is_err());
+    assert!(batch.clone().verify(thread_rng()).is_ok());
+
+    let mut bad_batch = batch::ArrayVerifier::<8>::new();
+    let sk = SigningKey::new(thread_rng());
+    for item in items.iter().take(7) {
+        assert!(bad_batch.queue(item.clone()).is_ok());
+    }
+    assert!(bad_batch
+        .queue((VerificationKeyBytes::from(&sk), sk.sign(b"a"), b"b"))
+        .is_ok());
+    assert_eq!(bad_batch.verify(thread_rng()), Err(Error::InvalidSignature));
+
+    assert!(batch::ArrayVerifier::<4>::new()
+        .verify(thread_rng())
+        .is_ok());
+}
+
+#[test]
+fn array_batch_stack_use() {
+    // An `ArrayVerifier` uses about 3 KiB of stack per item, so a full batch
+    // of 256 items fits in a stack of 3.5 KiB per item.
+    const N: usize = 256;
+    let verified = std::thread::Builder::new()
+        .stack_size(N * 3584)
+        .spawn(|| {
+            let mut batch = batch::ArrayVerifier::<N>::new();
+            let sk = SigningKey::new(thread_rng());
+            let vk_bytes = VerificationKeyBytes::from(&sk);
+            for i in 0..N {
+                let msg = i.to_le_bytes();
+                assert!(batch.queue((vk_bytes, sk.sign(&msg), &msg)).is_ok());
+            }
+            batch.verify(thread_rng())
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(verified.is_ok());
+}
+
+#[test]
+fn batch_verify_with_limits() {
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    let sig = sk.sign(b"limits");
+
+    let mut batch = batch::Verifier::new();
+    batch.set_limits(batch::Limits {
+        max_items: Some(3),
+        max_message_bytes: Some(12),
+        reject_invalid_encodings: true,
+    });
+    assert!(batch.try_queue(vk_bytes, sig, b"limits").is_ok());
+    assert_eq!(
+        batch.try_queue(vk_bytes, sig, b"limits too long"),
+        Err(Error::BatchLimitExceeded)
+    );
+
+    let mut bad_s = <[u8; 64]>::from(sig);
+    bad_s[63] = 0xff;
+    assert_eq!(
+        batch.try_queue(vk_bytes, Signature::from(bad_s), b"x"),
+        Err(Error::SignatureScalarOutOfRange)
+    );
+    let mut bad_r = <[u8; 64]>::from(sig);
+    bad_r[..32].copy_from_slice(&[0xff; 32]);
+    bad_r[0] = 0xf5;
+    assert_eq!(
+        batch.try_queue(vk_bytes, Signature::from(bad_r), b"x"),
+        Err(Error::MalformedSignatureR)
+    );
+
+    batch.queue((vk_bytes, sig, b"limits"));
+    assert!(batch.try_queue(vk_bytes, sig, b"limits").is_ok());
+    assert_eq!(
+        batch.try_queue(vk_bytes, sig, b""),
+        Err(Error::BatchLimitExceeded)
+    );
+    assert!(batch.verify(thread_rng()).is_ok());
+}
@@ -0,0 +1,54 @@
+use rand::thread_rng;
+
+use ed25519_zebra::*;
+
+#[test]
+fn parallel_batch_verify() {
+    let mut batch = batch::Verifier::new();
+    let sk = SigningKey::new(thread_rng());
+    for i in 0..64 {
+        // Mix repeated and distinct keys across shards.
+        let sk = if i % 4 == 0 {
+            sk.clone()
+        } else {
+            SigningKey::new(thread_rng())
+        };
+        let msg = b"BatchVerifyTest";
+        batch.queue((VerificationKeyBytes::from(&sk), sk.sign(&msg[..]), msg));
+    }
+    assert!(batch.verify_parallel(thread_rng()).is_ok());
+}
+
+#[test]
+fn parallel_batch_verify_with_one_bad_sig() {
+    let mut batch = batch::Verifier::new();
+    for i in 0..64 {
+        let sk = SigningKey::new(thread_rng());
+        let msg = b"BatchVerifyTest";
+        let sig = if i != 37 {
+            sk.sign(&msg[..])
+        } else {
+            sk.sign(b"badmsg")
+        };
+        batch.queue((VerificationKeyBytes::from(&sk), sig, msg));
+    }
+    assert_eq!(
+        batch.verify_parallel(thread_rng()),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn parallel_batch_verify_empty() {
+    assert!(batch::Verifier::new().verify_parallel(thread_rng()).is_ok());
+}
+
+#[test]
+fn generate_many_parallel_matches_sequential() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    assert_eq!(
+        SigningKey::generate_many_parallel(StdRng::seed_from_u64(7), 64),
+        SigningKey::generate_many(StdRng::seed_from_u64(7), 64)
+    );
+}
@@ -0,0 +1,141 @@
+use futures::{executor::block_on, stream, StreamExt};
+use rand::thread_rng;
+
+use ed25519_zebra::*;
+
+#[test]
+fn batch_verify_stream() {
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    let items = (0..20u8)
+        .map(|i| {
+            let msg = vec![i; 4];
+            let sig = if i % 7 == 3 {
+                sk.sign(b"wrong message")
+            } else {
+                sk.sign(&msg)
+            };
+            (vk_bytes, sig, msg)
+        })
+        .collect::<Vec<_>>();
+
+    let results = block_on(
+        batch::verify_stream(
+            stream::iter(
+                items
+                    .iter()
+                    .map(|(vk_bytes, sig, msg)| (*vk_bytes, *sig, msg)),
+            ),
+            8,
+            thread_rng(),
+        )
+        .collect::<Vec<_>>(),
+    );
+
+    assert_eq!(results.len(), items.len());
+    for (i, result) in results.into_iter().enumerate() {
+        if i % 7 == 3 {
+            assert_eq!(result, Err(Error::InvalidSignature));
+        } else {
+            assert_eq!(result, Ok(()));
+        }
+    }
+}
+
+fn stream_items() -> Vec<(VerificationKeyBytes, Signature, Vec<u8>)> {
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    (0..20u8)
+        .map(|i| {
+            let msg = vec![i; 4];
+            let sig = if i % 7 == 3 {
+                sk.sign(b"wrong message")
+            } else {
+                sk.sign(&msg)
+            };
+            (vk_bytes, sig, msg)
+        })
+        .collect()
+}
+
+fn check_results(results: Vec<Result<(), Error>>) {
+    assert_eq!(results.len(), 20);
+    for (i, result) in results.into_iter().enumerate() {
+        if i % 7 == 3 {
+            assert_eq!(result, Err(Error::InvalidSignature));
+        } else {
+            assert_eq!(result, Ok(()));
+        }
+    }
+}
+
+#[test]
+fn batch_verify_stream_windowed() {
+    use std::time::Duration;
+
+    use futures::future;
+
+    let items = stream_items();
+    let max_latency = Duration::from_millis(10);
+
+    // The input never ends, so results are only produced because each
+    // batch's window expires.
+    let input = stream::iter(
+        items
+            .iter()
+            .map(|(vk_bytes, sig, msg)| (*vk_bytes, *sig, msg)),
+    )
+    .chain(stream::pending());
+    let mut windows = 0;
+    let results = block_on(
+        batch::verify_stream_windowed(
+            input,
+            8,
+            max_latency,
+            |latency| {
+                assert_eq!(latency, max_latency);
+                windows += 1;
+                future::ready(())
+            },
+            thread_rng(),
+        )
+        .take(items.len())
+        .collect::<Vec<_>>(),
+    );
+    check_results(results);
+    assert_eq!(windows, 3);
+
+    // With a window that never expires, batches are bounded by size and by
+    // the end of the input.
+    let results = block_on(
+        batch::verify_stream_windowed(
+            stream::iter(
+                items
+                    .iter()
+                    .map(|(vk_bytes, sig, msg)| (*vk_bytes, *sig, msg)),
+            ),
+            8,
+            max_latency,
+            |_| future::pending(),
+            thread_rng(),
+        )
+        .collect::<Vec<_>>(),
+    );
+    check_results(results);
+}
+
+#[test]
+fn batch_verify_sink() {
+    use futures::{channel::mpsc, SinkExt};
+
+    let items = stream_items();
+    let (results, received) = mpsc::unbounded();
+    let mut sink = batch::VerifySink::new(results, 8, thread_rng());
+    block_on(async {
+        for (vk_bytes, sig, msg) in items.iter() {
+            sink.feed((*vk_bytes, *sig, msg).into()).await.unwrap();
+        }
+        sink.close().await.unwrap();
+    });
+    check_results(block_on(received.collect::<Vec<_>>()));
+}
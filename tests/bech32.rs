@@ -0,0 +1,158 @@
+use std::convert::TryFrom;
+
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    bech32::{Error, Variant},
+    Signature, SigningKey, VerificationKeyBytes,
+};
+
+/// The verification key of the seed `00 01 .. 1f` in bech32.
+const BECH32_KEY: &str = "edpk1qwss00lnecgtu8tsm5vwwj7qn9n7f43snwjs6hcamjrxgyj4xxuq7pmyvh";
+/// The verification key of the seed `00 01 .. 1f` in bech32m.
+const BECH32M_KEY: &str = "edpk1qwss00lnecgtu8tsm5vwwj7qn9n7f43snwjs6hcamjrxgyj4xxuqtatgf4";
+/// `BECH32_KEY` with a padding bit set, and a valid checksum.
+const NONZERO_PADDING: &str = "edpk1qwss00lnecgtu8tsm5vwwj7qn9n7f43snwjs6hcamjrxgyj4xxuprh0339";
+/// The signature of `hello, world` by the seed `00 01 .. 1f` in bech32m.
+const BECH32M_SIGNATURE: &str = "edsig12eela4nmgyhdqq6tk88p4w5u7lx9vg9z5slskqhfdftgeum7xpzj90gqtgt9hwelsdwxqh5ger5tzpjjqkrag33lcqpcrhpxgdmy6qsx2f5nx";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn keys() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    for (encoded, variant) in [
+        (BECH32_KEY, Variant::Bech32),
+        (BECH32M_KEY, Variant::Bech32m),
+    ] {
+        assert_eq!(vk_bytes.to_bech32("edpk", variant), Ok(encoded.to_string()));
+        assert_eq!(
+            VerificationKeyBytes::from_bech32(encoded, "edpk", variant),
+            Ok(vk_bytes)
+        );
+        // Uppercase strings and HRPs are accepted.
+        assert_eq!(
+            VerificationKeyBytes::from_bech32(&encoded.to_uppercase(), "EDPK", variant),
+            Ok(vk_bytes)
+        );
+    }
+    assert_eq!(
+        vk_bytes.to_bech32("EdPk", Variant::Bech32),
+        Ok(BECH32_KEY.to_string())
+    );
+
+    let vk_bytes = VerificationKeyBytes::from(&SigningKey::new(thread_rng()));
+    let encoded = vk_bytes
+        .to_bech32("cosmosvalconspub", Variant::Bech32)
+        .unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from_bech32(&encoded, "cosmosvalconspub", Variant::Bech32),
+        Ok(vk_bytes)
+    );
+}
+
+#[test]
+fn signatures() {
+    let signature = signing_key().sign(b"hello, world");
+    assert_eq!(
+        signature.to_bech32("edsig", Variant::Bech32m),
+        Ok(BECH32M_SIGNATURE.to_string())
+    );
+    assert_eq!(
+        Signature::from_bech32(BECH32M_SIGNATURE, "edsig", Variant::Bech32m),
+        Ok(signature)
+    );
+    assert_eq!(
+        Signature::from_bech32(BECH32M_KEY, "edpk", Variant::Bech32m),
+        Err(Error::InvalidLength(32))
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_bech32(BECH32M_SIGNATURE, "edsig", Variant::Bech32m),
+        Err(Error::InvalidLength(64))
+    );
+
+    let signature = Signature::try_from(&[0xff; 64][..]).unwrap();
+    let encoded = signature.to_bech32("sig", Variant::Bech32).unwrap();
+    assert_eq!(
+        Signature::from_bech32(&encoded, "sig", Variant::Bech32),
+        Ok(signature)
+    );
+}
+
+#[test]
+fn mismatches() {
+    assert_eq!(
+        VerificationKeyBytes::from_bech32(BECH32_KEY, "edpk", Variant::Bech32m),
+        Err(Error::UnexpectedVariant(Variant::Bech32))
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_bech32(BECH32M_KEY, "edpk", Variant::Bech32),
+        Err(Error::UnexpectedVariant(Variant::Bech32m))
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_bech32(BECH32_KEY, "edpub", Variant::Bech32),
+        Err(Error::UnexpectedHrp("edpk".to_string()))
+    );
+    // The HRP is covered by the checksum.
+    assert_eq!(
+        VerificationKeyBytes::from_bech32(
+            &BECH32_KEY.replacen("edpk", "edpc", 1),
+            "edpc",
+            Variant::Bech32
+        ),
+        Err(Error::InvalidChecksum)
+    );
+}
+
+#[test]
+fn malformed_strings() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    for hrp in ["", "ed pk", "edpké", &"x".repeat(84)] {
+        assert_eq!(
+            vk_bytes.to_bech32(hrp, Variant::Bech32),
+            Err(Error::InvalidHrp)
+        );
+    }
+
+    for malformed in [
+        BECH32_KEY.replacen("edpk1", "edpk", 1),
+        BECH32_KEY.replacen("edpk1q", "Edpk1q", 1),
+        BECH32_KEY.replacen("qwss", "bwss", 1),
+        "1qwss00lnecgtu8ts".to_string(),
+        "edpk1qpzry".to_string(),
+    ] {
+        assert_eq!(
+            VerificationKeyBytes::from_bech32(&malformed, "edpk", Variant::Bech32),
+            Err(Error::MalformedString),
+            "{}",
+            malformed
+        );
+    }
+
+    for i in 5..BECH32_KEY.len() {
+        let mut corrupted = BECH32_KEY.as_bytes().to_vec();
+        corrupted[i] = if corrupted[i] == b'q' { b'p' } else { b'q' };
+        assert_eq!(
+            VerificationKeyBytes::from_bech32(
+                std::str::from_utf8(&corrupted).unwrap(),
+                "edpk",
+                Variant::Bech32
+            ),
+            Err(Error::InvalidChecksum)
+        );
+    }
+}
+
+#[test]
+fn nonzero_padding() {
+    // 32 bytes leave 4 bits of padding in the last data character, which
+    // must be zero.
+    assert_eq!(
+        VerificationKeyBytes::from_bech32(NONZERO_PADDING, "edpk", Variant::Bech32),
+        Err(Error::MalformedString)
+    );
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use rand::thread_rng;
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+
+use ed25519_zebra::{
+    cbor::{self, Error},
+    SigningKey, VerificationKey,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    // Declared out of order, so serde emits "sensor" before "at".
+    sensor: String,
+    at: u64,
+    values: HashMap<String, i64>,
+}
+
+fn reading() -> Reading {
+    let mut values = HashMap::new();
+    values.insert("zz".to_string(), -1);
+    values.insert("b".to_string(), 1000);
+    values.insert("a".to_string(), 0);
+    Reading {
+        sensor: "t1".to_string(),
+        at: 24,
+        values,
+    }
+}
+
+#[test]
+fn deterministic_encoding() {
+    let expected = hex::decode(concat!(
+        // A map of three entries, sorted by encoded key.
+        "a3",
+        // "at": 24
+        "626174",
+        "1818",
+        // "sensor": "t1"
+        "6673656e736f72",
+        "627431",
+        // "values": {"a": 0, "b": 1000, "zz": -1}
+        "6676616c756573",
+        "a3",
+        "616100",
+        "61621903e8",
+        "627a7a20",
+    ))
+    .unwrap();
+    // `HashMap` iteration order varies, but the encoding does not.
+    for _ in 0..8 {
+        assert_eq!(cbor::to_vec(&reading()).unwrap(), expected);
+    }
+    assert_eq!(cbor::from_slice::<Reading>(&expected).unwrap(), reading());
+}
+
+#[test]
+fn sign_and_verify() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let (bytes, signature) = sk.sign_cbor(&reading()).unwrap();
+    assert_eq!(bytes, cbor::to_vec(&reading()).unwrap());
+    assert_eq!(
+        vk.verify_cbor::<Reading>(&bytes, &signature).unwrap(),
+        reading()
+    );
+
+    let other = VerificationKey::from(&SigningKey::new(thread_rng()));
+    assert_eq!(
+        other
+            .verify_cbor::<Reading>(&bytes, &signature)
+            .unwrap_err(),
+        Error::InvalidSignature(ed25519_zebra::Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn reject_non_deterministic_encodings() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    for encoding in &[
+        // {"b": 1, "a": 2}, with unsorted keys.
+        "a2616201616102",
+        // 24 encoded in two bytes instead of one.
+        "a16161190018",
+        // [1] with an indefinite length.
+        "9f01ff",
+    ] {
+        let bytes = hex::decode(encoding).unwrap();
+        let signature = sk.sign(&bytes);
+        assert_eq!(
+            vk.verify_cbor::<IgnoredAny>(&bytes, &signature)
+                .unwrap_err(),
+            Error::NonDeterministic
+        );
+    }
+}
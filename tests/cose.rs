@@ -0,0 +1,175 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    cose::{CoseSign1, Error},
+    SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The key from RFC 8032 §7.1 test 1, which is also the Ed25519 example key
+/// of RFC 8152 appendix C.7.
+const X: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+const D: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+
+fn cose(parts: &[&str]) -> Vec<u8> {
+    hex::decode(parts.concat()).unwrap()
+}
+
+#[test]
+fn encode_deterministically() {
+    let vk: VerificationKeyBytes = X.parse().unwrap();
+    // {1: 1, 3: -8, -1: 6, -2: x}
+    let expected = cose(&["a4", "0101", "0327", "2006", "215820", X]);
+    assert_eq!(vk.to_cose_key(), expected);
+    assert_eq!(VerificationKeyBytes::from_cose_key(&expected).unwrap(), vk);
+
+    let mut seed = [0u8; 32];
+    hex::decode_to_slice(D, &mut seed[..]).unwrap();
+    let sk = SigningKey::from(seed);
+    // {1: 1, 3: -8, -1: 6, -2: x, -4: d}
+    let expected = cose(&["a5", "0101", "0327", "2006", "215820", X, "235820", D]);
+    assert_eq!(&sk.to_cose_key()[..], &expected[..]);
+    assert_eq!(SigningKey::from_cose_key(&expected).unwrap(), sk);
+}
+
+#[test]
+fn decode_rfc8152_key() {
+    // {1: 1, 2: h'3131', -1: 6, -2: x, -4: d}, without `alg`, as in RFC 8152.
+    let key = cose(&["a5", "0101", "02423131", "2006", "215820", X, "235820", D]);
+    let sk = SigningKey::from_cose_key(&key).unwrap();
+    assert_eq!(VerificationKeyBytes::from(&sk).to_string(), X);
+    assert_eq!(
+        VerificationKeyBytes::from_cose_key(&key)
+            .unwrap()
+            .to_string(),
+        X
+    );
+}
+
+#[test]
+fn round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKeyBytes::from(&sk);
+    assert_eq!(SigningKey::from_cose_key(&sk.to_cose_key()).unwrap(), sk);
+    assert_eq!(
+        VerificationKeyBytes::from_cose_key(&vk.to_cose_key()).unwrap(),
+        vk
+    );
+}
+
+#[test]
+fn reject_other_keys() {
+    // crv: X25519 (4)
+    let x25519 = cose(&["a3", "0101", "2004", "215820", X]);
+    assert_eq!(
+        VerificationKeyBytes::from_cose_key(&x25519).unwrap_err(),
+        Error::UnsupportedKeyType
+    );
+    // alg: ES256 (-7)
+    let es256 = cose(&["a4", "0101", "0326", "2006", "215820", X]);
+    assert_eq!(
+        VerificationKeyBytes::from_cose_key(&es256).unwrap_err(),
+        Error::UnsupportedAlgorithm(-7)
+    );
+    // A public key without the private key.
+    let public = cose(&["a4", "0101", "0327", "2006", "215820", X]);
+    assert_eq!(
+        SigningKey::from_cose_key(&public).unwrap_err(),
+        Error::Malformed("missing d parameter")
+    );
+    // A private key with someone else's public key.
+    let other = VerificationKeyBytes::from(&SigningKey::new(thread_rng())).to_string();
+    let mismatched = cose(&["a5", "0101", "0327", "2006", "215820", &other, "235820", D]);
+    assert_eq!(
+        SigningKey::from_cose_key(&mismatched).unwrap_err(),
+        Error::PublicKeyMismatch
+    );
+    // Trailing data.
+    let mut trailing = public;
+    trailing.push(0);
+    assert_eq!(
+        VerificationKeyBytes::from_cose_key(&trailing).unwrap_err(),
+        Error::InvalidCbor
+    );
+}
+
+/// A `COSE_Sign1` message over "This is the content." with the RFC 8032 key,
+/// the protected header `{1: -8}`, and the unprotected header `{4: '11'}`.
+const SIGN1: &str = concat!(
+    "d28443a10127a10442313154546869732069732074686520636f6e74656e742e5840",
+    "6354488f9f290e36cd80e23762e664a5cb03e4267c66a8cffaef7c66d89a40bf2cbb",
+    "8222432a08e5ee410d8b540c6931d26fb6af673f7e2100655d8bae765c04",
+);
+const CONTENT: &[u8] = b"This is the content.";
+
+fn rfc8032_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    hex::decode_to_slice(D, &mut seed[..]).unwrap();
+    SigningKey::from(seed)
+}
+
+#[test]
+fn sign1_vector() {
+    let sk = rfc8032_key();
+    let message = sk.sign_cose_sign1(Some(b"11"), CONTENT, b"");
+    assert_eq!(hex::encode(&message), SIGN1);
+
+    let parsed = CoseSign1::from_slice(&message).unwrap();
+    assert_eq!(parsed.kid.as_deref(), Some(&b"11"[..]));
+    let vk = VerificationKey::from(&sk);
+    assert_eq!(parsed.verify(&vk, b"").unwrap(), CONTENT);
+}
+
+#[test]
+fn sign1_external_aad() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let message = sk.sign_cose_sign1(None, CONTENT, b"request-42");
+    let parsed = CoseSign1::from_slice(&message).unwrap();
+    assert_eq!(parsed.kid, None);
+    assert_eq!(parsed.verify(&vk, b"request-42").unwrap(), CONTENT);
+    assert_eq!(
+        parsed.verify(&vk, b"request-43").unwrap_err(),
+        Error::InvalidSignature(ed25519_zebra::Error::InvalidSignature)
+    );
+
+    // The untagged form is also accepted.
+    let untagged = CoseSign1::from_slice(&message[1..]).unwrap();
+    assert_eq!(untagged, parsed);
+}
+
+#[test]
+fn sign1_detached_payload() {
+    let sk = rfc8032_key();
+    let vk = VerificationKey::from(&sk);
+    // Replace the payload of the test vector with nil.
+    let attached = hex::decode(SIGN1).unwrap();
+    let mut detached = attached[..11].to_vec();
+    detached.push(0xf6);
+    detached.extend_from_slice(&attached[11 + 21..]);
+
+    let parsed = CoseSign1::from_slice(&detached).unwrap();
+    assert_eq!(parsed.payload, None);
+    assert_eq!(
+        parsed.verify(&vk, b"").unwrap_err(),
+        Error::Malformed("payload is detached")
+    );
+    parsed.verify_detached(&vk, CONTENT, b"").unwrap();
+}
+
+#[test]
+fn sign1_rejects_other_algorithms() {
+    // The protected header {1: -7} (ES256).
+    let mut message = hex::decode(SIGN1).unwrap();
+    message[5] = 0x26;
+    assert_eq!(
+        CoseSign1::from_slice(&message).unwrap_err(),
+        Error::UnsupportedAlgorithm(-7)
+    );
+    // An empty protected header.
+    let mut message = hex::decode(SIGN1).unwrap();
+    message.splice(2..6, vec![0x40]);
+    assert_eq!(
+        CoseSign1::from_slice(&message).unwrap_err(),
+        Error::Malformed("missing protected alg header")
+    );
+}
@@ -0,0 +1,89 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    cosign::{sign_blob, sign_image, verify_blob, verify_image, Error, SimpleSigning},
+    SigningKey, VerificationKey,
+};
+
+const BLOB: &[u8] = b"zebrad 1.0.0 release binary\n";
+const BLOB_SIGNATURE: &str =
+    "lAMD/44fgjYsqoUrCcNJazFRw8wu+wXEpmLVjIa/gcf2JbxWrwVz0trSWvm2eNYno+ou1EIe4/a3Rp0HMbd9AA==";
+
+const REFERENCE: &str = "ghcr.io/zcashfoundation/zebra";
+const DIGEST: &str = "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+const PAYLOAD: &str = r#"{"critical":{"identity":{"docker-reference":"ghcr.io/zcashfoundation/zebra"},"image":{"docker-manifest-digest":"sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"},"type":"cosign container image signature"},"optional":null}"#;
+const PAYLOAD_SIGNATURE: &str =
+    "1quMqKzI7C0Fokv3j3/tnYAlHIK2MMx34Ynv09bPvV/C0XIr1OQsjGx9yiJVcpSEGC2kB2yeAvDKdfFdJfNWAg==";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn blob_signatures() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+
+    assert_eq!(sign_blob(&sk, BLOB), BLOB_SIGNATURE);
+    assert_eq!(verify_blob(&vk, BLOB, BLOB_SIGNATURE), Ok(()));
+    // Signature files may end with a newline.
+    assert_eq!(
+        verify_blob(&vk, BLOB, &format!("{}\n", BLOB_SIGNATURE)),
+        Ok(())
+    );
+
+    assert!(matches!(
+        verify_blob(&vk, b"tampered", BLOB_SIGNATURE),
+        Err(Error::InvalidSignature(_))
+    ));
+    let other = VerificationKey::from(&SigningKey::new(thread_rng()));
+    assert!(matches!(
+        verify_blob(&other, BLOB, BLOB_SIGNATURE),
+        Err(Error::InvalidSignature(_))
+    ));
+    assert_eq!(
+        verify_blob(&vk, BLOB, "not base64!"),
+        Err(Error::MalformedSignature)
+    );
+    assert_eq!(
+        verify_blob(&vk, BLOB, &BLOB_SIGNATURE[4..]),
+        Err(Error::MalformedSignature)
+    );
+}
+
+#[test]
+fn image_signatures() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+    let claims = SimpleSigning::new(REFERENCE, DIGEST);
+
+    let (payload, signature) = sign_image(&sk, &claims);
+    assert_eq!(payload, PAYLOAD.as_bytes());
+    assert_eq!(signature, PAYLOAD_SIGNATURE);
+    assert_eq!(verify_image(&vk, &payload, &signature), Ok(claims));
+}
+
+#[test]
+fn simple_signing_payloads() {
+    // Payloads may carry optional annotations.
+    let annotated = br#"{"critical":{"identity":{"docker-reference":"ghcr.io/zcashfoundation/zebra"},"image":{"docker-manifest-digest":"sha256:00"},"type":"cosign container image signature"},"optional":{"tag":"v1.0.0"}}"#;
+    assert_eq!(
+        SimpleSigning::from_payload(annotated),
+        Ok(SimpleSigning::new(REFERENCE, "sha256:00"))
+    );
+
+    let wrong_type = PAYLOAD.replace(
+        "cosign container image signature",
+        "atomic container signature",
+    );
+    assert_eq!(
+        SimpleSigning::from_payload(wrong_type.as_bytes()),
+        Err(Error::MalformedPayload)
+    );
+    assert_eq!(
+        SimpleSigning::from_payload(b"{}"),
+        Err(Error::MalformedPayload)
+    );
+}
@@ -0,0 +1,75 @@
+use std::convert::TryFrom;
+
+use rand::thread_rng;
+
+use ed25519_zebra::{Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+use pkcs8::der::{Encode, ErrorKind};
+
+/// A self-signed certificate from `openssl req -new -x509` with an Ed25519
+/// key.
+const CERTIFICATE: &str = concat!(
+    "308201343081e7a003020102021403452984f734b7bcc84bb098f97dd35e5fd555fe30",
+    "0506032b65703010310e300c06035504030c057a65627261301e170d32363130313532",
+    "33343131355a170d3236313031363233343131355a3010310e300c06035504030c057a",
+    "65627261302a300506032b65700321000791886e2b50c9961a3c45ea700810bc255548",
+    "160dc51670a392b615b17d66e8a3533051301d0603551d0e04160414c87310f876b1f3",
+    "61fdd3f3732c591ebfe08a8cb8301f0603551d23041830168014c87310f876b1f361fd",
+    "d3f3732c591ebfe08a8cb8300f0603551d130101ff040530030101ff300506032b6570",
+    "03410014bcfaff34aa0cdd685ac5ce7219d09d9b0af5bcd629b5692dc22f7a4c0ce154",
+    "f6487180aaead7f3e25738bb08055d0c0269eaf4da665cd29aba1a68dc8a8405",
+);
+const CERTIFICATE_KEY: &str = "0791886e2b50c9961a3c45ea700810bc255548160dc51670a392b615b17d66e8";
+
+#[test]
+fn verify_certificate_signature() {
+    let cert = hex::decode(CERTIFICATE).unwrap();
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+    let (tbs, rest) = cert[4..].split_at(3 + 0xe7);
+    let (algorithm, signature_value) = rest.split_at(7);
+    assert_eq!(algorithm, &Signature::ALGORITHM_ID.to_der().unwrap()[..]);
+
+    let signature = Signature::from_der_bit_string(signature_value).unwrap();
+    assert_eq!(signature.to_der_bit_string(), signature_value);
+    let vk = VerificationKey::try_from(CERTIFICATE_KEY.parse::<VerificationKeyBytes>().unwrap())
+        .unwrap();
+    vk.verify(&signature, tbs).unwrap();
+}
+
+#[test]
+fn round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    let signature = sk.sign(b"zebra");
+
+    let bits = signature.to_der_bit_string();
+    assert_eq!(&bits[..3], &[0x03, 0x41, 0x00]);
+    assert_eq!(Signature::from_der_bit_string(&bits).unwrap(), signature);
+
+    let octets = signature.to_der_octet_string();
+    assert_eq!(&octets[..2], &[0x04, 0x40]);
+    assert_eq!(
+        Signature::from_der_octet_string(&octets).unwrap(),
+        signature
+    );
+}
+
+#[test]
+fn reject_malformed() {
+    let signature = SigningKey::new(thread_rng()).sign(b"zebra");
+
+    // A BIT STRING with unused bits.
+    let mut bits = signature.to_der_bit_string();
+    bits[2] = 1;
+    assert!(Signature::from_der_bit_string(&bits).is_err());
+
+    // A 63-byte OCTET STRING.
+    let mut octets = signature.to_der_octet_string();
+    octets[1] = 0x3f;
+    octets.pop();
+    assert!(matches!(
+        Signature::from_der_octet_string(&octets).map_err(|err| err.kind()),
+        Err(ErrorKind::Length { .. })
+    ));
+
+    // The wrong kind of string.
+    assert!(Signature::from_der_octet_string(&signature.to_der_bit_string()).is_err());
+}
@@ -0,0 +1,76 @@
+use std::convert::TryFrom;
+
+use rand::thread_rng;
+
+use ed25519_zebra::{did::Error, SigningKey, VerificationKeyBytes};
+
+/// Test vectors from the did:key method specification.
+const VECTORS: &[(&str, &str)] = &[
+    (
+        "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+        "2e6fcce36701dc791488e0d0b1745cc1e33a4c1c9fcc41c63bd343dbbe0970e6",
+    ),
+    (
+        "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp",
+        "3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29",
+    ),
+];
+
+#[test]
+fn did_key_vectors() {
+    for (did, key) in VECTORS {
+        let vk_bytes = VerificationKeyBytes::try_from(&hex::decode(key).unwrap()[..]).unwrap();
+        assert_eq!(&vk_bytes.to_did_key(), did);
+        assert_eq!(VerificationKeyBytes::from_did_key(did), Ok(vk_bytes));
+        assert_eq!(vk_bytes.to_multibase(), did["did:key:".len()..]);
+    }
+}
+
+#[test]
+fn did_key_round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    let did = vk_bytes.to_did_key();
+    assert!(did.starts_with("did:key:z6Mk"));
+    assert_eq!(VerificationKeyBytes::from_did_key(&did), Ok(vk_bytes));
+
+    let url = format!("{}#{}", did, &did["did:key:".len()..]);
+    assert_eq!(VerificationKeyBytes::from_did_key(&url), Ok(vk_bytes));
+}
+
+#[test]
+fn did_key_rejects_malformed() {
+    let (did, _) = VECTORS[0];
+    let id = &did["did:key:".len()..];
+
+    assert_eq!(
+        VerificationKeyBytes::from_did_key(&format!("did:web:{}", id)),
+        Err(Error::UnsupportedMethod)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_did_key(&format!("{}#key-1", did)),
+        Err(Error::UnsupportedMethod)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_did_key(&format!("did:key:u{}", &id[1..])),
+        Err(Error::UnsupportedMultibase)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_did_key(&format!("{}0", did)),
+        Err(Error::InvalidBase58)
+    );
+    // A secp256k1-pub (0xe7 0x01) key.
+    assert_eq!(
+        VerificationKeyBytes::from_did_key(
+            "did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme"
+        ),
+        Err(Error::UnsupportedKeyType)
+    );
+    // A 31-byte ed25519-pub key.
+    assert_eq!(
+        VerificationKeyBytes::from_did_key(
+            "did:key:z2DQVgKH8NoRsx74URviG72JDfT7jQo5xacBP7XJx7mmBnw"
+        ),
+        Err(Error::MalformedKey)
+    );
+}
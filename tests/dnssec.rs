@@ -0,0 +1,216 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    dnssec::{Dnskey, Error, Rrset, Rrsig},
+    SigningKey, VerificationKeyBytes,
+};
+
+// The example of RFC 8080 §6.1.
+
+/// The seed, base64 `ODIyNjAzODQ2MjgwODAxMjI2NDUxOTAyMDQxNDIyNjI=`.
+const SEED: &[u8; 32] = b"82260384628080122645190204142262";
+/// `example.com. 3600 IN DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=`
+const DNSKEY_RDATA: &str =
+    "0101030f974d96a22d224bc01adb915091477d44ccd91c9a41a11430010117d52c59240e";
+const KEY_TAG: u16 = 3613;
+/// `example.com. 3600 IN DS 3613 15 2 3aa5ab37...`
+const DS_RDATA: &str = "0e1d0f023aa5ab37efce57f737fc1627013fee07bdf241bd10f3b1964ab55c78e79a304b";
+/// `example.com. 3600 IN RRSIG MX 15 2 3600 1440021600 1438207200 3613 example.com. oL9krJun...`
+const RRSIG_RDATA: &str = "000f0f0200000e1055d4fc6055b94ce00e1d076578616d706c6503636f6d00a0bf64ac9ba7ef17c138859c1878bb99a839fe1759aca5b0d798cf1ab1e98d079102f4ddb3368f0fe40bb377f1f00e0cddedb799167d56b6e932783072ba8d02";
+const INCEPTION: u32 = 1_438_207_200;
+const EXPIRATION: u32 = 1_440_021_600;
+
+/// `example.com. 3600 IN MX 10 mail.example.com.`
+fn mx_rrset() -> Rrset {
+    let mut mx = vec![0, 10];
+    mx.extend_from_slice(b"\x04mail\x07example\x03com\x00");
+    Rrset {
+        name: "example.com.".to_string(),
+        rr_type: 15,
+        class: 1,
+        ttl: 3600,
+        rdata: vec![mx],
+    }
+}
+
+#[test]
+fn rfc8080_example() {
+    let sk = SigningKey::from(*SEED);
+    let dnskey = Dnskey::key_signing_key(VerificationKeyBytes::from(&sk));
+    assert_eq!(hex::encode(dnskey.to_rdata()), DNSKEY_RDATA);
+    assert_eq!(Dnskey::from_rdata(&dnskey.to_rdata()), Ok(dnskey));
+    assert_eq!(dnskey.key_tag(), KEY_TAG);
+    assert_eq!(
+        hex::encode(dnskey.to_ds_rdata("example.com").unwrap()),
+        DS_RDATA
+    );
+
+    let rrsig = mx_rrset()
+        .sign(&sk, dnskey.flags, "example.com.", INCEPTION, EXPIRATION)
+        .unwrap();
+    assert_eq!(hex::encode(rrsig.to_rdata().unwrap()), RRSIG_RDATA);
+    let decoded = Rrsig::from_rdata(&hex::decode(RRSIG_RDATA).unwrap()).unwrap();
+    assert_eq!(decoded, rrsig);
+    assert_eq!(decoded.signer_name, "example.com.");
+    assert_eq!(decoded.verify(&dnskey, &mx_rrset(), INCEPTION + 1), Ok(()));
+}
+
+#[test]
+fn canonical_rrset() {
+    let sk = SigningKey::new(thread_rng());
+    let dnskey = Dnskey::zone_signing_key(VerificationKeyBytes::from(&sk));
+    let rrset = Rrset {
+        name: "WWW.Example.COM".to_string(),
+        rr_type: 1,
+        class: 1,
+        ttl: 300,
+        rdata: vec![vec![192, 0, 2, 2], vec![192, 0, 2, 1]],
+    };
+    let rrsig = rrset
+        .sign(&sk, dnskey.flags, "example.com.", INCEPTION, EXPIRATION)
+        .unwrap();
+    assert_eq!(rrsig.labels, 3);
+
+    // Record order, duplicates, the owner name's case, and the TTL seen by a
+    // resolver do not affect the signature.
+    let received = Rrset {
+        name: "www.example.com.".to_string(),
+        ttl: 17,
+        rdata: vec![vec![192, 0, 2, 1], vec![192, 0, 2, 2], vec![192, 0, 2, 1]],
+        ..rrset.clone()
+    };
+    assert_eq!(rrsig.verify(&dnskey, &received, INCEPTION), Ok(()));
+
+    let tampered = Rrset {
+        rdata: vec![vec![192, 0, 2, 1], vec![192, 0, 2, 3]],
+        ..rrset.clone()
+    };
+    assert!(matches!(
+        rrsig.verify(&dnskey, &tampered, INCEPTION),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn wildcard_expansion() {
+    let sk = SigningKey::new(thread_rng());
+    let dnskey = Dnskey::zone_signing_key(VerificationKeyBytes::from(&sk));
+    let wildcard = Rrset {
+        name: "*.example.com.".to_string(),
+        rr_type: 16,
+        class: 1,
+        ttl: 300,
+        rdata: vec![b"\x05hello".to_vec()],
+    };
+    let rrsig = wildcard
+        .sign(&sk, dnskey.flags, "example.com.", INCEPTION, EXPIRATION)
+        .unwrap();
+    assert_eq!(rrsig.labels, 2);
+
+    let expanded = Rrset {
+        name: "a.b.example.com.".to_string(),
+        ..wildcard.clone()
+    };
+    assert_eq!(rrsig.verify(&dnskey, &expanded, INCEPTION), Ok(()));
+    let above = Rrset {
+        name: "com.".to_string(),
+        ..wildcard
+    };
+    assert_eq!(
+        rrsig.verify(&dnskey, &above, INCEPTION),
+        Err(Error::SignerMismatch)
+    );
+}
+
+#[test]
+fn verification_checks() {
+    let sk = SigningKey::from(*SEED);
+    let dnskey = Dnskey::key_signing_key(VerificationKeyBytes::from(&sk));
+    let rrsig = Rrsig::from_rdata(&hex::decode(RRSIG_RDATA).unwrap()).unwrap();
+    let rrset = mx_rrset();
+
+    assert_eq!(rrsig.verify(&dnskey, &rrset, EXPIRATION), Ok(()));
+    assert_eq!(
+        rrsig.verify(&dnskey, &rrset, EXPIRATION + 1),
+        Err(Error::Expired)
+    );
+    assert_eq!(
+        rrsig.verify(&dnskey, &rrset, INCEPTION - 1),
+        Err(Error::NotYetValid)
+    );
+
+    let not_zone_key = Dnskey { flags: 0, ..dnskey };
+    assert_eq!(
+        rrsig.verify(&not_zone_key, &rrset, INCEPTION),
+        Err(Error::NotZoneKey)
+    );
+    let other_key =
+        Dnskey::key_signing_key(VerificationKeyBytes::from(&SigningKey::new(thread_rng())));
+    assert_eq!(
+        rrsig.verify(&other_key, &rrset, INCEPTION),
+        Err(Error::KeyTagMismatch)
+    );
+
+    let other_type = Rrset {
+        rr_type: 16,
+        ..rrset.clone()
+    };
+    assert_eq!(
+        rrsig.verify(&dnskey, &other_type, INCEPTION),
+        Err(Error::TypeMismatch)
+    );
+    let too_many_labels = Rrsig {
+        labels: 3,
+        ..rrsig.clone()
+    };
+    assert_eq!(
+        too_many_labels.verify(&dnskey, &rrset, INCEPTION),
+        Err(Error::InvalidLabels)
+    );
+    let other_zone = Rrset {
+        name: "example.net.".to_string(),
+        ..rrset
+    };
+    assert_eq!(
+        rrsig.verify(&dnskey, &other_zone, INCEPTION),
+        Err(Error::SignerMismatch)
+    );
+}
+
+#[test]
+fn invalid_rdata() {
+    let mut rdata = hex::decode(DNSKEY_RDATA).unwrap();
+    rdata[3] = 13;
+    assert_eq!(
+        Dnskey::from_rdata(&rdata),
+        Err(Error::UnsupportedAlgorithm(13))
+    );
+    rdata[3] = 15;
+    rdata[2] = 2;
+    assert_eq!(Dnskey::from_rdata(&rdata), Err(Error::MalformedRdata));
+    assert_eq!(
+        Dnskey::from_rdata(&hex::decode(&DNSKEY_RDATA[..70]).unwrap()),
+        Err(Error::MalformedRdata)
+    );
+
+    let rdata = hex::decode(RRSIG_RDATA).unwrap();
+    assert_eq!(
+        Rrsig::from_rdata(&rdata[..rdata.len() - 1]),
+        Err(Error::MalformedRdata)
+    );
+    assert_eq!(Rrsig::from_rdata(&rdata[..20]), Err(Error::MalformedRdata));
+
+    let sk = SigningKey::from(*SEED);
+    assert_eq!(
+        mx_rrset().sign(&sk, Dnskey::ZONE_KEY, "example..com", INCEPTION, EXPIRATION),
+        Err(Error::InvalidName("example..com".to_string()))
+    );
+    let empty = Rrset {
+        rdata: Vec::new(),
+        ..mx_rrset()
+    };
+    assert_eq!(
+        empty.sign(&sk, Dnskey::ZONE_KEY, "example.com.", INCEPTION, EXPIRATION),
+        Err(Error::EmptyRrset)
+    );
+}
@@ -0,0 +1,115 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    dsse::{key_id, pae, Envelope, Error, IN_TOTO_PAYLOAD_TYPE},
+    SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The public key for the seed `00 01 .. 1f`.
+const VK: &str = "03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8";
+const KEY_ID: &str = "63d00ee3cf4f5c61ad94efa696c68f13b608fe56ecea13fb144a6fd18e9235b9";
+
+const STATEMENT: &str = r#"{"_type":"https://in-toto.io/Statement/v1","subject":[{"name":"zebrad","digest":{"sha256":"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"}}],"predicateType":"https://slsa.dev/provenance/v1","predicate":{}}"#;
+
+const ENVELOPE: &str = r#"{"payload":"eyJfdHlwZSI6Imh0dHBzOi8vaW4tdG90by5pby9TdGF0ZW1lbnQvdjEiLCJzdWJqZWN0IjpbeyJuYW1lIjoiemVicmFkIiwiZGlnZXN0Ijp7InNoYTI1NiI6ImUzYjBjNDQyOThmYzFjMTQ5YWZiZjRjODk5NmZiOTI0MjdhZTQxZTQ2NDliOTM0Y2E0OTU5OTFiNzg1MmI4NTUifX1dLCJwcmVkaWNhdGVUeXBlIjoiaHR0cHM6Ly9zbHNhLmRldi9wcm92ZW5hbmNlL3YxIiwicHJlZGljYXRlIjp7fX0=","payloadType":"application/vnd.in-toto+json","signatures":[{"keyid":"63d00ee3cf4f5c61ad94efa696c68f13b608fe56ecea13fb144a6fd18e9235b9","sig":"hKa5JC3dMF5ZABCK2+mkglzsUye5RBMT7qQ28D/g3cDDaIBp79vAfrQNRjYVdBP8zHR61AQqL1fWb1OJHiuZAg=="}]}"#;
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn pre_authentication_encoding() {
+    // The example from the DSSE protocol specification.
+    assert_eq!(
+        pae("http://example.com/HelloWorld", b"hello world"),
+        b"DSSEv1 29 http://example.com/HelloWorld 11 hello world"
+    );
+    assert_eq!(pae("", b""), b"DSSEv1 0  0 ");
+}
+
+#[test]
+fn sign_and_verify() {
+    let sk = signing_key();
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    assert_eq!(vk_bytes.to_string(), VK);
+    assert_eq!(key_id(&vk_bytes), KEY_ID);
+
+    let mut envelope = Envelope::new(IN_TOTO_PAYLOAD_TYPE, STATEMENT.as_bytes());
+    envelope.sign(&sk);
+    assert_eq!(envelope.to_json(), ENVELOPE);
+
+    let decoded = Envelope::from_json(ENVELOPE).unwrap();
+    assert_eq!(decoded, envelope);
+    assert_eq!(
+        decoded.verify(&VerificationKey::from(&sk)),
+        Ok(STATEMENT.as_bytes())
+    );
+}
+
+#[test]
+fn multiple_signatures() {
+    let sks: Vec<SigningKey> = (0..3).map(|_| SigningKey::new(thread_rng())).collect();
+    let mut envelope = Envelope::new("text/plain", b"release 1.0");
+    envelope.sign(&sks[0]);
+    envelope.sign(&sks[1]);
+
+    let envelope = Envelope::from_json(&envelope.to_json()).unwrap();
+    assert!(envelope.verify(&VerificationKey::from(&sks[0])).is_ok());
+    assert!(envelope.verify(&VerificationKey::from(&sks[1])).is_ok());
+    assert!(matches!(
+        envelope.verify(&VerificationKey::from(&sks[2])),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn authenticated_payload_type() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+    let mut envelope = Envelope::from_json(ENVELOPE).unwrap();
+    envelope.payload_type = "application/json".to_string();
+    assert!(matches!(
+        envelope.verify(&vk),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    let mut envelope = Envelope::from_json(ENVELOPE).unwrap();
+    envelope.payload.push(b'\n');
+    assert!(matches!(
+        envelope.verify(&vk),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    envelope.signatures.clear();
+    assert_eq!(envelope.verify(&vk), Err(Error::Unsigned));
+}
+
+#[test]
+fn decoding() {
+    // URL-safe and unpadded base64 is accepted, and the key ID is optional.
+    let envelope = Envelope::new("text/plain", b"\xfb\xff");
+    let json = r#"{"payload":"-_8","payloadType":"text/plain","signatures":[]}"#;
+    assert_eq!(Envelope::from_json(json), Ok(envelope));
+
+    let mut envelope = Envelope::new("text/plain", b"zebra");
+    envelope.sign(&signing_key());
+    envelope.signatures[0].keyid.clear();
+    let json = envelope.to_json();
+    assert!(!json.contains("keyid"));
+    assert_eq!(Envelope::from_json(&json), Ok(envelope));
+
+    assert_eq!(
+        Envelope::from_json(r#"{"payload":"","signatures":[]}"#),
+        Err(Error::MalformedEnvelope)
+    );
+    assert_eq!(
+        Envelope::from_json(r#"{"payload":"!","payloadType":"","signatures":[]}"#),
+        Err(Error::InvalidBase64)
+    );
+    assert_eq!(
+        Envelope::from_json(r#"{"payload":"","payloadType":"","signatures":[{"sig":"AAAA"}]}"#),
+        Err(Error::MalformedEnvelope)
+    );
+}
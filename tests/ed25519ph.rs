@@ -0,0 +1,54 @@
+//! Ed25519ph test vectors from [RFC8032 §7.3].
+//!
+//! [RFC8032 §7.3]: https://tools.ietf.org/html/rfc8032#section-7.3
+
+use std::convert::TryFrom;
+
+use ed25519_zebra::*;
+use sha2::{Digest, Sha512};
+
+#[test]
+fn rfc8032_test_ed25519ph_abc() {
+    let sk = SigningKey::try_from(
+        &hex::decode("833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42")
+            .expect("hex should decode")[..],
+    )
+    .expect("sk should parse");
+    let pk = VerificationKey::try_from(
+        &hex::decode("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf")
+            .expect("hex should decode")[..],
+    )
+    .expect("pk should parse");
+    let sig = Signature::try_from(
+        &hex::decode("98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406")
+            .expect("hex should decode")[..],
+    )
+    .expect("sig should parse");
+    let msg = hex::decode("616263").expect("hex should decode");
+    let prehashed_msg = Sha512::default().chain(&msg);
+
+    assert!(pk
+        .verify_prehashed(&sig, prehashed_msg.clone(), None)
+        .is_ok());
+    assert_eq!(
+        VerificationKeyBytes::from(pk),
+        VerificationKeyBytes::from(&sk),
+        "regenerated pubkey did not match test vector pubkey"
+    );
+    assert_eq!(
+        sig,
+        sk.sign_prehashed(prehashed_msg.clone(), None)
+            .expect("signing should succeed"),
+        "regenerated signature did not match test vector"
+    );
+
+    // Ed25519ph signatures are domain-separated from pure Ed25519 and bind the context.
+    assert!(pk.verify(&sig, &msg).is_err());
+    assert!(pk
+        .verify_prehashed(&sig, prehashed_msg.clone(), Some(b"ctx"))
+        .is_err());
+    assert_eq!(
+        sk.sign_prehashed(prehashed_msg, Some(&[0u8; 256])),
+        Err(Error::InvalidSliceLength)
+    );
+}
@@ -0,0 +1,10 @@
+use ed25519_zebra::*;
+
+#[test]
+fn generate_sign_verify() {
+    let sk = SigningKey::generate();
+    let vk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra generate";
+    assert!(vk.verify(&sk.sign(&msg[..]), &msg[..]).is_ok());
+    assert!(SigningKey::generate() != sk);
+}
@@ -0,0 +1,27 @@
+use curve25519_dalek::{constants, scalar::Scalar};
+use rand::{thread_rng, RngCore};
+
+use ed25519_zebra::{hazmat, SigningKey, VerificationKey};
+
+#[test]
+fn raw_sign_with_nonce_uses_supplied_nonce() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra hazmat";
+
+    let mut nonce = [0u8; 32];
+    thread_rng().fill_bytes(&mut nonce[..]);
+
+    let sig = hazmat::raw_sign_with_nonce(&sk, &nonce, &msg[..]);
+    assert_eq!(vk.verify(&sig, &msg[..]), Ok(()));
+
+    // The signature's R component is [r]B for the supplied nonce.
+    #[allow(non_snake_case)]
+    let R = (&Scalar::from_bytes_mod_order(nonce) * &constants::ED25519_BASEPOINT_TABLE)
+        .compress()
+        .to_bytes();
+    assert_eq!(&<[u8; 64]>::from(sig)[0..32], &R[..]);
+
+    // Signing is a deterministic function of the nonce.
+    assert_eq!(sig, hazmat::raw_sign_with_nonce(&sk, &nonce, &msg[..]));
+}
@@ -0,0 +1,48 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{Error, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+#[test]
+fn display_and_from_str_round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let vkb = VerificationKeyBytes::from(&sk);
+    let sig = sk.sign(b"ed25519-zebra hex");
+
+    assert_eq!(vkb.to_string(), hex::encode(vkb));
+    assert_eq!(vk.to_string(), vkb.to_string());
+    assert_eq!(sig.to_string(), hex::encode(sig.as_bytes()));
+
+    assert_eq!(vkb.to_string().parse::<VerificationKeyBytes>(), Ok(vkb));
+    assert_eq!(
+        VerificationKeyBytes::from(vk.to_string().parse::<VerificationKey>().unwrap()),
+        vkb
+    );
+    assert_eq!(sig.to_string().parse::<Signature>(), Ok(sig));
+    assert_eq!(sig.to_string().to_uppercase().parse::<Signature>(), Ok(sig));
+}
+
+#[test]
+fn from_str_errors() {
+    assert_eq!(
+        "zz".repeat(32).parse::<VerificationKeyBytes>(),
+        Err(Error::InvalidHexEncoding)
+    );
+    assert_eq!(
+        "00".repeat(31).parse::<VerificationKeyBytes>(),
+        Err(Error::InvalidSliceLength)
+    );
+    assert_eq!(
+        "00".repeat(65).parse::<Signature>(),
+        Err(Error::InvalidSliceLength)
+    );
+}
+
+#[cfg(feature = "hex-secrets")]
+#[test]
+fn signing_key_hex_round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    let sk2: SigningKey = sk.to_string().parse().unwrap();
+    assert_eq!(sk.to_string(), hex::encode(sk.as_ref()));
+    assert_eq!(sk.as_ref(), sk2.as_ref());
+}
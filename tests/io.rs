@@ -0,0 +1,40 @@
+use std::io::Write;
+
+use rand::thread_rng;
+use sha2::{Digest, Sha512};
+
+use ed25519_zebra::{io::SigningWriter, SigningKey, VerificationKey};
+
+#[test]
+fn signing_writer_matches_prehashed_signing() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let payload = vec![0x5a; 100_000];
+
+    let mut writer = SigningWriter::new(&sk, Vec::new());
+    for chunk in payload.chunks(4096) {
+        writer.write_all(chunk).unwrap();
+    }
+    writer.flush().unwrap();
+    let (sig, written) = writer.finalize();
+
+    assert_eq!(written, payload);
+    let prehashed_msg = Sha512::default().chain(&payload);
+    assert_eq!(sig, sk.sign_prehashed(prehashed_msg.clone(), None).unwrap());
+    assert_eq!(vk.verify_prehashed(&sig, prehashed_msg, None), Ok(()));
+}
+
+#[test]
+fn verify_reader_matches_verify() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let payload = vec![0xa5; 100_000];
+    let sig = sk.sign(&payload);
+
+    assert!(vk.verify_reader(&payload[..], &sig).is_ok());
+
+    let err = vk
+        .verify_reader(&payload[1..], &sig)
+        .expect_err("a truncated message should not verify");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
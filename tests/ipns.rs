@@ -0,0 +1,225 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    ipns::{Error, Record},
+    SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The IPNS name of the seed `00 01 .. 1f`.
+const NAME: &str = "k51qzi5uqu5dg9ufswxt229ntzdy7p4125xzv5rtyjso89ajdujg6csfxcj260";
+const PEER_ID: &str = "12D3KooWA4Xop1JaT3MHxwYMkCepYsv4iPVopMXwCz5iHYdBfeSB";
+
+const VALUE: &[u8] = b"/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+/// 2030-01-01T00:00:00Z.
+const EXPIRES: u64 = 1_893_456_000;
+/// The V2-only record of `VALUE` by the seed `00 01 .. 1f`, with sequence
+/// number 1 and a TTL of 5 minutes.
+const RECORD: &str = "42409ded50f187a83927f1e5c0eae2afb67a6b74f313852ca2086f5d751bb138c9e514a7afaa8783a4faba9d726c90afb4b28b1ace2a1b314a6c996647a77fbdbb084a8d01a56354544c1b00000045d964b8006556616c756558412f697066732f62616679626569676479727a74357366703775646d37687537367568377932366e6633656675796c71616266336f636c67747179353566627a64696853657175656e6365016856616c696469747954323033302d30312d30315430303a30303a30305a6c56616c69646974795479706500";
+/// The `data` field of `RECORD`.
+const DATA: &str = "a56354544c1b00000045d964b8006556616c756558412f697066732f62616679626569676479727a74357366703775646d37687537367568377932366e6633656675796c71616266336f636c67747179353566627a64696853657175656e6365016856616c696469747954323033302d30312d30315430303a30303a30305a6c56616c69646974795479706500";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+fn record() -> Record {
+    Record {
+        value: VALUE.to_vec(),
+        expires: EXPIRES,
+        sequence: 1,
+        ttl: 300_000_000_000,
+    }
+}
+
+/// A protobuf length-delimited field.
+fn field(number: u8, contents: &[u8]) -> Vec<u8> {
+    let mut field = vec![number << 3 | 2];
+    let mut len = contents.len();
+    while len >= 0x80 {
+        field.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+    field.push(len as u8);
+    field.extend_from_slice(contents);
+    field
+}
+
+#[test]
+fn names() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    assert_eq!(vk_bytes.to_ipns_name(), NAME);
+    for name in [NAME, PEER_ID, &format!("/ipns/{}", NAME)] {
+        assert_eq!(VerificationKeyBytes::from_ipns_name(name), Ok(vk_bytes));
+    }
+
+    let vk_bytes = VerificationKeyBytes::from(&SigningKey::new(thread_rng()));
+    assert_eq!(
+        VerificationKeyBytes::from_ipns_name(&vk_bytes.to_ipns_name()),
+        Ok(vk_bytes)
+    );
+
+    for name in [
+        "",
+        "k51",
+        "k51qzi5uqu5d!",
+        &NAME[..NAME.len() - 1],
+        "/ipns/example.com",
+    ] {
+        assert_eq!(
+            VerificationKeyBytes::from_ipns_name(name),
+            Err(Error::MalformedName),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn sign_and_verify() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+
+    let entry = record().sign(&sk).unwrap();
+    assert_eq!(hex::encode(&entry), RECORD);
+    assert_eq!(Record::verify(&entry, &vk, EXPIRES - 1), Ok(record()));
+    assert_eq!(Record::verify(&entry, &vk, EXPIRES), Ok(record()));
+    assert_eq!(
+        Record::verify(&entry, &vk, EXPIRES + 1),
+        Err(Error::Expired)
+    );
+
+    let other = VerificationKey::from(&SigningKey::new(thread_rng()));
+    assert!(matches!(
+        Record::verify(&entry, &other, 0),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn v1_fields() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+    let entry = hex::decode(RECORD).unwrap();
+    let validity = b"2030-01-01T00:00:00Z";
+
+    // Records created for V1 compatibility repeat the signed data in V1
+    // fields, and may embed the public key.
+    let mut compatible = field(1, VALUE);
+    compatible.extend_from_slice(&[3 << 3, 0]);
+    compatible.extend_from_slice(&field(4, validity));
+    compatible.extend_from_slice(&[5 << 3, 1]);
+    compatible.extend_from_slice(&field(
+        7,
+        &VerificationKeyBytes::from(&sk).to_libp2p_protobuf(),
+    ));
+    compatible.extend_from_slice(&entry);
+    assert_eq!(Record::verify(&compatible, &vk, 0), Ok(record()));
+
+    let mut inconsistent = field(1, b"/ipfs/other");
+    inconsistent.extend_from_slice(&entry);
+    assert_eq!(
+        Record::verify(&inconsistent, &vk, 0),
+        Err(Error::InconsistentRecord)
+    );
+
+    let mut other_key = field(
+        7,
+        &VerificationKeyBytes::from(&SigningKey::new(thread_rng())).to_libp2p_protobuf(),
+    );
+    other_key.extend_from_slice(&entry);
+    assert_eq!(
+        Record::verify(&other_key, &vk, 0),
+        Err(Error::MismatchedPublicKey)
+    );
+}
+
+#[test]
+fn validity_times() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+    let data = hex::decode(DATA).unwrap();
+
+    // Sign `data` with its validity replaced by `validity`.
+    let entry_with_validity = |validity: &str| {
+        let original = b"2030-01-01T00:00:00Z";
+        let start = data
+            .windows(original.len())
+            .position(|window| window == original)
+            .unwrap();
+        let mut modified = data[..start - 1].to_vec();
+        if validity.len() < 24 {
+            modified.push(0x40 | validity.len() as u8);
+        } else {
+            modified.extend_from_slice(&[0x58, validity.len() as u8]);
+        }
+        modified.extend_from_slice(validity.as_bytes());
+        modified.extend_from_slice(&data[start + original.len()..]);
+        let mut signed = b"ipns-signature:".to_vec();
+        signed.extend_from_slice(&modified);
+        let mut entry = field(8, &<[u8; 64]>::from(sk.sign(&signed)));
+        entry.extend_from_slice(&field(9, &modified));
+        entry
+    };
+
+    // Go writes nanoseconds and may use a numeric offset.
+    for (validity, expires) in [
+        ("2030-01-01T00:00:00.123456789Z", EXPIRES),
+        ("2030-01-01T01:30:00+01:30", EXPIRES),
+        ("2029-12-31T20:00:00-04:00", EXPIRES),
+        ("2024-02-29T12:00:00Z", 1_709_208_000),
+    ] {
+        let entry = entry_with_validity(validity);
+        assert_eq!(
+            Record::verify(&entry, &vk, 0).map(|record| record.expires),
+            Ok(expires),
+            "{}",
+            validity
+        );
+    }
+
+    for validity in [
+        "2030-01-01 00:00:00Z",
+        "2030-13-01T00:00:00Z",
+        "2023-02-29T12:00:00Z",
+        "2030-01-01T00:00:00",
+        "2030-01-01T00:00:00.Z",
+        "1969-12-31T23:59:59Z",
+    ] {
+        let entry = entry_with_validity(validity);
+        assert_eq!(
+            Record::verify(&entry, &vk, 0),
+            Err(Error::MalformedValidity),
+            "{}",
+            validity
+        );
+    }
+}
+
+#[test]
+fn malformed_records() {
+    let vk = VerificationKey::from(&signing_key());
+    let entry = hex::decode(RECORD).unwrap();
+
+    for malformed in [
+        &entry[..entry.len() - 1],
+        &entry[66..],
+        &[&entry[..], &entry[..66]].concat()[..],
+        &[0x0f][..],
+    ] {
+        assert_eq!(
+            Record::verify(malformed, &vk, 0),
+            Err(Error::MalformedRecord)
+        );
+    }
+
+    let oversized = [&entry[..], &field(1, &[0; 10 * 1024])].concat();
+    assert_eq!(
+        Record::verify(&oversized, &vk, 0),
+        Err(Error::RecordTooLarge)
+    );
+    let mut record = record();
+    record.value = vec![b'a'; 10 * 1024];
+    assert_eq!(record.sign(&signing_key()), Err(Error::RecordTooLarge));
+}
@@ -0,0 +1,86 @@
+use rand::thread_rng;
+use serde_json::json;
+
+use ed25519_zebra::{
+    jws::{self, Error, Header},
+    SigningKey, VerificationKey,
+};
+
+/// The Ed25519 example from RFC 8037 appendix A.4.
+const RFC8037_SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+const RFC8037_PAYLOAD: &[u8] = b"Example of Ed25519 signing";
+const RFC8037_TOKEN: &str = "eyJhbGciOiJFZERTQSJ9.RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc.hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR6dWbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg";
+
+fn rfc8037_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    hex::decode_to_slice(RFC8037_SEED, &mut seed[..]).unwrap();
+    SigningKey::from(seed)
+}
+
+#[test]
+fn rfc8037_vector() {
+    let sk = rfc8037_key();
+    assert_eq!(
+        sk.sign_jws(&Header::default(), RFC8037_PAYLOAD),
+        RFC8037_TOKEN
+    );
+
+    let vk = VerificationKey::from(&sk);
+    let (header, payload) = vk.verify_jws(RFC8037_TOKEN).unwrap();
+    assert_eq!(header, Header::default());
+    assert_eq!(payload, RFC8037_PAYLOAD);
+}
+
+#[test]
+fn round_trip_with_header() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let mut header = Header {
+        typ: Some("JWT".to_string()),
+        kid: Some("zebra-1".to_string()),
+        ..Header::default()
+    };
+    header.extra.insert("x-zebra".to_string(), json!([1, 2, 3]));
+
+    let token = sk.sign_jws(&header, b"{}");
+    assert_eq!(jws::decode_header(&token).unwrap(), header);
+    assert_eq!(vk.verify_jws(&token).unwrap(), (header, b"{}".to_vec()));
+
+    let other = VerificationKey::from(&SigningKey::new(thread_rng()));
+    assert_eq!(
+        other.verify_jws(&token).unwrap_err(),
+        Error::InvalidSignature(ed25519_zebra::Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn reject_other_algorithms() {
+    let vk = VerificationKey::from(&rfc8037_key());
+    let (_, rest) = RFC8037_TOKEN.split_at(RFC8037_TOKEN.find('.').unwrap());
+
+    // {"alg":"none"}
+    let none = format!("eyJhbGciOiJub25lIn0{}", rest);
+    assert_eq!(
+        vk.verify_jws(&none).unwrap_err(),
+        Error::UnsupportedAlgorithm("none".to_string())
+    );
+    // {"alg":"EdDSA","crit":["exp"],"exp":0}
+    let crit = format!(
+        "eyJhbGciOiJFZERTQSIsImNyaXQiOlsiZXhwIl0sImV4cCI6MH0{}",
+        rest
+    );
+    assert_eq!(
+        vk.verify_jws(&crit).unwrap_err(),
+        Error::UnsupportedCritical
+    );
+
+    assert_eq!(
+        vk.verify_jws(&RFC8037_TOKEN[..RFC8037_TOKEN.rfind('.').unwrap()])
+            .unwrap_err(),
+        Error::MalformedToken
+    );
+    assert_eq!(
+        vk.verify_jws(&format!("{}=", RFC8037_TOKEN)).unwrap_err(),
+        Error::InvalidBase64
+    );
+}
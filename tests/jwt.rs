@@ -0,0 +1,165 @@
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+
+use ed25519_zebra::{
+    jws::{self, Header},
+    jwt::{Audience, Claims, Error, Validation},
+    SigningKey, VerificationKey,
+};
+
+const NOW: u64 = 1_700_000_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Scope {
+    scope: String,
+}
+
+fn claims() -> Claims<Scope> {
+    Claims {
+        iss: Some("https://issuer.example".to_string()),
+        sub: Some("zebra".to_string()),
+        aud: Some(Audience::Multiple(vec![
+            "api".to_string(),
+            "admin".to_string(),
+        ])),
+        exp: Some(NOW + 300),
+        iat: Some(NOW),
+        custom: Scope {
+            scope: "read".to_string(),
+        },
+        ..Claims::default()
+    }
+}
+
+#[test]
+fn round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let token = sk.sign_jwt(&claims()).unwrap();
+    assert_eq!(
+        jws::decode_header(&token).unwrap().typ.as_deref(),
+        Some("JWT")
+    );
+
+    let validation = Validation {
+        audience: Some("api".to_string()),
+        issuer: Some("https://issuer.example".to_string()),
+        ..Validation::at(NOW)
+    };
+    assert_eq!(
+        vk.verify_jwt::<Scope>(&token, &validation).unwrap(),
+        claims()
+    );
+
+    // Claims not named by the type are ignored, or collected into a map.
+    let untyped: Claims = vk.verify_jwt(&token, &validation).unwrap();
+    assert_eq!(untyped.custom["scope"], "read");
+}
+
+#[test]
+fn time_checks() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let token = sk
+        .sign_jwt(&Claims {
+            nbf: Some(NOW),
+            ..claims()
+        })
+        .unwrap();
+
+    let at = |now| vk.verify_jwt::<Scope>(&token, &Validation::at(now));
+    assert!(at(NOW).is_ok());
+    // Within the default one minute of leeway.
+    assert!(at(NOW - 59).is_ok());
+    assert!(at(NOW + 359).is_ok());
+    assert_eq!(at(NOW - 61).unwrap_err(), Error::NotYetValid);
+    assert_eq!(at(NOW + 360).unwrap_err(), Error::Expired);
+
+    let forever = sk
+        .sign_jwt(&Claims {
+            exp: None,
+            ..claims()
+        })
+        .unwrap();
+    assert_eq!(
+        vk.verify_jwt::<Scope>(&forever, &Validation::at(NOW))
+            .unwrap_err(),
+        Error::MissingExpiration
+    );
+    let validation = Validation {
+        require_exp: false,
+        ..Validation::at(NOW)
+    };
+    assert!(vk.verify_jwt::<Scope>(&forever, &validation).is_ok());
+}
+
+#[test]
+fn audience_and_issuer_checks() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let token = sk.sign_jwt(&claims()).unwrap();
+
+    let validation = Validation {
+        audience: Some("billing".to_string()),
+        ..Validation::at(NOW)
+    };
+    assert_eq!(
+        vk.verify_jwt::<Scope>(&token, &validation).unwrap_err(),
+        Error::InvalidAudience
+    );
+    let validation = Validation {
+        issuer: Some("https://other.example".to_string()),
+        ..Validation::at(NOW)
+    };
+    assert_eq!(
+        vk.verify_jwt::<Scope>(&token, &validation).unwrap_err(),
+        Error::InvalidIssuer
+    );
+}
+
+#[test]
+fn invalid_tokens() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+
+    let not_claims = sk.sign_jws(&Header::default(), b"[1, 2, 3]");
+    assert_eq!(
+        vk.verify_jwt::<Scope>(&not_claims, &Validation::at(NOW))
+            .unwrap_err(),
+        Error::InvalidClaims
+    );
+
+    let other = SigningKey::new(thread_rng()).sign_jwt(&claims()).unwrap();
+    assert!(matches!(
+        vk.verify_jwt::<Scope>(&other, &Validation::at(NOW)),
+        Err(Error::Jws(jws::Error::InvalidSignature(_)))
+    ));
+}
+
+#[test]
+fn unserializable_claims() {
+    use std::collections::BTreeMap;
+
+    let sk = SigningKey::new(thread_rng());
+
+    // JSON object keys must be strings.
+    let mut custom = BTreeMap::new();
+    custom.insert((1u8, 2u8), 3u8);
+    assert_eq!(
+        sk.sign_jwt(&Claims {
+            custom,
+            ..Claims::default()
+        }),
+        Err(Error::InvalidClaims)
+    );
+
+    // Custom claims that are not a struct or map cannot be flattened into
+    // the claims set.
+    assert_eq!(
+        sk.sign_jwt(&Claims {
+            custom: 7u32,
+            ..Claims::default()
+        }),
+        Err(Error::InvalidClaims)
+    );
+}
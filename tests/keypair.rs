@@ -0,0 +1,51 @@
+use std::convert::TryFrom;
+
+use rand::thread_rng;
+
+use ed25519_zebra::{Error, Keypair, SigningKey, VerificationKey, VerificationKeyBytes};
+
+#[test]
+fn split_and_join() {
+    let keypair = Keypair::new(thread_rng());
+    let msg = b"ed25519-zebra keypair";
+
+    let sig = keypair.sign(&msg[..]);
+    assert_eq!(keypair.verify(&sig, &msg[..]), Ok(()));
+
+    let (sk, vk) = keypair.clone().split();
+    assert_eq!(vk.verify(&sig, &msg[..]), Ok(()));
+    assert_eq!(
+        VerificationKeyBytes::from(&sk),
+        VerificationKeyBytes::from(vk)
+    );
+
+    let joined = Keypair::join(sk.clone(), vk).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&joined),
+        VerificationKeyBytes::from(&keypair)
+    );
+
+    let other = VerificationKey::from(&SigningKey::new(thread_rng()));
+    assert_eq!(
+        Keypair::join(sk, other).unwrap_err(),
+        Error::MalformedPublicKey
+    );
+}
+
+#[test]
+fn parsing() {
+    let keypair = Keypair::new(thread_rng());
+    let bytes: [u8; 64] = keypair.clone().into();
+
+    assert_eq!(&bytes[..], &keypair.signing_key().to_libsodium_bytes()[..]);
+    assert_eq!(&bytes[32..], keypair.verification_key().as_ref());
+
+    let keypair2 = Keypair::try_from(&bytes[..]).unwrap();
+    assert_eq!(&<[u8; 64]>::from(keypair2)[..], &bytes[..]);
+
+    let keypair3: Keypair = bincode::deserialize(&bytes[..32]).unwrap();
+    assert_eq!(&<[u8; 64]>::from(keypair3.clone())[..], &bytes[..]);
+    assert_eq!(bincode::serialize(&keypair3).unwrap(), &bytes[..32]);
+
+    assert!(Keypair::try_from(&bytes[..32]).is_err());
+}
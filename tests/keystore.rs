@@ -0,0 +1,221 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    keystore::{Error, Kdf, Keystore},
+    SigningKey, VerificationKeyBytes,
+};
+
+/// The password of the EIP-2335 test vectors, `𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑`, normalized to
+/// NFKD form.
+const PASSWORD: &str = "testpassword🔑";
+/// The secret of the EIP-2335 test vectors, here an Ed25519 seed.
+const SEED: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+
+/// The scrypt test vector of EIP-2335, with the Ed25519 verification key of
+/// its secret.
+const SCRYPT_KEYSTORE: &str = r#"{
+    "crypto": {
+        "kdf": {
+            "function": "scrypt",
+            "params": {
+                "dklen": 32,
+                "n": 262144,
+                "p": 1,
+                "r": 8,
+                "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            },
+            "message": ""
+        },
+        "checksum": {
+            "function": "sha256",
+            "params": {},
+            "message": "d2217fe5f3e9a1e34581ef8a78f7c9928e436d36dacc5e846690a5581e8ea484"
+        },
+        "cipher": {
+            "function": "aes-128-ctr",
+            "params": {
+                "iv": "264daa3f303d7259501c93d997d84fe6"
+            },
+            "message": "06ae90d55fe0a6e9c5c3bc5b170827b2e5cce3929ed3f116c2811e6366dfe20f"
+        }
+    },
+    "description": "This is a test keystore that uses scrypt to secure the secret.",
+    "pubkey": "14331ceb41b8effe469e6ff6bdd2ca9fcf295c66b865797fc0cd389c6e5378b6",
+    "path": "m/12381/60/3141592653/589793238",
+    "uuid": "1d85ae20-35c5-4611-98e8-aa14a633906f",
+    "version": 4
+}"#;
+
+/// The PBKDF2 test vector of EIP-2335, with the Ed25519 verification key of
+/// its secret.
+const PBKDF2_KEYSTORE: &str = r#"{
+    "crypto": {
+        "kdf": {
+            "function": "pbkdf2",
+            "params": {
+                "dklen": 32,
+                "c": 262144,
+                "prf": "hmac-sha256",
+                "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            },
+            "message": ""
+        },
+        "checksum": {
+            "function": "sha256",
+            "params": {},
+            "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+        },
+        "cipher": {
+            "function": "aes-128-ctr",
+            "params": {
+                "iv": "264daa3f303d7259501c93d997d84fe6"
+            },
+            "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+        }
+    },
+    "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+    "pubkey": "14331ceb41b8effe469e6ff6bdd2ca9fcf295c66b865797fc0cd389c6e5378b6",
+    "path": "m/12381/60/0/0",
+    "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+    "version": 4
+}"#;
+
+/// Cheap scrypt parameters for round trips.
+const TEST_KDF: Kdf = Kdf::Scrypt {
+    log_n: 10,
+    r: 8,
+    p: 1,
+};
+
+#[test]
+fn eip2335_vectors() {
+    for (json, kdf) in [
+        (SCRYPT_KEYSTORE, Kdf::default()),
+        (PBKDF2_KEYSTORE, Kdf::Pbkdf2 { c: 262144 }),
+    ] {
+        let keystore = Keystore::from_json(json).unwrap();
+        assert_eq!(keystore.kdf(), kdf);
+        let sk = keystore.decrypt(PASSWORD).unwrap();
+        assert_eq!(hex::encode(sk.as_ref()), SEED);
+        assert_eq!(keystore.pubkey, VerificationKeyBytes::from(&sk));
+        assert_eq!(
+            keystore.decrypt("testpassword"),
+            Err(Error::InvalidPassword)
+        );
+
+        let reencoded = Keystore::from_json(&keystore.to_json()).unwrap();
+        assert_eq!(reencoded, keystore);
+    }
+}
+
+#[test]
+fn control_characters() {
+    // Control characters are removed from passwords.
+    let keystore = Keystore::from_json(PBKDF2_KEYSTORE).unwrap();
+    let sk = keystore.decrypt("test\u{7f}pass\u{0}word\u{85}🔑").unwrap();
+    assert_eq!(hex::encode(sk.as_ref()), SEED);
+}
+
+#[test]
+fn round_trip() {
+    let sk = SigningKey::new(thread_rng());
+    for kdf in [TEST_KDF, Kdf::Pbkdf2 { c: 1000 }] {
+        let mut keystore = Keystore::encrypt(&sk, "password", kdf, thread_rng()).unwrap();
+        keystore.description = "A test key.".to_string();
+        assert_eq!(keystore.pubkey, VerificationKeyBytes::from(&sk));
+        assert_eq!(keystore.uuid.len(), 36);
+        assert_eq!(&keystore.uuid[14..15], "4");
+
+        let decoded = Keystore::from_json(&keystore.to_json()).unwrap();
+        assert_eq!(decoded, keystore);
+        assert_eq!(decoded.decrypt("password"), Ok(sk.clone()));
+        assert_eq!(decoded.decrypt("Password"), Err(Error::InvalidPassword));
+    }
+}
+
+#[test]
+fn invalid_kdf_parameters() {
+    let sk = SigningKey::new(thread_rng());
+    for kdf in [
+        Kdf::Scrypt {
+            log_n: 0,
+            r: 8,
+            p: 1,
+        },
+        Kdf::Scrypt {
+            log_n: 10,
+            r: 0,
+            p: 1,
+        },
+        Kdf::Pbkdf2 { c: 0 },
+    ] {
+        assert_eq!(
+            Keystore::encrypt(&sk, "password", kdf, thread_rng()),
+            Err(Error::InvalidKdfParameters)
+        );
+    }
+
+    for (from, to) in [
+        ("\"n\": 262144", "\"n\": 262143"),
+        ("\"n\": 262144", "\"n\": 1"),
+        ("\"dklen\": 32", "\"dklen\": 16"),
+    ] {
+        assert_eq!(
+            Keystore::from_json(&SCRYPT_KEYSTORE.replace(from, to)),
+            Err(Error::InvalidKdfParameters)
+        );
+    }
+}
+
+#[test]
+fn malformed_keystores() {
+    assert_eq!(
+        Keystore::from_json(&SCRYPT_KEYSTORE.replace("\"version\": 4", "\"version\": 3")),
+        Err(Error::UnsupportedVersion(3))
+    );
+    for (from, to, function) in [
+        ("\"scrypt\"", "\"argon2id\"", "argon2id"),
+        ("\"sha256\"", "\"sha512\"", "sha512"),
+        ("\"aes-128-ctr\"", "\"aes-256-ctr\"", "aes-256-ctr"),
+    ] {
+        assert_eq!(
+            Keystore::from_json(&SCRYPT_KEYSTORE.replace(from, to)),
+            Err(Error::UnsupportedFunction(function.to_string()))
+        );
+    }
+    assert_eq!(
+        Keystore::from_json(&PBKDF2_KEYSTORE.replace("hmac-sha256", "hmac-sha512")),
+        Err(Error::UnsupportedFunction("hmac-sha512".to_string()))
+    );
+
+    for (from, to) in [
+        ("\"function\": \"scrypt\"", "\"function\": \"pbkdf2\""),
+        ("\"iv\": \"264daa3f", "\"iv\": \""),
+        ("\"message\": \"06ae90d5", "\"message\": \""),
+        ("\"pubkey\": \"14331ceb", "\"pubkey\": \"14331ce"),
+        ("\"uuid\"", "\"id\""),
+    ] {
+        assert_eq!(
+            Keystore::from_json(&SCRYPT_KEYSTORE.replace(from, to)),
+            Err(Error::MalformedKeystore),
+            "{}",
+            to
+        );
+    }
+}
+
+#[test]
+fn mismatched_public_key() {
+    let mut keystore = Keystore::encrypt(
+        &SigningKey::new(thread_rng()),
+        "password",
+        TEST_KDF,
+        thread_rng(),
+    )
+    .unwrap();
+    keystore.pubkey = VerificationKeyBytes::from(&SigningKey::new(thread_rng()));
+    assert_eq!(
+        keystore.decrypt("password"),
+        Err(Error::MismatchedPublicKey)
+    );
+}
@@ -0,0 +1,104 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{libp2p::Error, Keypair, SigningKey, VerificationKeyBytes};
+
+/// The Ed25519 test vector from the libp2p peer ID specification.
+const PRIVATE_KEY: &str = "080112407e0830617c4a7de83925dfb2694556b12936c477a0e1feb2e148ec9da60fee7d1ed1e8fae2c4a144b8be8fd4b47bf3d3b34b871c3cacf6010f0e42d474fce27e";
+const PUBLIC_KEY: &str = "080112201ed1e8fae2c4a144b8be8fd4b47bf3d3b34b871c3cacf6010f0e42d474fce27e";
+const PEER_ID: &str = "12D3KooWBtg3aaRMjxwedh83aGiUkwSxDwUZkzuJcfaqUmo7R3pq";
+
+#[test]
+fn specification_vector() {
+    let keypair = Keypair::from_libp2p_protobuf(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+    assert_eq!(hex::encode(&keypair.to_libp2p_protobuf()[..]), PRIVATE_KEY);
+
+    let vk_bytes = VerificationKeyBytes::from(&keypair);
+    assert_eq!(hex::encode(vk_bytes.to_libp2p_protobuf()), PUBLIC_KEY);
+    assert_eq!(
+        VerificationKeyBytes::from_libp2p_protobuf(&hex::decode(PUBLIC_KEY).unwrap()),
+        Ok(vk_bytes)
+    );
+    assert_eq!(vk_bytes.to_peer_id(), PEER_ID);
+    assert_eq!(VerificationKeyBytes::from_peer_id(PEER_ID), Ok(vk_bytes));
+}
+
+#[test]
+fn round_trip() {
+    let keypair = Keypair::from(SigningKey::new(thread_rng()));
+    let decoded = Keypair::from_libp2p_protobuf(&keypair.to_libp2p_protobuf()).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+
+    let vk_bytes = VerificationKeyBytes::from(&keypair);
+    assert!(vk_bytes.to_peer_id().starts_with("12D3KooW"));
+    assert_eq!(
+        VerificationKeyBytes::from_peer_id(&vk_bytes.to_peer_id()),
+        Ok(vk_bytes)
+    );
+}
+
+#[test]
+fn field_order() {
+    // Protobuf fields may appear in any order.
+    let public_key = hex::decode(PUBLIC_KEY).unwrap();
+    let mut reordered = public_key[2..].to_vec();
+    reordered.extend_from_slice(&public_key[..2]);
+    assert_eq!(
+        VerificationKeyBytes::from_libp2p_protobuf(&reordered),
+        VerificationKeyBytes::from_libp2p_protobuf(&public_key)
+    );
+}
+
+#[test]
+fn malformed_keys() {
+    let public_key = hex::decode(PUBLIC_KEY).unwrap();
+
+    // A secp256k1 key.
+    let mut secp256k1 = public_key.clone();
+    secp256k1[1] = 2;
+    assert_eq!(
+        VerificationKeyBytes::from_libp2p_protobuf(&secp256k1),
+        Err(Error::UnsupportedKeyType(2))
+    );
+
+    for malformed in [
+        &public_key[..35],
+        &public_key[2..],
+        &[&public_key[..], &public_key[..2]].concat()[..],
+        &[&public_key[..], &[0x18, 0x00]].concat()[..],
+        &hex::decode(PRIVATE_KEY).unwrap()[..],
+    ] {
+        assert_eq!(
+            VerificationKeyBytes::from_libp2p_protobuf(malformed),
+            Err(Error::MalformedKey),
+            "{}",
+            hex::encode(malformed)
+        );
+    }
+    assert_eq!(
+        Keypair::from_libp2p_protobuf(&public_key).err(),
+        Some(Error::MalformedKey)
+    );
+
+    let mut inconsistent = hex::decode(PRIVATE_KEY).unwrap();
+    inconsistent[67] ^= 1;
+    assert!(matches!(
+        Keypair::from_libp2p_protobuf(&inconsistent),
+        Err(Error::InconsistentKeypair(_))
+    ));
+}
+
+#[test]
+fn malformed_peer_ids() {
+    assert_eq!(
+        VerificationKeyBytes::from_peer_id("12D3KooW0OIl"),
+        Err(Error::InvalidBase58)
+    );
+    // An RSA peer ID, which is the SHA-256 multihash of the key.
+    assert_eq!(
+        VerificationKeyBytes::from_peer_id("QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N"),
+        Err(Error::MalformedPeerId)
+    );
+}
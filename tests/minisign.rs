@@ -0,0 +1,161 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    minisign::{Algorithm, Error, MinisignSignature, PublicKey, SecretKey},
+    VerificationKeyBytes,
+};
+
+const VK: &str = "29acbae141bccaf0b22e1a94d34d0bc7361e526d0bfe12c89794bc9322966dd7";
+const KEY_ID: [u8; 8] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+
+const PUBLIC_KEY: &str = "untrusted comment: minisign public key EFCDAB8967452301
+RWQBI0VniavN7ymsuuFBvMrwsi4alNNNC8c2HlJtC/4SyJeUvJMilm3X
+";
+
+/// An unencrypted secret key in the format written by `minisign -G -W`, for
+/// the seed `20 21 .. 3f`. It was assembled by hand rather than generated by
+/// minisign, so its untrusted comment differs from the one minisign writes.
+const SECRET_KEY: &str = "untrusted comment: minisign secret key
+RWQAAEIyAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAASNFZ4mrze8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+PymsuuFBvMrwsi4alNNNC8c2HlJtC/4SyJeUvJMilm3XfefeQ8QVk1hbRZEX2g96wJYjOmu5kc4/xGQl6BAGlAc=
+";
+
+const MESSAGE: &[u8] = b"release contents\n";
+
+const PREHASHED_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBI0VniavN7w5NlAWQxsmRektRhZd0pkTiDtcXmZi5RgyLV0MXSA/gFQMQN5cicn5dw0uh0SSbON2aaalk9ChQVa3GldNm1Qg=
+trusted comment: timestamp:1700000000\tfile:release.tar.gz\thashed
+mLicaivPjdwzVm7toXuAC16bt2M+L0KsY5aSD/b3NtrE/mM2ZM3BWEBCd7uTRHV52JPOdKNH0+XeD6Nk7+N6Cw==
+";
+
+const LEGACY_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RWQBI0VniavN75vIAJMHj+o2XQbQtqGuPdyy0T5FO1mXwXOjdYnGTyeJcfM3diaGkcAOtaefeRm2XQipD+AeBTTpkqrlnz381wM=
+trusted comment: timestamp:1700000000\tfile:release.tar.gz
+85Da4RFpmXrdjEty2ZA0rCnMgsEMiZ7sGkrAHMU5lFjWwGjenhShIYZ4T+mvhMiF7IG6NhxOxVZCLjmfC1/YBA==
+";
+
+#[test]
+fn key_files() {
+    let pk = PublicKey::decode(PUBLIC_KEY).unwrap();
+    assert_eq!(pk.key_id, KEY_ID);
+    assert_eq!(
+        VerificationKeyBytes::from(pk.verification_key).to_string(),
+        VK
+    );
+    assert_eq!(pk.encode(), PUBLIC_KEY);
+
+    let sk = SecretKey::decode(SECRET_KEY, None).unwrap();
+    assert_eq!(sk.key_id, KEY_ID);
+    assert_eq!(pk.to_base64(), sk.public_key().to_base64());
+    assert_eq!(&*sk.encode(), SECRET_KEY);
+}
+
+#[test]
+fn minisign_public_key() {
+    // The key that signs minisign's own releases.
+    let pk =
+        PublicKey::from_base64("RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3").unwrap();
+    assert!(pk
+        .encode()
+        .starts_with("untrusted comment: minisign public key E7620F1842B4E81F\n"));
+
+    // Key IDs are written with all 16 hex digits, as minisign does.
+    let mut pk = pk;
+    pk.key_id = [0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01];
+    assert!(pk
+        .encode()
+        .starts_with("untrusted comment: minisign public key 0123456789ABCDEF\n"));
+    pk.key_id = [0; 8];
+    assert!(pk
+        .encode()
+        .starts_with("untrusted comment: minisign public key 0000000000000000\n"));
+}
+
+#[test]
+fn signature_vectors() {
+    let pk = PublicKey::decode(PUBLIC_KEY).unwrap();
+    let sk = SecretKey::decode(SECRET_KEY, None).unwrap();
+
+    for (algorithm, trusted_comment, text) in &[
+        (
+            Algorithm::Prehashed,
+            "timestamp:1700000000\tfile:release.tar.gz\thashed",
+            PREHASHED_SIGNATURE,
+        ),
+        (
+            Algorithm::Legacy,
+            "timestamp:1700000000\tfile:release.tar.gz",
+            LEGACY_SIGNATURE,
+        ),
+    ] {
+        let signature = MinisignSignature::decode(text).unwrap();
+        assert_eq!(signature.algorithm(), *algorithm);
+        assert_eq!(signature.key_id(), KEY_ID);
+        assert_eq!(signature.trusted_comment(), *trusted_comment);
+        assert_eq!(signature.verify(&pk, MESSAGE), Ok(()));
+        assert!(signature.verify(&pk, b"tampered").is_err());
+
+        let signed = sk.sign(*algorithm, MESSAGE, trusted_comment).unwrap();
+        assert_eq!(signed, signature);
+        assert_eq!(signed.encode().unwrap(), *text);
+    }
+}
+
+#[test]
+fn trusted_comment_is_authenticated() {
+    let pk = PublicKey::decode(PUBLIC_KEY).unwrap();
+    let forged = PREHASHED_SIGNATURE.replace("timestamp:1700000000", "timestamp:1800000000");
+    let signature = MinisignSignature::decode(&forged).unwrap();
+    assert!(matches!(
+        signature.verify(&pk, MESSAGE),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    // The untrusted comment is not authenticated.
+    let relabeled = PREHASHED_SIGNATURE.replace("signature from", "anything from");
+    let signature = MinisignSignature::decode(&relabeled).unwrap();
+    assert_eq!(
+        signature.untrusted_comment,
+        "anything from minisign secret key"
+    );
+    assert_eq!(signature.verify(&pk, MESSAGE), Ok(()));
+}
+
+#[test]
+fn sign_and_verify() {
+    let sk = SecretKey::new(thread_rng());
+    let pk = PublicKey::decode(&sk.public_key().encode()).unwrap();
+    let sk = SecretKey::decode(&sk.encode(), None).unwrap();
+
+    let signature = sk
+        .sign(Algorithm::Prehashed, MESSAGE, "file:release.tar.gz")
+        .unwrap();
+    let decoded = MinisignSignature::decode(&signature.encode().unwrap()).unwrap();
+    assert_eq!(decoded.verify(&pk, MESSAGE), Ok(()));
+
+    let other = SecretKey::new(thread_rng()).public_key();
+    assert_eq!(decoded.verify(&other, MESSAGE), Err(Error::KeyIdMismatch));
+
+    assert_eq!(
+        sk.sign(Algorithm::Prehashed, MESSAGE, "two\nlines"),
+        Err(Error::InvalidComment)
+    );
+}
+
+#[test]
+fn malformed_files() {
+    assert_eq!(
+        PublicKey::decode("RWQBI0VniavN7ymsuuFBvMrwsi4alNNNC8c2HlJtC/4SyJeUvJMilm3X\n")
+            .unwrap_err(),
+        Error::Malformed("missing untrusted comment")
+    );
+    assert_eq!(
+        PublicKey::from_base64("RWQBI0VniavN7ymsuuFBvMrwsi4alNNNC8c2HlJtC/4SyJeUvJMilm3")
+            .unwrap_err(),
+        Error::InvalidBase64
+    );
+    assert_eq!(
+        MinisignSignature::decode(&PREHASHED_SIGNATURE.replace("\ntrusted comment: ", "\n"))
+            .unwrap_err(),
+        Error::Malformed("missing trusted comment")
+    );
+}
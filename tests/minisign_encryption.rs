@@ -0,0 +1,60 @@
+use ed25519_zebra::minisign::{Error, SecretKey};
+
+const KEY_ID: [u8; 8] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+const PASSWORD: &[u8] = b"correct horse";
+
+/// The key of `tests/minisign.rs`, encrypted under `PASSWORD` with an
+/// `opslimit` of 32768 and a `memlimit` of 16 MiB (scrypt with `N = 2^10`,
+/// `r = 8`, and `p = 1`) so that the test runs quickly.
+const ENCRYPTED_SECRET_KEY: &str = "untrusted comment: minisign encrypted secret key
+RWRTY0IyWlpaWlpaWlpaWlpaWlpaWlpaWlpaWlpaWlpaWlpaWloAgAAAAAAAAAAAAAEAAAAA7r0ELnASJz60Xl0++KMejCwjlFdgHnsEZf5FgxfuHc4/6ITuevdZrez4JXKdtKc9SFPOAl0oACPh9FOv5od6yfoClqknZKqkaZ+xNkAoDbDMQgH4xLV+lSXXR3fsxTf3EE/BHWgINX8=
+";
+
+const PUBLIC_KEY: &str = "RWQBI0VniavN7ymsuuFBvMrwsi4alNNNC8c2HlJtC/4SyJeUvJMilm3X";
+
+#[test]
+fn decrypt_secret_key() {
+    let sk = SecretKey::decode(ENCRYPTED_SECRET_KEY, Some(PASSWORD)).unwrap();
+    assert_eq!(sk.key_id, KEY_ID);
+    assert_eq!(sk.public_key().to_base64(), PUBLIC_KEY);
+}
+
+#[test]
+fn password_errors() {
+    assert_eq!(
+        SecretKey::decode(ENCRYPTED_SECRET_KEY, None).unwrap_err(),
+        Error::PassphraseRequired
+    );
+    assert_eq!(
+        SecretKey::decode(ENCRYPTED_SECRET_KEY, Some(b"incorrect horse")).unwrap_err(),
+        Error::IncorrectPassphrase
+    );
+}
+
+#[test]
+fn kdf_limits() {
+    use base64ct::{Base64, Encoding};
+    use ed25519_zebra::minisign::{MAX_MEMLIMIT, MAX_OPSLIMIT};
+
+    // The `opslimit` and `memlimit` follow the algorithms and the salt.
+    let with_limits = |opslimit: u64, memlimit: u64| {
+        let mut lines = ENCRYPTED_SECRET_KEY.lines();
+        let comment = lines.next().unwrap();
+        let mut bytes = Base64::decode_vec(lines.next().unwrap()).unwrap();
+        bytes[38..46].copy_from_slice(&opslimit.to_le_bytes());
+        bytes[46..54].copy_from_slice(&memlimit.to_le_bytes());
+        format!("{}\n{}\n", comment, Base64::encode_string(&bytes))
+    };
+
+    assert_eq!(with_limits(32768, 16 << 20), ENCRYPTED_SECRET_KEY);
+    for (opslimit, memlimit) in [
+        (MAX_OPSLIMIT + 1, 16 << 20),
+        (32768, MAX_MEMLIMIT + 1),
+        (u64::MAX, u64::MAX),
+    ] {
+        assert_eq!(
+            SecretKey::decode(&with_limits(opslimit, memlimit), Some(PASSWORD)).unwrap_err(),
+            Error::KdfLimitExceeded
+        );
+    }
+}
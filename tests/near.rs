@@ -0,0 +1,76 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{near::Error, Keypair, SigningKey, VerificationKeyBytes};
+
+/// The keys for the seed `00 01 .. 1f`.
+const PUBLIC_KEY: &str = "ed25519:FAe4sisG95oZ42w7buUn5qEE4TAnfTTFPiguZUHmhiF";
+const SECRET_KEY: &str = "ed25519:1GMkH3brNXiNNs1tiFZHu4yZSRrzJwxi5wB9bHFtMikjwpAW9DMZzU2Pqakc5it8X3N5vPmqdN7KF4CCUpmKhq";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn public_keys() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    assert_eq!(vk_bytes.to_near_public_key(), PUBLIC_KEY);
+    assert_eq!(
+        VerificationKeyBytes::from_near_public_key(PUBLIC_KEY),
+        Ok(vk_bytes)
+    );
+    // Keys without a type prefix are Ed25519 keys.
+    assert_eq!(
+        VerificationKeyBytes::from_near_public_key(&PUBLIC_KEY["ed25519:".len()..]),
+        Ok(vk_bytes)
+    );
+}
+
+#[test]
+fn secret_keys() {
+    let keypair = Keypair::from(signing_key());
+    assert_eq!(&keypair.to_near_secret_key()[..], SECRET_KEY);
+    let decoded = Keypair::from_near_secret_key(SECRET_KEY).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+
+    let keypair = Keypair::new(thread_rng());
+    let decoded = Keypair::from_near_secret_key(&keypair.to_near_secret_key()).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+}
+
+#[test]
+fn malformed_keys() {
+    assert_eq!(
+        VerificationKeyBytes::from_near_public_key(
+            "secp256k1:qMoRgcoXai4mBPsdbHi1wfyxF9TdbPCF4qSDQTRP3TfescSRoUdSx6nmeQoN3aiwGzwMyGXAb1gUjBTv5AY8DXj"
+        ),
+        Err(Error::UnsupportedKeyType("secp256k1".to_string()))
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_near_public_key("ed25519:0OIl"),
+        Err(Error::InvalidBase58)
+    );
+    // A secret key is not a public key, and vice versa.
+    assert_eq!(
+        VerificationKeyBytes::from_near_public_key(SECRET_KEY),
+        Err(Error::MalformedKey)
+    );
+    assert_eq!(
+        Keypair::from_near_secret_key(PUBLIC_KEY).err(),
+        Some(Error::MalformedKey)
+    );
+
+    // The seed `00 01 .. 1f` with an all-zero public key.
+    let mismatched = "ed25519:1GMkH3brNXiNNs1tiFZHu4yZSRrzJwxi5wB9bHFtMikVnB6dRM6RufULoee1BF73gp92UDzBAurvXNHe1Xzczb";
+    assert!(matches!(
+        Keypair::from_near_secret_key(mismatched),
+        Err(Error::InconsistentKeypair(_))
+    ));
+}
@@ -0,0 +1,122 @@
+use std::convert::TryFrom;
+
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    onion::Error, ExpandedSigningKey, SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The seed `00 01 .. 1f`, with its public key, expanded key, and onion
+/// address.
+const SEED: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const PUBLIC_KEY: &str = "03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8";
+const EXPANDED_KEY: &str = "3894eea49c580aef816935762be049559d6d1440dede12e6a125f1841fff8e6f\
+                            a9d71862a3e5746b571be3d187b0041046f52ebd850c7cbd5fde8ee38473b649";
+const ADDRESS: &str = "aoqqpp7tzyil4hlq3umoos6atft6jvrqtosq2xy53sdgiesvgg4bqead.onion";
+
+/// The onion service of the Tor Project's website.
+const TORPROJECT: &str = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion";
+
+fn signing_key() -> SigningKey {
+    SigningKey::try_from(&hex::decode(SEED).unwrap()[..]).unwrap()
+}
+
+#[test]
+fn onion_address_vector() {
+    let vk = VerificationKey::from(&signing_key());
+    assert_eq!(hex::encode(<[u8; 32]>::from(vk)), PUBLIC_KEY);
+    assert_eq!(vk.to_onion_address(), ADDRESS);
+    assert_eq!(
+        VerificationKey::from_onion_address(ADDRESS).map(VerificationKeyBytes::from),
+        Ok(vk.into())
+    );
+
+    let vk = VerificationKey::from_onion_address(TORPROJECT).unwrap();
+    assert_eq!(vk.to_onion_address(), TORPROJECT);
+}
+
+#[test]
+fn onion_address_parsing() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    let label = &ADDRESS[..56];
+    for address in &[
+        label.to_string(),
+        ADDRESS.to_uppercase(),
+        format!("www.{}", ADDRESS),
+    ] {
+        assert_eq!(
+            VerificationKeyBytes::from_onion_address(address),
+            Ok(vk_bytes)
+        );
+    }
+
+    assert_eq!(
+        VerificationKeyBytes::from_onion_address(&ADDRESS[1..]),
+        Err(Error::MalformedAddress)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_onion_address(&format!("1{}", &ADDRESS[1..])),
+        Err(Error::MalformedAddress)
+    );
+    // A v2 onion address is only 16 characters.
+    assert_eq!(
+        VerificationKeyBytes::from_onion_address("expyuzz4wqqyqhjn.onion"),
+        Err(Error::MalformedAddress)
+    );
+    // The last character holds the low bits of the version byte.
+    assert_eq!(
+        VerificationKeyBytes::from_onion_address(&format!("{}b.onion", &ADDRESS[..55])),
+        Err(Error::UnsupportedVersion(0x01))
+    );
+    // Corrupt the first character of the checksum.
+    let mut corrupted = ADDRESS.to_string();
+    corrupted.replace_range(52..53, "a");
+    assert_ne!(corrupted, ADDRESS);
+    assert_eq!(
+        VerificationKeyBytes::from_onion_address(&corrupted),
+        Err(Error::InvalidChecksum)
+    );
+}
+
+#[test]
+fn tor_secret_key_file() {
+    let esk = ExpandedSigningKey::from(&signing_key());
+    let file = esk.to_tor_secret_key_file();
+    assert_eq!(file.len(), 96);
+    assert_eq!(&file[..32], &b"== ed25519v1-secret: type0 ==\0\0\0"[..]);
+    assert_eq!(hex::encode(&file[32..]), EXPANDED_KEY);
+
+    let decoded = ExpandedSigningKey::from_tor_secret_key_file(&file).unwrap();
+    let vk = VerificationKey::from(&decoded);
+    assert_eq!(vk.to_onion_address(), ADDRESS);
+
+    let msg = b"onion service descriptor";
+    assert_eq!(decoded.sign(msg), esk.sign(msg));
+    assert!(vk.verify(&decoded.sign(msg), msg).is_ok());
+
+    assert_eq!(
+        ExpandedSigningKey::from_tor_secret_key_file(&file[..95]).err(),
+        Some(Error::MalformedKeyFile)
+    );
+    let mut public_header = file.to_vec();
+    public_header[..32].copy_from_slice(b"== ed25519v1-public: type0 ==\0\0\0");
+    assert_eq!(
+        ExpandedSigningKey::from_tor_secret_key_file(&public_header).err(),
+        Some(Error::MalformedKeyFile)
+    );
+}
+
+#[test]
+fn tor_public_key_file() {
+    let vk_bytes = VerificationKeyBytes::from(&SigningKey::new(thread_rng()));
+    let file = vk_bytes.to_tor_public_key_file();
+    assert_eq!(&file[..32], &b"== ed25519v1-public: type0 ==\0\0\0"[..]);
+    assert_eq!(
+        VerificationKeyBytes::from_tor_public_key_file(&file),
+        Ok(vk_bytes)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_tor_public_key_file(&file[1..]),
+        Err(Error::MalformedKeyFile)
+    );
+}
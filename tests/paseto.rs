@@ -0,0 +1,114 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    paseto::{decode_footer, Error},
+    SigningKey, VerificationKey,
+};
+
+// The `v4.public` test vectors of the PASETO specification.
+
+const SEED: &str = "b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a3774";
+const VK: &str = "1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2";
+const MESSAGE: &[u8] = br#"{"data":"this is a signed message","exp":"2022-01-01T00:00:00+00:00"}"#;
+const FOOTER: &[u8] = br#"{"kid":"zVhMiPBP9fRf2snEcT7gFTioeA9COcNy9DfgL1W60haN"}"#;
+const IMPLICIT_ASSERTION: &[u8] = br#"{"test-vector":"4-S-3"}"#;
+
+/// 4-S-1: no footer or implicit assertion.
+const TOKEN_1: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA";
+/// 4-S-2: a footer.
+const TOKEN_2: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9v3Jt8mx_TdM2ceTGoqwrh4yDFn0XsHvvV_D0DtwQxVrJEBMl0F2caAdgnpKlt4p7xBnx1HcO-SPo8FPp214HDw.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9";
+/// 4-S-3: a footer and an implicit assertion.
+const TOKEN_3: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9NPWciuD3d0o5eXJXG5pJy-DiVEoyPYWs1YSTwWHNJq6DZD3je5gf-0M4JR9ipdUSJbIovzmBECeaWmaqcaP0DQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    hex::decode_to_slice(SEED, &mut seed).unwrap();
+    SigningKey::from(seed)
+}
+
+#[test]
+fn specification_vectors() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+    assert_eq!(hex::encode(<[u8; 32]>::from(vk)), VK);
+
+    assert_eq!(sk.sign_paseto(MESSAGE, b"", b""), TOKEN_1);
+    assert_eq!(sk.sign_paseto(MESSAGE, FOOTER, b""), TOKEN_2);
+    assert_eq!(sk.sign_paseto(MESSAGE, FOOTER, IMPLICIT_ASSERTION), TOKEN_3);
+
+    assert_eq!(
+        vk.verify_paseto(TOKEN_1, b""),
+        Ok((MESSAGE.to_vec(), Vec::new()))
+    );
+    assert_eq!(
+        vk.verify_paseto(TOKEN_2, b""),
+        Ok((MESSAGE.to_vec(), FOOTER.to_vec()))
+    );
+    assert_eq!(
+        vk.verify_paseto(TOKEN_3, IMPLICIT_ASSERTION),
+        Ok((MESSAGE.to_vec(), FOOTER.to_vec()))
+    );
+    assert_eq!(decode_footer(TOKEN_3), Ok(FOOTER.to_vec()));
+}
+
+#[test]
+fn authenticated_inputs() {
+    let vk = VerificationKey::from(&signing_key());
+
+    // The implicit assertion must match.
+    assert!(matches!(
+        vk.verify_paseto(TOKEN_3, b""),
+        Err(Error::InvalidSignature(_))
+    ));
+    assert!(matches!(
+        vk.verify_paseto(TOKEN_1, IMPLICIT_ASSERTION),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    // The footer cannot be removed or replaced.
+    let (without_footer, _) = TOKEN_2.rsplit_once('.').unwrap();
+    assert!(matches!(
+        vk.verify_paseto(without_footer, b""),
+        Err(Error::InvalidSignature(_))
+    ));
+    let other_footer = format!("{}.e30", without_footer);
+    assert!(matches!(
+        vk.verify_paseto(&other_footer, b""),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    let other = VerificationKey::from(&SigningKey::new(thread_rng()));
+    assert!(matches!(
+        other.verify_paseto(TOKEN_1, b""),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn malformed_tokens() {
+    let vk = VerificationKey::from(&signing_key());
+    let body = TOKEN_1.strip_prefix("v4.public.").unwrap();
+
+    for header in &["v4.local.", "v3.public.", "v2.public.", ""] {
+        assert_eq!(
+            vk.verify_paseto(&format!("{}{}", header, body), b""),
+            Err(Error::UnsupportedHeader)
+        );
+    }
+    assert_eq!(
+        vk.verify_paseto(&format!("{}.", TOKEN_1), b""),
+        Err(Error::MalformedToken)
+    );
+    assert_eq!(
+        vk.verify_paseto(&format!("{}.e30.e30", TOKEN_1), b""),
+        Err(Error::InvalidBase64)
+    );
+    assert_eq!(
+        vk.verify_paseto(&TOKEN_1[..TOKEN_1.len() - 1], b""),
+        Err(Error::InvalidBase64)
+    );
+    assert_eq!(
+        vk.verify_paseto("v4.public.AAAA", b""),
+        Err(Error::MalformedToken)
+    );
+}
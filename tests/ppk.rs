@@ -0,0 +1,87 @@
+use ed25519_zebra::{ssh::Error, SigningKey, VerificationKeyBytes};
+
+const VK: &str = "125a5315d282f3b19ba16723c7dbba41b1a3a994c48e27adc484ccadbe45c321";
+
+// These files hold the key from `tests/openssh.rs`, and were constructed
+// following appendix C of the PuTTY manual.
+
+const PPK_V2: &str = "PuTTY-User-Key-File-2: ssh-ed25519
+Encryption: none
+Comment: ppk@v2
+Public-Lines: 2
+AAAAC3NzaC1lZDI1NTE5AAAAIBJaUxXSgvOxm6FnI8fbukGxo6mUxI4nrcSEzK2+
+RcMh
+Private-Lines: 1
+AAAAIHwdkuinlwaaXzNPh0QrsX9QnvO7h3SNI4VnCLpXFNI3
+Private-MAC: 51aac6a21375a21ca884e58ddab5f7444b86ac59
+";
+
+const PPK_V3: &str = "PuTTY-User-Key-File-3: ssh-ed25519\r
+Encryption: none\r
+Comment: ppk@v3\r
+Public-Lines: 2\r
+AAAAC3NzaC1lZDI1NTE5AAAAIBJaUxXSgvOxm6FnI8fbukGxo6mUxI4nrcSEzK2+\r
+RcMh\r
+Private-Lines: 1\r
+AAAAIHwdkuinlwaaXzNPh0QrsX9QnvO7h3SNI4VnCLpXFNI3\r
+Private-MAC: f513cdc89cba6db1ecade889e4ed7a45a27e355a502a9053424a889a14f2fe4b\r
+";
+
+#[test]
+fn decode_unencrypted_keys() {
+    for (ppk, expected_comment) in &[(PPK_V2, "ppk@v2"), (PPK_V3, "ppk@v3")] {
+        let (sk, comment) = SigningKey::from_ppk(ppk, None).unwrap();
+        assert_eq!(VerificationKeyBytes::from(&sk).to_string(), VK);
+        assert_eq!(&comment, expected_comment);
+    }
+}
+
+#[test]
+fn decode_short_private_key() {
+    // The seed ends in a zero byte, which PuTTY omits.
+    let ppk = "PuTTY-User-Key-File-3: ssh-ed25519
+Encryption: none
+Comment: short
+Public-Lines: 2
+AAAAC3NzaC1lZDI1NTE5AAAAIBDRPAIAup+fgBhKmUal00R0dEEVMtAF0BYpw22+
+P9nD
+Private-Lines: 1
+AAAAH51hsZ3v/VpguoRK9JLsLMREScVpezJpGXA7rAMcrn8=
+Private-MAC: eb2019ca3f257905427ba26afa28cf0fc4e299b82f3dc16a7ff9f2802f7f0b01
+";
+    let (sk, _) = SigningKey::from_ppk(ppk, None).unwrap();
+    assert_eq!(
+        <[u8; 32]>::from(sk),
+        hex_seed("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f00")
+    );
+}
+
+#[test]
+fn reject_tampered_keys() {
+    let tampered = PPK_V3.replace("ppk@v3", "ppk@v4");
+    assert_eq!(
+        SigningKey::from_ppk(&tampered, None).unwrap_err(),
+        Error::Malformed("invalid Private-MAC")
+    );
+
+    let v1 = PPK_V2.replace("File-2", "File-1");
+    assert_eq!(
+        SigningKey::from_ppk(&v1, None).unwrap_err(),
+        Error::UnsupportedVersion(1)
+    );
+
+    let reordered = PPK_V2.replace(
+        "Encryption: none\nComment: ppk@v2",
+        "Comment: ppk@v2\nEncryption: none",
+    );
+    assert_eq!(
+        SigningKey::from_ppk(&reordered, None).unwrap_err(),
+        Error::Malformed("missing or out-of-order PPK header")
+    );
+}
+
+fn hex_seed(seed: &str) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(seed, &mut bytes[..]).unwrap();
+    bytes
+}
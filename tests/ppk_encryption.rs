@@ -0,0 +1,81 @@
+use ed25519_zebra::{ssh::Error, SigningKey, VerificationKeyBytes};
+
+const PASSPHRASE: &[u8] = b"zebra";
+const VK: &str = "125a5315d282f3b19ba16723c7dbba41b1a3a994c48e27adc484ccadbe45c321";
+
+// These files hold the key from `tests/openssh.rs`, and were constructed
+// following appendix C of the PuTTY manual.
+
+const PPK_V2_ENCRYPTED: &str = "PuTTY-User-Key-File-2: ssh-ed25519
+Encryption: aes256-cbc
+Comment: ppk@v2-enc
+Public-Lines: 2
+AAAAC3NzaC1lZDI1NTE5AAAAIBJaUxXSgvOxm6FnI8fbukGxo6mUxI4nrcSEzK2+
+RcMh
+Private-Lines: 1
+q2gTzAfL4iY8ZFo7nEe5d7VZPTv5S32u7/4I/3ch9xEhUL8j0E/78su0ynumpzaH
+Private-MAC: 0f1c20004f709914ebca31f58694690690928747
+";
+
+const PPK_V3_ENCRYPTED: &str = "PuTTY-User-Key-File-3: ssh-ed25519
+Encryption: aes256-cbc
+Comment: ppk@v3-enc
+Public-Lines: 2
+AAAAC3NzaC1lZDI1NTE5AAAAIBJaUxXSgvOxm6FnI8fbukGxo6mUxI4nrcSEzK2+
+RcMh
+Key-Derivation: Argon2id
+Argon2-Memory: 8192
+Argon2-Passes: 8
+Argon2-Parallelism: 1
+Argon2-Salt: 000102030405060708090a0b0c0d0e0f
+Private-Lines: 1
+ahqKChTA73CdQQoH/TSTi2xQycQyb3LX3sXmLczzrxGFYWXJh+pztaRO7a9FocbY
+Private-MAC: 2c2c981a3419ba8c34f75ed75bbb94f84fc89766f985111ff99efb0820bb15c6
+";
+
+#[test]
+fn decrypt_keys() {
+    for (ppk, expected_comment) in &[
+        (PPK_V2_ENCRYPTED, "ppk@v2-enc"),
+        (PPK_V3_ENCRYPTED, "ppk@v3-enc"),
+    ] {
+        let (sk, comment) = SigningKey::from_ppk(ppk, Some(PASSPHRASE)).unwrap();
+        assert_eq!(VerificationKeyBytes::from(&sk).to_string(), VK);
+        assert_eq!(&comment, expected_comment);
+    }
+}
+
+#[test]
+fn passphrase_errors() {
+    for ppk in &[PPK_V2_ENCRYPTED, PPK_V3_ENCRYPTED] {
+        assert_eq!(
+            SigningKey::from_ppk(ppk, None).unwrap_err(),
+            Error::PassphraseRequired
+        );
+        assert_eq!(
+            SigningKey::from_ppk(ppk, Some(b"zebro")).unwrap_err(),
+            Error::IncorrectPassphrase
+        );
+    }
+}
+
+#[test]
+fn argon2_limits() {
+    use ed25519_zebra::ppk::{MAX_ARGON2_MEMORY, MAX_ARGON2_PASSES};
+
+    let memory = PPK_V3_ENCRYPTED.replace(
+        "Argon2-Memory: 8192",
+        &format!("Argon2-Memory: {}", MAX_ARGON2_MEMORY + 1),
+    );
+    let passes = PPK_V3_ENCRYPTED.replace(
+        "Argon2-Passes: 8",
+        &format!("Argon2-Passes: {}", MAX_ARGON2_PASSES + 1),
+    );
+    for ppk in &[memory, passes] {
+        assert_ne!(ppk, PPK_V3_ENCRYPTED);
+        assert_eq!(
+            SigningKey::from_ppk(ppk, Some(PASSPHRASE)).unwrap_err(),
+            Error::KdfLimitExceeded
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    saltpack::{self, Error},
+    SigningKey, VerificationKeyBytes,
+};
+
+/// `hello, world` signed by the seed `00 01 .. 1f` with the nonce `40 41 ..
+/// 5f`.
+const ATTACHED: &str = "c45295a873616c747061636b92020001c42003a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8c420404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f93c3c44085a495db736054523eaec3fbda9bcb8ae7b88ff10e91a8f2742844a93136e5ed23711530ddd343e7a88cb102c111ce59d1b49b25583570fe333a497a8075380fc40c68656c6c6f2c20776f726c64";
+/// `ATTACHED`, armored.
+const ATTACHED_ARMORED: &str = "BEGIN SALTPACK SIGNED MESSAGE. kYM5h1pg6qz9UMn j6G9T0lmMdZkBDO SHuEMpI6IPFQhjf c8eGIKN11NWp42p yudt7QgDARD0e2J MmAUbYeL5QJI5nb Fp5TzCXpwY3dWRp FpyjtGGrSLlLGY8 vXWOzBytOErZBqz Po6qPjV6owA1auq B0eRmhBR84XVRVw AZRT73fdy27ywQf Hs7662t6t5PmVXZ DboxFHPrJpQGguL UIWrc0A8TFNAm4. END SALTPACK SIGNED MESSAGE.";
+/// A detached signature of `hello, world` by the seed `00 01 .. 1f` with
+/// the nonce `40 41 .. 5f`.
+const DETACHED: &str = "c45295a873616c747061636b92020002c42003a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8c420404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5fc440c1a9d661e342b00c73dcb04123ab3c6bc97cd54d9543fa9c8984abbe1687afebe2f25d1e136b3a12b6272f299536b45b80454d5a7c58ae3443c791055fd6ce0d";
+/// `DETACHED`, armored.
+const DETACHED_ARMORED: &str = "BEGIN SALTPACK DETACHED SIGNATURE. kYM5h1pg6qz9UMn j6G9T0tZQfn0XIs 83eO9y4zPex2pjf c8eGIKN11NWp42p yudt7QgDARD0e2J MmAUbYeL5QJI5nb Fp5TzCXpwY3dWRp FpyjtGGrlrmYZXy zd59A0G5cfnJMGd DlwJka6AKOEsOob F9w0EGVioPWhDy6 AKts6Lv1qj79kX7 bEYByBtqm80lndQ sGbqqrt. END SALTPACK DETACHED SIGNATURE.";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn attached_vectors() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    let expected = Ok((vk_bytes, b"hello, world".to_vec()));
+    assert_eq!(
+        saltpack::verify_attached(&hex::decode(ATTACHED).unwrap()),
+        expected
+    );
+    assert_eq!(
+        saltpack::verify_attached_armored(ATTACHED_ARMORED),
+        expected
+    );
+
+    // Brands, line breaks, and `>` quoting are ignored.
+    let branded = ATTACHED_ARMORED
+        .replace("SALTPACK", "KEYBASE SALTPACK")
+        .replace("UMn j6G", "UMn\n> j6G")
+        .replace("Bqz Po6", "Bq\n> z Po6");
+    assert_eq!(saltpack::verify_attached_armored(&branded), expected);
+}
+
+#[test]
+fn detached_vectors() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    let signature = hex::decode(DETACHED).unwrap();
+    assert_eq!(
+        saltpack::verify_detached(&signature, b"hello, world"),
+        Ok(vk_bytes)
+    );
+    assert_eq!(
+        saltpack::verify_detached_armored(DETACHED_ARMORED, b"hello, world"),
+        Ok(vk_bytes)
+    );
+    assert!(matches!(
+        saltpack::verify_detached(&signature, b"goodbye"),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn round_trips() {
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    for msg in [&b""[..], b"hello, world", &vec![0x5a; (1 << 20) + 1][..]] {
+        let signed = saltpack::sign_attached(&sk, msg, thread_rng());
+        assert_eq!(
+            saltpack::verify_attached(&signed),
+            Ok((vk_bytes, msg.to_vec()))
+        );
+        let armored = saltpack::sign_attached_armored(&sk, msg, thread_rng());
+        assert_eq!(
+            saltpack::verify_attached_armored(&armored),
+            Ok((vk_bytes, msg.to_vec()))
+        );
+
+        let signature = saltpack::sign_detached(&sk, msg, thread_rng());
+        assert_eq!(saltpack::verify_detached(&signature, msg), Ok(vk_bytes));
+        let armored = saltpack::sign_detached_armored(&sk, msg, thread_rng());
+        assert_eq!(
+            saltpack::verify_detached_armored(&armored, msg),
+            Ok(vk_bytes)
+        );
+    }
+}
+
+#[test]
+fn armor_layout() {
+    let armored = saltpack::sign_attached_armored(&signing_key(), &[0; 10000], thread_rng());
+    let body = armored
+        .strip_prefix("BEGIN SALTPACK SIGNED MESSAGE. ")
+        .and_then(|armored| armored.strip_suffix(". END SALTPACK SIGNED MESSAGE."))
+        .unwrap();
+    let lines: Vec<&str> = body.lines().collect();
+    assert!(lines.len() > 1);
+    for line in &lines[..lines.len() - 1] {
+        assert_eq!(line.split(' ').count(), 200);
+    }
+    assert!(body.split_whitespace().all(|word| word.len() <= 15));
+}
+
+#[test]
+fn truncated_messages() {
+    let sk = signing_key();
+    let msg = vec![0x5a; (1 << 20) + 1];
+    let signed = saltpack::sign_attached(&sk, &msg, thread_rng());
+    // The header packet and the first payload packet, without the final one.
+    let first_packet_end = 0x54 + 2 + 66 + 5 + (1 << 20);
+    assert_eq!(
+        saltpack::verify_attached(&signed[..first_packet_end]),
+        Err(Error::MalformedMessage)
+    );
+
+    let mut extended = hex::decode(ATTACHED).unwrap();
+    extended.push(0xc0);
+    assert_eq!(
+        saltpack::verify_attached(&extended),
+        Err(Error::MalformedMessage)
+    );
+}
+
+#[test]
+fn malformed_messages() {
+    let attached = hex::decode(ATTACHED).unwrap();
+    let detached = hex::decode(DETACHED).unwrap();
+    assert_eq!(
+        saltpack::verify_attached(&detached),
+        Err(Error::UnexpectedMode(2))
+    );
+    assert_eq!(
+        saltpack::verify_detached(&attached, b"hello, world"),
+        Err(Error::UnexpectedMode(1))
+    );
+
+    let mut v1 = attached.clone();
+    v1[13] = 0x01;
+    assert_eq!(
+        saltpack::verify_attached(&v1),
+        Err(Error::UnsupportedVersion(1))
+    );
+
+    let mut renamed = attached.clone();
+    renamed[4] = b'S';
+    assert_eq!(
+        saltpack::verify_attached(&renamed),
+        Err(Error::MalformedMessage)
+    );
+
+    // The final flag is signed.
+    let mut not_final = attached.clone();
+    not_final[0x55] = 0xc2;
+    assert!(matches!(
+        saltpack::verify_attached(&not_final),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    let mut tampered = attached;
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        saltpack::verify_attached(&tampered),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn malformed_armor() {
+    for malformed in [
+        ATTACHED_ARMORED.replace("BEGIN SALTPACK", "BEGIN SALT PACK"),
+        ATTACHED_ARMORED.replace("END SALTPACK", "END KEYBASE SALTPACK"),
+        ATTACHED_ARMORED.replace("SIGNED MESSAGE", "DETACHED SIGNATURE"),
+        ATTACHED_ARMORED.replace("kYM5h1", "kYM5h_"),
+        ATTACHED_ARMORED.replace("NAm4.", "NA4."),
+        ATTACHED_ARMORED.replacen(". END", ". X. END", 1),
+    ] {
+        assert_eq!(
+            saltpack::verify_attached_armored(&malformed),
+            Err(Error::MalformedArmor),
+            "{}",
+            malformed
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::convert::TryFrom;
+
+use rand::{thread_rng, RngCore};
+use secrecy::{ExposeSecret, SecretBox};
+
+use ed25519_zebra::{SecretSigningKey, SigningKey, VerificationKey, VerificationKeyBytes};
+
+#[test]
+fn secret_signing_key() {
+    let mut seed = [0u8; 32];
+    thread_rng().fill_bytes(&mut seed[..]);
+    let sk = SigningKey::from(seed);
+    let secret_seed = SecretBox::new(Box::new(seed));
+
+    assert_eq!(SigningKey::from(&secret_seed), sk);
+    assert_eq!(
+        SigningKey::try_from(&SecretBox::new(seed.to_vec().into_boxed_slice())),
+        Ok(sk.clone())
+    );
+
+    let secret_sk = SecretSigningKey::from(secret_seed);
+    let msg = b"ed25519-zebra secrecy";
+    let sig = secret_sk.sign(&msg[..]);
+    assert_eq!(sig, sk.sign(&msg[..]));
+    assert_eq!(
+        VerificationKeyBytes::from(&secret_sk),
+        VerificationKeyBytes::from(&sk)
+    );
+    assert_eq!(
+        VerificationKey::from(&secret_sk).verify(&sig, &msg[..]),
+        Ok(())
+    );
+    assert_eq!(secret_sk.expose_secret(), &sk);
+
+    let debug = format!("{:?}", secret_sk);
+    assert!(!debug.contains(&hex::encode(seed)));
+}
@@ -0,0 +1,38 @@
+use rand::thread_rng;
+
+use ed25519::signature::{Signer, Verifier};
+use ed25519_zebra::{SigningKey, VerificationKey};
+
+#[test]
+fn signer_and_verifier_traits() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra signature traits";
+
+    let sig: ed25519::Signature = Signer::sign(&sk, &msg[..]);
+    assert_eq!(<[u8; 64]>::from(sk.sign(msg)), sig.to_bytes());
+    assert!(Verifier::verify(&vk, &msg[..], &sig).is_ok());
+    assert!(Verifier::verify(&vk, b"wrong message", &sig).is_err());
+}
+
+#[test]
+fn digest_signer_and_verifier_traits() {
+    use ed25519::signature::{DigestSigner, DigestVerifier};
+    use sha2::{Digest, Sha512};
+
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let digest = Sha512::default()
+        .chain(b"streamed ")
+        .chain(b"message bytes");
+
+    let sig: ed25519::Signature = sk.sign_digest(digest.clone());
+    assert_eq!(
+        <[u8; 64]>::from(sk.sign_prehashed(digest.clone(), None).unwrap()),
+        sig.to_bytes()
+    );
+    assert!(vk.verify_digest(digest, &sig).is_ok());
+    assert!(vk
+        .verify_digest(Sha512::default().chain(b"other bytes"), &sig)
+        .is_err());
+}
@@ -0,0 +1,118 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    signify::{Error, PublicKey, SecretKey, SignifySignature},
+    VerificationKeyBytes,
+};
+
+const VK: &str = "2543b92ff1095511476adc8369db6ddc933665a11978dda1404ee1066ca9559d";
+const KEY_ID: [u8; 8] = [0xa1, 0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0x07, 0x18];
+
+const PUBLIC_KEY: &str = "untrusted comment: signify public key
+RWShssPU5fYHGCVDuS/xCVURR2rcg2nbbdyTNmWhGXjdoUBO4QZsqVWd
+";
+
+/// An unencrypted secret key, as written by `signify -G -n`, for the seed
+/// `40 41 .. 5f`.
+const SECRET_KEY: &str = "untrusted comment: signify secret key
+RWRCSwAAAAAAAAAAAAAAAAAAAAAAAAAALXIaBHh0oFmhssPU5fYHGEBBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5fJUO5L/EJVRFHatyDadtt3JM2ZaEZeN2hQE7hBmypVZ0=
+";
+
+const MESSAGE: &[u8] = b"SHA256 (bsd.rd) = 0123456789abcdef\n";
+
+const SIGNATURE: &str = "untrusted comment: verify with signify.pub
+RWShssPU5fYHGCMCNSUHZc3cSUJvey3I1y9EdzMAXC98p9iJAp3Ms9S1xoimeGUgcaaTj48YdVutqFBgxgQz51xxb/pAR9r5hAg=
+";
+
+#[test]
+fn key_files() {
+    let pk = PublicKey::decode(PUBLIC_KEY).unwrap();
+    assert_eq!(pk.key_id, KEY_ID);
+    assert_eq!(
+        VerificationKeyBytes::from(pk.verification_key).to_string(),
+        VK
+    );
+    assert_eq!(pk.encode("signify public key").unwrap(), PUBLIC_KEY);
+
+    let sk = SecretKey::decode(SECRET_KEY, None).unwrap();
+    assert_eq!(sk.key_id, KEY_ID);
+    assert_eq!(sk.public_key().to_base64(), pk.to_base64());
+    assert_eq!(&*sk.encode("signify secret key").unwrap(), SECRET_KEY);
+}
+
+#[test]
+fn signature_vector() {
+    let pk = PublicKey::decode(PUBLIC_KEY).unwrap();
+    let sk = SecretKey::decode(SECRET_KEY, None).unwrap();
+
+    let signature = SignifySignature::decode(SIGNATURE).unwrap();
+    assert_eq!(signature.key_id(), KEY_ID);
+    assert_eq!(signature.untrusted_comment, "verify with signify.pub");
+    assert_eq!(signature.verify(&pk, MESSAGE), Ok(()));
+    assert!(matches!(
+        signature.verify(&pk, b"tampered"),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    let mut signed = sk.sign(MESSAGE);
+    signed.untrusted_comment = "verify with signify.pub".to_string();
+    assert_eq!(signed, signature);
+    assert_eq!(signed.encode().unwrap(), SIGNATURE);
+}
+
+#[test]
+fn embedded_signature() {
+    let pk = PublicKey::decode(PUBLIC_KEY).unwrap();
+    let mut file = SIGNATURE.as_bytes().to_vec();
+    file.extend_from_slice(MESSAGE);
+
+    let (signature, message) = SignifySignature::decode_embedded(&file).unwrap();
+    assert_eq!(message, MESSAGE);
+    assert_eq!(signature.verify(&pk, message), Ok(()));
+    assert_eq!(signature.encode_embedded(MESSAGE).unwrap(), file);
+
+    assert_eq!(
+        SignifySignature::decode_embedded(&file[..40]).unwrap_err(),
+        Error::Malformed("missing signature")
+    );
+}
+
+#[test]
+fn sign_and_verify() {
+    let sk = SecretKey::new(thread_rng());
+    let pk = PublicKey::decode(&sk.public_key().encode("key.pub").unwrap()).unwrap();
+    let sk = SecretKey::decode(&sk.encode("key.sec").unwrap(), None).unwrap();
+
+    let signature = SignifySignature::decode(&sk.sign(MESSAGE).encode().unwrap()).unwrap();
+    assert_eq!(signature.verify(&pk, MESSAGE), Ok(()));
+
+    let other = SecretKey::new(thread_rng()).public_key();
+    assert_eq!(signature.verify(&other, MESSAGE), Err(Error::KeyIdMismatch));
+
+    assert_eq!(
+        sk.public_key().encode("two\nlines").unwrap_err(),
+        Error::InvalidComment
+    );
+}
+
+#[test]
+fn malformed_files() {
+    assert_eq!(
+        PublicKey::decode("RWShssPU5fYHGCVDuS/xCVURR2rcg2nbbdyTNmWhGXjdoUBO4QZsqVWd\n")
+            .unwrap_err(),
+        Error::Malformed("missing untrusted comment")
+    );
+    assert_eq!(
+        PublicKey::decode(&format!("{}extra\n", PUBLIC_KEY)).unwrap_err(),
+        Error::Malformed("trailing data")
+    );
+    assert_eq!(
+        SignifySignature::decode(&SIGNATURE.replace("RWShss", "RWSh!s")).unwrap_err(),
+        Error::InvalidBase64
+    );
+    // A secret key with its checksum corrupted.
+    assert_eq!(
+        SecretKey::decode(&SECRET_KEY.replace("LXIa", "LXIb"), None).unwrap_err(),
+        Error::Malformed("checksum mismatch")
+    );
+}
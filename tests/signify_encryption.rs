@@ -0,0 +1,74 @@
+use rand::thread_rng;
+
+use ed25519_zebra::signify::{Error, SecretKey};
+
+const PASSPHRASE: &[u8] = b"signify";
+const KEY_ID: [u8; 8] = [0xa1, 0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0x07, 0x18];
+const PUBLIC_KEY: &str = "RWShssPU5fYHGCVDuS/xCVURR2rcg2nbbdyTNmWhGXjdoUBO4QZsqVWd";
+
+/// The key of `tests/signify.rs`, encrypted under `PASSPHRASE` with 42
+/// rounds of bcrypt-pbkdf, as by `signify -G`.
+const ENCRYPTED_SECRET_KEY: &str = "untrusted comment: signify secret key
+RWRCSwAAACrAwcLDxMXGx8jJysvMzc7PLXIaBHh0oFmhssPU5fYHGDrOzK+JVO4P4GOAaRY5H2ZJ1RJzQs0UBe4tqys30WO/qeCdxcqDGyk5WmvKC6isq+pV7YmcgACvSibvf5UOu7Y=
+";
+
+#[test]
+fn decrypt_secret_key() {
+    let sk = SecretKey::decode(ENCRYPTED_SECRET_KEY, Some(PASSPHRASE)).unwrap();
+    assert_eq!(sk.key_id, KEY_ID);
+    assert_eq!(sk.public_key().to_base64(), PUBLIC_KEY);
+}
+
+#[test]
+fn encrypted_round_trip() {
+    let sk = SecretKey::new(thread_rng());
+    let encrypted = sk
+        .encode_encrypted(thread_rng(), "signify secret key", PASSPHRASE)
+        .unwrap();
+    let decrypted = SecretKey::decode(&encrypted, Some(PASSPHRASE)).unwrap();
+    assert_eq!(decrypted.key_id, sk.key_id);
+    assert_eq!(
+        decrypted.public_key().to_base64(),
+        sk.public_key().to_base64()
+    );
+}
+
+#[test]
+fn passphrase_errors() {
+    assert_eq!(
+        SecretKey::decode(ENCRYPTED_SECRET_KEY, None).unwrap_err(),
+        Error::PassphraseRequired
+    );
+    assert_eq!(
+        SecretKey::decode(ENCRYPTED_SECRET_KEY, Some(b"signifu")).unwrap_err(),
+        Error::IncorrectPassphrase
+    );
+    let sk = SecretKey::new(thread_rng());
+    assert_eq!(
+        sk.encode_encrypted(thread_rng(), "", b"").unwrap_err(),
+        Error::PassphraseRequired
+    );
+}
+
+#[test]
+fn kdf_rounds_limit() {
+    use base64ct::{Base64, Encoding};
+    use ed25519_zebra::signify::MAX_KDF_ROUNDS;
+
+    // The rounds count follows the public key and KDF algorithms.
+    let with_rounds = |rounds: u32| {
+        let mut lines = ENCRYPTED_SECRET_KEY.lines();
+        let comment = lines.next().unwrap();
+        let mut bytes = Base64::decode_vec(lines.next().unwrap()).unwrap();
+        bytes[4..8].copy_from_slice(&rounds.to_be_bytes());
+        format!("{}\n{}\n", comment, Base64::encode_string(&bytes))
+    };
+
+    assert_eq!(with_rounds(42), ENCRYPTED_SECRET_KEY);
+    for rounds in [MAX_KDF_ROUNDS + 1, u32::MAX] {
+        assert_eq!(
+            SecretKey::decode(&with_rounds(rounds), Some(PASSPHRASE)).unwrap_err(),
+            Error::KdfLimitExceeded
+        );
+    }
+}
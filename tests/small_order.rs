@@ -55,6 +55,7 @@ pub static SMALL_ORDER_SIGS: Lazy<Vec<TestCase>> = Lazy::new(|| {
             );
             let check = R + k * A;
             let non_canonical_R = R.compress().as_bytes() != R_bytes;
+            #[allow(clippy::needless_bool)]
             let valid_legacy = if vk_bytes == [0; 32]
                 || util::EXCLUDED_POINT_ENCODINGS.contains(R.compress().as_bytes())
                 || !check.is_identity()
@@ -102,3 +103,36 @@ fn individual_matches_batch_verification() -> Result<(), Report> {
     }
     Ok(())
 }
+
+#[test]
+fn strict_verification_rejects_small_order_points() {
+    use ed25519_zebra::{Signature, VerificationKey};
+    use std::convert::TryFrom;
+    for case in SMALL_ORDER_SIGS.iter() {
+        let sig = Signature::from(case.sig_bytes);
+        if let Ok(vk) = VerificationKey::try_from(case.vk_bytes) {
+            assert!(vk.verify(&sig, b"Zcash").is_ok());
+            assert!(vk.verify_strict(&sig, b"Zcash").is_err());
+        }
+    }
+}
+
+#[test]
+fn small_order_keys_are_weak() {
+    use ed25519_zebra::{SigningKey, VerificationKeyBytes};
+    for case in SMALL_ORDER_SIGS.iter() {
+        assert!(VerificationKeyBytes::from(case.vk_bytes).is_weak());
+    }
+    let sk = SigningKey::new(rand::thread_rng());
+    assert!(!VerificationKeyBytes::from(&sk).is_weak());
+}
+
+#[test]
+fn verify_once_matches_verify() {
+    use ed25519_zebra::{Signature, VerificationKeyBytes};
+    for case in SMALL_ORDER_SIGS.iter() {
+        let sig = Signature::from(case.sig_bytes);
+        let vkb = VerificationKeyBytes::from(case.vk_bytes);
+        assert_eq!(vkb.verify_once(&sig, b"Zcash"), vkb.verify(&sig, b"Zcash"));
+    }
+}
@@ -0,0 +1,94 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{solana::Error, Keypair, SigningKey, VerificationKeyBytes};
+
+/// The keypair file for the seed `00 01 .. 1f`.
+const KEYPAIR: &str = "[0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,29,30,31,3,161,7,191,243,206,16,190,29,112,221,24,231,75,192,153,103,228,214,48,155,165,13,95,29,220,134,100,18,85,49,184]";
+const ADDRESS: &str = "FAe4sisG95oZ42w7buUn5qEE4TAnfTTFPiguZUHmhiF";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn keypair_files() {
+    let keypair = Keypair::from(signing_key());
+    assert_eq!(&keypair.to_solana_json()[..], KEYPAIR);
+
+    let decoded = Keypair::from_solana_json(KEYPAIR).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+
+    // Pretty-printed files are accepted.
+    let pretty = format!("{}\n", KEYPAIR.replace(',', ",\n  ").replace('[', "[\n  "));
+    assert!(Keypair::from_solana_json(&pretty).is_ok());
+
+    let keypair = Keypair::new(thread_rng());
+    let decoded = Keypair::from_solana_json(&keypair.to_solana_json()).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+}
+
+#[test]
+fn malformed_keypair_files() {
+    for json in [
+        "",
+        "[]",
+        "[0,1,2]",
+        &KEYPAIR.replace(",184]", "]"),
+        &KEYPAIR.replace(",184]", ",184,0]"),
+        &KEYPAIR.replace(",184]", ",256]"),
+        &KEYPAIR.replace(",184]", ",-1]"),
+        &KEYPAIR.replace(",184]", ",184,]"),
+        &KEYPAIR[1..],
+    ] {
+        assert_eq!(
+            Keypair::from_solana_json(json).err(),
+            Some(Error::MalformedKeypair),
+            "{:?}",
+            json
+        );
+    }
+
+    let inconsistent = KEYPAIR.replace(",184]", ",185]");
+    assert!(matches!(
+        Keypair::from_solana_json(&inconsistent),
+        Err(Error::InconsistentKeypair(_))
+    ));
+}
+
+#[test]
+fn addresses() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    assert_eq!(vk_bytes.to_solana_address(), ADDRESS);
+    assert_eq!(
+        VerificationKeyBytes::from_solana_address(ADDRESS),
+        Ok(vk_bytes)
+    );
+
+    // The system program address is 32 zero bytes.
+    let system_program = "11111111111111111111111111111111";
+    assert_eq!(
+        VerificationKeyBytes::from([0u8; 32]).to_solana_address(),
+        system_program
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_solana_address(system_program),
+        Ok(VerificationKeyBytes::from([0u8; 32]))
+    );
+
+    assert_eq!(
+        VerificationKeyBytes::from_solana_address("0OIl"),
+        Err(Error::InvalidBase58)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_solana_address(&ADDRESS[1..]),
+        Err(Error::MalformedAddress)
+    );
+}
@@ -0,0 +1,276 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    ssb::{self, Error},
+    Keypair, SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The feed ID of the seed `00 01 .. 1f`.
+const FEED_ID: &str = "@A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.ed25519";
+/// The secret file of the seed `00 01 .. 1f`.
+const SECRET: &str = r#"# this is your SECRET name.
+# this name gives you magical powers.
+# with it you can mark your messages so that your friends can verify
+# that they really did come from you.
+#
+# if any one learns this name, they can use it to destroy your identity
+# NEVER show this to anyone!!!
+
+{
+  "curve": "ed25519",
+  "public": "A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.ed25519",
+  "private": "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8DoQe/884Qvh1w3RjnS8CZZ+TWMJulDV8d3IZkElUxuA==.ed25519",
+  "id": "@A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.ed25519"
+}
+
+# WARNING! It's vital that you DO NOT edit OR share your secret name
+# instead, share your public name
+# your public name: @A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.ed25519
+"#;
+
+/// An unsigned message, compactly encoded.
+const UNSIGNED: &str = r#"{"previous":null,"author":"@A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.ed25519","sequence":1,"timestamp":1700000000000,"hash":"sha256","content":{"type":"post","text":"hello, world","mentions":[]}}"#;
+/// `UNSIGNED` signed by the seed `00 01 .. 1f`, as `JSON.stringify` formats
+/// it.
+const SIGNED: &str = r#"{
+  "previous": null,
+  "author": "@A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.ed25519",
+  "sequence": 1,
+  "timestamp": 1700000000000,
+  "hash": "sha256",
+  "content": {
+    "type": "post",
+    "text": "hello, world",
+    "mentions": []
+  },
+  "signature": "wEimmgNjc9BrfZUmIDhyM8bNzgMAwBL5lHUXy3b5DNb/Hd6dcL5BRvLKuB6HcZRc44mpHZHl/4vhhAIR1i1VBA==.sig.ed25519"
+}"#;
+/// The signature of `UNSIGNED` under the HMAC key `20 21 .. 3f`.
+const HMAC_SIGNATURE: &str = "lQZ/XT46w1FT79dvKc1bxiqwaClguY1X8Z84/CL0RQBPj1U/DQi0z+x9MS5CZ57pr9MBgaVclzjl9/BRah+FCA==.sig.ed25519";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+fn hmac_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key.iter_mut()
+        .enumerate()
+        .for_each(|(i, b)| *b = 0x20 + i as u8);
+    key
+}
+
+#[test]
+fn feed_ids() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    assert_eq!(vk_bytes.to_ssb_feed_id(), FEED_ID);
+    assert_eq!(
+        VerificationKeyBytes::from_ssb_feed_id(FEED_ID),
+        Ok(vk_bytes)
+    );
+
+    let vk_bytes = VerificationKeyBytes::from(&SigningKey::new(thread_rng()));
+    assert_eq!(
+        VerificationKeyBytes::from_ssb_feed_id(&vk_bytes.to_ssb_feed_id()),
+        Ok(vk_bytes)
+    );
+
+    for feed_id in [
+        &FEED_ID[1..],
+        &FEED_ID[..FEED_ID.len() - 1],
+        "@A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.sha256",
+        "@A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJV.ed25519",
+        "%A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=.ed25519",
+    ] {
+        assert_eq!(
+            VerificationKeyBytes::from_ssb_feed_id(feed_id),
+            Err(Error::MalformedFeedId),
+            "{}",
+            feed_id
+        );
+    }
+}
+
+#[test]
+fn secret_files() {
+    let keypair = Keypair::from(signing_key());
+    assert_eq!(&keypair.to_ssb_secret()[..], SECRET);
+
+    let decoded = Keypair::from_ssb_secret(SECRET).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+
+    // The comments are optional.
+    let json_only: String = SECRET
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    assert!(Keypair::from_ssb_secret(&json_only).is_ok());
+
+    let keypair = Keypair::from(SigningKey::new(thread_rng()));
+    let decoded = Keypair::from_ssb_secret(&keypair.to_ssb_secret()).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+}
+
+#[test]
+fn malformed_secret_files() {
+    for malformed in [
+        "",
+        "{}",
+        &SECRET.replace("\"curve\": \"ed25519\"", "\"curve\": \"k256\""),
+        &SECRET.replace("uA==.ed25519", "uA=="),
+        &SECRET.replace("DoQe/884Qvh1w3Rj", "DoQe"),
+    ] {
+        assert_eq!(
+            Keypair::from_ssb_secret(malformed).err(),
+            Some(Error::MalformedSecret)
+        );
+    }
+
+    let other_id = VerificationKeyBytes::from(&SigningKey::new(thread_rng())).to_ssb_feed_id();
+    for inconsistent in [
+        SECRET.replacen("\"public\": \"A", "\"public\": \"B", 1),
+        SECRET.replacen(
+            &format!("\"id\": \"{}\"", FEED_ID),
+            &format!("\"id\": \"{}\"", other_id),
+            1,
+        ),
+        // The public key half of the secret key.
+        SECRET.replacen("DoQe/884", "DoQf/884", 1),
+    ] {
+        assert_eq!(
+            Keypair::from_ssb_secret(&inconsistent).err(),
+            Some(Error::InconsistentSecret)
+        );
+    }
+}
+
+#[test]
+fn sign_and_verify() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+    let message = b"hello, world";
+
+    let signature = ssb::sign(&sk, None, message);
+    assert!(signature.ends_with(".sig.ed25519"));
+    assert_eq!(ssb::verify(&vk, None, message, &signature), Ok(()));
+    assert!(matches!(
+        ssb::verify(&vk, Some(&hmac_key()), message, &signature),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    let signature = ssb::sign(&sk, Some(&hmac_key()), message);
+    assert_eq!(
+        ssb::verify(&vk, Some(&hmac_key()), message, &signature),
+        Ok(())
+    );
+    assert!(matches!(
+        ssb::verify(&vk, None, message, &signature),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    for malformed in [&signature[..signature.len() - 12], &signature[4..]] {
+        assert_eq!(
+            ssb::verify(&vk, Some(&hmac_key()), message, malformed),
+            Err(Error::MalformedSignature)
+        );
+    }
+}
+
+#[test]
+fn messages() {
+    let sk = signing_key();
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+
+    assert_eq!(ssb::sign_message(&sk, None, UNSIGNED).unwrap(), SIGNED);
+    assert_eq!(ssb::verify_message(None, SIGNED), Ok(vk_bytes));
+
+    let signed = ssb::sign_message(&sk, Some(&hmac_key()), UNSIGNED).unwrap();
+    assert_eq!(
+        signed,
+        SIGNED.replace(
+            "wEimmgNjc9BrfZUmIDhyM8bNzgMAwBL5lHUXy3b5DNb/Hd6dcL5BRvLKuB6HcZRc44mpHZHl/4vhhAIR1i1VBA==.sig.ed25519",
+            HMAC_SIGNATURE
+        )
+    );
+    assert_eq!(
+        ssb::verify_message(Some(&hmac_key()), &signed),
+        Ok(vk_bytes)
+    );
+    assert!(matches!(
+        ssb::verify_message(None, &signed),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    // Verification depends on the order of the fields, not their layout.
+    let compact: String = SIGNED
+        .lines()
+        .map(|line| line.trim().replace("\": ", "\":"))
+        .collect();
+    assert_eq!(ssb::verify_message(None, &compact), Ok(vk_bytes));
+    let reordered = SIGNED
+        .replacen("\"previous\": null,\n  \"author\"", "\"author\"", 1)
+        .replacen("\"sequence\"", "\"previous\": null,\n  \"sequence\"", 1);
+    assert!(matches!(
+        ssb::verify_message(None, &reordered),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn message_numbers() {
+    let sk = signing_key();
+    // JavaScript writes integral floating-point numbers as integers.
+    let unsigned = UNSIGNED.replace("1700000000000", "1700000000000.0");
+    let signed = ssb::sign_message(&sk, None, &unsigned).unwrap();
+    assert_eq!(signed, SIGNED);
+
+    let unsigned = UNSIGNED.replace("1700000000000", "1700000000000.5");
+    let signed = ssb::sign_message(&sk, None, &unsigned).unwrap();
+    assert!(signed.contains("\"timestamp\": 1700000000000.5,"));
+    assert!(ssb::verify_message(None, &signed).is_ok());
+}
+
+#[test]
+fn malformed_messages() {
+    let sk = signing_key();
+
+    for malformed in [
+        "[]",
+        "{}",
+        r#"{"author":"@A6EHv.ed25519"}"#,
+        r#"{"author":1}"#,
+        SIGNED,
+    ] {
+        assert_eq!(
+            ssb::sign_message(&sk, None, malformed),
+            Err(Error::MalformedMessage),
+            "{}",
+            malformed
+        );
+    }
+    assert_eq!(
+        ssb::sign_message(&SigningKey::new(thread_rng()), None, UNSIGNED),
+        Err(Error::MismatchedAuthor)
+    );
+
+    // The signature must be the last field.
+    let misplaced = SIGNED.replacen("\"previous\": null,", "", 1).replacen(
+        "\n}",
+        ",\n  \"previous\": null\n}",
+        1,
+    );
+    for malformed in [UNSIGNED, &misplaced[..]] {
+        assert_eq!(
+            ssb::verify_message(None, malformed),
+            Err(Error::MalformedMessage)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{stellar::Error, SigningKey, VerificationKeyBytes};
+
+/// The StrKeys for the seed `00 01 .. 1f`.
+const ACCOUNT_ID: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const SECRET_SEED: &str = "SAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6NKI";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn account_ids() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    assert_eq!(vk_bytes.to_strkey(), ACCOUNT_ID);
+    assert_eq!(VerificationKeyBytes::from_strkey(ACCOUNT_ID), Ok(vk_bytes));
+
+    // The account ID example from SEP-23.
+    let example = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    let vk_bytes = VerificationKeyBytes::from_strkey(example).unwrap();
+    assert_eq!(
+        hex::encode(vk_bytes.as_ref()),
+        "3f0c34bf93ad0d9971d04ccc90f705511c838aad9734a4a2fb0d7a03fc7fe89a"
+    );
+    assert_eq!(vk_bytes.to_strkey(), example);
+}
+
+#[test]
+fn secret_seeds() {
+    let sk = signing_key();
+    assert_eq!(&sk.to_strkey()[..], SECRET_SEED);
+    let decoded = SigningKey::from_strkey(SECRET_SEED).unwrap();
+    assert_eq!(decoded.as_ref(), sk.as_ref());
+
+    let sk = SigningKey::new(thread_rng());
+    let decoded = SigningKey::from_strkey(&sk.to_strkey()).unwrap();
+    assert_eq!(decoded.as_ref(), sk.as_ref());
+}
+
+#[test]
+fn malformed_strkeys() {
+    // A secret seed is not an account ID, and vice versa.
+    assert_eq!(
+        VerificationKeyBytes::from_strkey(SECRET_SEED),
+        Err(Error::UnexpectedVersion(18 << 3))
+    );
+    assert_eq!(
+        SigningKey::from_strkey(ACCOUNT_ID).err(),
+        Some(Error::UnexpectedVersion(6 << 3))
+    );
+
+    let mut corrupted = ACCOUNT_ID.to_string();
+    corrupted.replace_range(10..11, "A");
+    assert_eq!(
+        VerificationKeyBytes::from_strkey(&corrupted),
+        Err(Error::InvalidChecksum)
+    );
+
+    for strkey in [
+        &ACCOUNT_ID[1..],
+        &ACCOUNT_ID.to_lowercase(),
+        &ACCOUNT_ID.replace('Q', "1"),
+    ] {
+        assert_eq!(
+            VerificationKeyBytes::from_strkey(strkey),
+            Err(Error::MalformedStrKey),
+            "{}",
+            strkey
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::{fs, path::PathBuf};
+
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    tendermint::{address, Error, SignState, Step, ValidatorSigner},
+    Keypair, SigningKey, VerificationKeyBytes,
+};
+
+/// The key file for the seed `00 01 .. 1f`.
+const KEY_FILE: &str = r#"{
+  "address": "56475AA75463474C0285DF5DBF2BCAB73DA65135",
+  "pub_key": {
+    "type": "tendermint/PubKeyEd25519",
+    "value": "A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg="
+  },
+  "priv_key": {
+    "type": "tendermint/PrivKeyEd25519",
+    "value": "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8DoQe/884Qvh1w3RjnS8CZZ+TWMJulDV8d3IZkElUxuA=="
+  }
+}"#;
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+/// A fresh path for a state file in the temporary directory.
+fn state_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "ed25519-zebra-{}-{}-priv_validator_state.json",
+        std::process::id(),
+        name
+    ));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn key_files() {
+    let keypair = Keypair::from(signing_key());
+    assert_eq!(&keypair.to_priv_validator_key()[..], KEY_FILE);
+    assert_eq!(
+        hex::encode_upper(address(&VerificationKeyBytes::from(&keypair))),
+        "56475AA75463474C0285DF5DBF2BCAB73DA65135"
+    );
+
+    let decoded = Keypair::from_priv_validator_key(KEY_FILE).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&decoded),
+        VerificationKeyBytes::from(&keypair)
+    );
+}
+
+#[test]
+fn malformed_key_files() {
+    assert_eq!(
+        Keypair::from_priv_validator_key("{}").err(),
+        Some(Error::MalformedKeyFile)
+    );
+    let secp256k1 = KEY_FILE.replace("PubKeyEd25519", "PubKeySecp256k1");
+    assert_eq!(
+        Keypair::from_priv_validator_key(&secp256k1).err(),
+        Some(Error::MalformedKeyFile)
+    );
+    let wrong_address = KEY_FILE.replace("56475AA7", "00000000");
+    assert_eq!(
+        Keypair::from_priv_validator_key(&wrong_address).err(),
+        Some(Error::InconsistentKeyFile)
+    );
+    let wrong_pub_key = KEY_FILE.replace("A6EHv/PO", "B6EHv/PO");
+    assert_eq!(
+        Keypair::from_priv_validator_key(&wrong_pub_key).err(),
+        Some(Error::InconsistentKeyFile)
+    );
+}
+
+#[test]
+fn state_files() {
+    // The state file written by `cometbft init`.
+    let state =
+        SignState::from_json("{\n  \"height\": \"0\",\n  \"round\": 0,\n  \"step\": 0\n}").unwrap();
+    assert_eq!(state, SignState::default());
+    assert_eq!(
+        state.to_json(),
+        "{\n  \"height\": \"0\",\n  \"round\": 0,\n  \"step\": 0\n}"
+    );
+
+    let sk = signing_key();
+    let state = SignState {
+        height: 1 << 40,
+        round: 2,
+        step: Step::Precommit as u8,
+        signature: Some(sk.sign(b"vote")),
+        sign_bytes: b"vote".to_vec(),
+    };
+    let json = state.to_json();
+    assert!(json.contains("\"height\": \"1099511627776\""));
+    assert!(json.contains("\"signbytes\": \"766F7465\""));
+    assert_eq!(SignState::from_json(&json), Ok(state));
+
+    assert_eq!(
+        SignState::from_json(r#"{"height":1,"round":0,"step":0}"#),
+        Err(Error::MalformedStateFile)
+    );
+}
+
+#[test]
+fn double_sign_protection() {
+    let keypair = Keypair::new(thread_rng());
+    let vk = *keypair.verification_key();
+    let path = state_path("guard");
+    let mut signer = ValidatorSigner::open(keypair.clone(), &path).unwrap();
+    assert_eq!(signer.state(), &SignState::default());
+
+    let signature = signer.sign(10, 0, Step::Prevote, b"prevote A").unwrap();
+    assert!(vk.verify(&signature, b"prevote A").is_ok());
+
+    // Signing the same vote again returns the same signature.
+    assert_eq!(
+        signer.sign(10, 0, Step::Prevote, b"prevote A"),
+        Ok(signature)
+    );
+    // A different vote at the same height, round, and step is refused.
+    assert_eq!(
+        signer.sign(10, 0, Step::Prevote, b"prevote B"),
+        Err(Error::ConflictingData(10, 0, 2))
+    );
+    // So is anything earlier.
+    assert_eq!(
+        signer.sign(10, 0, Step::Propose, b"proposal"),
+        Err(Error::Regression(10, 0, 1))
+    );
+    assert_eq!(
+        signer.sign(9, 5, Step::Precommit, b"precommit"),
+        Err(Error::Regression(9, 5, 3))
+    );
+
+    // Later steps, rounds, and heights are signed.
+    assert!(signer.sign(10, 0, Step::Precommit, b"precommit").is_ok());
+    assert!(signer.sign(10, 1, Step::Propose, b"proposal").is_ok());
+    assert!(signer.sign(11, 0, Step::Propose, b"proposal").is_ok());
+
+    // The state survives a restart.
+    drop(signer);
+    let mut signer = ValidatorSigner::open(keypair, &path).unwrap();
+    assert_eq!(signer.state().height, 11);
+    assert_eq!(signer.state().step, Step::Propose as u8);
+    assert_eq!(
+        signer.sign(10, 3, Step::Precommit, b"precommit"),
+        Err(Error::Regression(10, 3, 3))
+    );
+    assert_eq!(
+        SignState::from_json(&fs::read_to_string(&path).unwrap()).as_ref(),
+        Ok(signer.state())
+    );
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn malformed_state_file() {
+    let path = state_path("malformed");
+    fs::write(&path, "not json").unwrap();
+    assert_eq!(
+        ValidatorSigner::open(Keypair::new(thread_rng()), &path).err(),
+        Some(Error::MalformedStateFile)
+    );
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,78 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{tezos::Error, SigningKey, VerificationKeyBytes};
+
+/// The keys for the seed `00 01 .. 1f`.
+const TZ1: &str = "tz1haTDx9MiA53qTAkchH9sUWoKfFABN6ggh";
+const EDPK: &str = "edpktfpdouHjAze9TeFcihdpeMng7FSCWbY4BozpSffZ9z85nyyBBB";
+const EDSK_SEED: &str = "edsk2fuNzt29dPBwrTvES5JiKxLM8p4VTcKDF2oFZiwfJLEocqv1rb";
+const EDSK_SECRET_KEY: &str = "edskRc1okCG3fjFkaDuENVdbepWSsxM3BJCt6FiJZd8xK5tpZEQdHhyvD38T2Z2NKp9NYPF6ixJhrWmYMr1PEc1kVeN4boMhTY";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn addresses_and_public_keys() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    assert_eq!(vk_bytes.to_tz1(), TZ1);
+    assert_eq!(vk_bytes.to_edpk(), EDPK);
+    assert_eq!(VerificationKeyBytes::from_edpk(EDPK), Ok(vk_bytes));
+}
+
+#[test]
+fn sandbox_bootstrap_account() {
+    // The `bootstrap1` account of the Tezos sandbox.
+    let sk =
+        SigningKey::from_edsk("edsk3gUfUPyBSfrS9CCgmCiQsTCHGkviBDusMxDJstFtojtc1zcpsh").unwrap();
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    assert_eq!(
+        vk_bytes.to_edpk(),
+        "edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav"
+    );
+    assert_eq!(vk_bytes.to_tz1(), "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx");
+}
+
+#[test]
+fn secret_keys() {
+    let sk = signing_key();
+    assert_eq!(&sk.to_edsk()[..], EDSK_SEED);
+    for edsk in [EDSK_SEED, EDSK_SECRET_KEY] {
+        assert_eq!(SigningKey::from_edsk(edsk).unwrap().as_ref(), sk.as_ref());
+    }
+
+    let sk = SigningKey::new(thread_rng());
+    assert_eq!(
+        SigningKey::from_edsk(&sk.to_edsk()).unwrap().as_ref(),
+        sk.as_ref()
+    );
+}
+
+#[test]
+fn malformed_keys() {
+    assert_eq!(
+        VerificationKeyBytes::from_edpk(TZ1),
+        Err(Error::UnexpectedPrefix)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_edpk(EDSK_SEED),
+        Err(Error::UnexpectedPrefix)
+    );
+    assert_eq!(
+        SigningKey::from_edsk(EDPK).err(),
+        Some(Error::UnexpectedPrefix)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from_edpk("edpk0OIl"),
+        Err(Error::InvalidBase58)
+    );
+
+    let mut corrupted = EDPK.to_string();
+    corrupted.replace_range(10..11, "z");
+    assert_eq!(
+        VerificationKeyBytes::from_edpk(&corrupted),
+        Err(Error::InvalidChecksum)
+    );
+}
@@ -0,0 +1,211 @@
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    tink::{Error, Key, KeyMaterial, KeyStatus, Keyset, OutputPrefixType},
+    SigningKey, VerificationKeyBytes,
+};
+
+/// The key ID of the test keysets, which is negative as a signed integer.
+const KEY_ID: u32 = 0x8a3c5b71;
+
+/// A binary keyset of the seed `00 01 .. 1f` with a `TINK` output prefix.
+const BINARY: &str = "08f1b6f1d1081291010a84010a38747970652e676f6f676c65617069732e636f6d2f676f6f676c652e63727970746f2e74696e6b2e45643235353139507269766174654b657912461220000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f1a22122003a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b81802100118f1b6f1d1082001";
+/// The public keyset of `BINARY`.
+const PUBLIC_BINARY: &str = "08f1b6f1d108126b0a5f0a37747970652e676f6f676c65617069732e636f6d2f676f6f676c652e63727970746f2e74696e6b2e456432353531395075626c69634b65791222122003a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b81803100118f1b6f1d1082001";
+/// `BINARY` as a JSON keyset.
+const JSON: &str = r#"{
+  "primaryKeyId": 2319211377,
+  "key": [
+    {
+      "keyData": {
+        "typeUrl": "type.googleapis.com/google.crypto.tink.Ed25519PrivateKey",
+        "value": "EiAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHxoiEiADoQe/884Qvh1w3RjnS8CZZ+TWMJulDV8d3IZkElUxuA==",
+        "keyMaterialType": "ASYMMETRIC_PRIVATE"
+      },
+      "status": "ENABLED",
+      "keyId": 2319211377,
+      "outputPrefixType": "TINK"
+    }
+  ]
+}"#;
+/// The signature of `hello, world` by `BINARY`.
+const TINK_SIGNATURE: &str = "018a3c5b715673fed67b412ed0034bb1ce1aba9cf7cc5620a2a43f0b02e96a568cf37e304522bd005a165bbb3f835c605e88c8e8b106520587d4463fc00381dc2643764d02";
+/// The signature of `hello, world` by `BINARY` with a `LEGACY` output prefix.
+const LEGACY_SIGNATURE: &str = "008a3c5b71b5ddfadca49b955f8d46fcbe8aa768feae599437c06a706416ca45107fa1d3f6582a66d278c1b8189980f44ca212b80c1e5a7741f3aaf16388189ff00bb5b901";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+fn keyset() -> Keyset {
+    Keyset {
+        primary_key_id: KEY_ID,
+        keys: vec![Key {
+            key_id: KEY_ID,
+            status: KeyStatus::Enabled,
+            output_prefix_type: OutputPrefixType::Tink,
+            material: Some(KeyMaterial::Private(signing_key())),
+        }],
+    }
+}
+
+#[test]
+fn binary_keysets() {
+    let keyset = keyset();
+    assert_eq!(hex::encode(&*keyset.to_binary()), BINARY);
+    assert_eq!(
+        Keyset::from_binary(&hex::decode(BINARY).unwrap()),
+        Ok(keyset.clone())
+    );
+
+    let public = keyset.public_keyset();
+    assert_eq!(
+        public.keys[0].material,
+        Some(KeyMaterial::Public(VerificationKeyBytes::from(
+            &signing_key()
+        )))
+    );
+    assert_eq!(hex::encode(&*public.to_binary()), PUBLIC_BINARY);
+    assert_eq!(
+        Keyset::from_binary(&hex::decode(PUBLIC_BINARY).unwrap()),
+        Ok(public)
+    );
+}
+
+#[test]
+fn json_keysets() {
+    let keyset = keyset();
+    assert_eq!(&keyset.to_json()[..], JSON);
+    assert_eq!(Keyset::from_json(JSON), Ok(keyset.clone()));
+
+    // Some Tink versions write key IDs as signed integers.
+    let signed = JSON.replace("2319211377", "-1975755919");
+    assert_eq!(Keyset::from_json(&signed), Ok(keyset.clone()));
+
+    let public = keyset.public_keyset();
+    assert_eq!(Keyset::from_json(&public.to_json()), Ok(public));
+}
+
+#[test]
+fn output_prefixes() {
+    let mut key = keyset().keys.remove(0);
+    assert_eq!(key.output_prefix(), [0x01, 0x8a, 0x3c, 0x5b, 0x71]);
+    key.output_prefix_type = OutputPrefixType::Legacy;
+    assert_eq!(key.output_prefix(), [0x00, 0x8a, 0x3c, 0x5b, 0x71]);
+    key.output_prefix_type = OutputPrefixType::Crunchy;
+    assert_eq!(key.output_prefix(), [0x00, 0x8a, 0x3c, 0x5b, 0x71]);
+    key.output_prefix_type = OutputPrefixType::Raw;
+    assert!(key.output_prefix().is_empty());
+}
+
+#[test]
+fn sign_and_verify() {
+    let msg = b"hello, world";
+    let mut keyset = keyset();
+    assert_eq!(hex::encode(keyset.sign(msg).unwrap()), TINK_SIGNATURE);
+    let signature = hex::decode(TINK_SIGNATURE).unwrap();
+    assert_eq!(keyset.verify(&signature, msg), Ok(()));
+    assert_eq!(keyset.public_keyset().verify(&signature, msg), Ok(()));
+    assert_eq!(
+        keyset.verify(&signature, b"goodbye"),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(
+        keyset.verify(&signature[5..], msg),
+        Err(Error::InvalidSignature)
+    );
+
+    keyset.keys[0].output_prefix_type = OutputPrefixType::Legacy;
+    assert_eq!(hex::encode(keyset.sign(msg).unwrap()), LEGACY_SIGNATURE);
+    assert_eq!(
+        keyset.verify(&hex::decode(LEGACY_SIGNATURE).unwrap(), msg),
+        Ok(())
+    );
+
+    keyset.keys[0].output_prefix_type = OutputPrefixType::Raw;
+    let signature = keyset.sign(msg).unwrap();
+    assert_eq!(signature.len(), 64);
+    assert_eq!(keyset.verify(&signature, msg), Ok(()));
+
+    // Disabled keys neither sign nor verify.
+    keyset.keys[0].status = KeyStatus::Disabled;
+    assert_eq!(keyset.sign(msg), Err(Error::NoPrimaryKey));
+    assert_eq!(keyset.verify(&signature, msg), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn key_rotation() {
+    let mut keyset = keyset();
+    let old_signature = keyset.sign(b"message").unwrap();
+    keyset.keys.push(Key {
+        key_id: 42,
+        status: KeyStatus::Enabled,
+        output_prefix_type: OutputPrefixType::Tink,
+        material: Some(KeyMaterial::Private(SigningKey::new(thread_rng()))),
+    });
+    keyset.primary_key_id = 42;
+    let new_signature = keyset.sign(b"message").unwrap();
+    assert_eq!(&new_signature[..5], [0x01, 0, 0, 0, 42]);
+
+    let public = Keyset::from_binary(&keyset.public_keyset().to_binary()).unwrap();
+    assert_eq!(public.verify(&old_signature, b"message"), Ok(()));
+    assert_eq!(public.verify(&new_signature, b"message"), Ok(()));
+    assert_eq!(public.sign(b"message"), Err(Error::NoPrimaryKey));
+
+    keyset.keys[0].status = KeyStatus::Destroyed;
+    keyset.keys[0].material = None;
+    let decoded = Keyset::from_json(&keyset.to_json()).unwrap();
+    assert_eq!(decoded, keyset);
+    assert_eq!(
+        decoded.verify(&old_signature, b"message"),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn malformed_keysets() {
+    let other = VerificationKeyBytes::from(&SigningKey::new(thread_rng()));
+    let inconsistent = BINARY.replace(
+        "03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8",
+        &hex::encode(other.as_ref()),
+    );
+    assert_eq!(
+        Keyset::from_binary(&hex::decode(inconsistent).unwrap()),
+        Err(Error::InconsistentKey)
+    );
+
+    for malformed in [
+        &BINARY[..BINARY.len() - 2],
+        // An unknown output prefix type.
+        &BINARY.replace("2001", "2005"),
+        // A private key type URL with a public key material type.
+        &BINARY.replace("1802", "1803"),
+    ] {
+        assert_eq!(
+            Keyset::from_binary(&hex::decode(malformed).unwrap()),
+            Err(Error::MalformedKeyset),
+            "{}",
+            malformed
+        );
+    }
+
+    for malformed in [
+        "",
+        "{}",
+        &JSON.replace("\"ENABLED\"", "\"enabled\""),
+        &JSON.replace("\"value\": \"EiAA", "\"value\": \"EiA"),
+        &JSON.replace("2319211377", "4294967296"),
+    ] {
+        assert_eq!(Keyset::from_json(malformed), Err(Error::MalformedKeyset));
+    }
+
+    let aes = JSON.replace("Ed25519PrivateKey", "AesGcmKey");
+    assert_eq!(
+        Keyset::from_json(&aes),
+        Err(Error::UnsupportedKeyType(
+            "type.googleapis.com/google.crypto.tink.AesGcmKey".to_string()
+        ))
+    );
+}
@@ -0,0 +1,157 @@
+use rand::thread_rng;
+use serde_json::json;
+
+use ed25519_zebra::{
+    tuf::{canonical_json, key_id, key_object, Error, Metadata},
+    SigningKey, VerificationKey, VerificationKeyBytes,
+};
+
+/// The public key for the seed `00 01 .. 1f`.
+const VK: &str = "03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8";
+const KEY_ID: &str = "63d00ee3cf4f5c61ad94efa696c68f13b608fe56ecea13fb144a6fd18e9235b9";
+
+const SIGNED: &str = r#"{"_type":"targets","spec_version":"1.0.31","version":3,"expires":"2030-01-01T00:00:00Z","targets":{"zebrad-1.0.0.tar.gz":{"length":1024,"hashes":{"sha256":"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"},"custom":{"note":"café \"quoted\" back\\slash\n"}}}}"#;
+
+const CANONICAL: &str = "{\"_type\":\"targets\",\"expires\":\"2030-01-01T00:00:00Z\",\"spec_version\":\"1.0.31\",\"targets\":{\"zebrad-1.0.0.tar.gz\":{\"custom\":{\"note\":\"café \\\"quoted\\\" back\\\\slash\n\"},\"hashes\":{\"sha256\":\"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\"},\"length\":1024}},\"version\":3}";
+
+/// The signature of `CANONICAL` by the key above, made with Python's `cryptography`.
+const SIG: &str = "99305d85c421b8ad50138b29d7610858e99ec09f7a80d4ad68c6cd3c602ae9e3874ee142140cbc85683a56e96670d6a0676410e5dfd08a1afb806155fb187b04";
+
+fn signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    seed.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    SigningKey::from(seed)
+}
+
+#[test]
+fn canonical_encoding() {
+    let signed: serde_json::Value = serde_json::from_str(SIGNED).unwrap();
+    assert_eq!(canonical_json(&signed).unwrap(), CANONICAL.as_bytes());
+    assert_eq!(
+        canonical_json(&json!({"b": [1, -2, null, true], "a": false})).unwrap(),
+        b"{\"a\":false,\"b\":[1,-2,null,true]}"
+    );
+    assert_eq!(
+        canonical_json(&json!({"threshold": 1.5})),
+        Err(Error::UnsupportedNumber)
+    );
+}
+
+#[test]
+fn key_identifiers() {
+    let vk_bytes = VerificationKeyBytes::from(&signing_key());
+    assert_eq!(vk_bytes.to_string(), VK);
+    assert_eq!(key_id(&vk_bytes), KEY_ID);
+    assert_eq!(
+        key_object(&vk_bytes),
+        json!({"keytype": "ed25519", "keyval": {"public": VK}, "scheme": "ed25519"})
+    );
+}
+
+#[test]
+fn sign_and_verify() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+
+    let mut metadata = Metadata::new(serde_json::from_str(SIGNED).unwrap());
+    metadata.sign(&sk).unwrap();
+    assert_eq!(metadata.signatures.len(), 1);
+    assert_eq!(metadata.signatures[0].keyid, KEY_ID);
+    assert_eq!(metadata.signatures[0].sig, SIG);
+    assert_eq!(metadata.verify(&vk), Ok(()));
+
+    // Signing again replaces the signature rather than adding another.
+    metadata.sign(&sk).unwrap();
+    assert_eq!(metadata.signatures.len(), 1);
+
+    let decoded = Metadata::from_json(&metadata.to_json()).unwrap();
+    assert_eq!(decoded, metadata);
+    assert_eq!(decoded.verify(&vk), Ok(()));
+}
+
+#[test]
+fn verify_reordered_metadata() {
+    // Only the canonical form is signed, so key order and whitespace in the
+    // file do not matter.
+    let json = format!(
+        r#"{{ "signed": {}, "signatures": [ {{ "sig": "{}", "keyid": "{}" }} ] }}"#,
+        SIGNED, SIG, KEY_ID
+    );
+    let metadata = Metadata::from_json(&json).unwrap();
+    assert_eq!(
+        metadata.verify(&VerificationKey::from(&signing_key())),
+        Ok(())
+    );
+}
+
+#[test]
+fn verify_failures() {
+    let sk = signing_key();
+    let vk = VerificationKey::from(&sk);
+    let mut metadata = Metadata::new(serde_json::from_str(SIGNED).unwrap());
+
+    assert_eq!(
+        metadata.verify(&vk),
+        Err(Error::MissingSignature(KEY_ID.to_string()))
+    );
+
+    metadata.sign(&sk).unwrap();
+    metadata.signed["version"] = json!(4);
+    assert!(matches!(
+        metadata.verify(&vk),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    metadata.signatures[0].sig.truncate(126);
+    assert_eq!(metadata.verify(&vk), Err(Error::MalformedSignature));
+
+    // A bad signature does not hide a valid one with the same key ID.
+    let mut metadata = Metadata::new(serde_json::from_str(SIGNED).unwrap());
+    metadata.sign(&sk).unwrap();
+    let mut bad = metadata.signatures[0].clone();
+    bad.sig = "00".repeat(64);
+    metadata.signatures.insert(0, bad.clone());
+    assert_eq!(metadata.verify(&vk), Ok(()));
+    bad.sig.truncate(126);
+    metadata.signatures.insert(0, bad);
+    assert_eq!(metadata.verify(&vk), Ok(()));
+    metadata.signatures.pop();
+    assert!(matches!(
+        metadata.verify(&vk),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    assert_eq!(
+        Metadata::from_json(r#"{"signed":{}}"#),
+        Err(Error::MalformedMetadata)
+    );
+}
+
+#[test]
+fn threshold() {
+    let sks: Vec<_> = (0..3).map(|_| SigningKey::new(thread_rng())).collect();
+    let vks: Vec<_> = sks.iter().map(VerificationKey::from).collect();
+
+    let mut metadata = Metadata::new(json!({"_type": "root", "version": 1}));
+    metadata.sign(&sks[0]).unwrap();
+    metadata.sign(&sks[1]).unwrap();
+
+    assert_eq!(metadata.verify_threshold(&vks, 2), Ok(()));
+    assert_eq!(
+        metadata.verify_threshold(&vks, 3),
+        Err(Error::ThresholdNotMet(2, 3))
+    );
+    // A key listed twice counts once.
+    assert_eq!(
+        metadata.verify_threshold(&[vks[0], vks[0]], 2),
+        Err(Error::ThresholdNotMet(1, 2))
+    );
+    assert_eq!(
+        metadata.verify_threshold(&vks, 0),
+        Err(Error::InvalidThreshold)
+    );
+    assert_eq!(
+        Metadata::new(json!({})).verify_threshold(&[], 0),
+        Err(Error::InvalidThreshold)
+    );
+}
@@ -14,7 +14,7 @@ fn parsing() {
     // Most of these types don't implement Eq, so we check a round trip
     // conversion to bytes, using these as the reference points:
 
-    let sk_array: [u8; 32] = sk.into();
+    let sk_array: [u8; 32] = sk.clone().into();
     let pk_array: [u8; 32] = pk.into();
     let pkb_array: [u8; 32] = pkb.into();
     let sig_array: [u8; 64] = sig.into();
@@ -51,3 +51,670 @@ fn sign_and_verify() {
 
     assert_eq!(pk.verify(&sig, &msg[..]), Ok(()))
 }
+
+#[test]
+fn sign_with_rng_is_hedged() {
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+
+    let msg = b"ed25519-zebra hedged signature";
+
+    let sig1 = sk.sign_with_rng(thread_rng(), &msg[..]);
+    let sig2 = sk.sign_with_rng(thread_rng(), &msg[..]);
+
+    assert_ne!(sig1, sig2);
+    assert_ne!(sig1, sk.sign(&msg[..]));
+    assert_eq!(pk.verify(&sig1, &msg[..]), Ok(()));
+    assert_eq!(pk.verify(&sig2, &msg[..]), Ok(()));
+}
+
+#[test]
+fn expanded_signing_key() {
+    use ed25519_zebra::ExpandedSigningKey;
+    use sha2::{Digest, Sha512};
+
+    let sk = SigningKey::new(thread_rng());
+    let esk = ExpandedSigningKey::from(&sk);
+    let msg = b"ed25519-zebra expanded key";
+
+    // The expanded form of a seed is its clamped SHA-512 hash.
+    let seed: [u8; 32] = sk.clone().into();
+    let mut expected = [0u8; 64];
+    expected.copy_from_slice(&Sha512::digest(&seed[..]));
+    expected[0] &= 248;
+    expected[31] &= 127;
+    expected[31] |= 64;
+    let esk_bytes: [u8; 64] = esk.into();
+    assert_eq!(&esk_bytes[..], &expected[..]);
+
+    let esk2 = ExpandedSigningKey::try_from(&esk_bytes[..]).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&esk2),
+        VerificationKeyBytes::from(&sk)
+    );
+    assert_eq!(esk2.sign(&msg[..]), sk.sign(&msg[..]));
+    assert_eq!(
+        VerificationKey::from(&esk2).verify(&esk2.sign(&msg[..]), &msg[..]),
+        Ok(())
+    );
+
+    let esk3: ExpandedSigningKey = bincode::deserialize(&esk_bytes[..]).unwrap();
+    assert_eq!(&<[u8; 64]>::from(esk3)[..], &esk_bytes[..]);
+
+    assert!(ExpandedSigningKey::try_from(&esk_bytes[..32]).is_err());
+}
+
+#[test]
+fn libsodium_secret_key_encoding() {
+    // RFC 8032 test 1, in libsodium's seed || public key layout.
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(
+        &hex::decode(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60\
+             d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        )
+        .unwrap(),
+    );
+
+    let sk = SigningKey::from_libsodium_bytes(&bytes).unwrap();
+    assert_eq!(&<[u8; 32]>::from(sk.clone())[..], &bytes[0..32]);
+    assert_eq!(VerificationKeyBytes::from(&sk).as_ref(), &bytes[32..64]);
+    assert_eq!(&sk.to_libsodium_bytes()[..], &bytes[..]);
+
+    // A mismatched public key half is rejected.
+    bytes[63] ^= 1;
+    assert_eq!(
+        SigningKey::from_libsodium_bytes(&bytes).unwrap_err(),
+        ed25519_zebra::Error::MalformedSecretKey
+    );
+}
+
+#[test]
+fn attached_signatures() {
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+
+    let msg = b"ed25519-zebra attached signature";
+
+    let signed_msg = sk.sign_attached(&msg[..]);
+    assert_eq!(signed_msg.len(), 64 + msg.len());
+    assert_eq!(&signed_msg[..64], &<[u8; 64]>::from(sk.sign(&msg[..]))[..]);
+    assert_eq!(pk.open_attached(&signed_msg), Ok(&msg[..]));
+
+    let mut tampered = signed_msg.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(pk.open_attached(&tampered).is_err());
+    assert!(pk.open_attached(&signed_msg[..63]).is_err());
+}
+
+#[test]
+fn one_shot_verification() {
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+    let pkb = VerificationKeyBytes::from(&sk);
+
+    let msg = b"ed25519-zebra one-shot verification";
+    let sig = sk.sign(&msg[..]);
+
+    assert_eq!(sig.verify(&pk, &msg[..]), Ok(()));
+    assert_eq!(pkb.verify(&sig, &msg[..]), Ok(()));
+    assert!(sig.verify(&pk, b"wrong message").is_err());
+    assert!(pkb.verify(&sig, b"wrong message").is_err());
+}
+
+#[test]
+fn vectored_signing() {
+    use std::io::IoSlice;
+
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+
+    let header = b"header:";
+    let payload = b"ed25519-zebra vectored payload";
+    let bufs = [IoSlice::new(&header[..]), IoSlice::new(&payload[..])];
+    let msg = [&header[..], &payload[..]].concat();
+
+    let sig = sk.sign_vectored(&bufs);
+    assert_eq!(sig, sk.sign(&msg));
+    assert_eq!(pk.verify_vectored(&sig, &bufs), Ok(()));
+    assert_eq!(pk.verify(&sig, &msg), Ok(()));
+    assert!(pk
+        .verify_vectored(&sig, &[IoSlice::new(&payload[..])])
+        .is_err());
+}
+
+#[test]
+fn signature_components() {
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra signature components";
+    let sig = sk.sign(&msg[..]);
+
+    let sig_array: [u8; 64] = sig.into();
+    assert_eq!(&sig.R_bytes()[..], &sig_array[..32]);
+    assert_eq!(&sig.s_bytes()[..], &sig_array[32..]);
+
+    let sig2 = Signature::from_parts(*sig.R_bytes(), *sig.s_bytes());
+    assert_eq!(sig, sig2);
+    assert_eq!(
+        Signature::from_parts_checked(*sig.R_bytes(), *sig.s_bytes()),
+        Ok(sig)
+    );
+    assert_eq!(pk.verify(&sig2, &msg[..]), Ok(()));
+
+    // s = l is not canonical.
+    let mut l = [0u8; 32];
+    l.copy_from_slice(
+        &hex::decode("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010").unwrap(),
+    );
+    assert!(Signature::from_parts_checked(*sig.R_bytes(), l).is_err());
+    // y = 2 is not the y-coordinate of a curve point.
+    let mut not_on_curve = [0u8; 32];
+    not_on_curve[0] = 2;
+    assert!(Signature::from_parts_checked(not_on_curve, *sig.s_bytes()).is_err());
+}
+
+#[test]
+fn signature_bytes() {
+    let sk = SigningKey::new(thread_rng());
+    let sig = sk.sign(b"ed25519-zebra signature bytes");
+    let sig_array: [u8; 64] = sig.into();
+
+    assert_eq!(sig.as_bytes(), &sig_array);
+    assert_eq!(sig.to_bytes(), sig_array);
+    assert_eq!(sig.as_ref(), &sig_array[..]);
+    assert_eq!(Signature::try_from(sig.as_ref()), Ok(sig));
+    assert_eq!(
+        Signature::try_from(&sig_array[..63]),
+        Err(ed25519_zebra::Error::InvalidSliceLength)
+    );
+    assert_eq!(bincode::serialize(&sig).unwrap(), &sig_array[..]);
+}
+
+#[test]
+fn constant_time_equality() {
+    use subtle::ConstantTimeEq;
+
+    let sk = SigningKey::new(thread_rng());
+    let other_sk = SigningKey::new(thread_rng());
+    let sig = sk.sign(b"ed25519-zebra constant time equality");
+    let other_sig = sk.sign(b"another message");
+    let pkb = VerificationKeyBytes::from(&sk);
+    let other_pkb = VerificationKeyBytes::from(&other_sk);
+
+    assert!(bool::from(
+        sk.ct_eq(&SigningKey::try_from(sk.as_ref()).unwrap())
+    ));
+    assert!(!bool::from(sk.ct_eq(&other_sk)));
+    assert_eq!(sk, SigningKey::try_from(sk.as_ref()).unwrap());
+    assert_ne!(sk, other_sk);
+
+    assert!(bool::from(sig.ct_eq(&sig)));
+    assert!(!bool::from(sig.ct_eq(&other_sig)));
+    assert_ne!(sig, other_sig);
+
+    assert!(bool::from(pkb.ct_eq(&pkb)));
+    assert!(!bool::from(pkb.ct_eq(&other_pkb)));
+    assert_ne!(pkb, other_pkb);
+}
+
+#[test]
+fn map_keys() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let sk = SigningKey::new(thread_rng());
+    let pkb = VerificationKeyBytes::from(&sk);
+    let sigs = [sk.sign(b"one"), sk.sign(b"two"), sk.sign(b"three")];
+
+    let mut hash_map = HashMap::new();
+    let mut btree_map = BTreeMap::new();
+    for (i, sig) in sigs.iter().enumerate() {
+        hash_map.insert((pkb, *sig), i);
+        btree_map.insert(*sig, i);
+    }
+    for (i, sig) in sigs.iter().enumerate() {
+        assert_eq!(hash_map.get(&(pkb, *sig)), Some(&i));
+        assert_eq!(btree_map.get(sig), Some(&i));
+    }
+
+    // The ordering is the byte ordering of the encodings.
+    let mut sorted = sigs.to_vec();
+    sorted.sort();
+    let mut sorted_bytes = sigs.iter().map(|sig| sig.to_bytes()).collect::<Vec<_>>();
+    sorted_bytes.sort();
+    assert_eq!(
+        sorted.iter().map(|sig| sig.to_bytes()).collect::<Vec<_>>(),
+        sorted_bytes
+    );
+}
+
+#[test]
+fn zeroize_clears_key_material() {
+    use ed25519_zebra::ExpandedSigningKey;
+    use zeroize::Zeroize;
+
+    let mut sk = SigningKey::new(thread_rng());
+    let mut esk = ExpandedSigningKey::from(&sk);
+
+    sk.zeroize();
+    esk.zeroize();
+
+    assert_eq!(sk.as_ref(), &[0u8; 32][..]);
+    assert_eq!(&<[u8; 64]>::from(esk)[..], &[0u8; 64][..]);
+}
+
+#[test]
+fn signing_keys_zeroize_on_drop() {
+    use ed25519_zebra::{CompactSigningKey, ExpandedSigningKey, Keypair};
+    use zeroize::ZeroizeOnDrop;
+
+    fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+
+    assert_zeroize_on_drop::<SigningKey>();
+    assert_zeroize_on_drop::<ExpandedSigningKey>();
+    assert_zeroize_on_drop::<CompactSigningKey>();
+    assert_zeroize_on_drop::<Keypair>();
+}
+
+#[test]
+fn debug_output_redacts_secrets() {
+    use ed25519_zebra::ExpandedSigningKey;
+
+    let sk = SigningKey::new(thread_rng());
+    let seed_hex = hex::encode(sk.as_ref());
+    let vk_hex = hex::encode(VerificationKeyBytes::from(&sk));
+
+    let sk_debug = format!("{:?}", sk);
+    let esk_debug = format!("{:?}", ExpandedSigningKey::from(&sk));
+    assert!(sk_debug.contains(&vk_hex));
+    assert!(esk_debug.contains(&vk_hex));
+
+    if cfg!(feature = "debug-secrets") {
+        assert!(sk_debug.contains(&seed_hex));
+    } else {
+        assert!(!sk_debug.contains(&seed_hex));
+        assert!(sk_debug.contains("<redacted>"));
+        assert!(esk_debug.contains("<redacted>"));
+    }
+}
+
+#[test]
+fn const_constructors() {
+    // RFC 8032 test 1.
+    const VK_BYTES: VerificationKeyBytes = VerificationKeyBytes::from_bytes_const([
+        0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64, 0x07,
+        0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68, 0xf7, 0x07,
+        0x51, 0x1a,
+    ]);
+    const R_BYTES: [u8; 32] = [
+        0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e, 0x82,
+        0x8a, 0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65, 0x22, 0x49,
+        0x01, 0x55,
+    ];
+    const S_BYTES: [u8; 32] = [
+        0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e, 0x39, 0x70, 0x1c, 0xf9, 0xb4,
+        0x6b, 0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24, 0x65, 0x51, 0x41, 0x43, 0x8e, 0x7a,
+        0x10, 0x0b,
+    ];
+    const SIG: Signature = Signature::from_parts(R_BYTES, S_BYTES);
+    const SIG_BYTES: [u8; 64] = SIG.to_bytes();
+
+    assert_eq!(Signature::from_bytes_const(SIG_BYTES), SIG);
+    assert_eq!(&SIG_BYTES[..32], &R_BYTES[..]);
+    assert_eq!(VK_BYTES.as_bytes(), VK_BYTES.as_ref());
+    assert_eq!(VK_BYTES.verify(&SIG, b""), Ok(()));
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn granular_verification_errors() {
+    use ed25519_zebra::Error;
+
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra granular errors";
+    let sig = sk.sign(&msg[..]);
+
+    assert_eq!(
+        pk.verify(&sig, b"wrong message"),
+        Err(Error::InvalidSignature)
+    );
+
+    // y = 2 is not the y-coordinate of a curve point.
+    let mut bad_R = [0u8; 32];
+    bad_R[0] = 2;
+    let sig_bad_R = Signature::from_parts(bad_R, *sig.s_bytes());
+    assert_eq!(
+        pk.verify(&sig_bad_R, &msg[..]),
+        Err(Error::MalformedSignatureR)
+    );
+
+    let sig_bad_s = Signature::from_parts(*sig.R_bytes(), [0xff; 32]);
+    assert_eq!(
+        pk.verify(&sig_bad_s, &msg[..]),
+        Err(Error::SignatureScalarOutOfRange)
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn canonical_signature_encodings() {
+    use ed25519_zebra::Error;
+
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra canonical encodings";
+    let sig = sk.sign(&msg[..]);
+    assert!(sig.is_canonical());
+    assert_eq!(Signature::from_canonical_bytes(sig.into()), Ok(sig));
+
+    // Setting the sign bit of the identity's encoding gives a non-canonical
+    // encoding of the same point (x = 0 has no negative counterpart).
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let mut non_canonical_R = identity;
+    non_canonical_R[31] |= 0x80;
+    let sig_nc = Signature::from_parts(non_canonical_R, [0; 32]);
+    assert!(Signature::from_parts_checked(non_canonical_R, [0; 32]).is_ok());
+    assert!(!sig_nc.is_canonical());
+    assert_eq!(
+        Signature::from_canonical_bytes(sig_nc.into()),
+        Err(Error::NonCanonicalSignatureR)
+    );
+    assert!(Signature::from_parts(identity, [0; 32]).is_canonical());
+
+    let sig_bad_s = Signature::from_parts(*sig.R_bytes(), [0xff; 32]);
+    assert!(!sig_bad_s.is_canonical());
+    assert_eq!(
+        Signature::from_canonical_bytes(sig_bad_s.into()),
+        Err(Error::SignatureScalarOutOfRange)
+    );
+
+    let mut bad_R = [0u8; 32];
+    bad_R[0] = 2;
+    assert_eq!(
+        Signature::from_canonical_bytes(Signature::from_parts(bad_R, [0; 32]).into()),
+        Err(Error::MalformedSignatureR)
+    );
+    assert!(pk.verify(&sig, &msg[..]).is_ok());
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn strict_verification() {
+    use ed25519_zebra::Error;
+
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra strict verification";
+    let sig = sk.sign(&msg[..]);
+    assert!(pk.verify_strict(&sig, &msg[..]).is_ok());
+    assert_eq!(
+        pk.verify_strict(&sig, b"wrong message"),
+        Err(Error::InvalidSignature)
+    );
+
+    // A small-order verification key is rejected even with a signature that
+    // satisfies the cofactored equation.
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let weak_vk = VerificationKey::try_from(identity).unwrap();
+    let weak_sig = Signature::from_parts(identity, [0; 32]);
+    assert!(weak_vk.verify(&weak_sig, &msg[..]).is_ok());
+    assert_eq!(
+        weak_vk.verify_strict(&weak_sig, &msg[..]),
+        Err(Error::SmallOrderPoint)
+    );
+
+    let mut non_canonical_R = identity;
+    non_canonical_R[31] |= 0x80;
+    assert_eq!(
+        weak_vk.verify_strict(&Signature::from_parts(non_canonical_R, [0; 32]), &msg[..]),
+        Err(Error::NonCanonicalSignatureR)
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn verification_policies() {
+    use ed25519_zebra::{Error, VerificationPolicy};
+
+    let sk = SigningKey::new(thread_rng());
+    let pk = VerificationKey::from(&sk);
+    let msg = b"ed25519-zebra verification policies";
+    let sig = sk.sign(&msg[..]);
+    for policy in [
+        VerificationPolicy::Zip215,
+        VerificationPolicy::Rfc8032Strict,
+        VerificationPolicy::Libsodium,
+    ] {
+        assert!(pk.verify_with_policy(&sig, &msg[..], policy).is_ok());
+        assert_eq!(
+            pk.verify_with_policy(&sig, b"wrong message", policy),
+            Err(Error::InvalidSignature)
+        );
+    }
+    assert_eq!(VerificationPolicy::default(), VerificationPolicy::Zip215);
+
+    // A valid signature with a non-canonical R: the identity point encoded
+    // with its sign bit set, for which s = 0 satisfies the verification
+    // equation under any key.
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let weak_vk = VerificationKey::try_from(identity).unwrap();
+    let mut non_canonical_R = identity;
+    non_canonical_R[31] |= 0x80;
+    let sig_nc = Signature::from_parts(non_canonical_R, [0; 32]);
+    assert!(weak_vk
+        .verify_with_policy(&sig_nc, &msg[..], VerificationPolicy::Zip215)
+        .is_ok());
+    assert_eq!(
+        weak_vk.verify_with_policy(&sig_nc, &msg[..], VerificationPolicy::Rfc8032Strict),
+        Err(Error::NonCanonicalSignatureR)
+    );
+    assert_eq!(
+        weak_vk.verify_with_policy(&sig_nc, &msg[..], VerificationPolicy::Libsodium),
+        Err(Error::SmallOrderPoint)
+    );
+}
+
+#[test]
+fn precomputed_verification_key() {
+    use ed25519_zebra::{Error, PrecomputedVerificationKey};
+
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let pvk = PrecomputedVerificationKey::try_from(VerificationKeyBytes::from(&sk)).unwrap();
+    assert_eq!(
+        VerificationKeyBytes::from(&pvk),
+        VerificationKeyBytes::from(vk)
+    );
+    for i in 0..8u8 {
+        let msg = [i; 32];
+        let sig = sk.sign(&msg[..]);
+        assert!(pvk.verify(&sig, &msg[..]).is_ok());
+        assert_eq!(
+            pvk.verify(&sig, b"wrong message"),
+            Err(Error::InvalidSignature)
+        );
+        let sig_bad_s = Signature::from_parts(*sig.R_bytes(), [0xff; 32]);
+        assert_eq!(
+            pvk.verify(&sig_bad_s, &msg[..]),
+            vk.verify(&sig_bad_s, &msg[..])
+        );
+    }
+}
+
+#[test]
+fn lazy_verification_key() {
+    use ed25519_zebra::{Error, LazyVerificationKey};
+
+    let sk = SigningKey::new(thread_rng());
+    let msg = b"ed25519-zebra lazy verification key";
+    let sig = sk.sign(&msg[..]);
+
+    let lazy = LazyVerificationKey::from(VerificationKeyBytes::from(&sk));
+    assert!(lazy.verify(&sig, &msg[..]).is_ok());
+    assert_eq!(
+        lazy.verify(&sig, b"wrong message"),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(
+        VerificationKeyBytes::from(*lazy.verification_key().unwrap()),
+        VerificationKeyBytes::from(&sk)
+    );
+
+    let encoded = bincode::serialize(&lazy).unwrap();
+    assert_eq!(encoded, bincode::serialize(lazy.as_bytes()).unwrap());
+    let decoded: LazyVerificationKey = bincode::deserialize(&encoded).unwrap();
+    assert!(decoded.verify(&sig, &msg[..]).is_ok());
+
+    // y = 2 is not the y-coordinate of a curve point.
+    let mut bad_bytes = [0u8; 32];
+    bad_bytes[0] = 2;
+    let bad = LazyVerificationKey::from(bad_bytes);
+    for _ in 0..2 {
+        assert_eq!(bad.verify(&sig, &msg[..]), Err(Error::MalformedPublicKey));
+    }
+}
+
+#[test]
+fn compact_signing_key() {
+    use ed25519_zebra::CompactSigningKey;
+
+    let sk = SigningKey::new(thread_rng());
+    let csk = CompactSigningKey::from(&sk);
+    let msg = b"ed25519-zebra compact signing key";
+    assert_eq!(csk.sign(&msg[..]), sk.sign(&msg[..]));
+    assert_eq!(
+        VerificationKeyBytes::from(&csk),
+        VerificationKeyBytes::from(&sk)
+    );
+    assert!(SigningKey::from(&csk) == sk);
+    assert!(std::mem::size_of::<CompactSigningKey>() < std::mem::size_of::<SigningKey>());
+
+    let encoded = bincode::serialize(&csk).unwrap();
+    assert_eq!(encoded, bincode::serialize(&sk).unwrap());
+    let decoded: CompactSigningKey = bincode::deserialize(&encoded).unwrap();
+    assert!(decoded == csk);
+}
+
+#[test]
+fn sign_batch_matches_sign() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let msgs: Vec<&[u8]> = vec![b"", b"a", &[0x5a; 200][..], b"ed25519-zebra"];
+    let sigs = sk.sign_batch(&msgs);
+    assert_eq!(sigs.len(), msgs.len());
+    for (msg, sig) in msgs.iter().zip(sigs.iter()) {
+        assert_eq!(*sig, sk.sign(msg));
+        assert!(vk.verify(sig, msg).is_ok());
+    }
+    assert!(sk.sign_batch::<&[u8]>(&[]).is_empty());
+}
+
+#[test]
+fn verification_cache() {
+    use ed25519_zebra::{Error, VerificationCache};
+
+    let mut cache = VerificationCache::new(2);
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    let sigs = [sk.sign(b"zero"), sk.sign(b"one"), sk.sign(b"two")];
+
+    assert!(cache.verify(vk_bytes, &sigs[0], b"zero").is_ok());
+    assert!(cache.verify(vk_bytes, &sigs[1], b"one").is_ok());
+    assert_eq!(cache.len(), 2);
+
+    // Failures are not cached, and a cached signature is only a hit for the
+    // same message.
+    assert_eq!(
+        cache.verify(vk_bytes, &sigs[0], b"one"),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(cache.len(), 2);
+
+    // Touch "zero" so that "one" is evicted next.
+    assert!(cache.verify(vk_bytes, &sigs[0], b"zero").is_ok());
+    assert!(cache.verify(vk_bytes, &sigs[2], b"two").is_ok());
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(VerificationCache::new(0)
+        .verify(vk_bytes, &sigs[0], b"zero")
+        .is_ok());
+}
+
+#[test]
+fn signing_pool() {
+    use ed25519_zebra::pool::SigningPool;
+
+    let sk = SigningKey::new(thread_rng());
+    let expected = (0..32u8).map(|i| sk.sign(&[i; 8])).collect::<Vec<_>>();
+    let pool = SigningPool::new(sk, 3);
+    let pending = (0..32u8)
+        .map(|i| pool.submit(vec![i; 8]))
+        .collect::<Vec<_>>();
+    for (pending, expected) in pending.into_iter().zip(expected.iter()) {
+        assert_eq!(pending.wait(), *expected);
+    }
+    assert_eq!(pool.sign(vec![0; 8]), expected[0]);
+
+    // Dropping the pool waits for queued requests.
+    let pending = pool.submit(vec![1; 8]);
+    drop(pool);
+    assert_eq!(pending.try_wait().ok(), Some(expected[1]));
+}
+
+#[test]
+fn verify_once() {
+    use ed25519_zebra::Error;
+
+    let sk = SigningKey::new(thread_rng());
+    let vk_bytes = VerificationKeyBytes::from(&sk);
+    let msg = b"ed25519-zebra verify once";
+    let sig = sk.sign(&msg[..]);
+    assert!(vk_bytes.verify_once(&sig, &msg[..]).is_ok());
+    assert_eq!(
+        vk_bytes.verify_once(&sig, b"wrong message"),
+        Err(Error::InvalidSignature)
+    );
+
+    let mut bad_bytes = [0u8; 32];
+    bad_bytes[0] = 2;
+    assert_eq!(
+        VerificationKeyBytes::from(bad_bytes).verify_once(&sig, &msg[..]),
+        Err(Error::MalformedPublicKey)
+    );
+}
+
+#[test]
+fn verify_many_with_one_key() {
+    let sk = SigningKey::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let mut items = (0..16u8)
+        .map(|i| (vec![i; 16], sk.sign(&[i; 16])))
+        .collect::<Vec<_>>();
+    assert!(vk.verify_many(&items).is_ok());
+    assert!(vk.verify_many::<&[u8]>(&[]).is_ok());
+
+    items[5].0 = b"wrong message".to_vec();
+    assert!(vk.verify_many(&items).is_err());
+}
+
+#[test]
+fn generate_many_keys() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let keys = SigningKey::generate_many(StdRng::seed_from_u64(7), 16);
+    assert_eq!(keys.len(), 16);
+    assert_eq!(
+        keys,
+        SigningKey::generate_many(StdRng::seed_from_u64(7), 16)
+    );
+    for (i, sk) in keys.iter().enumerate() {
+        assert!(keys[..i].iter().all(|other| other != sk));
+    }
+    assert!(SigningKey::generate_many(thread_rng(), 0).is_empty());
+}
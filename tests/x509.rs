@@ -0,0 +1,260 @@
+use pkcs8::der::ErrorKind;
+use rand::thread_rng;
+
+use ed25519_zebra::{
+    x509::{CertificateBuilder, CertificateRequest, CertificateRequestBuilder, Error},
+    SigningKey, VerificationKeyBytes,
+};
+
+/// The seed `20 21 .. 3f`.
+const SEED: [u8; 32] = [
+    0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x3b, 0x3c, 0x3d, 0x3e, 0x3f,
+];
+const COMMON_NAME: &str = "zebra.example.com";
+const SERIAL_NUMBER: [u8; 16] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+/// 2023-11-14T22:13:20Z.
+const NOT_BEFORE: u64 = 1_700_000_000;
+
+// The certificates below are identical to those made by Python's
+// `cryptography` package, and `openssl verify` accepts them.
+
+/// Valid until 2052, so `notAfter` is a `GeneralizedTime`, with DNS subject
+/// alternative names.
+const CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIIBIjCB1aADAgECAhABAgMEBQYHCAkKCwwNDg8QMAUGAytlcDAcMRowGAYDVQQD
+DBF6ZWJyYS5leGFtcGxlLmNvbTAgFw0yMzExMTQyMjEzMjBaGA8yMDUyMDUyMjE0
+MTMyMFowHDEaMBgGA1UEAwwRemVicmEuZXhhbXBsZS5jb20wKjAFBgMrZXADIQAp
+rLrhQbzK8LIuGpTTTQvHNh5SbQv+EsiXlLyTIpZt16MrMCkwJwYDVR0RBCAwHoIR
+emVicmEuZXhhbXBsZS5jb22CCWxvY2FsaG9zdDAFBgMrZXADQQA0QG5Jb+REmeIP
+J7C6ttJjPyIYqjTqvXnw8kEJ+Whw4NOh4ulaQ0w0oQhgOWYnv3S9aThzuJMunfUp
+MSK/UsEM
+-----END CERTIFICATE-----
+";
+
+/// Valid for a year, without extensions.
+const CERTIFICATE_NO_EXTENSIONS: &str = "-----BEGIN CERTIFICATE-----
+MIHzMIGmoAMCAQICEAECAwQFBgcICQoLDA0ODxAwBQYDK2VwMBwxGjAYBgNVBAMM
+EXplYnJhLmV4YW1wbGUuY29tMB4XDTIzMTExNDIyMTMyMFoXDTI0MTExMzIyMTMy
+MFowHDEaMBgGA1UEAwwRemVicmEuZXhhbXBsZS5jb20wKjAFBgMrZXADIQAprLrh
+QbzK8LIuGpTTTQvHNh5SbQv+EsiXlLyTIpZt1zAFBgMrZXADQQAVFizKNeJAY5jK
+5hPIY5fU8gHjGmllCfhq08f8uo4k6A+Epj18VVZRijp200N+5YpXnwIijXfi8IjF
+EkLFHLYO
+-----END CERTIFICATE-----
+";
+
+/// With a serial number whose DER encoding needs a leading zero octet.
+const CERTIFICATE_HIGH_SERIAL: &str = "-----BEGIN CERTIFICATE-----
+MIH0MIGnoAMCAQICEQCAAAAAAAAAAAAAAAAAAAABMAUGAytlcDAcMRowGAYDVQQD
+DBF6ZWJyYS5leGFtcGxlLmNvbTAeFw0yMzExMTQyMjEzMjBaFw0yMzExMTQyMjEz
+MjBaMBwxGjAYBgNVBAMMEXplYnJhLmV4YW1wbGUuY29tMCowBQYDK2VwAyEAKay6
+4UG8yvCyLhqU000LxzYeUm0L/hLIl5S8kyKWbdcwBQYDK2VwA0EAU5/R4zU4izOu
+523eQO/+/Xnif9kDoppT84Al0+oJpzPSHCQDT5Gj84yKFU2I7VTOqxPpHKfMKpok
+mDQf8HwsBw==
+-----END CERTIFICATE-----
+";
+
+// The requests below are identical to those made by Python's `cryptography`
+// package, and `openssl req -verify` accepts them.
+
+/// For `device-0042.example.com`, with a matching DNS subject alternative
+/// name.
+const REQUEST: &str = "-----BEGIN CERTIFICATE REQUEST-----
+MIHXMIGKAgEAMCIxIDAeBgNVBAMMF2RldmljZS0wMDQyLmV4YW1wbGUuY29tMCow
+BQYDK2VwAyEAKay64UG8yvCyLhqU000LxzYeUm0L/hLIl5S8kyKWbdegNTAzBgkq
+hkiG9w0BCQ4xJjAkMCIGA1UdEQQbMBmCF2RldmljZS0wMDQyLmV4YW1wbGUuY29t
+MAUGAytlcANBAJEXfslJdMPZznBO/8qpNpSkeBJfZ1tIlt//2c7BQcSLHozmJphx
+T6TnZlC3lCzl4n/hCaSHv7WWjBn1uRuyTAE=
+-----END CERTIFICATE REQUEST-----
+";
+
+/// For `device-0042.example.com`, without attributes.
+const REQUEST_NO_ATTRIBUTES: &str = "-----BEGIN CERTIFICATE REQUEST-----
+MIGhMFUCAQAwIjEgMB4GA1UEAwwXZGV2aWNlLTAwNDIuZXhhbXBsZS5jb20wKjAF
+BgMrZXADIQAprLrhQbzK8LIuGpTTTQvHNh5SbQv+EsiXlLyTIpZt16AAMAUGAytl
+cANBACPbOuXqcEr/Z5cpK826nHmG32krEOPDarWWlSneA++Z2bL2fkSv6yiVMhZ2
+36K6qe54sB2fFqZxHpb4XKSy2gk=
+-----END CERTIFICATE REQUEST-----
+";
+
+/// From `openssl req -new -subj "/C=US/O=Zcash Foundation/CN=node.example.net"
+/// -addext "subjectAltName=DNS:node.example.net,DNS:node2.example.net,IP:10.0.0.1"`.
+const OPENSSL_REQUEST: &str = "-----BEGIN CERTIFICATE REQUEST-----
+MIIBCjCBvQIBADBDMQswCQYDVQQGEwJVUzEZMBcGA1UECgwQWmNhc2ggRm91bmRh
+dGlvbjEZMBcGA1UEAwwQbm9kZS5leGFtcGxlLm5ldDAqMAUGAytlcAMhAM+2a8e0
+fSaMXdJ7c1spwdZKNEd8g2mpVEQcU2BAqgt4oEcwRQYJKoZIhvcNAQkOMTgwNjA0
+BgNVHREELTArghBub2RlLmV4YW1wbGUubmV0ghFub2RlMi5leGFtcGxlLm5ldIcE
+CgAAATAFBgMrZXADQQAsuMYv7+liwNT+4hXePgaiUTOhF1txFjegGqKarbHl5XLi
+2DekSrpyiEaw/4/SQOlOnVsUgPdT2k61x8CBsM4G
+-----END CERTIFICATE REQUEST-----
+";
+const OPENSSL_REQUEST_VK: &str = "cfb66bc7b47d268c5dd27b735b29c1d64a34477c8369a954441c536040aa0b78";
+
+/// An ECDSA P-256 request, from `openssl req -new -newkey ec`.
+const P256_REQUEST: &str = "-----BEGIN CERTIFICATE REQUEST-----
+MIHWMH0CAQAwGzEZMBcGA1UEAwwQcDI1Ni5leGFtcGxlLm5ldDBZMBMGByqGSM49
+AgEGCCqGSM49AwEHA0IABAmCoE6ghSxKcqw45gp1d2Q7DsS+6O+4SAz9AuTLtyUS
+mdUjdOpBjaXIXxKACzhKYiuNxvoW02mWZKSV/9PHG9GgADAKBggqhkjOPQQDAgNJ
+ADBGAiEA6n01qFJxQbp1Yx6yxGdI8K+A5wh2CqN/5drMIJW8p9ICIQCHrOxn9YEh
+tF5xbAcwa/9lEQ2cyMVR7z0H4aEWBQlXEQ==
+-----END CERTIFICATE REQUEST-----
+";
+
+#[test]
+fn self_signed_certificates() {
+    let sk = SigningKey::from(SEED);
+
+    let certificate = CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, 2_600_000_000)
+        .dns_name(COMMON_NAME)
+        .dns_name("localhost")
+        .serial_number(SERIAL_NUMBER)
+        .self_sign(&sk)
+        .unwrap();
+    assert_eq!(certificate.to_pem(), CERTIFICATE);
+    assert_eq!(certificate.as_ref(), &certificate.to_der()[..]);
+
+    let certificate = CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, 1_731_536_000)
+        .serial_number(SERIAL_NUMBER)
+        .self_sign(&sk)
+        .unwrap();
+    assert_eq!(certificate.to_pem(), CERTIFICATE_NO_EXTENSIONS);
+
+    let mut serial_number = [0u8; 16];
+    serial_number[0] = 0x80;
+    serial_number[15] = 1;
+    let certificate = CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, NOT_BEFORE)
+        .serial_number(serial_number)
+        .self_sign(&sk)
+        .unwrap();
+    assert_eq!(certificate.to_pem(), CERTIFICATE_HIGH_SERIAL);
+}
+
+#[test]
+fn default_serial_number() {
+    let sk = SigningKey::from(SEED);
+    let builder = CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, 1_731_536_000);
+    let certificate = builder.self_sign(&sk).unwrap();
+    assert_eq!(builder.self_sign(&sk).unwrap(), certificate);
+
+    let renewed = CertificateBuilder::new(COMMON_NAME, NOT_BEFORE + 1, 1_731_536_000)
+        .self_sign(&sk)
+        .unwrap();
+    // The serial number INTEGER follows the certificate and TBSCertificate
+    // headers and the version.
+    let der = certificate.to_der();
+    assert_eq!(der[11..13], [0x02, 0x10]);
+    assert!(der[13] < 0x80);
+    assert_ne!(der[13..29], renewed.to_der()[13..29]);
+}
+
+#[test]
+fn invalid_certificates() {
+    let sk = SigningKey::new(thread_rng());
+    assert_eq!(
+        CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, NOT_BEFORE - 1).self_sign(&sk),
+        Err(Error::InvalidValidity)
+    );
+    assert_eq!(
+        CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, 253_402_300_800).self_sign(&sk),
+        Err(Error::TimeOutOfRange)
+    );
+    assert!(
+        CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, 253_402_300_799)
+            .self_sign(&sk)
+            .is_ok()
+    );
+    assert_eq!(
+        CertificateBuilder::new(COMMON_NAME, NOT_BEFORE, NOT_BEFORE)
+            .dns_name("bad name")
+            .self_sign(&sk),
+        Err(Error::InvalidDnsName("bad name".to_string()))
+    );
+}
+
+#[test]
+fn certificate_requests() {
+    let sk = SigningKey::from(SEED);
+
+    let request = CertificateRequestBuilder::new("device-0042.example.com")
+        .dns_name("device-0042.example.com")
+        .sign(&sk)
+        .unwrap();
+    assert_eq!(request.to_pem(), REQUEST);
+    assert_eq!(CertificateRequest::from_pem(REQUEST).unwrap(), request);
+    assert_eq!(request.verify(), Ok(()));
+    assert_eq!(request.common_name(), Some("device-0042.example.com"));
+    assert_eq!(request.dns_names(), ["device-0042.example.com"]);
+    assert_eq!(
+        request.verification_key_bytes(),
+        VerificationKeyBytes::from(&sk)
+    );
+
+    let request = CertificateRequestBuilder::new("device-0042.example.com")
+        .sign(&sk)
+        .unwrap();
+    assert_eq!(request.to_pem(), REQUEST_NO_ATTRIBUTES);
+    assert!(request.dns_names().is_empty());
+    assert_eq!(
+        CertificateRequest::from_der(request.as_ref()).unwrap(),
+        request
+    );
+
+    let sk = SigningKey::new(thread_rng());
+    let request = CertificateRequestBuilder::new("random.example.com")
+        .dns_name("random.example.com")
+        .dns_name("localhost")
+        .sign(&sk)
+        .unwrap();
+    let request = CertificateRequest::from_der(&request.to_der()).unwrap();
+    assert_eq!(request.verify(), Ok(()));
+    assert_eq!(request.dns_names(), ["random.example.com", "localhost"]);
+}
+
+#[test]
+fn openssl_certificate_request() {
+    let request = CertificateRequest::from_pem(OPENSSL_REQUEST).unwrap();
+    assert_eq!(request.verify(), Ok(()));
+    assert_eq!(request.common_name(), Some("node.example.net"));
+    // The IP address subject alternative name is ignored.
+    assert_eq!(
+        request.dns_names(),
+        ["node.example.net", "node2.example.net"]
+    );
+    assert_eq!(
+        request.verification_key_bytes().to_string(),
+        OPENSSL_REQUEST_VK
+    );
+}
+
+#[test]
+fn invalid_certificate_requests() {
+    let der = CertificateRequest::from_pem(REQUEST).unwrap().to_der();
+
+    // Change the requested common name to `device-0043.example.com`.
+    let mut tampered = der.clone();
+    let offset = der.windows(4).position(|w| w == b"0042").unwrap();
+    tampered[offset + 3] = b'3';
+    let request = CertificateRequest::from_der(&tampered).unwrap();
+    assert_eq!(request.common_name(), Some("device-0043.example.com"));
+    assert!(matches!(request.verify(), Err(Error::InvalidSignature(_))));
+
+    let mut trailing = der.clone();
+    trailing.push(0);
+    assert!(matches!(
+        CertificateRequest::from_der(&trailing),
+        Err(Error::Asn1(err)) if matches!(err.kind(), ErrorKind::TrailingData { .. })
+    ));
+    assert!(matches!(
+        CertificateRequest::from_der(&der[..der.len() - 1]),
+        Err(Error::Asn1(err)) if matches!(err.kind(), ErrorKind::Incomplete { .. })
+    ));
+
+    assert_eq!(
+        CertificateRequest::from_pem(P256_REQUEST),
+        Err(Error::UnsupportedAlgorithm)
+    );
+    assert_eq!(
+        CertificateRequest::from_pem(CERTIFICATE),
+        Err(Error::InvalidPem)
+    );
+}